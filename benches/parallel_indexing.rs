@@ -0,0 +1,103 @@
+// Sequential vs parallel indexing of 10k stored pages into a fresh index.
+//
+//   cargo bench --bench parallel_indexing
+
+use chrono::Utc;
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion, Throughput};
+use crawler::search::SearchIndexer;
+use crawler::storage::models::StoredPage;
+use tempfile::TempDir;
+
+const PAGE_COUNT: usize = 10_000;
+const BATCH_SIZE: usize = 500;
+
+const WORDS: &[&str] = &[
+    "rust", "crawler", "search", "engine", "index", "ranking", "web", "page", "link", "graph",
+    "query", "score", "document", "network", "async", "storage", "database", "cache", "robots", "sitemap",
+];
+
+fn stored_pages() -> Vec<StoredPage> {
+    (0..PAGE_COUNT)
+        .map(|i| {
+            // deterministic pseudo-random body so runs are comparable
+            let content: Vec<&str> = (0..200)
+                .map(|j| WORDS[(i * 7 + j * 13 + (i * j) % 11) % WORDS.len()])
+                .collect();
+            let content = content.join(" ");
+
+            StoredPage {
+                id: i as i64,
+                url: format!("https://example{}.com/page/{}", i % 100, i),
+                url_hash: format!("url{}", i),
+                domain: format!("example{}.com", i % 100),
+                title: Some(content[..40].to_string()),
+                description: None,
+                content_hash: format!("content{}", i),
+                quality_score: 0.5,
+                word_count: 200,
+                language: "en".to_string(),
+                crawl_depth: 1,
+                crawled_at: Utc::now(),
+                last_modified: None,
+                status_code: 200,
+                content_type: "text/html".to_string(),
+                content_length: content.len() as i32,
+                content,
+                pagerank: Some((i % 1000) as f64 / 100_000.0),
+                tfidf_score: None,
+                authority_score: None,
+                hub_score: None,
+                simhash: None,
+                personalized_pagerank: None,
+                etag: None,
+                og_image: None,
+                og_type: None,
+                screenshot_path: None,
+                content_compressed: false,
+                cluster_id: None,
+            }
+        })
+        .collect()
+}
+
+fn fresh_indexer() -> (TempDir, SearchIndexer) {
+    let dir = tempfile::tempdir().expect("temp dir");
+    let indexer = SearchIndexer::new(dir.path()).expect("indexer");
+    (dir, indexer)
+}
+
+fn bench_indexing(c: &mut Criterion) {
+    let pages = stored_pages();
+    let runtime = tokio::runtime::Runtime::new().expect("tokio runtime");
+
+    let mut group = c.benchmark_group("indexing");
+    group.sample_size(10);
+    group.throughput(Throughput::Elements(PAGE_COUNT as u64));
+
+    group.bench_function("sequential", |b| {
+        b.iter_batched(
+            fresh_indexer,
+            |(_dir, indexer)| indexer.index_stored_pages(&pages).expect("index"),
+            BatchSize::PerIteration,
+        )
+    });
+
+    for workers in [2, 4, 8] {
+        group.bench_function(format!("parallel_{}_workers", workers), |b| {
+            b.iter_batched(
+                || (fresh_indexer(), pages.clone()),
+                |((_dir, indexer), pages)| {
+                    runtime
+                        .block_on(indexer.index_stored_pages_parallel(pages, workers, BATCH_SIZE))
+                        .expect("index")
+                },
+                BatchSize::PerIteration,
+            )
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_indexing);
+criterion_main!(benches);
@@ -0,0 +1,107 @@
+// Queries per second for the combined BM25 + PageRank + TF-IDF scoring
+// over a 10k document index, and for running the queries one by one vs in one batch.
+//
+//   cargo bench --bench search_scoring
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
+use crawler::config::SearchConfig;
+use crawler::search::{SearchOptions, SearchQuery, SearchSchema};
+use tempfile::TempDir;
+
+const DOC_COUNT: usize = 10_000;
+
+const WORDS: &[&str] = &[
+    "rust", "crawler", "search", "engine", "index", "ranking", "web", "page", "link", "graph",
+    "query", "score", "document", "network", "async", "storage", "database", "cache", "robots", "sitemap",
+];
+
+const QUERIES: &[&str] = &["rust crawler", "search engine ranking", "web graph", "\"async storage\"", "database cache index"];
+
+fn build_index() -> TempDir {
+    let dir = tempfile::tempdir().expect("temp dir");
+    let index = SearchSchema::create_index(dir.path()).expect("create index");
+    let schema = SearchSchema::build();
+    let mut writer: tantivy::IndexWriter = index.writer(50_000_000).expect("index writer");
+
+    for i in 0..DOC_COUNT {
+        // deterministic pseudo-random body so runs are comparable
+        let body: Vec<&str> = (0..40)
+            .map(|j| WORDS[(i * 7 + j * 13 + (i * j) % 11) % WORDS.len()])
+            .collect();
+
+        let mut doc = tantivy::TantivyDocument::default();
+        doc.add_text(schema.url_field, format!("https://example{}.com/page/{}", i % 100, i));
+        doc.add_text(schema.title_field, body[..5].join(" "));
+        doc.add_text(schema.content_field, body.join(" "));
+        doc.add_f64(schema.pagerank_field, (i % 1000) as f64 / 100_000.0);
+        doc.add_f64(schema.tfidf_field, (i % 500) as f64 / 10_000.0);
+        writer.add_document(doc).expect("add document");
+    }
+    writer.commit().expect("commit");
+    dir
+}
+
+fn bench_scoring(c: &mut Criterion) {
+    let dir = build_index();
+
+    let mut group = c.benchmark_group("combined_scoring");
+    group.throughput(Throughput::Elements(QUERIES.len() as u64));
+
+    let default_query = SearchQuery::new(dir.path()).expect("open index");
+    group.bench_function("tantivy_bm25", |b| {
+        b.iter(|| {
+            for query in QUERIES {
+                black_box(default_query.search(query, 20).expect("search"));
+            }
+        })
+    });
+
+    let custom_query = SearchQuery::new(dir.path()).expect("open index").with_config(SearchConfig {
+        bm25_k1: 1.6,
+        bm25_b: 0.5,
+        quality_weight: 0.1,
+        ..SearchConfig::default()
+    });
+    group.bench_function("custom_bm25", |b| {
+        b.iter(|| {
+            for query in QUERIES {
+                black_box(custom_query.search(query, 20).expect("search"));
+            }
+        })
+    });
+
+    group.finish();
+}
+
+fn bench_batch(c: &mut Criterion) {
+    let dir = build_index();
+    let search = SearchQuery::new(dir.path()).expect("open index");
+
+    // every query a few times, like an evaluation run
+    let queries: Vec<&str> = QUERIES.iter().copied().cycle().take(QUERIES.len() * 20).collect();
+
+    let mut group = c.benchmark_group("batch_search");
+    group.throughput(Throughput::Elements(queries.len() as u64));
+
+    group.bench_function("single", |b| {
+        b.iter(|| {
+            for query in &queries {
+                black_box(search.search(query, 20).expect("search"));
+            }
+        })
+    });
+
+    group.bench_function("batch", |b| {
+        b.iter(|| {
+            let batch: Vec<(String, SearchOptions)> = queries.iter()
+                .map(|query| (query.to_string(), SearchOptions::new(20)))
+                .collect();
+            black_box(search.batch_search(&batch).expect("batch search"));
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_scoring, bench_batch);
+criterion_main!(benches);
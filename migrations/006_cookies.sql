@@ -0,0 +1,12 @@
+-- Persisted HTTP cookies for session-aware crawling
+CREATE TABLE IF NOT EXISTS cookies (
+    id BIGSERIAL PRIMARY KEY,
+    domain TEXT NOT NULL,
+    path TEXT NOT NULL DEFAULT '/',
+    name TEXT NOT NULL,
+    cookie_json TEXT NOT NULL,
+    updated_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (domain, path, name)
+);
+
+CREATE INDEX IF NOT EXISTS idx_cookies_domain ON cookies(domain);
@@ -0,0 +1,115 @@
+-- SQLite dialect of the complete schema (001 + 004 + 005 + 006 + 008 + 009 + 010 + 011 + 012 + 013 + 014)
+CREATE TABLE IF NOT EXISTS domains (
+    domain TEXT PRIMARY KEY,
+    robots_txt TEXT,
+    robots_fetched_at TEXT,
+    crawl_delay INTEGER DEFAULT 1000,
+    page_count INTEGER DEFAULT 0,
+    avg_quality_score REAL,
+    last_crawled TEXT,
+    crawl_allowed INTEGER DEFAULT 1
+);
+
+CREATE TABLE IF NOT EXISTS pages (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    url TEXT UNIQUE NOT NULL,
+    url_hash TEXT UNIQUE NOT NULL,
+    domain TEXT NOT NULL,
+    title TEXT,
+    description TEXT,
+    content TEXT,
+    content_hash TEXT,
+    quality_score REAL DEFAULT 0.0,
+    word_count INTEGER DEFAULT 0,
+    language TEXT DEFAULT 'en',
+    crawl_depth INTEGER DEFAULT 0,
+    crawled_at TEXT DEFAULT CURRENT_TIMESTAMP,
+    last_modified TEXT,
+    status_code INTEGER,
+    content_type TEXT,
+    content_length INTEGER,
+    pagerank REAL DEFAULT 0.0,
+    tfidf_score REAL DEFAULT 0.0,
+    authority_score REAL,
+    hub_score REAL,
+    simhash INTEGER,
+    personalized_pagerank REAL,
+    etag TEXT,
+    og_image TEXT,
+    og_type TEXT,
+    screenshot_path TEXT
+);
+
+CREATE TABLE IF NOT EXISTS links (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    source_page_id INTEGER NOT NULL,
+    target_page_id INTEGER,
+    target_url TEXT NOT NULL,
+    anchor_text TEXT,
+    link_position INTEGER DEFAULT 0,
+    created_at TEXT DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (source_page_id) REFERENCES pages(id) ON DELETE CASCADE,
+    FOREIGN KEY (target_page_id) REFERENCES pages(id) ON DELETE SET NULL
+);
+
+CREATE TABLE IF NOT EXISTS crawl_sessions (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    started_at TEXT DEFAULT CURRENT_TIMESTAMP,
+    ended_at TEXT,
+    pages_crawled INTEGER DEFAULT 0,
+    pages_failed INTEGER DEFAULT 0,
+    seed_urls TEXT,
+    config_snapshot TEXT,
+    status TEXT DEFAULT 'running'
+);
+
+CREATE TABLE IF NOT EXISTS cookies (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    domain TEXT NOT NULL,
+    path TEXT NOT NULL DEFAULT '/',
+    name TEXT NOT NULL,
+    cookie_json TEXT NOT NULL,
+    updated_at TEXT DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (domain, path, name)
+);
+
+CREATE TABLE IF NOT EXISTS page_versions (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    page_id INTEGER NOT NULL REFERENCES pages(id) ON DELETE CASCADE,
+    version INTEGER NOT NULL,
+    content_hash TEXT NOT NULL,
+    title TEXT,
+    content TEXT,
+    crawled_at TEXT DEFAULT CURRENT_TIMESTAMP,
+    diff_size_bytes INTEGER DEFAULT 0,
+    UNIQUE (page_id, version)
+);
+
+CREATE TABLE IF NOT EXISTS url_aliases (
+    alias_url_hash TEXT PRIMARY KEY,
+    alias_url TEXT NOT NULL,
+    page_id INTEGER NOT NULL REFERENCES pages(id) ON DELETE CASCADE,
+    created_at TEXT DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Indexes for performance
+CREATE INDEX IF NOT EXISTS idx_pages_url_hash ON pages(url_hash);
+CREATE INDEX IF NOT EXISTS idx_pages_domain ON pages(domain);
+CREATE INDEX IF NOT EXISTS idx_pages_quality ON pages(quality_score DESC);
+CREATE INDEX IF NOT EXISTS idx_pages_crawled_at ON pages(crawled_at DESC);
+CREATE INDEX IF NOT EXISTS idx_pages_content_hash ON pages(content_hash);
+CREATE INDEX IF NOT EXISTS idx_pagerank ON pages(pagerank DESC);
+CREATE INDEX IF NOT EXISTS idx_pages_authority ON pages(authority_score DESC);
+CREATE INDEX IF NOT EXISTS idx_pages_personalized_pagerank ON pages(personalized_pagerank DESC);
+CREATE INDEX IF NOT EXISTS idx_pages_simhash_b0 ON pages(((simhash >> 48) & 65535));
+CREATE INDEX IF NOT EXISTS idx_pages_simhash_b1 ON pages(((simhash >> 32) & 65535));
+CREATE INDEX IF NOT EXISTS idx_pages_simhash_b2 ON pages(((simhash >> 16) & 65535));
+CREATE INDEX IF NOT EXISTS idx_pages_simhash_b3 ON pages((simhash & 65535));
+CREATE INDEX IF NOT EXISTS idx_links_source ON links(source_page_id);
+CREATE INDEX IF NOT EXISTS idx_links_target_page ON links(target_page_id);
+CREATE INDEX IF NOT EXISTS idx_links_target_url ON links(target_url);
+CREATE INDEX IF NOT EXISTS idx_sessions_started ON crawl_sessions(started_at DESC);
+CREATE INDEX IF NOT EXISTS idx_domains_last_crawled ON domains(last_crawled);
+CREATE INDEX IF NOT EXISTS idx_cookies_domain ON cookies(domain);
+CREATE INDEX IF NOT EXISTS idx_page_versions_page ON page_versions(page_id, version DESC);
+CREATE INDEX IF NOT EXISTS idx_url_aliases_page ON url_aliases(page_id);
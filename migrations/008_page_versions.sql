@@ -0,0 +1,14 @@
+-- Content history for pages, one row per observed change
+CREATE TABLE IF NOT EXISTS page_versions (
+    id BIGSERIAL PRIMARY KEY,
+    page_id BIGINT NOT NULL REFERENCES pages(id) ON DELETE CASCADE,
+    version INTEGER NOT NULL,
+    content_hash VARCHAR(64) NOT NULL,
+    title TEXT,
+    content TEXT,
+    crawled_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP,
+    diff_size_bytes BIGINT DEFAULT 0,
+    UNIQUE (page_id, version)
+);
+
+CREATE INDEX IF NOT EXISTS idx_page_versions_page ON page_versions(page_id, version DESC);
@@ -0,0 +1,3 @@
+ALTER TABLE pages ADD COLUMN IF NOT EXISTS authority_score DOUBLE PRECISION;
+ALTER TABLE pages ADD COLUMN IF NOT EXISTS hub_score DOUBLE PRECISION;
+CREATE INDEX IF NOT EXISTS idx_pages_authority ON pages(authority_score DESC);
@@ -0,0 +1,6 @@
+-- SimHash fingerprint plus one expression index per 16-bit LSH band
+ALTER TABLE pages ADD COLUMN IF NOT EXISTS simhash BIGINT;
+CREATE INDEX IF NOT EXISTS idx_pages_simhash_b0 ON pages (((simhash >> 48) & 65535));
+CREATE INDEX IF NOT EXISTS idx_pages_simhash_b1 ON pages (((simhash >> 32) & 65535));
+CREATE INDEX IF NOT EXISTS idx_pages_simhash_b2 ON pages (((simhash >> 16) & 65535));
+CREATE INDEX IF NOT EXISTS idx_pages_simhash_b3 ON pages ((simhash & 65535));
@@ -0,0 +1,3 @@
+-- Seed-biased PageRank, kept apart from the global pagerank column
+ALTER TABLE pages ADD COLUMN IF NOT EXISTS personalized_pagerank DOUBLE PRECISION;
+CREATE INDEX IF NOT EXISTS idx_pages_personalized_pagerank ON pages(personalized_pagerank DESC);
@@ -0,0 +1,2 @@
+-- Validators for conditional recrawls (last_modified already exists from 001)
+ALTER TABLE pages ADD COLUMN IF NOT EXISTS etag TEXT;
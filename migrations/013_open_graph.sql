@@ -0,0 +1,3 @@
+-- Open Graph metadata extracted from page <meta property="og:..."> tags
+ALTER TABLE pages ADD COLUMN IF NOT EXISTS og_image TEXT;
+ALTER TABLE pages ADD COLUMN IF NOT EXISTS og_type TEXT;
@@ -0,0 +1,2 @@
+-- Screenshot file of the page, relative to the crawler's screenshot_dir
+ALTER TABLE pages ADD COLUMN IF NOT EXISTS screenshot_path TEXT;
@@ -0,0 +1,9 @@
+-- Full-text search fallback that works without the Tantivy index (PostgreSQL only)
+CREATE OR REPLACE FUNCTION pages_search_vector(title TEXT, description TEXT, content TEXT)
+RETURNS tsvector LANGUAGE sql IMMUTABLE AS $$
+    SELECT setweight(to_tsvector('english', coalesce(title, '')), 'A')
+        || setweight(to_tsvector('english', coalesce(description, '')), 'B')
+        || setweight(to_tsvector('english', coalesce(content, '')), 'C')
+$$;
+ALTER TABLE pages ADD COLUMN IF NOT EXISTS search_vector tsvector;
+CREATE INDEX IF NOT EXISTS idx_pages_search_vector ON pages USING GIN (search_vector);
@@ -0,0 +1,3 @@
+-- zstd-compressed page content; when content_compressed is set, content holds an empty string
+ALTER TABLE pages ADD COLUMN IF NOT EXISTS content_zstd BYTEA;
+ALTER TABLE pages ADD COLUMN IF NOT EXISTS content_compressed BOOLEAN NOT NULL DEFAULT FALSE;
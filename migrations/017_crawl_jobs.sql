@@ -0,0 +1,13 @@
+-- Crawl jobs started through the API, seed_urls, config_snapshot and statistics are JSON encoded
+CREATE TABLE IF NOT EXISTS crawl_jobs (
+    id UUID PRIMARY KEY,
+    seed_urls TEXT NOT NULL,
+    config_snapshot TEXT NOT NULL,
+    status VARCHAR(20) NOT NULL DEFAULT 'running',
+    statistics TEXT,
+    error TEXT,
+    started_at TIMESTAMPTZ NOT NULL,
+    completed_at TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS idx_crawl_jobs_started_at ON crawl_jobs(started_at DESC);
@@ -0,0 +1,3 @@
+-- Topic cluster assigned by the cluster command, NULL until pages are clustered
+ALTER TABLE pages ADD COLUMN IF NOT EXISTS cluster_id INTEGER;
+CREATE INDEX IF NOT EXISTS idx_pages_cluster_id ON pages(cluster_id);
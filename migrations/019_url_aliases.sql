@@ -0,0 +1,9 @@
+-- Crawled URLs that declared another <link rel="canonical"> URL, the page is stored under the canonical one
+CREATE TABLE IF NOT EXISTS url_aliases (
+    alias_url_hash VARCHAR(64) PRIMARY KEY,
+    alias_url TEXT NOT NULL,
+    page_id BIGINT NOT NULL REFERENCES pages(id) ON DELETE CASCADE,
+    created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_url_aliases_page ON url_aliases(page_id);
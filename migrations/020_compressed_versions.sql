@@ -0,0 +1,2 @@
+-- zstd-compressed version content; when content_zstd is set, content holds an empty string
+ALTER TABLE page_versions ADD COLUMN IF NOT EXISTS content_zstd BYTEA;
@@ -0,0 +1,5 @@
+-- Crawl jobs record only the crawler, network and algorithm settings, the storage
+-- settings with the database and Redis urls are removed from older snapshots
+UPDATE crawl_jobs
+SET config_snapshot = (config_snapshot::jsonb - 'storage' - 'search')::text
+WHERE config_snapshot::jsonb ? 'storage';
@@ -0,0 +1,6 @@
+-- Data migrations run in code rather than SQL, recorded so they run once.
+-- Shared by the PostgreSQL and SQLite schemas.
+CREATE TABLE IF NOT EXISTS data_migrations (
+    name TEXT PRIMARY KEY,
+    applied_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
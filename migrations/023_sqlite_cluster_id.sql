@@ -0,0 +1,4 @@
+-- Topic cluster for SQLite, the counterpart of 018_cluster_id.sql.
+-- SQLite has no ADD COLUMN IF NOT EXISTS, so this only runs while pages lacks the column.
+ALTER TABLE pages ADD COLUMN cluster_id INTEGER;
+CREATE INDEX IF NOT EXISTS idx_pages_cluster_id ON pages(cluster_id);
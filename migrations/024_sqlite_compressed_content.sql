@@ -0,0 +1,5 @@
+-- zstd-compressed content for SQLite, the counterpart of 016_compressed_content.sql and 020_compressed_versions.sql.
+-- Runs only while pages lacks content_zstd. When content_compressed is set, content holds an empty string
+ALTER TABLE pages ADD COLUMN content_zstd BLOB;
+ALTER TABLE pages ADD COLUMN content_compressed BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE page_versions ADD COLUMN content_zstd BLOB;
@@ -0,0 +1,149 @@
+use std::collections::HashMap;
+use super::tfidf::TfIdfCalculator;
+use tracing::{info, debug};
+
+/// Sparse TF-IDF vector, scaled to unit length
+type TermVector = HashMap<String, f64>;
+
+const MAX_ITERATIONS: usize = 50;
+
+/// Groups documents by topic with spherical k-means on their TF-IDF vectors
+pub struct ContentCluster;
+
+impl ContentCluster {
+    /// Split the documents of `tfidf` into at most `k` clusters of doc ids. Documents join
+    /// the centroid they are most cosine-similar to, centroids are the normalized mean of
+    /// their documents. Documents without scored terms are left out, clusters come back
+    /// largest first and each one sorted.
+    pub fn cluster_pages(tfidf: &TfIdfCalculator, k: usize) -> Vec<Vec<String>> {
+        let mut doc_ids: Vec<&str> = tfidf.document_ids().collect();
+        doc_ids.sort_unstable();
+
+        let documents: Vec<(&str, TermVector)> = doc_ids.into_iter()
+            .filter_map(|doc_id| normalized(tfidf.document_vector(doc_id)).map(|vector| (doc_id, vector)))
+            .collect();
+
+        if k == 0 || documents.is_empty() {
+            return Vec::new();
+        }
+
+        let vectors: Vec<&TermVector> = documents.iter().map(|(_, vector)| vector).collect();
+        let mut centroids = Self::initial_centroids(&vectors, k);
+        let mut assignments = vec![usize::MAX; vectors.len()];
+
+        for iteration in 0..MAX_ITERATIONS {
+            let mut changed = false;
+            for (assignment, vector) in assignments.iter_mut().zip(&vectors) {
+                let nearest = Self::nearest(&centroids, vector);
+                if *assignment != nearest {
+                    *assignment = nearest;
+                    changed = true;
+                }
+            }
+
+            if !changed {
+                debug!("k-means converged after {} iterations", iteration);
+                break;
+            }
+
+            centroids = (0..centroids.len())
+                .map(|cluster| {
+                    let members = vectors.iter()
+                        .zip(&assignments)
+                        .filter(|(_, assignment)| **assignment == cluster)
+                        .map(|(vector, _)| *vector);
+                    // an emptied cluster keeps its centroid and may win documents back
+                    normalized(mean(members)).unwrap_or_else(|| centroids[cluster].clone())
+                })
+                .collect();
+        }
+
+        let mut clusters: Vec<Vec<String>> = vec![Vec::new(); centroids.len()];
+        for ((doc_id, _), cluster) in documents.iter().zip(&assignments) {
+            clusters[*cluster].push(doc_id.to_string());
+        }
+        clusters.retain(|cluster| !cluster.is_empty());
+        clusters.sort_by(|a, b| b.len().cmp(&a.len()).then_with(|| a.cmp(b)));
+
+        info!("Clustered {} documents into {} clusters", documents.len(), clusters.len());
+        clusters
+    }
+
+    /// Top 3 terms of a cluster by their TF-IDF score averaged over its documents
+    pub fn label_cluster(doc_ids: &[String], tfidf: &TfIdfCalculator) -> Vec<String> {
+        let vectors: Vec<TermVector> = doc_ids.iter().map(|doc_id| tfidf.document_vector(doc_id)).collect();
+
+        let mut terms: Vec<(String, f64)> = mean(vectors.iter()).into_iter().collect();
+        terms.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal).then_with(|| a.0.cmp(&b.0)));
+        terms.into_iter().take(3).map(|(term, _)| term).collect()
+    }
+
+    /// Farthest-first seeding: start from the first document, then repeatedly take the
+    /// document least similar to every centroid so far. Deterministic, unlike k-means++.
+    fn initial_centroids(vectors: &[&TermVector], k: usize) -> Vec<TermVector> {
+        let mut centroids = vec![vectors[0].clone()];
+        let mut best_similarity: Vec<f64> = vectors.iter().map(|vector| cosine(vector, vectors[0])).collect();
+
+        while centroids.len() < k.min(vectors.len()) {
+            let (farthest, similarity) = best_similarity.iter()
+                .enumerate()
+                .min_by(|a, b| a.1.partial_cmp(b.1).unwrap_or(std::cmp::Ordering::Equal))
+                .map(|(i, similarity)| (i, *similarity))
+                .unwrap();
+            // every document is already a centroid or identical to one
+            if similarity >= 1.0 - 1e-9 {
+                break;
+            }
+
+            centroids.push(vectors[farthest].clone());
+            for (best, vector) in best_similarity.iter_mut().zip(vectors) {
+                *best = best.max(cosine(vector, vectors[farthest]));
+            }
+        }
+        centroids
+    }
+
+    fn nearest(centroids: &[TermVector], vector: &TermVector) -> usize {
+        centroids.iter()
+            .map(|centroid| cosine(vector, centroid))
+            .enumerate()
+            .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal).then(b.0.cmp(&a.0)))
+            .map(|(cluster, _)| cluster)
+            .unwrap_or(0)
+    }
+}
+
+/// Cosine similarity of two unit vectors is their dot product
+fn cosine(a: &TermVector, b: &TermVector) -> f64 {
+    let (small, large) = if a.len() <= b.len() { (a, b) } else { (b, a) };
+    small.iter()
+        .filter_map(|(term, weight)| large.get(term).map(|other| weight * other))
+        .sum()
+}
+
+fn mean<'a>(vectors: impl Iterator<Item = &'a TermVector>) -> TermVector {
+    let mut sum = TermVector::new();
+    let mut count = 0;
+    for vector in vectors {
+        for (term, weight) in vector {
+            *sum.entry(term.clone()).or_insert(0.0) += weight;
+        }
+        count += 1;
+    }
+    for weight in sum.values_mut() {
+        *weight /= count as f64;
+    }
+    sum
+}
+
+/// None for a vector without any weight
+fn normalized(mut vector: TermVector) -> Option<TermVector> {
+    let length = vector.values().map(|weight| weight * weight).sum::<f64>().sqrt();
+    if length == 0.0 {
+        return None;
+    }
+    for weight in vector.values_mut() {
+        *weight /= length;
+    }
+    Some(vector)
+}
@@ -3,6 +3,7 @@ use clap::builder::Str;
 use log::info;
 use crate::storage::models::PageFilter;
 use crate::storage::Result;
+use crate::utils::extract_domain_info;
 
 #[derive(Debug, Clone)]
 pub struct LinkGraph{
@@ -14,6 +15,9 @@ pub struct LinkGraph{
 
     // All unique urls in graph
     pub nodes: Vec<String>,
+
+    // URL -> detected language of the page, used to color exported graphs
+    pub languages: HashMap<String, String>,
 }
 
 impl LinkGraph {
@@ -22,6 +26,7 @@ impl LinkGraph {
             outbounds: HashMap::new(),
             inbounds: HashMap::new(),
             nodes: Vec::new(),
+            languages: HashMap::new(),
         }
     }
     pub async fn from_database(db: &crate::storage::repository::PageRepository)->Result<Self>{
@@ -32,7 +37,7 @@ impl LinkGraph {
         // get all pages
 
         let filter = PageFilter::new();
-        let pages = db.get_pages(&filter).await?;
+        let pages = db.get_pages(&filter).await?.items;
 
         info!("Loaded {} pages from database", pages.len());
 
@@ -43,6 +48,7 @@ impl LinkGraph {
         // collect all unique urls
         let all_urls: HashSet<String> = pages.iter().map(|p| p.url.clone()).collect();
         nodes.extend(all_urls.iter().cloned());
+        let languages = pages.iter().map(|p| (p.url.clone(), p.language.clone())).collect();
 
         // get all links
         let links = db.get_all_links().await?;
@@ -77,6 +83,7 @@ impl LinkGraph {
             outbounds,
             inbounds,
             nodes,
+            languages,
         })
     }
 
@@ -89,7 +96,7 @@ impl LinkGraph {
 
         // Get all pages using existing get_pages method
         let filter = PageFilter::new();
-        let pages = repo.get_pages(&filter).await?;
+        let pages = repo.get_pages(&filter).await?.items;
 
         info!("Loaded {} pages from database", pages.len());
 
@@ -100,6 +107,7 @@ impl LinkGraph {
         // Collect all unique URLs
         let all_urls: HashSet<String> = pages.iter().map(|p| p.url.clone()).collect();
         nodes.extend(all_urls.iter().cloned());
+        let languages = pages.iter().map(|p| (p.url.clone(), p.language.clone())).collect();
 
         // Get all links from database using the new method
         let links = repo.get_all_links().await?;
@@ -135,6 +143,7 @@ impl LinkGraph {
             outbounds: outbound,
             inbounds: inbound,
             nodes,
+            languages,
         })
     }
 
@@ -155,4 +164,688 @@ impl LinkGraph {
             .filter(|url| self.outbound_count(url) == 0)
             .collect()
     }
-}
\ No newline at end of file
+
+    /// Weakly connected components (link direction ignored), largest first,
+    /// with the urls of each component sorted. Links to pages outside the graph are ignored.
+    pub fn connected_components(&self) -> Vec<Vec<String>> {
+        let (index_of, adjacency) = self.adjacency_lists();
+
+        // union-find with path halving and union by size
+        let mut parent: Vec<usize> = (0..self.nodes.len()).collect();
+        let mut size = vec![1usize; self.nodes.len()];
+        fn find(parent: &mut [usize], mut node: usize) -> usize {
+            while parent[node] != node {
+                parent[node] = parent[parent[node]];
+                node = parent[node];
+            }
+            node
+        }
+
+        for (from, targets) in adjacency.iter().enumerate() {
+            for &to in targets {
+                let (a, b) = (find(&mut parent, from), find(&mut parent, to));
+                if a == b {
+                    continue;
+                }
+                let (small, large) = if size[a] < size[b] { (a, b) } else { (b, a) };
+                parent[small] = large;
+                size[large] += size[small];
+            }
+        }
+
+        let mut components: HashMap<usize, Vec<String>> = HashMap::new();
+        for (url, &node) in &index_of {
+            components.entry(find(&mut parent, node)).or_default().push(url.to_string());
+        }
+        Self::sorted_components(components.into_values().collect())
+    }
+
+    /// Strongly connected components (Tarjan's algorithm), largest first,
+    /// with the urls of each component sorted
+    pub fn strongly_connected_components(&self) -> Vec<Vec<String>> {
+        let (_, adjacency) = self.adjacency_lists();
+        let n = self.nodes.len();
+
+        let mut index = vec![usize::MAX; n];
+        let mut lowlink = vec![0usize; n];
+        let mut on_stack = vec![false; n];
+        let mut stack: Vec<usize> = Vec::new();
+        let mut next_index = 0;
+        let mut components = Vec::new();
+
+        for start in 0..n {
+            if index[start] != usize::MAX {
+                continue;
+            }
+
+            // explicit call stack of (node, next neighbour to visit), deep graphs would overflow recursion
+            let mut call_stack = vec![(start, 0usize)];
+            index[start] = next_index;
+            lowlink[start] = next_index;
+            next_index += 1;
+            stack.push(start);
+            on_stack[start] = true;
+
+            while let Some(frame) = call_stack.last_mut() {
+                let node = frame.0;
+                if let Some(&neighbour) = adjacency[node].get(frame.1) {
+                    frame.1 += 1;
+                    if index[neighbour] == usize::MAX {
+                        index[neighbour] = next_index;
+                        lowlink[neighbour] = next_index;
+                        next_index += 1;
+                        stack.push(neighbour);
+                        on_stack[neighbour] = true;
+                        call_stack.push((neighbour, 0));
+                    } else if on_stack[neighbour] {
+                        lowlink[node] = lowlink[node].min(index[neighbour]);
+                    }
+                    continue;
+                }
+
+                call_stack.pop();
+                if let Some(&(caller, _)) = call_stack.last() {
+                    lowlink[caller] = lowlink[caller].min(lowlink[node]);
+                }
+
+                if lowlink[node] == index[node] {
+                    let mut component = Vec::new();
+                    while let Some(member) = stack.pop() {
+                        on_stack[member] = false;
+                        component.push(self.nodes[member].clone());
+                        if member == node {
+                            break;
+                        }
+                    }
+                    components.push(component);
+                }
+            }
+        }
+
+        Self::sorted_components(components)
+    }
+
+    /// Share of the pages in the largest strongly connected component, 0.0 for an empty graph
+    pub fn largest_scc_fraction(&self) -> f64 {
+        if self.nodes.is_empty() {
+            return 0.0;
+        }
+        let largest = self.strongly_connected_components().first().map(Vec::len).unwrap_or(0);
+        largest as f64 / self.nodes.len() as f64
+    }
+
+    /// Pages on one of `domains` (or their subdomains) and the links between them
+    pub fn subgraph(&self, domains: &[String]) -> LinkGraph {
+        self.restricted_to(|url| {
+            let host = extract_domain_info(url).map(|info| info.host).unwrap_or_else(|| url.to_string());
+            domains.iter().any(|domain| {
+                host == *domain || host.strip_suffix(domain.as_str()).is_some_and(|prefix| prefix.ends_with('.'))
+            })
+        })
+    }
+
+    /// Pages within `hops` links of `url`, following links in either direction, and the
+    /// links between them. Empty when `url` is not in the graph.
+    pub fn ego_network(&self, url: &str, hops: usize) -> LinkGraph {
+        let known: HashSet<&str> = self.nodes.iter().map(String::as_str).collect();
+        if !known.contains(url) {
+            return LinkGraph::new();
+        }
+
+        let mut reached: HashSet<&str> = HashSet::from([url]);
+        let mut frontier = vec![url];
+        for _ in 0..hops {
+            let mut next = Vec::new();
+            for node in frontier {
+                let neighbours = self.outbounds.get(node).into_iter().flatten()
+                    .chain(self.inbounds.get(node).into_iter().flatten());
+                for neighbour in neighbours {
+                    if known.contains(neighbour.as_str()) && reached.insert(neighbour.as_str()) {
+                        next.push(neighbour.as_str());
+                    }
+                }
+            }
+            if next.is_empty() {
+                break;
+            }
+            frontier = next;
+        }
+
+        self.restricted_to(|node| reached.contains(node))
+    }
+
+    // copy of the graph with only the nodes `keep` accepts and the links between them
+    fn restricted_to(&self, keep: impl Fn(&str) -> bool) -> LinkGraph {
+        let nodes: Vec<String> = self.nodes.iter().filter(|url| keep(url)).cloned().collect();
+        let included: HashSet<&str> = nodes.iter().map(String::as_str).collect();
+
+        let links_within = |links: &HashMap<String, Vec<String>>| -> HashMap<String, Vec<String>> {
+            nodes.iter()
+                .map(|url| {
+                    let targets = links.get(url).into_iter()
+                        .flatten()
+                        .filter(|target| included.contains(target.as_str()))
+                        .cloned()
+                        .collect();
+                    (url.clone(), targets)
+                })
+                .collect()
+        };
+
+        LinkGraph {
+            outbounds: links_within(&self.outbounds),
+            inbounds: links_within(&self.inbounds),
+            languages: self.languages.iter()
+                .filter(|(url, _)| included.contains(url.as_str()))
+                .map(|(url, language)| (url.clone(), language.clone()))
+                .collect(),
+            nodes,
+        }
+    }
+
+    // node positions and the deduplicated outbound links between known nodes
+    fn adjacency_lists(&self) -> (HashMap<&str, usize>, Vec<Vec<usize>>) {
+        let index_of: HashMap<&str, usize> = self.nodes
+            .iter()
+            .enumerate()
+            .map(|(i, url)| (url.as_str(), i))
+            .collect();
+
+        let adjacency = self.nodes
+            .iter()
+            .map(|url| {
+                let mut targets: Vec<usize> = self.outbounds
+                    .get(url)
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|target| index_of.get(target.as_str()).copied())
+                    .collect();
+                targets.sort_unstable();
+                targets.dedup();
+                targets
+            })
+            .collect();
+
+        (index_of, adjacency)
+    }
+
+    fn sorted_components(mut components: Vec<Vec<String>>) -> Vec<Vec<String>> {
+        for component in &mut components {
+            component.sort();
+        }
+        components.sort_by(|a, b| b.len().cmp(&a.len()).then_with(|| a.cmp(b)));
+        components
+    }
+
+    /// Graphviz DOT export of the `max_nodes` highest ranked pages with a PageRank of at least
+    /// `min_pagerank`. Nodes are labelled with their domain, sized by PageRank and colored by
+    /// language. Only links between different domains become edges, weighted by how many
+    /// links there are; links within a domain are summed into the node's `internal_links`.
+    pub fn to_dot(&self, max_nodes: usize, min_pagerank: f64, ranks: &HashMap<String, f64>) -> String {
+        let mut selected: Vec<(&String, f64)> = self.nodes.iter()
+            .map(|url| (url, ranks.get(url).copied().unwrap_or(0.0)))
+            .filter(|(_, rank)| *rank >= min_pagerank)
+            .collect();
+        selected.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal).then(a.0.cmp(b.0)));
+        selected.truncate(max_nodes);
+
+        let included: HashSet<&str> = selected.iter().map(|(url, _)| url.as_str()).collect();
+        let max_rank = selected.iter().map(|(_, rank)| *rank).fold(0.0, f64::max);
+
+        let mut dot = String::from("digraph links {\n    node [shape=ellipse, style=filled, fontcolor=white];\n");
+        let mut edges: Vec<(&str, &str, usize)> = Vec::new();
+
+        for (url, rank) in &selected {
+            let domain = Self::domain_of(url);
+            let mut internal_links = 0;
+
+            for target in self.outbounds.get(*url).into_iter().flatten() {
+                if !included.contains(target.as_str()) {
+                    continue;
+                }
+                if Self::domain_of(target) == domain {
+                    internal_links += 1;
+                } else if let Some(edge) = edges.iter_mut().find(|(from, to, _)| *from == url.as_str() && *to == target.as_str()) {
+                    edge.2 += 1;
+                } else {
+                    edges.push((url.as_str(), target.as_str(), 1));
+                }
+            }
+
+            // 8pt for the lowest ranked page up to 32pt for the highest
+            let fontsize = if max_rank > 0.0 { 8.0 + 24.0 * rank / max_rank } else { 8.0 };
+            let language = self.languages.get(*url).map(String::as_str).unwrap_or("");
+
+            dot.push_str(&format!(
+                "    \"{}\" [label=\"{}\", tooltip=\"{}\", fontsize={:.1}, color=\"{}\", fillcolor=\"{}\", internal_links={}];\n",
+                Self::escape_dot(url),
+                Self::escape_dot(&domain),
+                Self::escape_dot(url),
+                fontsize,
+                Self::language_color(language),
+                Self::language_color(language),
+                internal_links,
+            ));
+        }
+
+        for (from, to, weight) in edges {
+            dot.push_str(&format!(
+                "    \"{}\" -> \"{}\" [weight={}, penwidth={:.1}];\n",
+                Self::escape_dot(from),
+                Self::escape_dot(to),
+                weight,
+                1.0 + (weight as f64).ln(),
+            ));
+        }
+
+        dot.push_str("}\n");
+        dot
+    }
+
+    /// Cypher for importing the graph into Neo4j, one statement per entry: an index on `Page.url`
+    /// first, then an `UNWIND ... CREATE (:Page ...)` per `batch_size` pages and an
+    /// `UNWIND ... MATCH ... CREATE (a)-[:LINKS_TO]->(b)` per `batch_size` distinct links between pages of the graph
+    pub fn to_cypher_statements(&self, batch_size: usize, ranks: &HashMap<String, f64>) -> Vec<String> {
+        if self.nodes.is_empty() {
+            return Vec::new();
+        }
+
+        let pages: Vec<String> = self.nodes.iter()
+            .map(|url| format!(
+                "{{url: \"{}\", domain: \"{}\", pagerank: {}}}",
+                Self::escape_cypher(url),
+                Self::escape_cypher(&Self::domain_of(url)),
+                ranks.get(url).copied().unwrap_or(0.0),
+            ))
+            .collect();
+        let links: Vec<String> = self.distinct_links().into_iter()
+            .map(|(from, to)| format!(
+                "{{from: \"{}\", to: \"{}\"}}",
+                Self::escape_cypher(from),
+                Self::escape_cypher(to),
+            ))
+            .collect();
+
+        let batch_size = batch_size.max(1);
+        let mut statements = vec!["CREATE INDEX page_url IF NOT EXISTS FOR (p:Page) ON (p.url);\n".to_string()];
+        statements.extend(pages.chunks(batch_size).map(|batch| format!(
+            "UNWIND [{}] AS row CREATE (:Page {{url: row.url, domain: row.domain, pagerank: row.pagerank}});\n",
+            batch.join(", "),
+        )));
+        statements.extend(links.chunks(batch_size).map(|batch| format!(
+            "UNWIND [{}] AS row MATCH (a:Page {{url: row.from}}), (b:Page {{url: row.to}}) CREATE (a)-[:LINKS_TO]->(b);\n",
+            batch.join(", "),
+        )));
+        statements
+    }
+
+    /// GraphML export for Gephi or Cytoscape, pages carry their url, domain, language and
+    /// PageRank, links between pages of the graph are deduplicated into weighted edges
+    pub fn to_graphml(&self, ranks: &HashMap<String, f64>) -> String {
+        let mut xml = String::from(concat!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n",
+            "<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">\n",
+            "  <key id=\"url\" for=\"node\" attr.name=\"url\" attr.type=\"string\"/>\n",
+            "  <key id=\"domain\" for=\"node\" attr.name=\"domain\" attr.type=\"string\"/>\n",
+            "  <key id=\"language\" for=\"node\" attr.name=\"language\" attr.type=\"string\"/>\n",
+            "  <key id=\"pagerank\" for=\"node\" attr.name=\"pagerank\" attr.type=\"double\"/>\n",
+            "  <key id=\"weight\" for=\"edge\" attr.name=\"weight\" attr.type=\"int\"/>\n",
+            "  <graph id=\"links\" edgedefault=\"directed\">\n",
+        ));
+
+        let index_of: HashMap<&str, usize> = self.nodes.iter().enumerate().map(|(i, url)| (url.as_str(), i)).collect();
+        for (i, url) in self.nodes.iter().enumerate() {
+            xml.push_str(&format!(
+                "    <node id=\"n{}\"><data key=\"url\">{}</data><data key=\"domain\">{}</data><data key=\"language\">{}</data><data key=\"pagerank\">{}</data></node>\n",
+                i,
+                Self::escape_xml(url),
+                Self::escape_xml(&Self::domain_of(url)),
+                Self::escape_xml(self.languages.get(url).map(String::as_str).unwrap_or("")),
+                ranks.get(url).copied().unwrap_or(0.0),
+            ));
+        }
+
+        let mut weights: HashMap<(&str, &str), usize> = HashMap::new();
+        for url in &self.nodes {
+            for target in self.outbounds.get(url).into_iter().flatten() {
+                if index_of.contains_key(target.as_str()) {
+                    *weights.entry((url.as_str(), target.as_str())).or_insert(0) += 1;
+                }
+            }
+        }
+        for (i, (from, to)) in self.distinct_links().into_iter().enumerate() {
+            xml.push_str(&format!(
+                "    <edge id=\"e{}\" source=\"n{}\" target=\"n{}\"><data key=\"weight\">{}</data></edge>\n",
+                i,
+                index_of[from],
+                index_of[to],
+                weights[&(from, to)],
+            ));
+        }
+
+        xml.push_str("  </graph>\n</graphml>\n");
+        xml
+    }
+
+    // outbound links between pages of the graph, each (source, target) pair once, in node order
+    fn distinct_links(&self) -> Vec<(&str, &str)> {
+        let (_, adjacency) = self.adjacency_lists();
+        adjacency.iter()
+            .enumerate()
+            .flat_map(|(from, targets)| targets.iter().map(move |&to| (self.nodes[from].as_str(), self.nodes[to].as_str())))
+            .collect()
+    }
+
+    /// Row `i`, column `j` holds the number of links from `urls[i]` to `urls[j]`
+    pub fn to_adjacency_matrix(&self, urls: &[String]) -> Vec<Vec<f64>> {
+        let index: HashMap<&str, usize> = urls.iter()
+            .enumerate()
+            .map(|(i, url)| (url.as_str(), i))
+            .collect();
+
+        let mut matrix = vec![vec![0.0; urls.len()]; urls.len()];
+        for (i, url) in urls.iter().enumerate() {
+            for target in self.outbounds.get(url).into_iter().flatten() {
+                if let Some(&j) = index.get(target.as_str()) {
+                    matrix[i][j] += 1.0;
+                }
+            }
+        }
+        matrix
+    }
+
+    // subdomains count as the same site, so blog.example.com -> example.com is an internal link
+    fn domain_of(url: &str) -> String {
+        extract_domain_info(url)
+            .map(|info| info.registrable_domain)
+            .unwrap_or_else(|| url.to_string())
+    }
+
+    fn escape_dot(value: &str) -> String {
+        value.replace('\\', "\\\\").replace('"', "\\\"")
+    }
+
+    fn escape_cypher(value: &str) -> String {
+        value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+    }
+
+    fn escape_xml(value: &str) -> String {
+        value.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+    }
+
+    fn language_color(language: &str) -> &'static str {
+        match language {
+            "en" => "steelblue",
+            "hi" => "darkorange",
+            "mr" => "forestgreen",
+            "ta" => "firebrick",
+            "te" => "purple",
+            "kn" => "goldenrod",
+            "ml" => "teal",
+            _ => "gray",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn five_node_graph() -> (LinkGraph, HashMap<String, f64>) {
+        let mut graph = LinkGraph::new();
+        let pages = [
+            ("https://a.com/1", "en", 0.3),
+            ("https://a.com/2", "en", 0.2),
+            ("https://b.com/1", "hi", 0.25),
+            ("https://c.com/\"quoted\"", "ta", 0.15),
+            ("https://d.com/1", "", 0.1),
+        ];
+        let edges = [
+            ("https://a.com/1", "https://a.com/2"),
+            ("https://a.com/1", "https://b.com/1"),
+            ("https://a.com/1", "https://b.com/1"),
+            ("https://a.com/2", "https://b.com/1"),
+            ("https://b.com/1", "https://c.com/\"quoted\""),
+            ("https://c.com/\"quoted\"", "https://a.com/1"),
+            ("https://d.com/1", "https://a.com/1"),
+        ];
+
+        let mut ranks = HashMap::new();
+        for (url, language, rank) in pages {
+            graph.nodes.push(url.to_string());
+            graph.outbounds.entry(url.to_string()).or_default();
+            graph.inbounds.entry(url.to_string()).or_default();
+            graph.languages.insert(url.to_string(), language.to_string());
+            ranks.insert(url.to_string(), rank);
+        }
+        for (from, to) in edges {
+            graph.outbounds.get_mut(from).unwrap().push(to.to_string());
+            graph.inbounds.get_mut(to).unwrap().push(from.to_string());
+        }
+        (graph, ranks)
+    }
+
+    /// Node ids declared in `dot` after checking its statements are well formed
+    fn parse_dot(dot: &str) -> (Vec<String>, Vec<(String, String)>) {
+        let lines: Vec<&str> = dot.lines().collect();
+        assert_eq!(lines.first(), Some(&"digraph links {"));
+        assert_eq!(lines.last(), Some(&"}"));
+
+        let quoted = |s: &str| -> Vec<String> {
+            let mut ids = Vec::new();
+            let mut chars = s.chars();
+            while let Some(c) = chars.next() {
+                if c != '"' {
+                    continue;
+                }
+                let mut id = String::new();
+                loop {
+                    match chars.next().expect("unterminated string") {
+                        '\\' => id.push(chars.next().unwrap()),
+                        '"' => break,
+                        c => id.push(c),
+                    }
+                }
+                ids.push(id);
+            }
+            ids
+        };
+
+        let mut nodes = Vec::new();
+        let mut edges = Vec::new();
+        for line in &lines[1..lines.len() - 1] {
+            let line = line.trim();
+            assert!(line.ends_with("];"), "unterminated statement: {}", line);
+            if line.starts_with("node ") {
+                continue;
+            }
+            let ids = quoted(line);
+            if line.contains("\" -> \"") {
+                edges.push((ids[0].clone(), ids[1].clone()));
+            } else {
+                nodes.push(ids[0].clone());
+            }
+        }
+        for (from, to) in &edges {
+            assert!(nodes.contains(from) && nodes.contains(to), "edge to undeclared node");
+        }
+        (nodes, edges)
+    }
+
+    #[test]
+    fn test_to_dot_five_node_graph() {
+        let (graph, ranks) = five_node_graph();
+        let dot = graph.to_dot(10, 0.0, &ranks);
+
+        let (nodes, edges) = parse_dot(&dot);
+        assert_eq!(nodes.len(), 5);
+        assert_eq!(nodes[0], "https://a.com/1");
+        assert!(nodes.contains(&"https://c.com/\"quoted\"".to_string()));
+
+        // a.com/1 -> a.com/2 stays inside the domain, the duplicate link to b.com is one edge
+        assert_eq!(edges.len(), 5);
+        assert!(!edges.contains(&("https://a.com/1".to_string(), "https://a.com/2".to_string())));
+        assert!(dot.contains("\"https://a.com/1\" -> \"https://b.com/1\" [weight=2"));
+        assert!(dot.contains("label=\"a.com\", tooltip=\"https://a.com/1\", fontsize=32.0, color=\"steelblue\", fillcolor=\"steelblue\", internal_links=1"));
+        assert!(dot.contains("label=\"b.com\", tooltip=\"https://b.com/1\", fontsize=28.0, color=\"darkorange\""));
+        assert!(dot.contains("label=\"d.com\", tooltip=\"https://d.com/1\", fontsize=16.0, color=\"gray\""));
+    }
+
+    #[test]
+    fn test_to_dot_limits_nodes() {
+        let (graph, ranks) = five_node_graph();
+
+        let (nodes, edges) = parse_dot(&graph.to_dot(3, 0.0, &ranks));
+        assert_eq!(nodes, vec!["https://a.com/1", "https://b.com/1", "https://a.com/2"]);
+        assert_eq!(edges.len(), 2);
+
+        let (nodes, _) = parse_dot(&graph.to_dot(10, 0.25, &ranks));
+        assert_eq!(nodes, vec!["https://a.com/1", "https://b.com/1"]);
+    }
+
+    #[test]
+    fn test_to_cypher_statements() {
+        let (graph, ranks) = five_node_graph();
+        let statements = graph.to_cypher_statements(4, &ranks);
+
+        // the index, two batches of the five pages, two of the six distinct links
+        // (the duplicate a.com/1 -> b.com/1 becomes one)
+        assert_eq!(statements.len(), 5);
+        assert!(statements.iter().all(|statement| statement.ends_with(";\n") && statement.matches(';').count() == 1));
+        assert_eq!(statements[0], "CREATE INDEX page_url IF NOT EXISTS FOR (p:Page) ON (p.url);\n");
+
+        assert!(statements[1].starts_with(
+            "UNWIND [{url: \"https://a.com/1\", domain: \"a.com\", pagerank: 0.3}, "
+        ));
+        assert!(statements[1].ends_with("] AS row CREATE (:Page {url: row.url, domain: row.domain, pagerank: row.pagerank});\n"));
+        assert_eq!(statements[1].matches("{url: \"").count(), 4);
+        assert_eq!(statements[2].matches("{url: \"").count(), 1);
+        assert!(statements[1..3].concat().contains("{url: \"https://c.com/\\\"quoted\\\"\", domain: \"c.com\", pagerank: 0.15}"));
+
+        assert!(statements[3].starts_with("UNWIND [{from: \"https://a.com/1\", to: \"https://a.com/2\"}, "));
+        assert!(statements[3..].iter().all(|statement| {
+            statement.ends_with("] AS row MATCH (a:Page {url: row.from}), (b:Page {url: row.to}) CREATE (a)-[:LINKS_TO]->(b);\n")
+        }));
+        assert_eq!(statements[3].matches("{from: ").count(), 4);
+        assert_eq!(statements[4].matches("{from: ").count(), 2);
+
+        assert_eq!(graph.to_cypher_statements(0, &ranks).len(), 12);
+        assert!(LinkGraph::new().to_cypher_statements(100, &ranks).is_empty());
+    }
+
+    #[test]
+    fn test_to_graphml() {
+        let (graph, ranks) = five_node_graph();
+        let xml = graph.to_graphml(&ranks);
+
+        assert!(xml.starts_with("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<graphml"));
+        assert!(xml.ends_with("  </graph>\n</graphml>\n"));
+        assert_eq!(xml.matches("<node ").count(), 5);
+        assert_eq!(xml.matches("<edge ").count(), 6);
+        assert!(xml.contains(
+            "<node id=\"n0\"><data key=\"url\">https://a.com/1</data><data key=\"domain\">a.com</data><data key=\"language\">en</data><data key=\"pagerank\">0.3</data></node>"
+        ));
+        assert!(xml.contains("<data key=\"url\">https://c.com/&quot;quoted&quot;</data>"));
+        assert!(xml.contains("source=\"n0\" target=\"n2\"><data key=\"weight\">2</data>"));
+    }
+
+    #[test]
+    fn test_to_adjacency_matrix() {
+        let (graph, _) = five_node_graph();
+        let urls = vec![
+            "https://a.com/1".to_string(),
+            "https://b.com/1".to_string(),
+            "https://unknown.com/".to_string(),
+        ];
+
+        let matrix = graph.to_adjacency_matrix(&urls);
+        assert_eq!(matrix, vec![
+            vec![0.0, 2.0, 0.0],
+            vec![0.0, 0.0, 0.0],
+            vec![0.0, 0.0, 0.0],
+        ]);
+    }
+
+    fn graph(nodes: &[&str], edges: &[(&str, &str)]) -> LinkGraph {
+        let mut graph = LinkGraph::new();
+        for url in nodes {
+            graph.nodes.push(url.to_string());
+            graph.outbounds.entry(url.to_string()).or_default();
+            graph.inbounds.entry(url.to_string()).or_default();
+        }
+        for (from, to) in edges {
+            graph.outbounds.entry(from.to_string()).or_default().push(to.to_string());
+            graph.inbounds.entry(to.to_string()).or_default().push(from.to_string());
+        }
+        graph
+    }
+
+    #[test]
+    fn test_strongly_connected_components() {
+        // cycle a->b->c->a feeding the cycle d<->e, f only links in, g is isolated
+        let graph = graph(
+            &["a", "b", "c", "d", "e", "f", "g"],
+            &[("a", "b"), ("b", "c"), ("c", "a"), ("c", "d"), ("d", "e"), ("e", "d"), ("f", "a"), ("e", "https://external.com/")],
+        );
+
+        assert_eq!(graph.strongly_connected_components(), vec![
+            vec!["a".to_string(), "b".to_string(), "c".to_string()],
+            vec!["d".to_string(), "e".to_string()],
+            vec!["f".to_string()],
+            vec!["g".to_string()],
+        ]);
+        assert!((graph.largest_scc_fraction() - 3.0 / 7.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_connected_components_ignore_direction() {
+        let graph = graph(
+            &["a", "b", "c", "d", "e", "f", "g"],
+            &[("a", "b"), ("c", "b"), ("d", "e"), ("f", "e"), ("f", "d")],
+        );
+
+        assert_eq!(graph.connected_components(), vec![
+            vec!["a".to_string(), "b".to_string(), "c".to_string()],
+            vec!["d".to_string(), "e".to_string(), "f".to_string()],
+            vec!["g".to_string()],
+        ]);
+        // no cycles, every page is its own strongly connected component
+        assert_eq!(graph.strongly_connected_components().len(), 7);
+        assert_eq!(LinkGraph::new().largest_scc_fraction(), 0.0);
+    }
+
+    #[test]
+    fn test_subgraph_keeps_domain_pages_and_links_between_them() {
+        let (five_nodes, _) = five_node_graph();
+        let mut sub = five_nodes.subgraph(&["a.com".to_string(), "b.com".to_string()]);
+
+        assert_eq!(sub.nodes, vec!["https://a.com/1", "https://a.com/2", "https://b.com/1"]);
+        // b.com/1 -> c.com and d.com/1 -> a.com/1 leave the subgraph
+        assert!(sub.outbounds["https://b.com/1"].is_empty());
+        assert_eq!(sub.inbounds["https://a.com/1"], Vec::<String>::new());
+        assert_eq!(sub.outbounds["https://a.com/1"].len(), 3);
+        assert_eq!(sub.languages.len(), 3);
+
+        let with_subdomain = graph(&["https://blog.a.com/1", "https://nota.com/1"], &[]);
+        sub = with_subdomain.subgraph(&["a.com".to_string()]);
+        assert_eq!(sub.nodes, vec!["https://blog.a.com/1"]);
+    }
+
+    #[test]
+    fn test_ego_network_follows_links_both_ways() {
+        let chain = graph(
+            &["a", "b", "c", "d", "e"],
+            &[("a", "b"), ("c", "b"), ("c", "d"), ("d", "e"), ("e", "https://external.com/")],
+        );
+
+        let mut ego = chain.ego_network("b", 1);
+        ego.nodes.sort();
+        assert_eq!(ego.nodes, vec!["a", "b", "c"]);
+        assert_eq!(ego.outbounds["c"], vec!["b".to_string()]);
+
+        assert_eq!(chain.ego_network("b", 2).node_count(), 4);
+        assert_eq!(chain.ego_network("b", 10).node_count(), 5);
+        assert_eq!(chain.ego_network("b", 0).nodes, vec!["b"]);
+        assert_eq!(chain.ego_network("missing", 3).node_count(), 0);
+    }
+}
@@ -0,0 +1,174 @@
+use std::collections::HashMap;
+use super::graph::LinkGraph;
+use tracing::{info, debug};
+
+// Hub and authority score for every node in the graph
+#[derive(Debug, Clone, Default)]
+pub struct HitsResult {
+    pub hub_scores: HashMap<String, f64>,
+    pub authority_scores: HashMap<String, f64>,
+}
+
+impl HitsResult {
+    // top n pages by authority score
+    pub fn top_authorities(&self, n: usize) -> Vec<(String, f64)> {
+        Self::top(&self.authority_scores, n)
+    }
+
+    // top n pages by hub score
+    pub fn top_hubs(&self, n: usize) -> Vec<(String, f64)> {
+        Self::top(&self.hub_scores, n)
+    }
+
+    fn top(scores: &HashMap<String, f64>, n: usize) -> Vec<(String, f64)> {
+        let mut sorted: Vec<(String, f64)> = scores.iter()
+            .map(|(url, score)| (url.clone(), *score))
+            .collect();
+
+        sorted.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+        sorted.truncate(n);
+        sorted
+    }
+}
+
+// Kleinberg's Hyperlink-Induced Topic Search
+pub struct HitsCalculator {
+    convergence_threshold: f64,
+}
+
+impl HitsCalculator {
+    pub fn new() -> Self {
+        Self {
+            convergence_threshold: 0.0001,
+        }
+    }
+
+    pub fn calculate(&self, graph: &LinkGraph, iterations: usize) -> HitsResult {
+        if graph.node_count() == 0 {
+            return HitsResult::default();
+        }
+
+        info!("Calculating HITS scores for {} nodes", graph.node_count());
+
+        let mut hubs: HashMap<String, f64> = graph.nodes.iter()
+            .map(|url| (url.clone(), 1.0))
+            .collect();
+        let mut authorities = hubs.clone();
+
+        for iteration in 0..iterations {
+            // authority(v) = sum of hub(u) for all u linking to v
+            let mut new_authorities: HashMap<String, f64> = graph.nodes.iter()
+                .map(|url| {
+                    let score = graph.inbounds.get(url)
+                        .map(|sources| sources.iter().map(|u| hubs.get(u).unwrap_or(&0.0)).sum())
+                        .unwrap_or(0.0);
+                    (url.clone(), score)
+                })
+                .collect();
+            Self::normalize(&mut new_authorities);
+
+            // hub(u) = sum of authority(v) for all v linked from u
+            let mut new_hubs: HashMap<String, f64> = graph.nodes.iter()
+                .map(|url| {
+                    let score = graph.outbounds.get(url)
+                        .map(|targets| targets.iter().map(|v| new_authorities.get(v).unwrap_or(&0.0)).sum())
+                        .unwrap_or(0.0);
+                    (url.clone(), score)
+                })
+                .collect();
+            Self::normalize(&mut new_hubs);
+
+            let diff = Self::total_diff(&authorities, &new_authorities) + Self::total_diff(&hubs, &new_hubs);
+            authorities = new_authorities;
+            hubs = new_hubs;
+
+            debug!("iterations : {} : diff = {:.6}", iteration + 1, diff);
+
+            if diff < self.convergence_threshold {
+                info!("HITS converged at iteration {}", iteration + 1);
+                break;
+            }
+        }
+
+        HitsResult {
+            hub_scores: hubs,
+            authority_scores: authorities,
+        }
+    }
+
+    // scale scores to unit euclidean length
+    fn normalize(scores: &mut HashMap<String, f64>) {
+        let norm = scores.values().map(|s| s * s).sum::<f64>().sqrt();
+        if norm > 0.0 {
+            for score in scores.values_mut() {
+                *score /= norm;
+            }
+        }
+    }
+
+    fn total_diff(old: &HashMap<String, f64>, new: &HashMap<String, f64>) -> f64 {
+        new.iter()
+            .map(|(url, score)| (score - old.get(url).unwrap_or(&0.0)).abs())
+            .sum()
+    }
+}
+
+impl Default for HitsCalculator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn star_graph() -> LinkGraph {
+        // hub1 and hub2 both link to auth1 and auth2, hub1 also links to auth3
+        let mut graph = LinkGraph::new();
+        let edges = [
+            ("hub1", "auth1"), ("hub1", "auth2"), ("hub1", "auth3"),
+            ("hub2", "auth1"), ("hub2", "auth2"),
+        ];
+
+        for node in ["hub1", "hub2", "auth1", "auth2", "auth3"] {
+            graph.nodes.push(node.to_string());
+            graph.outbounds.entry(node.to_string()).or_default();
+            graph.inbounds.entry(node.to_string()).or_default();
+        }
+        for (from, to) in edges {
+            graph.outbounds.get_mut(from).unwrap().push(to.to_string());
+            graph.inbounds.get_mut(to).unwrap().push(from.to_string());
+        }
+        graph
+    }
+
+    #[test]
+    fn test_hits_hubs_and_authorities() {
+        let result = HitsCalculator::new().calculate(&star_graph(), 50);
+
+        let auth = &result.authority_scores;
+        let hub = &result.hub_scores;
+
+        // Pages only linked to have no hub value, pages only linking have no authority
+        assert_eq!(hub["auth1"], 0.0);
+        assert_eq!(auth["hub1"], 0.0);
+
+        assert!(auth["auth1"] > auth["auth3"]);
+        assert!((auth["auth1"] - auth["auth2"]).abs() < 1e-9);
+        assert!(hub["hub1"] > hub["hub2"]);
+
+        // Scores are normalized to unit length
+        let norm: f64 = auth.values().map(|s| s * s).sum::<f64>().sqrt();
+        assert!((norm - 1.0).abs() < 1e-9);
+
+        assert_eq!(result.top_hubs(1)[0].0, "hub1");
+    }
+
+    #[test]
+    fn test_hits_empty_graph() {
+        let result = HitsCalculator::new().calculate(&LinkGraph::new(), 10);
+        assert!(result.hub_scores.is_empty());
+        assert!(result.authority_scores.is_empty());
+    }
+}
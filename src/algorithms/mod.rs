@@ -1,9 +1,17 @@
+mod content_similarity;
 mod graph;
+mod hits;
 mod pagerank;
+mod quality;
+mod simhash;
 mod tests;
 mod tfidf;
 
-pub use pagerank::PageRankCalculator;
+pub use pagerank::{ConvergenceInfo, PageRankBuilder, PageRankCalculator, Teleportation};
 pub use graph::LinkGraph;
+pub use hits::{HitsCalculator, HitsResult};
+pub use quality::QualityRescorer;
 pub use tfidf::TfIdfCalculator;
+pub use simhash::SimHashCalculator;
+pub use content_similarity::ContentCluster;
 pub use tfidf::{TfIdfStats};
\ No newline at end of file
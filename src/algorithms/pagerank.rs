@@ -6,24 +6,120 @@ pub struct PageRankCalculator{
     damping_factor: f64,
     iterations: usize,
     convergence_threshold: f64,
+    teleportation: Teleportation,
+}
+
+/// Where the random surfer jumps to instead of following a link
+#[derive(Debug, Clone, PartialEq)]
+pub enum Teleportation {
+    /// Every page equally
+    Uniform,
+    /// Only these pages, seeds missing from the graph are ignored
+    Personalized(Vec<String>),
+}
+
+/// How a power iteration run ended
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ConvergenceInfo {
+    pub iterations_run: usize,
+    /// Sum of the absolute rank changes in the last iteration
+    pub final_delta: f64,
+    /// `final_delta` dropped below the convergence threshold before the iteration limit
+    pub converged: bool,
 }
 
 impl PageRankCalculator{
     pub fn new() -> Self{
-        Self{
-            damping_factor: 0.85,
-            iterations: 30,
-            convergence_threshold: 0.0001,
-        }
+        Self::builder().build()
+    }
+
+    pub fn builder() -> PageRankBuilder {
+        PageRankBuilder::default()
     }
 
     pub fn calculate(&self, graph: &LinkGraph) -> HashMap<String, f64> {
-        let n = graph.node_count() as f64;
-        if n == 0.0 {
+        self.calculate_with_convergence_info(graph).0
+    }
+
+    /// Ranks with the configured teleportation, plus how the iteration converged
+    pub fn calculate_with_convergence_info(&self, graph: &LinkGraph) -> (HashMap<String, f64>, ConvergenceInfo) {
+        if graph.node_count() == 0 {
+            return (HashMap::new(), ConvergenceInfo { iterations_run: 0, final_delta: 0.0, converged: true });
+        }
+
+        let teleport = match &self.teleportation {
+            Teleportation::Uniform => None,
+            Teleportation::Personalized(seeds) => Self::personalized_teleport(graph, seeds, 1.0),
+        };
+
+        match teleport {
+            Some(teleport) => self.iterate(graph, &teleport),
+            None => {
+                info!("Calculating page rank for {} nodes", graph.node_count());
+                self.iterate(graph, &Self::uniform_teleport(graph))
+            }
+        }
+    }
+
+    /// PageRank biased towards `seed_urls`: with `personalization_weight` 1.0 the random
+    /// surfer only teleports back to the seeds, with 0.0 this is plain PageRank.
+    /// Seeds missing from the graph are ignored.
+    pub fn calculate_personalized(
+        &self,
+        graph: &LinkGraph,
+        seed_urls: &[String],
+        personalization_weight: f64,
+    ) -> HashMap<String, f64> {
+        if graph.node_count() == 0 {
             return HashMap::new();
         }
 
-        info!("Calculating page rank for {} nodes", n);
+        match Self::personalized_teleport(graph, seed_urls, personalization_weight) {
+            Some(teleport) => self.iterate(graph, &teleport).0,
+            None => self.iterate(graph, &Self::uniform_teleport(graph)).0,
+        }
+    }
+
+    // uniform teleportation: (1-d)/N to every page
+    fn uniform_teleport(graph: &LinkGraph) -> HashMap<String, f64> {
+        let n = graph.node_count() as f64;
+        graph.nodes
+            .iter()
+            .map(|url| (url.clone(), 1.0 / n))
+            .collect()
+    }
+
+    // `weight` of the teleportation split over the seeds in the graph, the rest spread evenly.
+    // None when no seed is in the graph.
+    fn personalized_teleport(graph: &LinkGraph, seed_urls: &[String], weight: f64) -> Option<HashMap<String, f64>> {
+        let n = graph.node_count() as f64;
+        let seeds: Vec<&String> = seed_urls.iter()
+            .filter(|url| graph.nodes.contains(url))
+            .collect();
+
+        if seeds.is_empty() {
+            info!("None of the {} personalization seeds are in the graph, using global PageRank", seed_urls.len());
+            return None;
+        }
+
+        info!("Calculating personalized page rank for {} nodes from {} seeds", n, seeds.len());
+
+        let weight = weight.clamp(0.0, 1.0);
+        let mut teleport: HashMap<String, f64> = graph.nodes
+            .iter()
+            .map(|url| (url.clone(), (1.0 - weight) / n))
+            .collect();
+
+        let seed_share = weight / seeds.len() as f64;
+        for seed in seeds {
+            *teleport.entry(seed.clone()).or_insert(0.0) += seed_share;
+        }
+        Some(teleport)
+    }
+
+    // Power iteration where each page receives (1-d) * teleport[page] plus its damped inbound rank
+    fn iterate(&self, graph: &LinkGraph, teleport: &HashMap<String, f64>) -> (HashMap<String, f64>, ConvergenceInfo) {
+        let n = graph.node_count() as f64;
 
         // initialize all pages with equal rank
         let initial_rank = 1.0 / n;
@@ -32,6 +128,7 @@ impl PageRankCalculator{
             .iter()
             .map(|url| (url.clone(), initial_rank))
             .collect();
+        let mut convergence = ConvergenceInfo { iterations_run: 0, final_delta: 0.0, converged: false };
 
         // iterative calculation
         for iteration in 0..self.iterations {
@@ -54,7 +151,8 @@ impl PageRankCalculator{
                 }
 
                 // apply damping factor
-                let new_rank = (1.0 - self.damping_factor) / n + self.damping_factor * rank_sum;
+                let teleport_share = teleport.get(url).copied().unwrap_or(0.0);
+                let new_rank = (1.0 - self.damping_factor) * teleport_share + self.damping_factor * rank_sum;
 
                 // Track convergence
                 let old_rank = ranks.get(url).unwrap_or(&initial_rank);
@@ -63,12 +161,15 @@ impl PageRankCalculator{
                 new_ranks.insert(url.clone(), new_rank);
             }
             ranks = new_ranks;
+            convergence.iterations_run = iteration + 1;
+            convergence.final_delta = total_diff;
 
             debug!("iterations : {} : diff = {:.6}", iteration+1, total_diff);
 
             // check convergence
             if total_diff < self.convergence_threshold {
                 info!("Pagerank converged at iteration {}", iteration+1);
+                convergence.converged = true;
                 break;
             }
         }
@@ -81,7 +182,7 @@ impl PageRankCalculator{
                 *rank /= sum;
             }
         }
-        ranks
+        (ranks, convergence)
     }
 
     pub fn get_top_pages(&self, ranks: &HashMap<String, f64>, limit: usize) -> Vec<(String, f64)> {
@@ -92,4 +193,63 @@ impl PageRankCalculator{
         ranked.sort_by(|a,b| b.1.partial_cmp(&a.1).unwrap());
         ranked.into_iter().take(limit).collect()
     }
-}
\ No newline at end of file
+}
+
+/// Builds a `PageRankCalculator`, defaults to damping 0.85, 30 iterations,
+/// a 0.0001 convergence threshold and uniform teleportation
+pub struct PageRankBuilder {
+    damping_factor: f64,
+    max_iterations: usize,
+    convergence_threshold: f64,
+    teleportation: Teleportation,
+}
+
+impl Default for PageRankBuilder {
+    fn default() -> Self {
+        Self {
+            damping_factor: 0.85,
+            max_iterations: 30,
+            convergence_threshold: 0.0001,
+            teleportation: Teleportation::Uniform,
+        }
+    }
+}
+
+impl PageRankBuilder {
+    /// Probability of following a link rather than teleporting, clamped to 0.0-1.0
+    pub fn with_damping_factor(mut self, damping_factor: f64) -> Self {
+        self.damping_factor = damping_factor.clamp(0.0, 1.0);
+        self
+    }
+
+    pub fn with_max_iterations(mut self, max_iterations: usize) -> Self {
+        self.max_iterations = max_iterations;
+        self
+    }
+
+    /// Stop once the ranks change by less than this in total
+    pub fn with_convergence_threshold(mut self, convergence_threshold: f64) -> Self {
+        self.convergence_threshold = convergence_threshold;
+        self
+    }
+
+    pub fn with_teleportation_uniform(mut self) -> Self {
+        self.teleportation = Teleportation::Uniform;
+        self
+    }
+
+    /// Teleport only to `seeds`, uniform when none of them are in the graph
+    pub fn with_teleportation_personalized(mut self, seeds: Vec<String>) -> Self {
+        self.teleportation = Teleportation::Personalized(seeds);
+        self
+    }
+
+    pub fn build(self) -> PageRankCalculator {
+        PageRankCalculator {
+            damping_factor: self.damping_factor,
+            iterations: self.max_iterations,
+            convergence_threshold: self.convergence_threshold,
+            teleportation: self.teleportation,
+        }
+    }
+}
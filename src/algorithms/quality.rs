@@ -0,0 +1,71 @@
+use crate::core::{OpenGraphData, PageProcessor};
+use crate::storage::models::{PageFilter, StoredPage};
+use crate::storage::repository::PageRepository;
+use scraper::Html;
+use tracing::{debug, info};
+
+// pages scored and written back per round trip
+const RESCORE_BATCH_SIZE: usize = 1000;
+
+// scores closer than this to the stored one are not written back
+const SCORE_EPSILON: f64 = 1e-9;
+
+// Recomputes the quality score of stored pages, e.g. after the scoring formula changed
+pub struct QualityRescorer {
+    processor: PageProcessor,
+}
+
+impl QualityRescorer {
+    pub fn new(processor: PageProcessor) -> Self {
+        Self { processor }
+    }
+
+    /// Quality of a stored page by the current formula. Only the extracted text is stored,
+    /// so the markup factors (headings, links, code blocks) score it as a page without any
+    pub fn score_page(&self, page: &StoredPage) -> f64 {
+        let escaped = page.content.replace('&', "&amp;").replace('<', "&lt;");
+        let document = Html::parse_document(&format!("<body>{}</body>", escaped));
+        let open_graph = OpenGraphData {
+            image: page.og_image.clone(),
+            og_type: page.og_type.clone(),
+            ..OpenGraphData::default()
+        };
+
+        let (score, _) = self.processor.calculate_content_quality(
+            &document,
+            &page.content,
+            &page.title,
+            &page.description,
+            &open_graph,
+        );
+        score
+    }
+
+    /// Rescore every stored page in batches, returns how many scores changed
+    pub async fn rescore_all(&self, repo: &PageRepository) -> crate::Result<u64> {
+        let mut filter = PageFilter::new().with_limit(RESCORE_BATCH_SIZE).with_cursor(0);
+        let mut scored = 0usize;
+        let mut updated = 0u64;
+
+        loop {
+            let batch = repo.get_pages(&filter).await?;
+            scored += batch.items.len();
+
+            let updates: Vec<(String, f64)> = batch.items.iter()
+                .map(|page| (page, self.score_page(page)))
+                .filter(|(page, score)| (page.quality_score - score).abs() > SCORE_EPSILON)
+                .map(|(page, score)| (page.url_hash.clone(), score))
+                .collect();
+            updated += repo.update_quality_scores_batch(&updates).await?;
+            debug!("Rescored {} pages, {} changed so far", scored, updated);
+
+            match batch.next_cursor {
+                Some(cursor) if batch.has_more => filter = filter.with_cursor(cursor),
+                _ => break,
+            }
+        }
+
+        info!("Rescored {} pages, {} quality scores changed", scored, updated);
+        Ok(updated)
+    }
+}
@@ -0,0 +1,111 @@
+use std::collections::HashMap;
+
+// 64-bit SimHash fingerprints for near-duplicate detection
+pub struct SimHashCalculator;
+
+impl SimHashCalculator {
+    // Fingerprint content from its term-frequency weighted tokens
+    pub fn calculate(content: &str) -> u64 {
+        let mut term_freqs: HashMap<String, u32> = HashMap::new();
+        for token in Self::tokenize(content) {
+            *term_freqs.entry(token).or_insert(0) += 1;
+        }
+
+        if term_freqs.is_empty() {
+            return 0;
+        }
+
+        let mut weights = [0i64; 64];
+        for (token, freq) in &term_freqs {
+            let hash = Self::fnv1a(token);
+            for (bit, weight) in weights.iter_mut().enumerate() {
+                if hash & (1u64 << bit) != 0 {
+                    *weight += *freq as i64;
+                } else {
+                    *weight -= *freq as i64;
+                }
+            }
+        }
+
+        weights.iter()
+            .enumerate()
+            .filter(|(_, w)| **w > 0)
+            .fold(0u64, |acc, (bit, _)| acc | (1u64 << bit))
+    }
+
+    // Number of differing bits
+    pub fn hamming_distance(a: u64, b: u64) -> u32 {
+        (a ^ b).count_ones()
+    }
+
+    // True when the fingerprints differ by fewer than `threshold` bits
+    pub fn are_near_duplicates(a: u64, b: u64, threshold: u32) -> bool {
+        Self::hamming_distance(a, b) < threshold
+    }
+
+    // Split a fingerprint into four 16-bit bands, highest bits first.
+    // Two fingerprints within 3 bits of each other always share at least one band.
+    pub fn bands(hash: u64) -> [u16; 4] {
+        [
+            (hash >> 48) as u16,
+            (hash >> 32) as u16,
+            (hash >> 16) as u16,
+            hash as u16,
+        ]
+    }
+
+    fn tokenize(content: &str) -> impl Iterator<Item = String> + '_ {
+        content
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|t| t.len() > 1)
+            .map(|t| t.to_lowercase())
+    }
+
+    // FNV-1a is stable across runs and platforms, unlike DefaultHasher
+    fn fnv1a(token: &str) -> u64 {
+        const OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+        const PRIME: u64 = 0x100000001b3;
+
+        token.bytes().fold(OFFSET_BASIS, |hash, byte| {
+            (hash ^ byte as u64).wrapping_mul(PRIME)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_simhash_identical_content() {
+        let a = SimHashCalculator::calculate("The quick brown fox jumps over the lazy dog");
+        let b = SimHashCalculator::calculate("the quick brown fox jumps over the lazy dog");
+        assert_eq!(a, b);
+        assert_eq!(SimHashCalculator::hamming_distance(a, b), 0);
+    }
+
+    #[test]
+    fn test_simhash_near_duplicates() {
+        let base = "Rust is a systems programming language focused on safety, speed and concurrency. \
+                    It accomplishes these goals without a garbage collector, making it useful for \
+                    embedding in other languages and writing low level code like device drivers.";
+        let print_view = format!("{} Print this page.", base);
+        let unrelated = "Recipes for sourdough bread need flour, water, salt and a lively starter \
+                         that has been fed for several days before baking.";
+
+        let a = SimHashCalculator::calculate(base);
+        let b = SimHashCalculator::calculate(&print_view);
+        let c = SimHashCalculator::calculate(unrelated);
+
+        assert!(SimHashCalculator::hamming_distance(a, b) < SimHashCalculator::hamming_distance(a, c));
+        assert!(SimHashCalculator::are_near_duplicates(a, b, 16));
+        assert!(!SimHashCalculator::are_near_duplicates(a, c, 16));
+    }
+
+    #[test]
+    fn test_simhash_bands() {
+        let bands = SimHashCalculator::bands(0x0001_0002_0003_0004);
+        assert_eq!(bands, [1, 2, 3, 4]);
+        assert_eq!(SimHashCalculator::calculate(""), 0);
+    }
+}
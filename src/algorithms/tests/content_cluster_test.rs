@@ -0,0 +1,59 @@
+//! Tests for k-means topic clustering
+
+#[cfg(test)]
+mod content_cluster_tests {
+    use crate::algorithms::{ContentCluster, TfIdfCalculator};
+
+    // Three pages about rust programming and three about baking
+    fn two_topic_corpus() -> TfIdfCalculator {
+        let docs = [
+            ("rust-1", "rust compiler borrow checker ownership lifetimes"),
+            ("rust-2", "rust ownership borrow checker traits generics"),
+            ("rust-3", "rust compiler traits lifetimes cargo crates"),
+            ("bake-1", "bread flour yeast oven dough baking"),
+            ("bake-2", "sourdough bread yeast starter flour oven"),
+            ("bake-3", "cake flour sugar butter oven baking"),
+        ];
+
+        let mut tfidf = TfIdfCalculator::new();
+        tfidf.build_from_corpus(&docs.map(|(id, content)| (id.to_string(), content.to_string())));
+        tfidf
+    }
+
+    #[test]
+    fn test_cluster_pages_separates_topics() {
+        let tfidf = two_topic_corpus();
+        let clusters = ContentCluster::cluster_pages(&tfidf, 2);
+
+        assert_eq!(clusters, vec![
+            vec!["bake-1".to_string(), "bake-2".to_string(), "bake-3".to_string()],
+            vec!["rust-1".to_string(), "rust-2".to_string(), "rust-3".to_string()],
+        ]);
+    }
+
+    #[test]
+    fn test_cluster_pages_edge_cases() {
+        let tfidf = two_topic_corpus();
+        assert!(ContentCluster::cluster_pages(&tfidf, 0).is_empty());
+        assert!(ContentCluster::cluster_pages(&TfIdfCalculator::new(), 3).is_empty());
+
+        // more clusters than documents puts every document on its own
+        let clusters = ContentCluster::cluster_pages(&tfidf, 10);
+        assert_eq!(clusters.len(), 6);
+        assert!(clusters.iter().all(|cluster| cluster.len() == 1));
+    }
+
+    #[test]
+    fn test_label_cluster() {
+        let tfidf = two_topic_corpus();
+        let rust_pages: Vec<String> = ["rust-1", "rust-2", "rust-3"].map(String::from).to_vec();
+
+        let labels = ContentCluster::label_cluster(&rust_pages, &tfidf);
+        assert_eq!(labels.len(), 3);
+        // terms shared by the cluster outrank those of a single page
+        assert!(labels.iter().all(|label| tfidf.document_vector("rust-1").contains_key(label)
+            || tfidf.document_vector("rust-2").contains_key(label)));
+        assert!(labels.contains(&"borrow".to_string()));
+        assert!(ContentCluster::label_cluster(&[], &tfidf).is_empty());
+    }
+}
@@ -1 +1,5 @@
-mod pagerank_integration_test;
\ No newline at end of file
+mod pagerank_integration_test;
+mod personalized_pagerank_test;
+mod pagerank_builder_test;
+mod quality_rescorer_test;
+mod content_cluster_test;
@@ -0,0 +1,84 @@
+//! Tests for configuring PageRank through the builder
+
+#[cfg(test)]
+mod builder_tests {
+    use crate::algorithms::{LinkGraph, PageRankCalculator};
+
+    // a -> b -> c -> a with d linking into the cycle
+    fn cycle_graph() -> LinkGraph {
+        let mut graph = LinkGraph::new();
+        for node in ["a", "b", "c", "d"] {
+            graph.nodes.push(node.to_string());
+            graph.outbounds.entry(node.to_string()).or_default();
+            graph.inbounds.entry(node.to_string()).or_default();
+        }
+        for (from, to) in [("a", "b"), ("b", "c"), ("c", "a"), ("d", "a")] {
+            graph.outbounds.get_mut(from).unwrap().push(to.to_string());
+            graph.inbounds.get_mut(to).unwrap().push(from.to_string());
+        }
+        graph
+    }
+
+    #[test]
+    fn test_builder_defaults_match_new() {
+        let graph = cycle_graph();
+        let default = PageRankCalculator::new().calculate(&graph);
+        let built = PageRankCalculator::builder().build().calculate(&graph);
+
+        for (url, rank) in &default {
+            assert!((rank - built[url]).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_convergence_info() {
+        let graph = cycle_graph();
+
+        let (_, info) = PageRankCalculator::builder()
+            .with_max_iterations(200)
+            .with_convergence_threshold(1e-10)
+            .build()
+            .calculate_with_convergence_info(&graph);
+        assert!(info.converged);
+        assert!(info.final_delta < 1e-10);
+        assert!(info.iterations_run > 1 && info.iterations_run < 200);
+
+        let (ranks, info) = PageRankCalculator::builder()
+            .with_max_iterations(2)
+            .with_convergence_threshold(0.0)
+            .build()
+            .calculate_with_convergence_info(&graph);
+        assert!(!info.converged);
+        assert_eq!(info.iterations_run, 2);
+        assert!((ranks.values().sum::<f64>() - 1.0).abs() < 1e-9);
+
+        let (ranks, info) = PageRankCalculator::new().calculate_with_convergence_info(&LinkGraph::new());
+        assert!(ranks.is_empty());
+        assert_eq!(info.iterations_run, 0);
+    }
+
+    #[test]
+    fn test_damping_and_personalized_teleportation() {
+        let graph = cycle_graph();
+
+        // without damping every page only receives teleportation
+        let uniform = PageRankCalculator::builder().with_damping_factor(0.0).build().calculate(&graph);
+        assert!((uniform["a"] - 0.25).abs() < 1e-9);
+        assert!((uniform["d"] - 0.25).abs() < 1e-9);
+
+        // only d is linked to by no one, it keeps rank only as a teleport target
+        let global = PageRankCalculator::new().calculate(&graph);
+        let seeded = PageRankCalculator::builder()
+            .with_teleportation_personalized(vec!["d".to_string()])
+            .build()
+            .calculate(&graph);
+        assert!(seeded["d"] > global["d"]);
+
+        let reset = PageRankCalculator::builder()
+            .with_teleportation_personalized(vec!["d".to_string()])
+            .with_teleportation_uniform()
+            .build()
+            .calculate(&graph);
+        assert!((reset["d"] - global["d"]).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,67 @@
+//! Tests for seed-biased PageRank
+
+#[cfg(test)]
+mod personalized_tests {
+    use crate::algorithms::{LinkGraph, PageRankCalculator};
+
+    // Two disconnected clusters: rust-a <-> rust-b and cook-a <-> cook-b
+    fn two_topic_graph() -> LinkGraph {
+        let mut graph = LinkGraph::new();
+        let edges = [
+            ("rust-a", "rust-b"), ("rust-b", "rust-a"),
+            ("cook-a", "cook-b"), ("cook-b", "cook-a"),
+        ];
+
+        for node in ["rust-a", "rust-b", "cook-a", "cook-b"] {
+            graph.nodes.push(node.to_string());
+            graph.outbounds.entry(node.to_string()).or_default();
+            graph.inbounds.entry(node.to_string()).or_default();
+        }
+        for (from, to) in edges {
+            graph.outbounds.get_mut(from).unwrap().push(to.to_string());
+            graph.inbounds.get_mut(to).unwrap().push(from.to_string());
+        }
+        graph
+    }
+
+    #[test]
+    fn test_personalized_pagerank_favours_seed_topic() {
+        let graph = two_topic_graph();
+        let calculator = PageRankCalculator::new();
+
+        let global = calculator.calculate(&graph);
+        assert!((global["rust-b"] - global["cook-b"]).abs() < 1e-9);
+
+        let personalized = calculator.calculate_personalized(&graph, &["rust-a".to_string()], 1.0);
+        assert!(personalized["rust-b"] > personalized["cook-b"]);
+
+        // all teleportation goes to the rust cluster, the cooking cluster drains away
+        assert!(personalized["cook-a"] < global["cook-a"] / 2.0);
+
+        let sum: f64 = personalized.values().sum();
+        assert!((sum - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_personalized_pagerank_weight_and_unknown_seeds() {
+        let graph = two_topic_graph();
+        let calculator = PageRankCalculator::new();
+        let global = calculator.calculate(&graph);
+
+        // zero weight is plain PageRank
+        let unbiased = calculator.calculate_personalized(&graph, &["rust-a".to_string()], 0.0);
+        for (url, rank) in &global {
+            assert!((rank - unbiased[url]).abs() < 1e-9);
+        }
+
+        // seeds outside the graph fall back to global PageRank
+        let fallback = calculator.calculate_personalized(&graph, &["missing".to_string()], 1.0);
+        assert!((fallback["cook-a"] - global["cook-a"]).abs() < 1e-9);
+
+        // partial weight lies between the two
+        let half = calculator.calculate_personalized(&graph, &["rust-a".to_string()], 0.5);
+        let full = calculator.calculate_personalized(&graph, &["rust-a".to_string()], 1.0);
+        assert!(half["cook-a"] > full["cook-a"]);
+        assert!(half["cook-a"] < global["cook-a"]);
+    }
+}
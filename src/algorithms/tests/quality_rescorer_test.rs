@@ -0,0 +1,42 @@
+//! Tests for rescoring stored pages
+
+#[cfg(test)]
+mod rescorer_tests {
+    use crate::algorithms::QualityRescorer;
+    use crate::core::PageProcessor;
+    use crate::models::PageData;
+    use crate::storage::models::StoredPage;
+    use crate::storage::models::tests::page_data;
+
+    fn stored_page(content: &str, description: Option<&str>) -> StoredPage {
+        let page_data = PageData {
+            title: Some("An article about web crawlers".to_string()),
+            description: description.map(String::from),
+            content_quality_score: 0.0,
+            ..page_data("https://example.com/article", content)
+        };
+        StoredPage::from_page_data(&page_data, "hash".to_string(), "content_hash".to_string())
+    }
+
+    #[test]
+    fn test_score_stored_page() {
+        let rescorer = QualityRescorer::new(PageProcessor::new());
+        let text = (0..400).map(|i| format!("word{}", i % 150)).collect::<Vec<_>>().join(" ");
+
+        let short = rescorer.score_page(&stored_page("too short", None));
+        let long = rescorer.score_page(&stored_page(&text, Some("A long description of what this article covers in detail")));
+        assert!(long > short, "{} should beat {}", long, short);
+        assert!((0.0..=1.0).contains(&long));
+
+        // Open Graph tags survive in the database and still earn their boost
+        let mut with_og = stored_page(&text, None);
+        let without_og = rescorer.score_page(&with_og);
+        with_og.og_type = Some("article".to_string());
+        assert!(rescorer.score_page(&with_og) > without_og);
+
+        // stored text that looks like markup is not parsed as markup
+        let code = rescorer.score_page(&stored_page("see <code>main()</code> here", None));
+        let span = rescorer.score_page(&stored_page("see <span>main()</span> here", None));
+        assert_eq!(code, span);
+    }
+}
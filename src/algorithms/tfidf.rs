@@ -1,4 +1,6 @@
+use serde::{Deserialize, Serialize};
 use std::collections::{ HashMap, HashSet };
+use std::path::Path;
 use tantivy::schema::Value;
 use tracing::info;
 
@@ -19,6 +21,7 @@ lazy_static::lazy_static! {
 }
 
 /// TF-IDF Calculator for document corpus
+#[derive(Serialize, Deserialize)]
 pub struct TfIdfCalculator {
     /// term -> (doc_id -> count)
     term_doc_freq: HashMap<String, HashMap<String, usize>>,
@@ -31,6 +34,14 @@ pub struct TfIdfCalculator {
 
     /// Total number of documents in corpus
     total_docs: usize,
+
+    /// doc_id -> distinct terms of the document, for `remove_document`
+    #[serde(default)]
+    doc_terms: HashMap<String, Vec<String>>,
+
+    /// Size of the file last written by `save` or read by `load`
+    #[serde(skip)]
+    serialized_size: u64,
 }
 
 impl TfIdfCalculator {
@@ -40,38 +51,122 @@ impl TfIdfCalculator {
             document_freq: HashMap::new(),
             doc_lengths: HashMap::new(),
             total_docs: 0,
+            doc_terms: HashMap::new(),
+            serialized_size: 0,
+        }
+    }
+
+    /// Write the index to `path` as MessagePack so it can be reused without rebuilding.
+    /// The file is replaced atomically so a crash mid-write keeps the previous index.
+    pub fn save(&mut self, path: &Path) -> crate::Result<()> {
+        let bytes = rmp_serde::to_vec(self)?;
+
+        let tmp_path = path.with_extension("tmp");
+        std::fs::write(&tmp_path, &bytes)?;
+        std::fs::rename(&tmp_path, path)?;
+
+        self.serialized_size = bytes.len() as u64;
+        info!("Saved TF-IDF index ({} terms, {} bytes) to {}", self.term_doc_freq.len(), bytes.len(), path.display());
+        Ok(())
+    }
+
+    /// Restore an index written by `save`
+    pub fn load(path: &Path) -> crate::Result<Self> {
+        let bytes = std::fs::read(path)?;
+        let mut calculator: Self = rmp_serde::from_slice(&bytes)?;
+        calculator.serialized_size = bytes.len() as u64;
+
+        // indexes saved before documents could be removed lack the reverse mapping
+        if calculator.doc_terms.len() < calculator.doc_lengths.len() {
+            calculator.doc_terms.clear();
+            for (term, docs) in &calculator.term_doc_freq {
+                for doc_id in docs.keys() {
+                    calculator.doc_terms.entry(doc_id.clone()).or_default().push(term.clone());
+                }
+            }
         }
+
+        info!("Loaded TF-IDF index ({} documents, {} terms) from {}", calculator.total_docs, calculator.term_doc_freq.len(), path.display());
+        Ok(calculator)
     }
 
-    /// Build TF-IDF index from corpus
+    /// Build TF-IDF index from corpus, replacing anything indexed before
     ///
     /// # Arguments
     /// * `documents` - Vec of (doc_id, content) tuples
     pub fn build_from_corpus(&mut self, documents: &[(String, String)]) {
         info!("Building TF-IDF index from {} documents...", documents.len());
 
-        self.total_docs = documents.len();
+        self.term_doc_freq.clear();
+        self.document_freq.clear();
+        self.doc_lengths.clear();
+        self.doc_terms.clear();
+        self.total_docs = 0;
 
         for (doc_id, content) in documents {
-            let terms = Self::tokenize(content);
-            let term_counts = Self::count_terms(&terms);
+            self.add_document(doc_id, content);
+        }
 
-            self.doc_lengths.insert(doc_id.clone(), terms.len());
+        info!("TF-IDF index built: {} unique terms", self.term_doc_freq.len());
+    }
+
+    /// Index one more document without rebuilding, replacing an earlier version of `doc_id`
+    pub fn add_document(&mut self, doc_id: &str, content: &str) {
+        self.remove_document(doc_id);
 
-            // Update term frequencies
-            for (term, count) in term_counts {
-                // Update document frequency
-                *self.document_freq.entry(term.clone()).or_insert(0) += 1;
+        let terms = Self::tokenize(content);
+        let term_counts = Self::count_terms(&terms);
 
-                // Update term-document frequency
-                self.term_doc_freq
-                    .entry(term)
-                    .or_insert_with(HashMap::new)
-                    .insert(doc_id.clone(), count);
+        self.doc_lengths.insert(doc_id.to_string(), terms.len());
+        self.doc_terms.insert(doc_id.to_string(), term_counts.keys().cloned().collect());
+        self.total_docs += 1;
+
+        for (term, count) in term_counts {
+            *self.document_freq.entry(term.clone()).or_insert(0) += 1;
+            self.term_doc_freq
+                .entry(term)
+                .or_default()
+                .insert(doc_id.to_string(), count);
+        }
+    }
+
+    /// Drop a document from the index, returns false if it wasn't indexed.
+    /// Terms no other document contains are forgotten entirely.
+    pub fn remove_document(&mut self, doc_id: &str) -> bool {
+        let Some(terms) = self.doc_terms.remove(doc_id) else {
+            return false;
+        };
+
+        for term in terms {
+            if let Some(docs) = self.term_doc_freq.get_mut(&term) {
+                docs.remove(doc_id);
+                if docs.is_empty() {
+                    self.term_doc_freq.remove(&term);
+                }
+            }
+            if let Some(df) = self.document_freq.get_mut(&term) {
+                *df -= 1;
+                if *df == 0 {
+                    self.document_freq.remove(&term);
+                }
             }
         }
+        self.doc_lengths.remove(doc_id);
+        self.total_docs -= 1;
+        true
+    }
 
-        info!("TF-IDF index built: {} unique terms", self.term_doc_freq.len());
+    pub fn document_count(&self) -> usize {
+        self.total_docs
+    }
+
+    pub fn contains_document(&self, doc_id: &str) -> bool {
+        self.doc_terms.contains_key(doc_id)
+    }
+
+    /// Ids of every indexed document, in no particular order
+    pub fn document_ids(&self) -> impl Iterator<Item = &str> {
+        self.doc_terms.keys().map(String::as_str)
     }
 
     /// Calculate Term Frequency for a term in a document
@@ -108,6 +203,19 @@ impl TfIdfCalculator {
         tf * idf
     }
 
+    /// TF-IDF score of every term in a document, empty if it isn't indexed
+    pub fn document_vector(&self, doc_id: &str) -> HashMap<String, f64> {
+        self.doc_terms
+            .get(doc_id)
+            .map(|terms| {
+                terms.iter()
+                    .map(|term| (term.clone(), self.calculate_tfidf(term, doc_id)))
+                    .filter(|(_, score)| *score > 0.0)
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
     /// Get top N terms for a document by TF-IDF score
     pub fn get_top_terms(&self, doc_id: &str, n: usize) -> Vec<(String, f64)> {
         let mut scores: Vec<(String, f64)> = self.term_doc_freq
@@ -183,6 +291,7 @@ impl TfIdfCalculator {
             } else {
                 self.doc_lengths.values().sum::<usize>() as f64 / self.doc_lengths.len() as f64
             },
+            serialized_size_bytes: self.serialized_size,
         }
     }
 }
@@ -193,6 +302,8 @@ pub struct TfIdfStats {
     pub total_documents: usize,
     pub unique_terms: usize,
     pub avg_doc_length: f64,
+    /// On-disk size of the index, 0 until it has been saved or loaded
+    pub serialized_size_bytes: u64,
 }
 
 #[cfg(test)]
@@ -241,4 +352,63 @@ mod tests {
 
         println!("Top terms test passed");
     }
+
+    #[test]
+    fn test_save_and_load() {
+        let mut calculator = TfIdfCalculator::new();
+        calculator.build_from_corpus(&[
+            ("doc1".to_string(), "web crawler crawls the web".to_string()),
+            ("doc2".to_string(), "search engine crawler technology".to_string()),
+        ]);
+        assert_eq!(calculator.get_stats().serialized_size_bytes, 0);
+
+        let path = std::env::temp_dir().join(format!("tfidf_{}.msgpack", std::process::id()));
+        calculator.save(&path).unwrap();
+        let saved_size = calculator.get_stats().serialized_size_bytes;
+        assert_eq!(saved_size, std::fs::metadata(&path).unwrap().len());
+
+        let loaded = TfIdfCalculator::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let stats = loaded.get_stats();
+        assert_eq!(stats.total_documents, 2);
+        assert_eq!(stats.unique_terms, calculator.get_stats().unique_terms);
+        assert_eq!(stats.serialized_size_bytes, saved_size);
+        assert_eq!(loaded.calculate_tfidf("web", "doc1"), calculator.calculate_tfidf("web", "doc1"));
+        assert!(TfIdfCalculator::load(&path).is_err());
+    }
+
+    #[test]
+    fn test_incremental_updates_match_rebuild() {
+        let docs = [
+            ("doc1".to_string(), "web crawler crawls the web".to_string()),
+            ("doc2".to_string(), "web design for modern websites".to_string()),
+            ("doc3".to_string(), "search engine crawler technology".to_string()),
+        ];
+
+        let mut rebuilt = TfIdfCalculator::new();
+        rebuilt.build_from_corpus(&docs);
+
+        let mut incremental = TfIdfCalculator::new();
+        incremental.build_from_corpus(&docs[..1]);
+        incremental.add_document("doc2", "an old version of doc2 about gardening");
+        incremental.add_document("doc2", &docs[1].1);
+        incremental.add_document("doc3", &docs[2].1);
+        incremental.add_document("doc4", "temporary gardening page");
+
+        assert!(incremental.remove_document("doc4"));
+        assert!(!incremental.remove_document("doc4"));
+        assert!(!incremental.contains_document("doc4"));
+        assert!(incremental.contains_document("doc2"));
+        assert_eq!(incremental.document_count(), 3);
+
+        // terms of replaced and removed documents are gone
+        assert_eq!(incremental.calculate_idf("gardening"), 0.0);
+        let stats = incremental.get_stats();
+        assert_eq!(stats.unique_terms, rebuilt.get_stats().unique_terms);
+        assert_eq!(stats.avg_doc_length, rebuilt.get_stats().avg_doc_length);
+        for (term, doc_id) in [("crawler", "doc1"), ("web", "doc2"), ("engine", "doc3")] {
+            assert_eq!(incremental.calculate_tfidf(term, doc_id), rebuilt.calculate_tfidf(term, doc_id));
+        }
+    }
 }
\ No newline at end of file
@@ -0,0 +1,457 @@
+use axum::extract::{Path, Query, State};
+use axum::http::{header, StatusCode};
+use axum::response::IntoResponse;
+use axum::Json;
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+use uuid::Uuid;
+
+use super::{ApiError, ApiResult, ApiState, CrawlJob, CrawlJobLimits, CrawlJobStatus};
+use crate::models::CrawlUrl;
+use crate::search::{FacetedSearchRequest, FacetedSearchResponse, ScoreExplanation, SearchFilter, SearchOptions, SearchResult, SortBy};
+use crate::storage::cache::{CacheStats, WindowedCacheStats};
+use crate::storage::models::{DatabaseStats, StoredPage, StoredPageSummary};
+
+#[derive(Debug, Deserialize)]
+pub struct SearchParams {
+    pub q: String,
+    #[serde(default = "default_limit")]
+    pub limit: usize,
+    #[serde(default)]
+    pub offset: usize,
+    pub domain: Option<String>,
+    pub sort: Option<String>,
+    #[serde(default)]
+    pub snippets: bool,
+    #[serde(default)]
+    pub highlight: bool,
+}
+
+fn default_limit() -> usize {
+    10
+}
+
+#[derive(Debug, Serialize)]
+pub struct SearchResponse {
+    pub query: String,
+    pub count: usize,
+    pub results: Vec<SearchResult>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SuggestParams {
+    pub q: String,
+    #[serde(default = "default_limit")]
+    pub limit: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SuggestResponse {
+    pub query: String,
+    pub suggestions: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ExplainParams {
+    pub q: String,
+    pub url: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct LinksParams {
+    #[serde(default = "default_limit")]
+    pub limit: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PageLinksResponse {
+    pub url: String,
+    pub outbound: Vec<String>,
+    pub inbound: Vec<StoredPageSummary>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct QueueParams {
+    #[serde(default = "default_queue_limit")]
+    pub limit: usize,
+}
+
+fn default_queue_limit() -> usize {
+    20
+}
+
+#[derive(Debug, Serialize)]
+pub struct CrawlQueueResponse {
+    pub job_id: Uuid,
+    pub queue: Vec<CrawlUrl>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CrawlRequest {
+    pub seed_urls: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CrawlJobResponse {
+    pub job_id: Uuid,
+    pub status: CrawlJobStatus,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CrawlUpdateRequest {
+    pub max_pages: Option<usize>,
+    pub concurrent_requests: Option<usize>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CrawlLimitsResponse {
+    pub job_id: Uuid,
+    #[serde(flatten)]
+    pub limits: CrawlJobLimits,
+}
+
+// Validate the query and turn the optional parameters into a filter and sort order
+fn search_options(params: &SearchParams) -> Result<(SearchFilter, SortBy), ApiError> {
+    if params.q.trim().is_empty() {
+        return Err(ApiError::BadRequest("query parameter 'q' must not be empty".to_string()));
+    }
+
+    let sort = match params.sort.as_deref() {
+        Some(sort) => SortBy::from_str(sort).map_err(ApiError::BadRequest)?,
+        None => SortBy::Relevance,
+    };
+
+    let mut filters = SearchFilter::new();
+    if let Some(domain) = &params.domain {
+        filters = filters.with_domain(domain.clone());
+    }
+
+    Ok((filters, sort))
+}
+
+pub async fn search(State(state): State<ApiState>, Query(params): Query<SearchParams>) -> ApiResult<SearchResponse> {
+    let (filters, sort) = search_options(&params)?;
+
+    let options = SearchOptions::new(params.limit)
+        .with_offset(params.offset)
+        .with_filters(filters)
+        .with_sort(sort)
+        .with_snippets(params.snippets, params.highlight);
+    let results = state.search_engine
+        .search(&params.q, &options)
+        .map_err(|e| ApiError::Internal(e.to_string()))?;
+
+    Ok(Json(SearchResponse {
+        query: params.q,
+        count: results.len(),
+        results,
+    }))
+}
+
+pub async fn search_facets(State(state): State<ApiState>, Query(params): Query<SearchParams>) -> ApiResult<FacetedSearchResponse> {
+    let (filters, sort) = search_options(&params)?;
+
+    let request = FacetedSearchRequest::new(params.limit)
+        .with_offset(params.offset)
+        .with_filters(filters)
+        .with_sort(sort)
+        .with_snippets(params.snippets, params.highlight);
+
+    let response = state.search_engine
+        .search_with_facets(&params.q, request)
+        .map_err(|e| ApiError::Internal(e.to_string()))?;
+    Ok(Json(response))
+}
+
+pub async fn explain(State(state): State<ApiState>, Query(params): Query<ExplainParams>) -> ApiResult<ScoreExplanation> {
+    if params.q.trim().is_empty() {
+        return Err(ApiError::BadRequest("query parameter 'q' must not be empty".to_string()));
+    }
+
+    // explain only fails for urls missing from the index, or on index errors
+    state.search_engine
+        .explain(&params.q, &params.url)
+        .map(Json)
+        .map_err(|e| match e.downcast_ref::<tantivy::TantivyError>() {
+            Some(tantivy::TantivyError::InvalidArgument(_)) => ApiError::NotFound(format!("url {}", params.url)),
+            _ => ApiError::Internal(e.to_string()),
+        })
+}
+
+pub async fn suggest(State(state): State<ApiState>, Query(params): Query<SuggestParams>) -> ApiResult<SuggestResponse> {
+    let suggestions = state.search_engine
+        .suggest(&params.q, params.limit.min(100))
+        .map_err(|e| ApiError::Internal(e.to_string()))?;
+
+    Ok(Json(SuggestResponse {
+        query: params.q,
+        suggestions,
+    }))
+}
+
+pub async fn get_page(State(state): State<ApiState>, Path(id): Path<i64>) -> ApiResult<StoredPage> {
+    state.repository
+        .get_page_by_id(id)
+        .await
+        .map_err(|e| ApiError::Internal(e.to_string()))?
+        .map(Json)
+        .ok_or_else(|| ApiError::NotFound(format!("page {}", id)))
+}
+
+pub async fn page_links(
+    State(state): State<ApiState>,
+    Path(id): Path<i64>,
+    Query(params): Query<LinksParams>,
+) -> ApiResult<PageLinksResponse> {
+    let page = state.repository
+        .get_page_by_id(id)
+        .await
+        .map_err(|e| ApiError::Internal(e.to_string()))?
+        .ok_or_else(|| ApiError::NotFound(format!("page {}", id)))?;
+
+    let limit = params.limit.min(1000);
+    let outbound = state.repository
+        .get_outbound_links(&page.url, limit)
+        .await
+        .map_err(|e| ApiError::Internal(e.to_string()))?;
+    let inbound = state.repository
+        .get_inbound_links(&page.url, limit)
+        .await
+        .map_err(|e| ApiError::Internal(e.to_string()))?;
+
+    Ok(Json(PageLinksResponse { url: page.url, outbound, inbound }))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SimilarParams {
+    #[serde(default = "default_limit")]
+    pub limit: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SimilarPagesResponse {
+    pub url_hash: String,
+    pub count: usize,
+    pub results: Vec<SearchResult>,
+}
+
+/// Pages most similar in content to the indexed page with this url hash
+pub async fn similar_pages(
+    State(state): State<ApiState>,
+    Path(url_hash): Path<String>,
+    Query(params): Query<SimilarParams>,
+) -> ApiResult<SimilarPagesResponse> {
+    let results = state.search_engine
+        .search_similar_by_hash(&url_hash, params.limit.min(100))
+        .map_err(|e| match e.downcast_ref::<tantivy::TantivyError>() {
+            Some(tantivy::TantivyError::InvalidArgument(_)) => ApiError::NotFound(format!("page {}", url_hash)),
+            _ => ApiError::Internal(e.to_string()),
+        })?;
+
+    Ok(Json(SimilarPagesResponse {
+        url_hash,
+        count: results.len(),
+        results,
+    }))
+}
+
+pub async fn delete_page(State(state): State<ApiState>, Path(url_hash): Path<String>) -> Result<StatusCode, ApiError> {
+    // the repository also drops the page from the search index when one is attached
+    let deleted = state.repository
+        .delete_page_by_hash(&url_hash)
+        .await
+        .map_err(|e| ApiError::Internal(e.to_string()))?;
+
+    match deleted {
+        Some(_) => Ok(StatusCode::NO_CONTENT),
+        None => Err(ApiError::NotFound(format!("page {}", url_hash))),
+    }
+}
+
+pub async fn stats(State(state): State<ApiState>) -> ApiResult<DatabaseStats> {
+    let stats = state.repository
+        .get_stats()
+        .await
+        .map_err(|e| ApiError::Internal(e.to_string()))?;
+    Ok(Json(stats))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CacheStatsParams {
+    #[serde(default = "default_cache_window_secs")]
+    pub window_secs: u64,
+}
+
+fn default_cache_window_secs() -> u64 {
+    300
+}
+
+#[derive(Debug, Serialize)]
+pub struct CacheStatsResponse {
+    pub overall: CacheStats,
+    pub window: WindowedCacheStats,
+}
+
+/// Entry counts and hit rates of the search result cache, overall and for the last `window_secs`
+pub async fn cache_stats(State(state): State<ApiState>, Query(params): Query<CacheStatsParams>) -> ApiResult<CacheStatsResponse> {
+    let cache = state.search_engine.memory_cache();
+    Ok(Json(CacheStatsResponse {
+        overall: cache.get_stats(),
+        window: cache.get_windowed_stats(std::time::Duration::from_secs(params.window_secs)),
+    }))
+}
+
+#[derive(Debug, Serialize)]
+pub struct LanguageCount {
+    pub language: String,
+    pub pages: i64,
+}
+
+/// Stored pages per language, most common first
+pub async fn language_stats(State(state): State<ApiState>) -> ApiResult<Vec<LanguageCount>> {
+    let distribution = state.repository
+        .get_language_distribution()
+        .await
+        .map_err(|e| ApiError::Internal(e.to_string()))?;
+
+    Ok(Json(distribution
+        .into_iter()
+        .map(|(language, pages)| LanguageCount { language, pages })
+        .collect()))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DistributionParams {
+    #[serde(default = "default_limit")]
+    pub buckets: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PageRankBucket {
+    /// Lowest PageRank in the bucket, buckets are equally wide
+    pub lower_bound: f64,
+    pub pages: i64,
+}
+
+/// Histogram of stored PageRank values, empty before PageRank was calculated
+pub async fn pagerank_distribution(
+    State(state): State<ApiState>,
+    Query(params): Query<DistributionParams>,
+) -> ApiResult<Vec<PageRankBucket>> {
+    if !(1..=1000).contains(&params.buckets) {
+        return Err(ApiError::BadRequest("buckets must be between 1 and 1000".to_string()));
+    }
+
+    let distribution = state.repository
+        .get_pagerank_distribution(params.buckets)
+        .await
+        .map_err(|e| ApiError::Internal(e.to_string()))?;
+
+    Ok(Json(distribution
+        .into_iter()
+        .map(|(lower_bound, pages)| PageRankBucket { lower_bound, pages })
+        .collect()))
+}
+
+/// PNG screenshot taken when the page was crawled, 404 if none was stored
+pub async fn page_screenshot(State(state): State<ApiState>, Path(id): Path<i64>) -> Result<impl IntoResponse, ApiError> {
+    let page = state.repository
+        .get_page_by_id(id)
+        .await
+        .map_err(|e| ApiError::Internal(e.to_string()))?
+        .ok_or_else(|| ApiError::NotFound(format!("page {}", id)))?;
+
+    // only the file name is used so a stored path can't point outside screenshot_dir
+    let file_name = page.screenshot_path
+        .as_deref()
+        .and_then(|path| std::path::Path::new(path).file_name())
+        .ok_or_else(|| ApiError::NotFound(format!("screenshot of page {}", id)))?;
+    let path = std::path::Path::new(&state.crawler.config().crawler.screenshot_dir).join(file_name);
+
+    let image = tokio::fs::read(&path).await.map_err(|e| match e.kind() {
+        std::io::ErrorKind::NotFound => ApiError::NotFound(format!("screenshot of page {}", id)),
+        _ => ApiError::Internal(e.to_string()),
+    })?;
+    Ok(([(header::CONTENT_TYPE, "image/png")], image))
+}
+
+/// Prometheus scrape endpoint
+pub async fn metrics(State(state): State<ApiState>) -> impl IntoResponse {
+    (
+        [(header::CONTENT_TYPE, state.metrics.content_type())],
+        state.metrics.render(),
+    )
+}
+
+pub async fn start_crawl(
+    State(state): State<ApiState>,
+    Json(request): Json<CrawlRequest>,
+) -> Result<(StatusCode, Json<CrawlJobResponse>), ApiError> {
+    if request.seed_urls.is_empty() {
+        return Err(ApiError::BadRequest("seed_urls must not be empty".to_string()));
+    }
+
+    let job_id = state.jobs
+        .submit(request.seed_urls, state.crawler.config().clone())
+        .await
+        .map_err(|e| ApiError::Internal(e.to_string()))?;
+
+    Ok((StatusCode::ACCEPTED, Json(CrawlJobResponse {
+        job_id,
+        status: CrawlJobStatus::Running,
+    })))
+}
+
+pub async fn crawl_status(State(state): State<ApiState>, Path(job_id): Path<Uuid>) -> ApiResult<CrawlJobResponse> {
+    let status = state.jobs
+        .status(job_id)
+        .ok_or_else(|| ApiError::NotFound(format!("crawl job {}", job_id)))?;
+    Ok(Json(CrawlJobResponse { job_id, status }))
+}
+
+pub async fn crawl_queue(
+    State(state): State<ApiState>,
+    Path(job_id): Path<Uuid>,
+    Query(params): Query<QueueParams>,
+) -> ApiResult<CrawlQueueResponse> {
+    let queue = state.jobs
+        .queue(job_id, params.limit.min(1000))
+        .await
+        .ok_or_else(|| ApiError::NotFound(format!("crawl job {}", job_id)))?;
+    Ok(Json(CrawlQueueResponse { job_id, queue }))
+}
+
+pub async fn update_crawl(
+    State(state): State<ApiState>,
+    Path(job_id): Path<Uuid>,
+    Json(request): Json<CrawlUpdateRequest>,
+) -> ApiResult<CrawlLimitsResponse> {
+    if request.max_pages.is_none() && request.concurrent_requests.is_none() {
+        return Err(ApiError::BadRequest("expected max_pages or concurrent_requests".to_string()));
+    }
+    if request.concurrent_requests == Some(0) {
+        return Err(ApiError::BadRequest("concurrent_requests must be at least 1".to_string()));
+    }
+
+    let limits = state.jobs
+        .update_limits(job_id, request.max_pages, request.concurrent_requests)
+        .ok_or_else(|| ApiError::NotFound(format!("crawl job {}", job_id)))?;
+    Ok(Json(CrawlLimitsResponse { job_id, limits }))
+}
+
+// Finished jobs are left as they are, the response has the status either way
+pub async fn cancel_crawl(State(state): State<ApiState>, Path(job_id): Path<Uuid>) -> ApiResult<CrawlJobResponse> {
+    state.jobs.cancel(job_id);
+    let status = state.jobs
+        .status(job_id)
+        .ok_or_else(|| ApiError::NotFound(format!("crawl job {}", job_id)))?;
+    Ok(Json(CrawlJobResponse { job_id, status }))
+}
+
+// The whole job record: seeds, configuration, status and timestamps
+pub async fn crawl_job(State(state): State<ApiState>, Path(job_id): Path<Uuid>) -> ApiResult<CrawlJob> {
+    state.jobs
+        .job(job_id)
+        .map(Json)
+        .ok_or_else(|| ApiError::NotFound(format!("crawl job {}", job_id)))
+}
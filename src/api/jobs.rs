@@ -0,0 +1,166 @@
+use dashmap::DashMap;
+use serde::Serialize;
+use std::sync::Arc;
+use tokio::task::JoinHandle;
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+use crate::config::CrawlerConfig;
+use crate::models::{CrawlJob, CrawlJobStatus, CrawlUrl};
+use crate::storage::repository::PageRepository;
+use crate::utils::CrawlMetrics;
+use crate::WebCrawler;
+
+// The task running a job and the crawler it shares the frontier and runtime limits with
+struct JobHandle {
+    task: Option<JoinHandle<()>>,
+    crawler: WebCrawler,
+}
+
+/// Limits of a crawl job that can change while it runs
+#[derive(Debug, Clone, Serialize)]
+pub struct CrawlJobLimits {
+    pub max_pages: usize,
+    pub concurrent_requests: usize,
+}
+
+/// Crawls started through the API, each with its own crawler, keyed by job id.
+/// With a repository the crawls store their pages there and every job is
+/// recorded in the `crawl_jobs` table.
+pub struct CrawlJobManager {
+    jobs: Arc<DashMap<Uuid, CrawlJob>>,
+    handles: DashMap<Uuid, JobHandle>,
+    repository: Option<PageRepository>,
+}
+
+impl CrawlJobManager {
+    pub fn new() -> Self {
+        Self {
+            jobs: Arc::new(DashMap::new()),
+            handles: DashMap::new(),
+            repository: None,
+        }
+    }
+
+    pub fn with_repository(mut self, repository: PageRepository) -> Self {
+        self.repository = Some(repository);
+        self
+    }
+
+    // Spawn a crawl of `seed_urls` in the background and return its job id
+    pub async fn submit(&self, seed_urls: Vec<String>, mut config: CrawlerConfig) -> crate::Result<Uuid> {
+        config.crawler.seed_urls = seed_urls.clone();
+        let crawler = WebCrawler::new(config.clone(), Arc::new(CrawlMetrics::new())).await?;
+
+        let job = CrawlJob::new(seed_urls, &config);
+        let job_id = job.id;
+        self.persist(job.clone());
+        self.jobs.insert(job_id, job);
+
+        let jobs = self.jobs.clone();
+        let repository = self.repository.clone();
+        let job_crawler = crawler.clone();
+
+        let task = tokio::spawn(async move {
+            let status = match crawler.start_crawling_with_repository(repository.clone()).await {
+                Ok(statistics) => CrawlJobStatus::Completed { statistics },
+                Err(e) => {
+                    error!("Crawl job {} failed: {}", job_id, e);
+                    CrawlJobStatus::Failed { error: e.to_string() }
+                }
+            };
+
+            // a cancelled job keeps its status
+            let finished = jobs.get_mut(&job_id)
+                .filter(|job| !job.status.is_finished())
+                .map(|mut job| {
+                    job.finish(status);
+                    job.clone()
+                });
+            if let (Some(job), Some(repository)) = (finished, repository)
+                && let Err(e) = repository.save_crawl_job(&job).await
+            {
+                warn!("Could not record crawl job {}: {}", job_id, e);
+            }
+        });
+
+        self.handles.insert(job_id, JobHandle { task: Some(task), crawler: job_crawler });
+
+        info!("Started crawl job {}", job_id);
+        Ok(job_id)
+    }
+
+    pub fn status(&self, job_id: Uuid) -> Option<CrawlJobStatus> {
+        self.jobs.get(&job_id).map(|job| job.status.clone())
+    }
+
+    pub fn job(&self, job_id: Uuid) -> Option<CrawlJob> {
+        self.jobs.get(&job_id).map(|job| job.clone())
+    }
+
+    // The next `limit` URLs the job will crawl, None if the job is unknown
+    pub async fn queue(&self, job_id: Uuid, limit: usize) -> Option<Vec<CrawlUrl>> {
+        let frontier = self.handles.get(&job_id)?.crawler.url_frontier().clone();
+        Some(frontier.peek_top_n(limit).await)
+    }
+
+    // Change the limits of a job, returns the limits afterwards or None if unknown
+    pub fn update_limits(
+        &self,
+        job_id: Uuid,
+        max_pages: Option<usize>,
+        concurrent_requests: Option<usize>,
+    ) -> Option<CrawlJobLimits> {
+        let handle = self.handles.get(&job_id)?;
+        let crawler = &handle.crawler;
+
+        if let Some(max_pages) = max_pages {
+            crawler.set_max_pages(max_pages);
+        }
+        if let Some(concurrent_requests) = concurrent_requests {
+            crawler.set_concurrent_requests(concurrent_requests);
+        }
+        info!("Updated limits of crawl job {}", job_id);
+
+        Some(CrawlJobLimits {
+            max_pages: crawler.max_pages(),
+            concurrent_requests: crawler.concurrent_requests(),
+        })
+    }
+
+    /// Abort a running job, returns false if the job is unknown or already finished
+    pub fn cancel(&self, job_id: Uuid) -> bool {
+        let Some(mut job) = self.jobs.get_mut(&job_id) else {
+            return false;
+        };
+        if job.status.is_finished() {
+            return false;
+        }
+
+        if let Some(task) = self.handles.get_mut(&job_id).and_then(|mut handle| handle.task.take()) {
+            task.abort();
+        }
+        job.finish(CrawlJobStatus::Cancelled);
+        self.persist(job.clone());
+
+        info!("Cancelled crawl job {}", job_id);
+        true
+    }
+
+    // Record the job in the background, the API doesn't wait on the database
+    fn persist(&self, job: CrawlJob) {
+        if let Some(repository) = self.repository.clone() {
+            tokio::spawn(async move {
+                if let Err(e) = repository.save_crawl_job(&job).await {
+                    warn!("Could not record crawl job {}: {}", job.id, e);
+                }
+            });
+        }
+    }
+}
+
+impl Default for CrawlJobManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
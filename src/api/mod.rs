@@ -0,0 +1,109 @@
+//! HTTP API for search, stored pages and crawl jobs
+
+mod handlers;
+mod jobs;
+mod tests;
+
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::{Json, Router};
+use std::sync::Arc;
+use thiserror::Error;
+use tracing::info;
+
+use crate::SearchEngine;
+use crate::WebCrawler;
+use crate::storage::repository::PageRepository;
+use crate::utils::MetricsHandle;
+
+pub use jobs::{CrawlJobLimits, CrawlJobManager};
+pub use crate::models::{CrawlJob, CrawlJobStatus};
+
+/// Shared state for all handlers
+#[derive(Clone)]
+pub struct ApiState {
+    pub search_engine: Arc<SearchEngine>,
+    pub repository: Arc<PageRepository>,
+    // template crawler, every job is started with its configuration
+    pub crawler: Arc<WebCrawler>,
+    pub jobs: Arc<CrawlJobManager>,
+    // rendered by GET /metrics, shared with the template crawler
+    pub metrics: MetricsHandle,
+}
+
+impl ApiState {
+    pub fn new(search_engine: SearchEngine, repository: PageRepository, crawler: WebCrawler) -> Self {
+        let metrics = crawler.metrics().clone();
+        let jobs = CrawlJobManager::new().with_repository(repository.clone());
+        Self {
+            search_engine: Arc::new(search_engine),
+            repository: Arc::new(repository),
+            crawler: Arc::new(crawler),
+            jobs: Arc::new(jobs),
+            metrics,
+        }
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum ApiError {
+    #[error("Not found: {0}")]
+    NotFound(String),
+
+    #[error("Bad request: {0}")]
+    BadRequest(String),
+
+    #[error("Internal error: {0}")]
+    Internal(String),
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let status = match self {
+            ApiError::NotFound(_) => StatusCode::NOT_FOUND,
+            ApiError::BadRequest(_) => StatusCode::BAD_REQUEST,
+            ApiError::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+
+        let body = Json(serde_json::json!({ "error": self.to_string() }));
+        (status, body).into_response()
+    }
+}
+
+pub type ApiResult<T> = std::result::Result<Json<T>, ApiError>;
+
+pub fn router(state: ApiState) -> Router {
+    Router::new()
+        .route("/search", get(handlers::search))
+        .route("/search/facets", get(handlers::search_facets))
+        .route("/search/explain", get(handlers::explain))
+        .route("/suggest", get(handlers::suggest))
+        // GET takes the page id, DELETE the url hash
+        .route("/pages/{id}", get(handlers::get_page).delete(handlers::delete_page))
+        .route("/pages/{id}/links", get(handlers::page_links))
+        .route("/pages/{id}/similar", get(handlers::similar_pages))
+        .route("/pages/{id}/screenshot", get(handlers::page_screenshot))
+        .route("/stats", get(handlers::stats))
+        .route("/stats/languages", get(handlers::language_stats))
+        .route("/stats/cache", get(handlers::cache_stats))
+        .route("/stats/pagerank-distribution", get(handlers::pagerank_distribution))
+        .route("/metrics", get(handlers::metrics))
+        .route("/crawl", axum::routing::post(handlers::start_crawl))
+        .route("/crawl/{job_id}/status", get(handlers::crawl_status))
+        .route("/crawl/{job_id}/queue", get(handlers::crawl_queue))
+        .route(
+            "/crawl/{job_id}",
+            get(handlers::crawl_job).delete(handlers::cancel_crawl).patch(handlers::update_crawl),
+        )
+        .with_state(state)
+}
+
+/// Serve the API on 0.0.0.0:`port` until the process is stopped
+pub async fn serve(state: ApiState, port: u16) -> crate::Result<()> {
+    let listener = tokio::net::TcpListener::bind(("0.0.0.0", port)).await?;
+    info!("API server listening on {}", listener.local_addr()?);
+
+    axum::serve(listener, router(state)).await?;
+    Ok(())
+}
@@ -0,0 +1,266 @@
+use crate::api::{router, ApiState};
+use crate::config::CrawlerConfig;
+use crate::search::SearchSchema;
+use crate::storage::repository::PageRepository;
+use crate::utils::CrawlMetrics;
+use crate::{SearchEngine, WebCrawler};
+use serde_json::Value;
+use std::sync::Arc;
+use tempfile::TempDir;
+
+// Start the API on an ephemeral port, returning its base url.
+// The repository is lazily connected so only handlers that touch it need a database.
+async fn start_server() -> (String, TempDir) {
+    let dir = tempfile::tempdir().unwrap();
+    let index = SearchSchema::create_index(dir.path()).unwrap();
+    let schema = SearchSchema::build();
+
+    let mut writer: tantivy::IndexWriter = index.writer(15_000_000).unwrap();
+    let mut doc = tantivy::TantivyDocument::default();
+    schema.add_url(&mut doc, "https://example.com/rust");
+    schema.add_hashes(&mut doc, &PageRepository::calculate_url_hash("https://example.com/rust"), "content_hash");
+    doc.add_text(schema.domain_field, "example.com");
+    doc.add_text(schema.content_field, "a web crawler written in rust");
+    schema.add_title(&mut doc, "Rust Web Crawler");
+    schema.add_facets(&mut doc, "example.com", "en", 0.8);
+    writer.add_document(doc).unwrap();
+    writer.commit().unwrap();
+
+    let pool = sqlx::postgres::PgPoolOptions::new()
+        .connect_lazy("postgresql://localhost/crawler_test")
+        .unwrap();
+
+    let state = ApiState::new(
+        SearchEngine::new(dir.path()).unwrap(),
+        PageRepository::new(pool),
+        WebCrawler::new(CrawlerConfig::default(), Arc::new(CrawlMetrics::new())).await.unwrap(),
+    );
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, router(state)).await.unwrap();
+    });
+
+    (format!("http://{}", addr), dir)
+}
+
+#[tokio::test]
+async fn test_search_endpoint() {
+    let (base, _dir) = start_server().await;
+    let client = reqwest::Client::new();
+
+    let response = client.get(format!("{}/search?q=crawler&limit=5", base)).send().await.unwrap();
+    assert_eq!(response.status(), 200);
+
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body["count"], 1);
+    assert_eq!(body["results"][0]["url"], "https://example.com/rust");
+
+    let empty: Value = client.get(format!("{}/search?q=bread", base)).send().await.unwrap().json().await.unwrap();
+    assert_eq!(empty["count"], 0);
+}
+
+#[tokio::test]
+async fn test_search_endpoint_returns_highlight_positions() {
+    let (base, _dir) = start_server().await;
+    let client = reqwest::Client::new();
+
+    let body: Value = client.get(format!("{}/search?q=crawler&snippets=true&highlight=true", base))
+        .send().await.unwrap().json().await.unwrap();
+    let result = &body["results"][0];
+    assert_eq!(result["snippet"], "a web **crawler** written in rust");
+    // offsets into the returned snippet, inside the ** markers
+    assert_eq!(result["highlight_positions"], serde_json::json!([
+        {"field": "content", "term": "crawler", "start_byte": 8, "end_byte": 15}
+    ]));
+}
+
+#[tokio::test]
+async fn test_search_facets_endpoint() {
+    let (base, _dir) = start_server().await;
+    let client = reqwest::Client::new();
+
+    let body: Value = client.get(format!("{}/search/facets?q=crawler", base)).send().await.unwrap().json().await.unwrap();
+    assert_eq!(body["results"][0]["url"], "https://example.com/rust");
+    assert_eq!(body["facets"]["domains"], serde_json::json!([["example.com", 1]]));
+    assert_eq!(body["facets"]["languages"], serde_json::json!([["en", 1]]));
+    assert_eq!(body["facets"]["quality_buckets"], serde_json::json!([["low", 0], ["medium", 0], ["high", 1]]));
+
+    let response = client.get(format!("{}/search/facets?q=", base)).send().await.unwrap();
+    assert_eq!(response.status(), 400);
+}
+
+#[tokio::test]
+async fn test_search_explain_endpoint() {
+    let (base, _dir) = start_server().await;
+    let client = reqwest::Client::new();
+
+    let body: Value = client.get(format!("{}/search/explain?q=crawler&url=https://example.com/rust", base))
+        .send().await.unwrap().json().await.unwrap();
+    assert_eq!(body["url"], "https://example.com/rust");
+    assert!(body["tantivy_score"].as_f64().unwrap() > 0.0);
+    assert_eq!(body["url_penalty"], 1.0);
+
+    let missing = client.get(format!("{}/search/explain?q=crawler&url=https://example.com/none", base)).send().await.unwrap();
+    assert_eq!(missing.status(), 404);
+}
+
+#[tokio::test]
+async fn test_suggest_endpoint() {
+    let (base, _dir) = start_server().await;
+    let client = reqwest::Client::new();
+
+    let body: Value = client.get(format!("{}/suggest?q=rust%20w&limit=3", base)).send().await.unwrap().json().await.unwrap();
+    assert_eq!(body["suggestions"], serde_json::json!(["Rust Web Crawler"]));
+
+    let none: Value = client.get(format!("{}/suggest?q=python", base)).send().await.unwrap().json().await.unwrap();
+    assert_eq!(none["suggestions"], serde_json::json!([]));
+}
+
+#[tokio::test]
+async fn test_cache_stats_endpoint() {
+    let (base, _dir) = start_server().await;
+    let client = reqwest::Client::new();
+
+    // the first search misses the result cache, the repeat hits it
+    for _ in 0..2 {
+        client.get(format!("{}/search?q=crawler", base)).send().await.unwrap();
+    }
+
+    let body: Value = client.get(format!("{}/stats/cache?window_secs=60", base)).send().await.unwrap().json().await.unwrap();
+    assert_eq!(body["overall"]["total_requests"], 2);
+    assert_eq!(body["overall"]["total_hits"], 1);
+    assert_eq!(body["overall"]["hit_rate"], 0.5);
+    assert_eq!(body["window"]["window_secs"], 60);
+    assert_eq!(body["window"]["requests"], 2);
+}
+
+#[tokio::test]
+async fn test_similar_pages_endpoint() {
+    let (base, _dir) = start_server().await;
+    let client = reqwest::Client::new();
+
+    // the only indexed page has nothing similar besides itself
+    let url_hash = PageRepository::calculate_url_hash("https://example.com/rust");
+    let response = client.get(format!("{}/pages/{}/similar?limit=5", base, url_hash)).send().await.unwrap();
+    assert_eq!(response.status(), 200);
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body["url_hash"], url_hash.as_str());
+    assert_eq!(body["count"], 0);
+
+    let response = client.get(format!("{}/pages/not-indexed/similar", base)).send().await.unwrap();
+    assert_eq!(response.status(), 404);
+}
+
+#[tokio::test]
+async fn test_search_rejects_bad_parameters() {
+    let (base, _dir) = start_server().await;
+    let client = reqwest::Client::new();
+
+    let response = client.get(format!("{}/search?q=", base)).send().await.unwrap();
+    assert_eq!(response.status(), 400);
+    let body: Value = response.json().await.unwrap();
+    assert!(body["error"].as_str().unwrap().contains("'q'"));
+
+    let response = client.get(format!("{}/search?q=rust&sort=bogus", base)).send().await.unwrap();
+    assert_eq!(response.status(), 400);
+
+    // checked before the database is queried
+    let response = client.get(format!("{}/stats/pagerank-distribution?buckets=0", base)).send().await.unwrap();
+    assert_eq!(response.status(), 400);
+}
+
+#[tokio::test]
+async fn test_crawl_job_lifecycle() {
+    let (base, _dir) = start_server().await;
+    let client = reqwest::Client::new();
+
+    let response = client
+        .post(format!("{}/crawl", base))
+        .json(&serde_json::json!({ "seed_urls": ["http://127.0.0.1:9/"] }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 202);
+
+    let body: Value = response.json().await.unwrap();
+    let job_id = body["job_id"].as_str().unwrap().to_string();
+    assert_eq!(body["status"]["state"], "running");
+    let unknown = uuid::Uuid::nil();
+
+    let status: Value = client
+        .get(format!("{}/crawl/{}/status", base, job_id))
+        .send().await.unwrap()
+        .json().await.unwrap();
+    assert_eq!(status["job_id"], job_id);
+
+    let queue: Value = client
+        .get(format!("{}/crawl/{}/queue?limit=5", base, job_id))
+        .send().await.unwrap()
+        .json().await.unwrap();
+    assert!(queue["queue"].as_array().unwrap().len() <= 1);
+    let missing_queue = client.get(format!("{}/crawl/{}/queue", base, unknown)).send().await.unwrap();
+    assert_eq!(missing_queue.status(), 404);
+
+    let limits: Value = client
+        .patch(format!("{}/crawl/{}", base, job_id))
+        .json(&serde_json::json!({ "max_pages": 500, "concurrent_requests": 2 }))
+        .send().await.unwrap()
+        .json().await.unwrap();
+    assert_eq!(limits["max_pages"], 500);
+    assert_eq!(limits["concurrent_requests"], 2);
+    let no_change = client
+        .patch(format!("{}/crawl/{}", base, job_id))
+        .json(&serde_json::json!({}))
+        .send().await.unwrap();
+    assert_eq!(no_change.status(), 400);
+    let missing_patch = client
+        .patch(format!("{}/crawl/{}", base, unknown))
+        .json(&serde_json::json!({ "max_pages": 1 }))
+        .send().await.unwrap();
+    assert_eq!(missing_patch.status(), 404);
+
+    let job: Value = client
+        .get(format!("{}/crawl/{}", base, job_id))
+        .send().await.unwrap()
+        .json().await.unwrap();
+    assert_eq!(job["id"], job_id);
+    assert_eq!(job["seed_urls"], serde_json::json!(["http://127.0.0.1:9/"]));
+    assert!(job["started_at"].is_string());
+    assert_eq!(job["config"]["crawler"]["seed_urls"], job["seed_urls"]);
+    assert!(job["config"].get("storage").is_none());
+
+    let cancelled: Value = client
+        .delete(format!("{}/crawl/{}", base, job_id))
+        .send().await.unwrap()
+        .json().await.unwrap();
+    assert_eq!(cancelled["status"]["state"], "cancelled");
+    let job: Value = client.get(format!("{}/crawl/{}", base, job_id)).send().await.unwrap().json().await.unwrap();
+    assert!(job["completed_at"].is_string());
+
+    let missing = client.get(format!("{}/crawl/{}/status", base, unknown)).send().await.unwrap();
+    assert_eq!(missing.status(), 404);
+
+    let empty = client
+        .post(format!("{}/crawl", base))
+        .json(&serde_json::json!({ "seed_urls": [] }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(empty.status(), 400);
+}
+
+#[tokio::test]
+async fn test_metrics_endpoint() {
+    let (base, _dir) = start_server().await;
+    let client = reqwest::Client::new();
+
+    let response = client.get(format!("{}/metrics", base)).send().await.unwrap();
+    assert_eq!(response.status(), 200);
+    assert!(response.headers()["content-type"].to_str().unwrap().starts_with("text/plain"));
+
+    let body = response.text().await.unwrap();
+    assert!(body.contains("# TYPE pages_crawled_total counter"));
+    assert!(body.contains("# TYPE db_query_duration_seconds histogram"));
+}
@@ -0,0 +1,4 @@
+//! Integration tests for the HTTP API
+
+#[cfg(test)]
+mod api_tests;
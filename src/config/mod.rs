@@ -1,8 +1,7 @@
 mod crawler_config;
-mod database_config;
+mod validation;
 mod tests;
 
 
-pub use crawler_config::CrawlerConfig;
-pub use database_config::DatabaseConfig;
-
+pub use crawler_config::{AlgorithmSettings, CacheSettings, CircuitBreakerConfig, CrawlerConfig, CrawlerSettings, CustomFieldKind, CustomFieldSpec, NetworkSettings, PriorityFunctionKind, RetryConfig, SearchConfig, SearchSchemaBoosts, StorageSettings};
+pub use validation::{ConfigError, ConfigWarning, MAX_RETRIES_LIMIT, MIN_REQUEST_DELAY_MS};
@@ -1,4 +1,4 @@
-use crate::config::CrawlerConfig;
+use crate::config::{ConfigError, ConfigWarning, CrawlerConfig, CustomFieldKind, CustomFieldSpec, SearchConfig};
 
 #[test]
 fn test_default_config_creation() {
@@ -104,3 +104,125 @@ fn test_config_from_toml_string() {
     assert_eq!(config.network.request_timeout_secs, 30);
     assert_eq!(config.storage.database_url, "sqlite://test.db");
 }
+
+fn vars(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+    pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+}
+
+#[test]
+fn test_env_overrides_flat_and_nested_keys() {
+    let config = CrawlerConfig::default().with_env_overrides(vars(&[
+        ("CRAWLER_MAX_PAGES", "500"),
+        ("CRAWLER_DATABASE_URL", "postgres://crawler@db:5432/crawler"),
+        ("CRAWLER_SEED_URLS", "https://a.com, https://b.com"),
+        ("CRAWLER_NETWORK__REQUEST_TIMEOUT_SECS", "60"),
+        ("CRAWLER_NETWORK__RESPECT_ROBOTS_TXT", "false"),
+        ("CRAWLER_SEARCH__PAGERANK_WEIGHT", "0.5"),
+        ("CRAWLER_CHECKPOINT_PATH", "/data/checkpoint.json"),
+        ("CRAWLER_DOMAIN_DEPTH_OVERRIDES", r#"{"example.com": 5}"#),
+        ("CRAWLER_DB_CONNECT_RETRIES", "3"),
+        ("HOME", "/root"),
+    ])).unwrap();
+
+    assert_eq!(config.crawler.max_pages, 500);
+    assert_eq!(config.storage.database_url, "postgres://crawler@db:5432/crawler");
+    assert_eq!(config.crawler.seed_urls, vec!["https://a.com", "https://b.com"]);
+    assert_eq!(config.network.request_timeout_secs, 60);
+    assert!(!config.network.respect_robots_txt);
+    assert_eq!(config.search.pagerank_weight, 0.5);
+    assert_eq!(config.crawler.checkpoint_path.as_deref(), Some("/data/checkpoint.json"));
+    assert_eq!(config.crawler.domain_depth_overrides.get("example.com"), Some(&5));
+    assert_eq!(config.storage.db_connect_retries, 3);
+    // untouched values keep their defaults
+    assert_eq!(config.crawler.max_depth, CrawlerConfig::default().crawler.max_depth);
+}
+
+#[test]
+fn test_env_overrides_reject_invalid_values() {
+    let result = CrawlerConfig::default().with_env_overrides(vars(&[("CRAWLER_MAX_PAGES", "lots")]));
+    assert!(result.unwrap_err().to_string().contains("CRAWLER_MAX_PAGES"));
+
+    // unknown keys are skipped rather than failing the whole config
+    let config = CrawlerConfig::default()
+        .with_env_overrides(vars(&[("CRAWLER_NO_SUCH_KEY", "1"), ("CRAWLER_NETWORK__NOPE", "1")]))
+        .unwrap();
+    assert_eq!(config.crawler.max_pages, CrawlerConfig::default().crawler.max_pages);
+}
+
+#[test]
+fn test_from_env_reads_process_environment() {
+    // the only test touching the process environment, other tests pass their variables in
+    unsafe {
+        std::env::set_var("CRAWLER_CONCURRENT_REQUESTS", "3");
+        std::env::set_var("CRAWLER_NETWORK__MAX_REDIRECTS", "2");
+    }
+    let config = CrawlerConfig::from_env();
+    unsafe {
+        std::env::remove_var("CRAWLER_CONCURRENT_REQUESTS");
+        std::env::remove_var("CRAWLER_NETWORK__MAX_REDIRECTS");
+    }
+
+    let config = config.unwrap();
+    assert_eq!(config.crawler.concurrent_requests, 3);
+    assert_eq!(config.network.max_redirects, 2);
+}
+
+#[test]
+fn test_search_custom_fields_toml_round_trip() {
+    let search: SearchConfig = toml::from_str(r#"
+        [[custom_fields]]
+        name = "price"
+        kind = "numeric"
+        stored = true
+        fast = true
+
+        [[custom_fields]]
+        name = "isbn"
+        kind = "text"
+        indexed = true
+    "#).unwrap();
+
+    assert_eq!(search.custom_fields, vec![
+        CustomFieldSpec::numeric("price", true, true),
+        CustomFieldSpec::text("isbn", false, true),
+    ]);
+    assert_eq!(search.custom_fields[1].kind, CustomFieldKind::Text);
+
+    let reparsed: SearchConfig = toml::from_str(&toml::to_string(&search).unwrap()).unwrap();
+    assert_eq!(reparsed.custom_fields, search.custom_fields);
+}
+
+#[test]
+fn test_validate_default_config() {
+    let mut config = CrawlerConfig::default();
+    config.crawler.seed_urls = vec!["https://example.com".to_string()];
+    assert_eq!(config.validate(), Ok(vec![]));
+
+    config.crawler.seed_urls.clear();
+    config.network.max_retries = 20;
+    config.network.request_delay_ms = 10;
+    assert_eq!(config.validate(), Ok(vec![
+        ConfigWarning::NoSeedUrls,
+        ConfigWarning::TooManyRetries { value: 20, max: 10 },
+        ConfigWarning::ShortRequestDelay { value: 10, min: 100 },
+    ]));
+}
+
+#[test]
+fn test_validate_reports_every_error() {
+    let mut config = CrawlerConfig::default();
+    config.crawler.concurrent_requests = 0;
+    config.crawler.max_depth = 0;
+    config.crawler.seed_urls = vec!["https://example.com".to_string(), "example.com/no-scheme".to_string()];
+    config.storage.database_url = "mysql://localhost/crawler".to_string();
+
+    let errors = config.validate().unwrap_err();
+    assert_eq!(errors.len(), 4);
+    assert_eq!(errors[0], ConfigError::TooSmall { key: "crawler.concurrent_requests", min: 1, value: 0 });
+    assert_eq!(errors[1], ConfigError::TooSmall { key: "crawler.max_depth", min: 1, value: 0 });
+    assert!(matches!(&errors[2], ConfigError::InvalidSeedUrl { url, .. } if url == "example.com/no-scheme"));
+    assert!(matches!(errors[3], ConfigError::InvalidDatabaseUrl(_)));
+
+    config.storage.database_url = " ".to_string();
+    assert!(config.validate().unwrap_err().contains(&ConfigError::EmptyDatabaseUrl));
+}
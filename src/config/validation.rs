@@ -0,0 +1,96 @@
+use std::fmt;
+use thiserror::Error;
+
+use super::crawler_config::CrawlerConfig;
+
+/// Most retries before a failing URL is given up on without a warning
+pub const MAX_RETRIES_LIMIT: u32 = 10;
+
+/// Shortest delay between requests to a host that doesn't warn
+pub const MIN_REQUEST_DELAY_MS: u64 = 100;
+
+/// Configuration the crawler can't run with
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum ConfigError {
+    #[error("{key} must be at least {min}, got {value}")]
+    TooSmall { key: &'static str, min: u64, value: u64 },
+
+    #[error("Invalid seed URL '{url}': {reason}")]
+    InvalidSeedUrl { url: String, reason: String },
+
+    #[error("storage.database_url is empty")]
+    EmptyDatabaseUrl,
+
+    #[error("storage.database_url '{0}' must start with postgres://, postgresql:// or sqlite:")]
+    InvalidDatabaseUrl(String),
+}
+
+/// Configuration that works but is probably not what was intended
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigWarning {
+    /// Fine for `serve` and `index`, a crawl needs `--seed-urls`
+    NoSeedUrls,
+    TooManyRetries { value: u32, max: u32 },
+    ShortRequestDelay { value: u64, min: u64 },
+}
+
+impl fmt::Display for ConfigWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigWarning::NoSeedUrls => write!(f, "crawler.seed_urls is empty, crawls need --seed-urls"),
+            ConfigWarning::TooManyRetries { value, max } => {
+                write!(f, "network.max_retries is {}, more than {} retries rarely help", value, max)
+            }
+            ConfigWarning::ShortRequestDelay { value, min } => {
+                write!(f, "network.request_delay_ms is {}, below {} ms may overload servers", value, min)
+            }
+        }
+    }
+}
+
+impl CrawlerConfig {
+    /// Check the values the crawler can't work with, e.g. `concurrent_requests = 0`.
+    /// All problems are reported at once: every hard error when there are any,
+    /// otherwise the soft warnings.
+    pub fn validate(&self) -> Result<Vec<ConfigWarning>, Vec<ConfigError>> {
+        let mut errors = Vec::new();
+        let mut warnings = Vec::new();
+
+        let minimums = [
+            ("crawler.max_pages", self.crawler.max_pages as u64),
+            ("crawler.concurrent_requests", self.crawler.concurrent_requests as u64),
+            ("crawler.max_depth", self.crawler.max_depth as u64),
+            ("network.request_timeout_secs", self.network.request_timeout_secs),
+        ];
+        for (key, value) in minimums {
+            if value < 1 {
+                errors.push(ConfigError::TooSmall { key, min: 1, value });
+            }
+        }
+
+        if self.crawler.seed_urls.is_empty() {
+            warnings.push(ConfigWarning::NoSeedUrls);
+        }
+        for url in &self.crawler.seed_urls {
+            if let Err(e) = url::Url::parse(url) {
+                errors.push(ConfigError::InvalidSeedUrl { url: url.clone(), reason: e.to_string() });
+            }
+        }
+
+        let database_url = self.storage.database_url.trim();
+        if database_url.is_empty() {
+            errors.push(ConfigError::EmptyDatabaseUrl);
+        } else if !["postgres://", "postgresql://", "sqlite:"].iter().any(|scheme| database_url.starts_with(scheme)) {
+            errors.push(ConfigError::InvalidDatabaseUrl(database_url.to_string()));
+        }
+
+        if self.network.max_retries > MAX_RETRIES_LIMIT {
+            warnings.push(ConfigWarning::TooManyRetries { value: self.network.max_retries, max: MAX_RETRIES_LIMIT });
+        }
+        if self.network.request_delay_ms < MIN_REQUEST_DELAY_MS {
+            warnings.push(ConfigWarning::ShortRequestDelay { value: self.network.request_delay_ms, min: MIN_REQUEST_DELAY_MS });
+        }
+
+        if errors.is_empty() { Ok(warnings) } else { Err(errors) }
+    }
+}
@@ -1,13 +1,40 @@
-use crate::config::CrawlerConfig;
-use crate::core::{UrlFrontier, PageProcessor};
-pub(crate) use crate::models::{CrawlUrl, PageData, CrawlStatistics};
-use crate::network::HttpClient;
+use crate::config::{CrawlerConfig, PriorityFunctionKind};
+use crate::core::{UrlFrontier, PageProcessor, FeedProcessor};
+use crate::core::feed_processor::FEED_ITEM_PRIORITY;
+use crate::core::priority::priority_function_for;
+pub(crate) use crate::models::{CrawlUrl, CrawlResult, PageData, CrawlStatistics, CrawlEvent};
+use crate::network::{CachedHeaders, ConditionalResponse, CrawlHints, HttpClient, NetworkError, ResponseProcessor, RobotsTxtRules, ScreenshotCapture};
+use dashmap::{DashMap, DashSet};
+use futures_util::future::BoxFuture;
+use std::future::Future;
 use std::sync::Arc;
-use std::sync::atomic::{AtomicUsize, Ordering as AtomicOrdering};
-use tokio::task::JoinHandle;
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicUsize, Ordering as AtomicOrdering};
+use tokio::sync::{broadcast, watch};
+use tokio::task::{JoinHandle, JoinSet};
 use tracing::{error, info, debug, warn};
-use crate::core::scheduler::CrawlScheduler;
+use crate::core::scheduler::{CrawlScheduler, SchedulerError};
+use crate::storage::domain_repository::DomainRepository;
 use crate::storage::repository::PageRepository;
+use crate::utils::{canonicalize_with, extract_domain_info, url_host, CrawlMetrics, MetricsHandle};
+
+/// Priority of queued URLs from the domain with the highest PageRank, that of a depth 0 link
+const DOMAIN_RANK_PRIORITY: f64 = 1.0;
+
+/// Pages crawled between checks of `get_memory_usage` against `max_memory_mb`
+const MEMORY_CHECK_INTERVAL: u64 = 100;
+
+/// Backpressure ends once the queued and held links, the part of the estimate that
+/// crawling frees, drop below this fraction of `max_memory_mb`
+const MEMORY_RESUME_FRACTION: f64 = 0.8;
+
+/// Held back links handed to the queue per memory check outside of backpressure
+const MEMORY_RELEASE_BATCH: usize = 1000;
+
+/// Times a URL is queued again after its domain's circuit was open before it counts as failed
+const MAX_CIRCUIT_DEFERRALS: u32 = 3;
+
+/// Called with every fetched page before it is saved, see `WebCrawler::with_page_callback`
+pub type PageCallback = Arc<dyn Fn(PageData) -> BoxFuture<'static, crate::Result<()>> + Send + Sync>;
 
 /// Main web crawler that orchestrates the crawling process
 #[derive(Clone)]
@@ -17,45 +44,329 @@ pub struct WebCrawler {
     page_processor: Arc<PageProcessor>,
     scheduler: Arc<CrawlScheduler>,
     http_client: Arc<HttpClient>,
+    // feeds are shared by every page of a site, fetch each once per crawl
+    fetched_feeds: Arc<DashSet<String>>,
+    // set when capture_screenshots is enabled and a capture backend is available
+    screenshot_capture: Option<Arc<dyn ScreenshotCapture>>,
+    // cached robots.txt Crawl-delays, looked up once per domain and crawl
+    domain_repository: Option<DomainRepository>,
+    crawl_delays_loaded: Arc<DashSet<String>>,
+    // parsed robots.txt of every domain crawled so far, when respect_robots_txt is on
+    robots_rules: Arc<DashMap<String, Arc<RobotsTxtRules>>>,
+    // limits that can change mid-crawl, see set_max_pages and set_concurrent_requests
+    max_pages: Arc<AtomicUsize>,
+    concurrent_requests: Arc<watch::Sender<usize>>,
+    // set while the crawl state is estimated above max_memory_mb, discovered links are held back
+    backpressure: Arc<AtomicBool>,
 
     // Statistics tracking
-    pages_crawled: Arc<AtomicUsize>,
-    pages_failed: Arc<AtomicUsize>,
+    crawl_metrics: Arc<CrawlMetrics>,
+    max_depth_reached: Arc<AtomicU32>,
+    errors_by_type: Arc<DashMap<String, usize>>,
+    // pages a dry run did not save
+    simulated_saves: Arc<AtomicUsize>,
+    // pages robots.txt disallowed
+    pages_skipped: Arc<AtomicUsize>,
+    // times each URL was put back because its domain's circuit was open
+    circuit_deferrals: Arc<DashMap<String, u32>>,
+    // deferred URLs waiting to be queued again, workers don't stop while there are any
+    deferred_urls: Arc<AtomicUsize>,
     start_time: std::time::Instant,
+    metrics: MetricsHandle,
+    resumed_from_checkpoint: bool,
+    events: broadcast::Sender<CrawlEvent>,
+    page_callback: Option<PageCallback>,
+}
+
+/// Estimated heap use of the crawl state, see `WebCrawler::get_memory_usage`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MemoryUsage {
+    /// Seen and crawled URL sets of the frontier
+    pub seen_urls_bytes: usize,
+    pub frontier_queue_bytes: usize,
+    /// Links held back under backpressure, queued once it ends
+    pub held_links_bytes: usize,
+    /// robots.txt rules, fetched feeds and other per-crawl lookups
+    pub cache_bytes: usize,
+    pub total_estimate_bytes: usize,
+}
+
+/// A processed page with the size and duration of its download
+struct FetchedPage {
+    page_data: PageData,
+    bytes: u64,
+    fetch_time_ms: u64,
+}
+
+/// What `WebCrawler::fetch_page` came back with
+enum FetchOutcome {
+    Fetched(Box<FetchedPage>),
+    // unchanged since the validators sent along were recorded
+    NotModified,
+    // robots.txt doesn't let the crawler fetch the page, nothing was requested
+    Disallowed,
 }
 
 impl WebCrawler {
-    pub async fn new(config: CrawlerConfig) -> crate::Result<Self> {
-        let url_frontier = Arc::new(UrlFrontier::new(config.crawler.max_pages * 10));
+    /// `crawl_metrics` counts this crawl's pages, share it to watch the crawl from outside
+    pub async fn new(config: CrawlerConfig, crawl_metrics: Arc<CrawlMetrics>) -> crate::Result<Self> {
+        let checkpoint = config.crawler.checkpoint_path.as_deref().map(std::path::Path::new);
+        let resumed_from_checkpoint = config.crawler.resume && checkpoint.is_some_and(|path| path.exists());
+        let url_frontier = match checkpoint {
+            Some(path) if resumed_from_checkpoint => UrlFrontier::load_checkpoint(path)?,
+            _ => UrlFrontier::new(config.crawler.max_pages * 10),
+        };
+
+        let mut url_frontier = url_frontier
+            .with_max_depth(config.crawler.max_depth)
+            .with_domain_depth_overrides(config.crawler.domain_depth_overrides.clone())
+            .with_strip_params(config.crawler.strip_params.clone());
+        if config.algorithms.enable_opic || config.algorithms.priority_function == PriorityFunctionKind::Opic {
+            url_frontier = url_frontier.with_opic_mode();
+        }
         let mut page_processor = PageProcessor::new();
 
-        // Add priority domains from config
-        for domain in &config.algorithms.priority_boost_domains {
-            page_processor.add_priority_domain(domain.clone());
+        // Link priority function and boost domains from config
+        page_processor.set_priority_function(priority_function_for(&config.algorithms, url_frontier.opic_scorer()));
+        let url_frontier = Arc::new(url_frontier);
+
+        for pattern in &config.crawler.allowed_domains {
+            if let Err(e) = page_processor.add_allowed_domain(pattern) {
+                warn!("Ignoring invalid allowed domain pattern '{}': {}", pattern, e);
+            }
+        }
+        for pattern in &config.crawler.blocked_domains {
+            if let Err(e) = page_processor.add_blocked_domain(pattern) {
+                warn!("Ignoring invalid blocked domain pattern '{}': {}", pattern, e);
+            }
         }
 
         // Create HTTP Client with config
         let http_client = HttpClient::new()?
             .with_timeout(std::time::Duration::from_secs(config.network.request_timeout_secs))
             .with_user_agents(config.network.user_agents.clone())
-            .with_max_content_size(config.network.max_content_size_mb * 1024 * 1024);
+            .with_max_content_size(config.network.max_content_size_mb * 1024 * 1024)
+            .with_cookie_store(config.network.persist_cookies)
+            .with_dns_overrides(config.network.dns_overrides.clone())
+            .with_dns_cache_ttl(std::time::Duration::from_secs(config.network.dns_cache_ttl_secs))
+            .with_allowed_content_types(config.network.allowed_content_types.clone());
+        let http_client = with_js_rendering(http_client, config.crawler.enable_js_rendering);
 
         let scheduler = Arc::new(CrawlScheduler::new(&config));
+        let (events, _) = broadcast::channel(config.crawler.event_channel_capacity.max(1));
+
+        let screenshot_capture = if config.crawler.capture_screenshots {
+            default_screenshot_capture()
+        } else {
+            None
+        };
 
         let crawler = Self {
+            screenshot_capture,
+            domain_repository: None,
+            crawl_delays_loaded: Arc::new(DashSet::new()),
+            robots_rules: Arc::new(DashMap::new()),
+            max_pages: Arc::new(AtomicUsize::new(config.crawler.max_pages)),
+            concurrent_requests: Arc::new(watch::Sender::new(config.crawler.concurrent_requests)),
+            backpressure: Arc::new(AtomicBool::new(false)),
             config,
             url_frontier,
             page_processor: Arc::new(page_processor),
             scheduler,
             http_client: Arc::new(http_client),
-            pages_crawled: Arc::new(AtomicUsize::new(0)),
-            pages_failed: Arc::new(AtomicUsize::new(0)),
+            fetched_feeds: Arc::new(DashSet::new()),
+            crawl_metrics,
+            max_depth_reached: Arc::new(AtomicU32::new(0)),
+            errors_by_type: Arc::new(DashMap::new()),
+            simulated_saves: Arc::new(AtomicUsize::new(0)),
+            pages_skipped: Arc::new(AtomicUsize::new(0)),
+            circuit_deferrals: Arc::new(DashMap::new()),
+            deferred_urls: Arc::new(AtomicUsize::new(0)),
             start_time: std::time::Instant::now(),
+            metrics: MetricsHandle::global(),
+            resumed_from_checkpoint,
+            events,
+            page_callback: None,
         };
 
         Ok(crawler)
     }
 
+    /// The configuration the crawler was created with, runtime changes to
+    /// `max_pages` and `concurrent_requests` are not reflected here
+    pub fn config(&self) -> &CrawlerConfig {
+        &self.config
+    }
+
+    pub fn max_pages(&self) -> usize {
+        self.max_pages.load(AtomicOrdering::Relaxed)
+    }
+
+    /// Stop after `max_pages` pages, also applies to a crawl in progress
+    pub fn set_max_pages(&self, max_pages: usize) {
+        self.max_pages.store(max_pages, AtomicOrdering::Relaxed);
+        info!("Max pages set to {}", max_pages);
+    }
+
+    pub fn concurrent_requests(&self) -> usize {
+        *self.concurrent_requests.borrow()
+    }
+
+    /// Run up to `concurrent_requests` (at least 1) requests at once, also applies to a
+    /// crawl in progress: workers are added right away, surplus ones stop after their current page
+    pub fn set_concurrent_requests(&self, concurrent_requests: usize) {
+        let concurrent_requests = concurrent_requests.max(1);
+        self.scheduler.set_max_concurrent(concurrent_requests);
+        self.concurrent_requests.send_replace(concurrent_requests);
+        info!("Concurrent requests set to {}", concurrent_requests);
+    }
+
+    /// Report into `metrics` instead of the process-wide handle
+    pub fn with_metrics(mut self, metrics: MetricsHandle) -> Self {
+        self.metrics = metrics;
+        self
+    }
+
+    pub fn metrics(&self) -> &MetricsHandle {
+        &self.metrics
+    }
+
+    /// Take screenshots with `capture` instead of the headless browser, still only
+    /// when `capture_screenshots` is enabled
+    pub fn with_screenshot_capture(mut self, capture: Arc<dyn ScreenshotCapture>) -> Self {
+        self.screenshot_capture = Some(capture);
+        self
+    }
+
+    /// Honour the `Crawl-delay` of robots.txt files cached in `repository`
+    pub fn with_domain_repository(mut self, repository: DomainRepository) -> Self {
+        self.domain_repository = Some(repository);
+        self
+    }
+
+    /// Hand every successfully fetched page to `callback` before it is saved, e.g. to push it
+    /// to a queue. Workers wait for the callback; an `Err` is logged and the crawl goes on.
+    pub fn with_page_callback<F, Fut>(mut self, callback: F) -> Self
+    where
+        F: Fn(PageData) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = crate::Result<()>> + Send + 'static,
+    {
+        self.page_callback = Some(Arc::new(move |page| Box::pin(callback(page))));
+        self
+    }
+
+    /// Estimate the memory held by the frontier and the crawler's caches from the sizes
+    /// of the URLs and strings they store, allocator overhead is not included
+    pub async fn get_memory_usage(&self) -> MemoryUsage {
+        let string_bytes = |s: &str| std::mem::size_of::<String>() + s.len();
+        let seen_urls_bytes = self.url_frontier.seen_urls_bytes();
+        let frontier_queue_bytes = self.url_frontier.queue_bytes().await;
+        let held_links_bytes = self.url_frontier.held_bytes().await;
+        let cache_bytes = self.robots_rules.iter()
+                .map(|entry| string_bytes(entry.key()) + entry.value().estimated_bytes())
+                .sum::<usize>()
+            + self.fetched_feeds.iter().map(|url| string_bytes(&url)).sum::<usize>()
+            + self.crawl_delays_loaded.iter().map(|domain| string_bytes(&domain)).sum::<usize>()
+            + self.errors_by_type.iter().map(|entry| string_bytes(entry.key()) + std::mem::size_of::<usize>()).sum::<usize>();
+
+        MemoryUsage {
+            seen_urls_bytes,
+            frontier_queue_bytes,
+            held_links_bytes,
+            cache_bytes,
+            total_estimate_bytes: seen_urls_bytes + frontier_queue_bytes + held_links_bytes + cache_bytes,
+        }
+    }
+
+    /// Whether discovered links are currently held back because of `max_memory_mb`
+    pub fn is_under_backpressure(&self) -> bool {
+        self.backpressure.load(AtomicOrdering::Relaxed)
+    }
+
+    // Enter backpressure above max_memory_mb. The seen sets only grow, so leave it once the
+    // queued and held links drop below MEMORY_RESUME_FRACTION of the limit, or the queue
+    // runs dry. Outside of backpressure the held links go back to the queue in batches.
+    pub(crate) async fn check_memory(&self) {
+        let max_bytes = self.config.crawler.max_memory_mb.saturating_mul(1024 * 1024);
+        if max_bytes == 0 {
+            // a checkpoint can still bring held back links along
+            self.release_held_links().await;
+            return;
+        }
+
+        let usage = self.get_memory_usage().await;
+        let total = usage.total_estimate_bytes;
+        let freeable = usage.frontier_queue_bytes + usage.held_links_bytes;
+        if !self.is_under_backpressure() {
+            if total > max_bytes {
+                self.backpressure.store(true, AtomicOrdering::Relaxed);
+                warn!(
+                    total_bytes = total,
+                    seen_urls_bytes = usage.seen_urls_bytes,
+                    frontier_queue_bytes = usage.frontier_queue_bytes,
+                    held_links_bytes = usage.held_links_bytes,
+                    cache_bytes = usage.cache_bytes,
+                    "Crawl state above max_memory_mb ({} MB), holding back discovered links",
+                    self.config.crawler.max_memory_mb
+                );
+                return;
+            }
+        } else if (freeable as f64) < max_bytes as f64 * MEMORY_RESUME_FRACTION || usage.frontier_queue_bytes == 0 {
+            self.backpressure.store(false, AtomicOrdering::Relaxed);
+            warn!(
+                total_bytes = total,
+                frontier_queue_bytes = usage.frontier_queue_bytes,
+                held_links_bytes = usage.held_links_bytes,
+                "Frontier crawled down, queueing discovered links again"
+            );
+        } else {
+            return;
+        }
+
+        // also picks up links held by workers that saw backpressure just before it ended
+        self.release_held_links().await;
+    }
+
+    // Queue the next batch of links held back under backpressure
+    async fn release_held_links(&self) {
+        let released = self.url_frontier.release_held(MEMORY_RELEASE_BATCH).await;
+        if released > 0 {
+            self.metrics.urls_discovered_total.inc_by(released as u64);
+            debug!("Queued {} links held back under max_memory_mb", released);
+        }
+    }
+
+    // Queue discovered links, or hold them back in the frontier while memory is short
+    pub(crate) async fn queue_links(&self, links: Vec<CrawlUrl>) -> usize {
+        if self.is_under_backpressure() {
+            let held = self.url_frontier.hold_urls(links).await;
+            debug!("Under memory backpressure, held back {} links", held);
+            0
+        } else {
+            self.url_frontier.add_urls(links).await
+        }
+    }
+
+    /// Queue of URLs this crawler works through
+    pub fn url_frontier(&self) -> &Arc<UrlFrontier> {
+        &self.url_frontier
+    }
+
+    pub fn crawl_metrics(&self) -> &Arc<CrawlMetrics> {
+        &self.crawl_metrics
+    }
+
+    /// Receive crawl progress events from now on. Receivers that fall more than
+    /// `event_channel_capacity` events behind skip the oldest ones.
+    pub fn subscribe(&self) -> broadcast::Receiver<CrawlEvent> {
+        self.events.subscribe()
+    }
+
+    fn emit(&self, event: CrawlEvent) {
+        // no subscribers is not an error
+        let _ = self.events.send(event);
+    }
+
     // 🔥 FIX 1: Correct syntax for start_crawling_with_repository
     pub async fn start_crawling_with_repository(
         &self,
@@ -69,44 +380,199 @@ impl WebCrawler {
         self.crawl_internal(None).await
     }
 
+    /// Crawl the seed URLs without saving pages, handing each fetched page to `callback`
+    /// instead, see `with_page_callback`
+    pub async fn crawl_with_callback<F, Fut>(&self, callback: F) -> crate::Result<CrawlStatistics>
+    where
+        F: Fn(PageData) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = crate::Result<()>> + Send + 'static,
+    {
+        self.clone().with_page_callback(callback).crawl_internal(None).await
+    }
+
+    /// Fetch and process `urls` right away, outside the frontier, so pages crawled before
+    /// are fetched again. Requests still share the scheduler's concurrency limit and
+    /// per-domain delay. Results are in the order of `urls`.
+    pub async fn crawl_url_list(&self, urls: &[String]) -> crate::Result<Vec<CrawlResult>> {
+        info!("Recrawling {} URLs", urls.len());
+
+        let handles: Vec<JoinHandle<CrawlResult>> = urls.iter()
+            .map(|url| {
+                let crawler = self.clone();
+                let url = url.clone();
+                tokio::spawn(async move { crawler.crawl_listed_url(url).await })
+            })
+            .collect();
+
+        let mut results = Vec::with_capacity(handles.len());
+        for (handle, url) in handles.into_iter().zip(urls) {
+            results.push(handle.await.unwrap_or_else(|e| CrawlResult::Failed {
+                url: url.clone(),
+                error: e.to_string(),
+                retry_count: 0,
+            }));
+        }
+
+        Ok(results)
+    }
+
+    async fn crawl_listed_url(&self, url: String) -> CrawlResult {
+        let domain = match self.extract_domain(&url) {
+            Ok(domain) => domain,
+            Err(e) => return CrawlResult::Failed { url, error: e.to_string(), retry_count: 0 },
+        };
+
+        let crawl_url = CrawlUrl {
+            url: url.clone(),
+            priority: 0.0,
+            depth: 0,
+            discovered_at: chrono::Utc::now().timestamp() as u64,
+        };
+
+        match self.fetch_page(crawl_url, &domain, None, None).await {
+            Ok(FetchOutcome::Fetched(fetched)) => {
+                let page_data = fetched.page_data;
+                self.emit(CrawlEvent::PageCrawled {
+                    url: page_data.url.clone(),
+                    depth: page_data.depth,
+                    quality: page_data.content_quality_score,
+                });
+                CrawlResult::Success(page_data)
+            }
+            // only conditional requests come back unmodified
+            Ok(FetchOutcome::NotModified) => CrawlResult::Skipped { url, reason: "not modified".to_string() },
+            Ok(FetchOutcome::Disallowed) => {
+                self.pages_skipped.fetch_add(1, AtomicOrdering::Relaxed);
+                CrawlResult::Skipped { url, reason: "disallowed by robots.txt".to_string() }
+            }
+            // nothing was requested, the domain failed too often recently
+            Err(SchedulerError::CircuitOpen(domain)) => {
+                CrawlResult::Skipped { url, reason: format!("circuit open for {}", domain) }
+            }
+            Err(e) => {
+                let retry_count = match e {
+                    SchedulerError::MaxRetriesExceeded(_) => self.config.network.retry_config().max_retries,
+                    _ => 0,
+                };
+                self.emit(CrawlEvent::PageFailed { url: url.clone(), error: e.to_string() });
+                CrawlResult::Failed { url, error: e.to_string(), retry_count }
+            }
+        }
+    }
+
     // 🔥 FIX 2: Add the missing crawl_internal method
     async fn crawl_internal(&self, repository: Option<PageRepository>) -> crate::Result<CrawlStatistics> {
         info!("Starting web crawler with {} seed URLs", self.config.crawler.seed_urls.len());
 
         // Add seed URLs to frontier
         self.initialize_frontier().await?;
+        self.emit(CrawlEvent::FrontierUpdated {
+            queue_size: self.url_frontier.get_stats().await.queue_size,
+        });
 
         // Start crawling workers
-        let mut worker_handles: Vec<JoinHandle<crate::Result<()>>> = Vec::new();
+        let mut workers: JoinSet<crate::Result<()>> = JoinSet::new();
+        let mut concurrency = self.concurrent_requests.subscribe();
+        let mut spawned = 0;
+
+        // Restore cookies from a previous run
+        if self.config.network.persist_cookies
+            && let Some(repo) = &repository
+        {
+            match repo.load_cookies().await {
+                Ok(cookies) => self.http_client.import_cookies(&cookies),
+                Err(e) => warn!("Failed to load persisted cookies: {}", e),
+            }
+        }
 
         // Clone repository for workers
         let repo_arc = repository.map(Arc::new);
 
-        // Spawn crawler worker tasks
-        for worker_id in 0..self.config.crawler.concurrent_requests {
-            let crawler_clone = self.clone();
-            let repo_clone = repo_arc.clone();
+        // Queued URLs are re-scored with the stored PageRank once per reprioritize_interval pages
+        let reprioritize_interval = self.config.crawler.reprioritize_interval as u64;
+        let mut reprioritize_ticker = tokio::time::interval(tokio::time::Duration::from_secs(1));
+        let mut reprioritized_at = 0;
 
-            let handle = tokio::spawn(async move {
-                crawler_clone.crawler_worker(worker_id, repo_clone).await
-            });
-            worker_handles.push(handle);
-        }
+        // Spawn crawler worker tasks, following changes of the concurrency limit
+        // until all workers have completed
+        loop {
+            let target = *concurrency.borrow_and_update();
+            // workers numbered at or above a lowered limit stop by themselves
+            spawned = spawned.min(target);
+            while spawned < target {
+                let crawler_clone = self.clone();
+                let repo_clone = repo_arc.clone();
+                let worker_id = spawned;
+
+                workers.spawn(async move {
+                    crawler_clone.crawler_worker(worker_id, repo_clone).await
+                });
+                spawned += 1;
+            }
 
-        // Wait for all workers to complete
-        for handle in worker_handles {
-            if let Err(e) = handle.await {
-                error!("Web crawler worker task failed: {}", e);
+            tokio::select! {
+                joined = workers.join_next() => match joined {
+                    Some(Err(e)) => error!("Web crawler worker task failed: {}", e),
+                    Some(Ok(_)) => {}
+                    None => break,
+                },
+                // the sender lives as long as the crawler, so this never errors
+                _ = concurrency.changed() => {}
+                _ = reprioritize_ticker.tick(), if reprioritize_interval > 0 => {
+                    let crawled = self.crawl_metrics.pages_crawled();
+                    if let Some(ref repo) = repo_arc
+                        && crawled >= reprioritized_at + reprioritize_interval
+                    {
+                        reprioritized_at = crawled;
+                        self.reprioritize_frontier(repo).await;
+                    }
+                }
             }
         }
 
+        // Persist cookies for the next run
+        if self.config.network.persist_cookies
+            && !self.config.crawler.dry_run
+            && let Some(repo) = &repo_arc
+            && let Err(e) = repo.save_cookies(&self.http_client.export_cookies()).await
+        {
+            warn!("Failed to persist cookies: {}", e);
+        }
+
+        self.save_checkpoint().await;
+
         // Generate final stats
         let stats = self.generate_statistics().await;
         info!("Crawling completed: {:?}", stats);
+        self.emit(CrawlEvent::CrawlCompleted(stats.clone()));
 
         Ok(stats)
     }
 
+    /// Raise queued URLs of domains with a high stored PageRank, the best ranked domain's
+    /// URLs to at least `DOMAIN_RANK_PRIORITY`. Does nothing before PageRank was calculated.
+    async fn reprioritize_frontier(&self, repository: &PageRepository) {
+        let ranks = match repository.get_domain_pagerank().await {
+            Ok(ranks) => ranks,
+            Err(e) => {
+                warn!("Failed to load domain PageRank: {}", e);
+                return;
+            }
+        };
+        let max_rank = ranks.values().copied().fold(0.0, f64::max);
+        if max_rank <= 0.0 {
+            return;
+        }
+
+        let changed = self.url_frontier.reprioritize(|queued| {
+            let rank = extract_domain_info(&queued.url)
+                .and_then(|info| ranks.get(&info.registrable_domain).copied())
+                .unwrap_or(0.0);
+            queued.priority.max(DOMAIN_RANK_PRIORITY * rank / max_rank)
+        }).await;
+        info!("Reprioritized queued URLs with PageRank of {} domains, {} changed", ranks.len(), changed);
+    }
+
     // 🔥 FIX 3: Update crawler_worker to accept repository
     async fn crawler_worker(
         &self,
@@ -115,14 +581,20 @@ impl WebCrawler {
     ) -> crate::Result<()> {
         info!("Starting crawler worker {}", worker_id);
 
-        while self.pages_crawled.load(AtomicOrdering::Relaxed) < self.config.crawler.max_pages {
+        while self.crawl_metrics.pages_crawled() < self.max_pages() as u64
+            && worker_id < self.concurrent_requests()
+        {
             // Get next URL from frontier
             let crawl_url = match self.url_frontier.next_url().await {
                 Some(url) => url,
                 None => {
                     tokio::time::sleep(tokio::time::Duration::from_millis(1000)).await;
                     if self.url_frontier.is_empty().await {
-                        break;
+                        // an empty queue ends backpressure and queues held back links, if any
+                        self.check_memory().await;
+                        if self.url_frontier.is_empty().await && self.deferred_urls.load(AtomicOrdering::SeqCst) == 0 {
+                            break;
+                        }
                     }
                     continue;
                 }
@@ -137,13 +609,15 @@ impl WebCrawler {
             let domain = self.extract_domain(&crawl_url.url)?;
 
             // Crawl the page
-            match self.crawl_single_page(crawl_url, &domain, repository.as_ref()).await {
-                Ok(_) => {
-                    self.pages_crawled.fetch_add(1, AtomicOrdering::Relaxed);
+            // failures are logged and counted by crawl_single_page
+            if self.crawl_single_page(crawl_url, &domain, repository.as_ref()).await.is_ok() {
+                let crawled = self.crawl_metrics.pages_crawled();
+                // an interval of 0 never matches, disabling periodic checkpoints
+                if crawled.is_multiple_of(self.config.crawler.checkpoint_interval as u64) {
+                    self.save_checkpoint().await;
                 }
-                Err(e) => {
-                    self.pages_failed.fetch_add(1, AtomicOrdering::Relaxed);
-                    error!("Failed to crawl page: {}", e);
+                if crawled.is_multiple_of(MEMORY_CHECK_INTERVAL) {
+                    self.check_memory().await;
                 }
             }
         }
@@ -152,29 +626,160 @@ impl WebCrawler {
         Ok(())
     }
 
-    // 🔥 FIX 4: Update crawl_single_page to save to database
-    async fn crawl_single_page(
+    pub(crate) async fn crawl_single_page(
         &self,
         crawl_url: CrawlUrl,
         domain: &str,
         repository: Option<&Arc<PageRepository>>
     ) -> crate::Result<()> {
         let url = crawl_url.url.clone();
+        let depth = crawl_url.depth;
 
-        // Use scheduler to manage the request
-        let page_data = self.scheduler.schedule_crawl(domain, || async {
-            self.fetch_and_process_page(crawl_url.clone()).await
-        }).await?;
+        self.metrics.active_workers.inc();
+        let result = self.crawl_and_store_page(crawl_url.clone(), domain, repository).await;
+        self.metrics.active_workers.dec();
+
+        let deferred = match &result {
+            Err(e) if Self::is_circuit_open(e.as_ref()) => self.defer_until_circuit_closes(crawl_url),
+            _ => false,
+        };
+
+        match &result {
+            Ok(FetchOutcome::Fetched(fetched)) => {
+                self.metrics.pages_crawled_total.inc();
+                self.crawl_metrics.record_page_crawled(fetched.bytes, fetched.fetch_time_ms);
+                let quality = fetched.page_data.content_quality_score;
+                self.emit(CrawlEvent::PageCrawled { url, depth, quality });
+            }
+            // unchanged since the last crawl, nothing was downloaded
+            Ok(FetchOutcome::NotModified) => {
+                self.metrics.pages_crawled_total.inc();
+                self.crawl_metrics.record_page_crawled(0, 0);
+            }
+            Ok(FetchOutcome::Disallowed) => {
+                self.pages_skipped.fetch_add(1, AtomicOrdering::Relaxed);
+            }
+            Err(_) if deferred => {
+                debug!(url = %url, domain = domain, "Circuit open, crawling the URL again later");
+            }
+            Err(e) => {
+                self.metrics.pages_failed_total.inc();
+                self.crawl_metrics.record_page_failed();
+                *self.errors_by_type.entry(Self::error_type(e.as_ref())).or_insert(0) += 1;
+
+                match Self::network_error(e.as_ref()) {
+                    Some(error) => warn!(
+                        url = %url,
+                        domain = error.domain().unwrap_or(domain),
+                        kind = error.kind(),
+                        status = ?error.http_status(),
+                        retryable = error.is_retryable(),
+                        error = %error,
+                        "Crawl failed"
+                    ),
+                    None => warn!(url = %url, domain = domain, error = %e, "Crawl failed"),
+                }
+                self.emit(CrawlEvent::PageFailed { url, error: e.to_string() });
+            }
+        }
+        let queue_size = self.url_frontier.get_stats().await.queue_size;
+        self.metrics.queue_depth.set(queue_size as i64);
+        self.emit(CrawlEvent::FrontierUpdated { queue_size });
+
+        result.map(|_| ())
+    }
+
+    // Queue `crawl_url` again once its domain's circuit lets a trial request through, up to
+    // MAX_CIRCUIT_DEFERRALS times. False when the URL was deferred that often already.
+    fn defer_until_circuit_closes(&self, crawl_url: CrawlUrl) -> bool {
+        {
+            let mut deferrals = self.circuit_deferrals.entry(crawl_url.url.clone()).or_insert(0);
+            if *deferrals >= MAX_CIRCUIT_DEFERRALS {
+                return false;
+            }
+            *deferrals += 1;
+        }
+
+        self.deferred_urls.fetch_add(1, AtomicOrdering::SeqCst);
+        let open_duration = std::time::Duration::from_secs(self.config.network.circuit_breaker.open_duration_secs);
+        let crawler = self.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(open_duration).await;
+            crawler.url_frontier.requeue(crawl_url).await;
+            crawler.deferred_urls.fetch_sub(1, AtomicOrdering::SeqCst);
+        });
+        true
+    }
+
+    // 🔥 FIX 4: Update crawl_single_page to save to database
+    // Returns the fetched page with its links handed to the frontier
+    async fn crawl_and_store_page(
+        &self,
+        crawl_url: CrawlUrl,
+        domain: &str,
+        repository: Option<&Arc<PageRepository>>
+    ) -> crate::Result<FetchOutcome> {
+        let url = crawl_url.url.clone();
+
+        // Validators from the previous crawl turn a recrawl of an unchanged page into a 304
+        let cached_headers = match repository {
+            Some(repo) => {
+                let started = std::time::Instant::now();
+                let cached = repo.get_cached_headers(&url).await;
+                self.metrics.observe_db_query(started.elapsed());
+                cached.unwrap_or_else(|e| {
+                    warn!("Failed to load cached headers for {}: {}", url, e);
+                    None
+                })
+            }
+            None => None,
+        };
+
+        let sent_validators = cached_headers.is_some();
+        let mut fetched = match self.fetch_page(crawl_url, domain, cached_headers, repository).await? {
+            FetchOutcome::Fetched(fetched) => fetched,
+            FetchOutcome::NotModified => {
+                info!("Not modified since last crawl: {}", url);
+                self.crawl_metrics.record_cache_hit();
+                self.url_frontier.mark_crawled(&url);
+                return Ok(FetchOutcome::NotModified);
+            }
+            FetchOutcome::Disallowed => return Ok(FetchOutcome::Disallowed),
+        };
+        if sent_validators {
+            self.crawl_metrics.record_cache_miss();
+        }
+        let page_data = &mut fetched.page_data;
+
+        if let Some(callback) = &self.page_callback
+            && let Err(e) = callback(page_data.clone()).await
+        {
+            warn!("Page callback failed for {}: {}", url, e);
+        }
 
         // 🔥 NEW: Save to database if repository exists
-        if let Some(repo) = repository {
-            match repo.save_page(&page_data, 0).await {
-                Ok(page_id) => {
-                    info!("💾 Saved page to database: ID {}, URL: {}", page_id, page_data.url);
+        if self.config.crawler.dry_run {
+            self.simulated_saves.fetch_add(1, AtomicOrdering::Relaxed);
+            info!(
+                "Dry run, not saving {} (title: {:?}, {} words, {} links, quality {:.2})",
+                page_data.url,
+                page_data.title,
+                page_data.word_count,
+                page_data.outgoing_links.len(),
+                page_data.content_quality_score
+            );
+        } else if let Some(repo) = repository {
+            let started = std::time::Instant::now();
+            let saved = repo.save_page(page_data, 0).await;
+            self.metrics.observe_db_query(started.elapsed());
+
+            match saved {
+                Ok(saved) => {
+                    info!("💾 Saved page to database: ID {}, version {}, URL: {}", saved.id, saved.version, page_data.url);
 
                     // Save links if any
                     if !page_data.outgoing_links.is_empty() {
-                        if let Err(e) = repo.save_links(page_id, &page_data.outgoing_links).await {
+                        if let Err(e) = repo.save_links(saved.id, &page_data.outgoing_links).await {
                             warn!("⚠️ Failed to save links: {}", e);
                         }
                     }
@@ -185,28 +790,71 @@ impl WebCrawler {
             }
         }
 
-        // Mark as crawled
+        // Mark as crawled, along with the canonical URL it was stored under
         self.url_frontier.mark_crawled(&url);
+        if page_data.alias_url().is_some() {
+            let canonical = page_data.storage_url().to_string();
+            debug!("{} declares canonical URL {}", url, canonical);
+            self.url_frontier.mark_crawled(&canonical);
+        }
+        self.max_depth_reached.fetch_max(page_data.depth, AtomicOrdering::Relaxed);
 
-        // Add discovered links to frontier
-        let links_added = self.url_frontier.add_urls(page_data.outgoing_links).await;
+        // Add discovered links to frontier, held back while memory is short
+        self.url_frontier.record_crawl(&url, &page_data.outgoing_links);
+        let mut links_added = self.queue_links(std::mem::take(&mut page_data.outgoing_links)).await;
+        if self.config.crawler.follow_feeds {
+            links_added += self.follow_feeds(&page_data.feed_urls, page_data.depth + 1).await;
+        }
+        self.metrics.urls_discovered_total.inc_by(links_added as u64);
 
         info!("Crawled: {} (found {} new links)", url, links_added);
 
-        Ok(())
+        Ok(FetchOutcome::Fetched(fetched))
+    }
+
+    // Fetch a page through the scheduler unless robots.txt disallows it, shared by the
+    // crawl loop and crawl_url_list
+    async fn fetch_page(
+        &self,
+        crawl_url: CrawlUrl,
+        domain: &str,
+        cached_headers: Option<CachedHeaders>,
+        repository: Option<&Arc<PageRepository>>,
+    ) -> Result<FetchOutcome, SchedulerError> {
+        if self.config.network.respect_robots_txt && !self.robots_allowed(&crawl_url.url, domain, repository).await {
+            info!("Skipping {}, disallowed by robots.txt", crawl_url.url);
+            return Ok(FetchOutcome::Disallowed);
+        }
+
+        self.load_crawl_delay(domain).await;
+
+        let fetched = self.scheduler.schedule_crawl(domain, || async {
+            self.fetch_and_process_page(crawl_url.clone(), cached_headers.clone()).await
+        }).await?;
+        Ok(fetched.map_or(FetchOutcome::NotModified, |fetched| FetchOutcome::Fetched(Box::new(fetched))))
     }
 
-    /// Fetch and process a single page (REAL HTTP CLIENT)
-    async fn fetch_and_process_page(&self, crawl_url: CrawlUrl) -> Result<PageData, Box<dyn std::error::Error + Send + Sync>> {
+    /// Fetch and process a single page (REAL HTTP CLIENT), None when the server
+    /// reports it unchanged since `cached_headers` were recorded
+    async fn fetch_and_process_page(
+        &self,
+        crawl_url: CrawlUrl,
+        cached_headers: Option<CachedHeaders>,
+    ) -> Result<Option<FetchedPage>, Box<dyn std::error::Error + Send + Sync>> {
         let url = crawl_url.url.clone();
         debug!("Fetching page: {} (depth: {})", url, crawl_url.depth);
 
         // Use HTTP client to fetch the page
-        let http_response = self.http_client.fetch(&url).await
-            .map_err(|e| {
-                warn!("Failed to fetch page {}: {}", url, e);
-                e
-            })?;
+        let started = std::time::Instant::now();
+        let response = self.http_client.fetch_conditional(&url, cached_headers).await;
+        self.record_response_time(&url, started.elapsed().as_millis() as u64, &response);
+
+        let response = response?;
+
+        let http_response = match response {
+            ConditionalResponse::NotModified => return Ok(None),
+            ConditionalResponse::Modified(http_response) => http_response,
+        };
 
         info!("Fetched page: {} - {} bytes in {}ms",
             url,
@@ -214,10 +862,10 @@ impl WebCrawler {
             http_response.fetch_time_ms
         );
 
-        // Use page processor to extract data from real HTML
-        let page_data = self.page_processor.process_page(
+        // Use page processor to extract data from the response, by content type
+        let mut page_data = self.page_processor.process_response(
             &url,
-            &http_response.content,
+            &http_response,
             crawl_url.depth as u32
         ).await.map_err(|e| {
             warn!("Page processing failed for {}: {}", url, e);
@@ -231,11 +879,170 @@ impl WebCrawler {
             page_data.content_quality_score
         );
 
-        Ok(page_data)
+        let validators = http_response.cached_headers();
+        page_data.etag = validators.etag;
+        page_data.last_modified = validators.last_modified;
+        page_data.screenshot_path = self.capture_screenshot(&url).await;
+
+        Ok(Some(FetchedPage {
+            page_data,
+            bytes: http_response.content_length.unwrap_or(http_response.content.len()) as u64,
+            fetch_time_ms: http_response.fetch_time_ms,
+        }))
+    }
+
+    // Whether robots.txt lets the crawler's user agent fetch `url`. The rules are read from the
+    // domains table while fresh, otherwise fetched again and cached there; a robots.txt that
+    // can't be fetched allows everything.
+    async fn robots_allowed(&self, url: &str, domain: &str, repository: Option<&Arc<PageRepository>>) -> bool {
+        let Ok(parsed) = url::Url::parse(url) else { return true };
+        let path = match parsed.query() {
+            Some(query) => format!("{}?{}", parsed.path(), query),
+            None => parsed.path().to_string(),
+        };
+
+        if let Some(rules) = self.robots_rules.get(domain) {
+            return rules.is_allowed(&self.config.crawler.user_agent, &path);
+        }
+
+        let rules = Arc::new(RobotsTxtRules::parse(&self.load_robots_txt(&parsed, domain, repository).await));
+        self.robots_rules.insert(domain.to_string(), rules.clone());
+        rules.is_allowed(&self.config.crawler.user_agent, &path)
+    }
+
+    async fn load_robots_txt(&self, url: &url::Url, domain: &str, repository: Option<&Arc<PageRepository>>) -> String {
+        let max_age = std::time::Duration::from_secs(self.config.network.robots_txt_max_age_secs);
+        if let Some(repo) = repository {
+            match repo.is_robots_txt_stale(domain, max_age).await {
+                Ok(false) => match repo.get_domain_robots_txt(domain).await {
+                    Ok(robots_txt) => return robots_txt.unwrap_or_default(),
+                    Err(e) => warn!("Failed to load robots.txt of {}: {}", domain, e),
+                },
+                Ok(true) => {}
+                Err(e) => warn!("Failed to check robots.txt age of {}: {}", domain, e),
+            }
+        }
+
+        let robots_url = format!("{}/robots.txt", url.origin().ascii_serialization());
+        let robots_txt = match self.http_client.fetch(&robots_url).await {
+            Ok(response) => response.content,
+            // no robots.txt, everything is allowed
+            Err(NetworkError::Http { status, .. }) if (400..500).contains(&status) => String::new(),
+            Err(e) => {
+                debug!("Could not fetch {}, allowing every page this crawl: {}", robots_url, e);
+                return String::new();
+            }
+        };
+
+        if let Some(crawl_delay) = crate::network::parse_crawl_delay(&robots_txt) {
+            self.scheduler.set_crawl_delay(domain, crawl_delay);
+        }
+        if let Some(repo) = repository.filter(|_| !self.config.crawler.dry_run)
+            && let Err(e) = repo.upsert_domain_robots_txt(domain, &robots_txt, chrono::Utc::now()).await
+        {
+            warn!("Failed to cache robots.txt of {}: {}", domain, e);
+        }
+        robots_txt
+    }
+
+    // Hand the cached robots.txt Crawl-delay of a domain to the scheduler the first time it is crawled
+    async fn load_crawl_delay(&self, domain: &str) {
+        let Some(repository) = &self.domain_repository else { return };
+        if !self.crawl_delays_loaded.insert(domain.to_string()) {
+            return;
+        }
+
+        // parsed again rather than read from crawl_delay, older rows hold a 1000ms default
+        // for robots.txt files without a Crawl-delay
+        match repository.get_domain(domain).await {
+            Ok(Some(info)) => {
+                if let Some(crawl_delay) = info.robots_txt.as_deref().and_then(crate::network::parse_crawl_delay) {
+                    self.scheduler.set_crawl_delay(domain, crawl_delay);
+                }
+            }
+            Ok(None) => {}
+            Err(e) => warn!("Failed to load crawl delay of {}: {}", domain, e),
+        }
+    }
+
+    // Screenshot file name relative to screenshot_dir, a failed capture doesn't fail the page
+    async fn capture_screenshot(&self, url: &str) -> Option<String> {
+        if !self.config.crawler.capture_screenshots {
+            return None;
+        }
+        let capture = self.screenshot_capture.as_ref()?;
+        let dir = std::path::Path::new(&self.config.crawler.screenshot_dir);
+
+        match capture.capture(url, dir).await {
+            Ok(path) => Some(path.strip_prefix(dir).unwrap_or(&path).to_string_lossy().into_owned()),
+            Err(e) => {
+                warn!("Screenshot of {} failed: {}", url, e);
+                None
+            }
+        }
+    }
+
+    /// Fetch feeds not seen yet in this crawl and queue their items at `FEED_ITEM_PRIORITY`,
+    /// returns how many items were new to the frontier
+    pub(crate) async fn follow_feeds(&self, feed_urls: &[String], depth: u32) -> usize {
+        let mut added = 0;
+
+        for feed_url in feed_urls {
+            if !self.fetched_feeds.insert(canonicalize_with(feed_url, &self.config.crawler.strip_params)) {
+                continue;
+            }
+            let Ok(domain) = self.extract_domain(feed_url) else {
+                continue;
+            };
+
+            let response = self.scheduler.schedule_crawl(&domain, || async {
+                self.http_client.fetch(feed_url).await
+                    .map_err(|e| Box::new(e) as Box<dyn std::error::Error + Send + Sync>)
+            }).await;
+
+            let items = match response.map(|r| FeedProcessor::parse_feed(feed_url, &r.content)) {
+                Ok(Ok(items)) => items,
+                Ok(Err(e)) => {
+                    warn!("Failed to parse feed {}: {}", feed_url, e);
+                    continue;
+                }
+                Err(e) => {
+                    warn!("Failed to fetch feed {}: {}", feed_url, e);
+                    continue;
+                }
+            };
+
+            let discovered_at = chrono::Utc::now().timestamp() as u64;
+            let item_urls: Vec<CrawlUrl> = items.into_iter()
+                .filter(|item| url::Url::parse(&item.url).ok()
+                    .and_then(|u| u.host_str().map(|host| self.page_processor.is_domain_allowed(host)))
+                    .unwrap_or(false))
+                .map(|item| CrawlUrl {
+                    url: item.url,
+                    priority: FEED_ITEM_PRIORITY,
+                    depth,
+                    discovered_at,
+                })
+                .collect();
+
+            let count = item_urls.len();
+            let new_items = self.queue_links(item_urls).await;
+            info!("Feed {}: {} items, {} new", feed_url, count, new_items);
+            added += new_items;
+        }
+
+        added
     }
 
     /// Initialize the URL frontier with seed URLs
     async fn initialize_frontier(&self) -> crate::Result<()> {
+        if let Some(opic) = self.url_frontier.opic() {
+            let seeds: Vec<String> = self.config.crawler.seed_urls.iter()
+                .map(|url| canonicalize_with(url, &self.config.crawler.strip_params))
+                .collect();
+            opic.initialize(&seeds);
+        }
+
         for seed_url in &self.config.crawler.seed_urls {
             let crawl_url = CrawlUrl {
                 url: seed_url.clone(),
@@ -251,23 +1058,156 @@ impl WebCrawler {
         Ok(())
     }
 
-    /// Extract domain from URL for rate limiting
+    /// Checkpoint the frontier to `checkpoint_path`, if configured
+    async fn save_checkpoint(&self) {
+        if let Some(path) = &self.config.crawler.checkpoint_path
+            && let Err(e) = self.url_frontier.save_checkpoint(std::path::Path::new(path)).await
+        {
+            warn!("Failed to save frontier checkpoint to {}: {}", path, e);
+        }
+    }
+
+    /// Let the scheduler adapt the domain's delay to how the server answered,
+    /// including the Retry-After and X-Crawl-Delay it asked for
+    fn record_response_time(&self, url: &str, elapsed_ms: u64, response: &Result<ConditionalResponse, NetworkError>) {
+        let (response_time_ms, status_code, hints) = match response {
+            Ok(ConditionalResponse::Modified(http_response)) => (
+                http_response.fetch_time_ms,
+                http_response.status_code,
+                ResponseProcessor::extract_crawl_hints(&http_response.headers),
+            ),
+            Ok(ConditionalResponse::NotModified) => (elapsed_ms, 304, CrawlHints::default()),
+            Err(NetworkError::RateLimited { retry_after_secs, .. }) => {
+                (elapsed_ms, 429, CrawlHints { retry_after_secs: *retry_after_secs, ..CrawlHints::default() })
+            }
+            Err(NetworkError::Http { status, .. }) => (elapsed_ms, *status, CrawlHints::default()),
+            // a timed out request is as slow as it gets
+            Err(NetworkError::Timeout(_)) => (elapsed_ms, 0, CrawlHints::default()),
+            // no response to learn from
+            Err(_) => return,
+        };
+
+        if let Ok(domain) = self.extract_domain(url) {
+            self.scheduler.adaptive_delay(&domain, response_time_ms, status_code);
+            if let Some(secs) = hints.crawl_delay_secs {
+                self.scheduler.set_crawl_delay(&domain, std::time::Duration::from_secs(secs));
+            }
+            if let Some(secs) = hints.retry_after_secs {
+                self.scheduler.apply_retry_after(&domain, std::time::Duration::from_secs(secs));
+            }
+        }
+    }
+
+    /// Statistics key of a failed crawl: the HTTP status for error responses, otherwise the kind of error
+    fn error_type(error: &(dyn std::error::Error + 'static)) -> String {
+        if let Some(error) = Self::network_error(error) {
+            return match error.http_status() {
+                Some(status) => format!("http_{}", status),
+                None => error.kind().to_string(),
+            };
+        }
+
+        let mut current = Some(error);
+        while let Some(error) = current {
+            if error.is::<crate::core::page_processor::ProcessorError>() {
+                return "processing".to_string();
+            }
+            if error.is::<crate::storage::StorageError>() {
+                return "storage".to_string();
+            }
+            current = error.source();
+        }
+        if Self::is_circuit_open(error) {
+            return "circuit_open".to_string();
+        }
+        "other".to_string()
+    }
+
+    /// Whether the crawl failed because the domain's circuit was open
+    fn is_circuit_open(error: &(dyn std::error::Error + 'static)) -> bool {
+        let mut current = Some(error);
+        while let Some(error) = current {
+            if let Some(SchedulerError::CircuitOpen(_)) = error.downcast_ref::<SchedulerError>() {
+                return true;
+            }
+            current = error.source();
+        }
+        false
+    }
+
+    /// The `NetworkError` behind a crawl failure, looking through wrappers like `SchedulerError`
+    fn network_error<'a>(error: &'a (dyn std::error::Error + 'static)) -> Option<&'a NetworkError> {
+        let mut current = Some(error);
+        while let Some(error) = current {
+            if let Some(network_error) = error.downcast_ref::<NetworkError>() {
+                return Some(network_error);
+            }
+            current = error.source();
+        }
+        None
+    }
+
+    // Rate limits are per host, subdomains may well be served by different machines
     fn extract_domain(&self, url: &str) -> crate::Result<String> {
-        let parsed_url = url::Url::parse(url)?;
-        Ok(parsed_url.host_str().unwrap_or("unknown").to_string())
+        url::Url::parse(url)?;
+        Ok(url_host(url))
     }
 
     /// Generate crawling statistics
     pub(crate) async fn generate_statistics(&self) -> CrawlStatistics {
         let frontier_stats = self.url_frontier.get_stats().await;
+        let snapshot = self.crawl_metrics.snapshot();
 
         CrawlStatistics {
-            pages_crawled: self.pages_crawled.load(AtomicOrdering::Relaxed),
-            pages_failed: self.pages_failed.load(AtomicOrdering::Relaxed),
+            pages_crawled: snapshot.pages_crawled as usize,
+            pages_failed: snapshot.pages_failed as usize,
             urls_discovered: frontier_stats.seen_count,
             urls_in_queue: frontier_stats.queue_size,
             elapsed_time: self.start_time.elapsed(),
-            crawl_rate: self.pages_crawled.load(AtomicOrdering::Relaxed) as f64 / self.start_time.elapsed().as_secs_f64(),
+            crawl_rate: snapshot.pages_crawled as f64 / self.start_time.elapsed().as_secs_f64(),
+            max_depth_reached: self.max_depth_reached.load(AtomicOrdering::Relaxed),
+            resumed_from_checkpoint: self.resumed_from_checkpoint,
+            bytes_downloaded: snapshot.bytes_downloaded,
+            avg_fetch_time_ms: snapshot.avg_fetch_time_ms(),
+            cache_hits: snapshot.cache_hits,
+            cache_misses: snapshot.cache_misses,
+            errors_by_type: self.errors_by_type.iter().map(|entry| (entry.key().clone(), *entry.value())).collect(),
+            simulated_saves: self.simulated_saves.load(AtomicOrdering::Relaxed),
+            pages_skipped: self.pages_skipped.load(AtomicOrdering::Relaxed),
+        }
+    }
+}
+
+#[cfg(feature = "screenshots")]
+fn default_screenshot_capture() -> Option<Arc<dyn ScreenshotCapture>> {
+    match crate::network::HeadlessBrowserCapture::new() {
+        Ok(capture) => Some(Arc::new(capture)),
+        Err(e) => {
+            warn!("Screenshots disabled: {}", e);
+            None
         }
     }
 }
+
+#[cfg(not(feature = "screenshots"))]
+fn default_screenshot_capture() -> Option<Arc<dyn ScreenshotCapture>> {
+    warn!("capture_screenshots needs a build with the `screenshots` feature, screenshots disabled");
+    None
+}
+
+// pages with less body text than this are assumed to be rendered by scripts
+#[cfg(feature = "js-rendering")]
+const JS_RENDERING_MIN_WORDS: usize = 50;
+
+#[cfg(feature = "js-rendering")]
+fn with_js_rendering(http_client: HttpClient, enabled: bool) -> HttpClient {
+    http_client.with_js_fallback(enabled, JS_RENDERING_MIN_WORDS)
+}
+
+#[cfg(not(feature = "js-rendering"))]
+fn with_js_rendering(http_client: HttpClient, enabled: bool) -> HttpClient {
+    if enabled {
+        warn!("enable_js_rendering needs a build with the `js-rendering` feature, JavaScript rendering disabled");
+    }
+    http_client
+}
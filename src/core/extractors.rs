@@ -0,0 +1,167 @@
+/// Text extraction for responses that aren't HTML
+use crate::core::page_processor::ProcessorError;
+use crate::models::PageData;
+
+/// Longest first line still taken as the title of a text document
+const MAX_TITLE_CHARS: usize = 120;
+
+/// Text of PDF documents, needs the `pdf` feature
+pub struct PdfExtractor;
+
+impl PdfExtractor {
+    #[cfg(feature = "pdf")]
+    pub fn extract(bytes: &[u8]) -> Result<PageData, ProcessorError> {
+        let text = pdf_extract::extract_text_from_mem(bytes)
+            .map_err(|e| ProcessorError::ExtractionError(format!("PDF: {}", e)))?;
+        Ok(PlainTextExtractor::extract(&text))
+    }
+
+    #[cfg(not(feature = "pdf"))]
+    pub fn extract(_bytes: &[u8]) -> Result<PageData, ProcessorError> {
+        Err(ProcessorError::UnsupportedContentType(
+            "application/pdf needs a build with the `pdf` feature".to_string(),
+        ))
+    }
+}
+
+/// Plain text documents, titled by their first line when it is short enough
+pub struct PlainTextExtractor;
+
+impl PlainTextExtractor {
+    pub fn extract(text: &str) -> PageData {
+        let title = text
+            .lines()
+            .map(str::trim)
+            .find(|line| !line.is_empty())
+            .filter(|line| line.chars().count() <= MAX_TITLE_CHARS)
+            .map(String::from);
+
+        let content = text.split_whitespace().collect::<Vec<_>>().join(" ");
+        page_data(title, content)
+    }
+}
+
+/// XML documents: the text of every element, titled by the first `<title>` element
+pub struct XmlExtractor;
+
+impl XmlExtractor {
+    pub fn extract(xml: &str) -> PageData {
+        let mut text = String::new();
+        let mut title: Option<String> = None;
+        let mut in_title = false;
+        let mut rest = xml;
+
+        while let Some(start) = rest.find('<') {
+            Self::push_text(&mut text, &rest[..start], in_title, &mut title);
+            rest = &rest[start..];
+
+            if let Some(cdata) = rest.strip_prefix("<![CDATA[") {
+                let end = cdata.find("]]>").unwrap_or(cdata.len());
+                Self::push_raw(&mut text, &cdata[..end], in_title, &mut title);
+                rest = cdata.get(end + 3..).unwrap_or("");
+                continue;
+            }
+
+            // comments, processing instructions and declarations hold no document text
+            let terminator = if rest.starts_with("<!--") { "-->" } else { ">" };
+            let Some(end) = rest.find(terminator) else { break };
+            let tag = &rest[1..end];
+            rest = &rest[end + terminator.len()..];
+
+            // <title>, <dc:title> and the like, but only the first one names the document
+            let name = tag.trim_start_matches('/').split(|c: char| c.is_whitespace() || c == '/').next().unwrap_or("");
+            if name.rsplit(':').next().is_some_and(|local| local.eq_ignore_ascii_case("title")) {
+                in_title = !tag.starts_with('/') && !tag.ends_with('/') && title.is_none();
+            }
+        }
+        Self::push_text(&mut text, rest, in_title, &mut title);
+
+        let title = title.map(|t| t.trim().to_string()).filter(|t| !t.is_empty());
+        page_data(title, text.split_whitespace().collect::<Vec<_>>().join(" "))
+    }
+
+    fn push_text(text: &mut String, raw: &str, in_title: bool, title: &mut Option<String>) {
+        Self::push_raw(text, &decode_entities(raw), in_title, title);
+    }
+
+    fn push_raw(text: &mut String, value: &str, in_title: bool, title: &mut Option<String>) {
+        if value.trim().is_empty() {
+            return;
+        }
+        if in_title {
+            title.get_or_insert_with(String::new).push_str(value);
+        }
+        text.push(' ');
+        text.push_str(value);
+    }
+}
+
+// The predefined XML entities and numeric character references
+fn decode_entities(raw: &str) -> String {
+    if !raw.contains('&') {
+        return raw.to_string();
+    }
+
+    let mut decoded = String::with_capacity(raw.len());
+    let mut rest = raw;
+    while let Some(start) = rest.find('&') {
+        decoded.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        let Some(end) = rest.find(';').filter(|end| *end <= 10) else {
+            decoded.push('&');
+            rest = &rest[1..];
+            continue;
+        };
+        let entity = &rest[1..end];
+        let character = match entity {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            _ => entity
+                .strip_prefix("#x")
+                .or_else(|| entity.strip_prefix("#X"))
+                .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                .or_else(|| entity.strip_prefix('#').and_then(|dec| dec.parse().ok()))
+                .and_then(char::from_u32),
+        };
+        match character {
+            Some(c) => {
+                decoded.push(c);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                decoded.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    decoded.push_str(rest);
+    decoded
+}
+
+// PageData holding only the extracted text; url, depth and quality are filled in by the caller
+fn page_data(title: Option<String>, content: String) -> PageData {
+    PageData {
+        url: String::new(),
+        title,
+        description: None,
+        keywords: Vec::new(),
+        word_count: content.split_whitespace().count(),
+        content,
+        outgoing_links: Vec::new(),
+        content_quality_score: 0.0,
+        quality_factors: Vec::new(),
+        crawled_at: chrono::Utc::now(),
+        depth: 0,
+        etag: None,
+        last_modified: None,
+        feed_urls: Vec::new(),
+        og_image: None,
+        og_type: None,
+        screenshot_path: None,
+        canonical_url: None,
+    }
+}
@@ -0,0 +1,55 @@
+/// Parse RSS and Atom feeds into crawlable items
+use crate::core::page_processor::ProcessorError;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+/// Frontier priority of feed items, above any regular link (at most 3.0)
+/// but below seed URLs
+pub const FEED_ITEM_PRIORITY: f64 = 5.0;
+
+/// A single entry of an RSS or Atom feed
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FeedItem {
+    pub url: String,
+    pub title: String,
+    pub published: Option<DateTime<Utc>>,
+    pub summary: Option<String>,
+}
+
+pub struct FeedProcessor;
+
+impl FeedProcessor {
+    /// Parse an RSS 0.9x/1.0/2.0 or Atom document. Entries without a link are skipped,
+    /// relative links are resolved against the feed `url`.
+    pub fn parse_feed(url: &str, xml_content: &str) -> Result<Vec<FeedItem>, ProcessorError> {
+        let base_url = Url::parse(url).map_err(|_| ProcessorError::InvalidBaseUrl)?;
+        let feed = feed_rs::parser::parse(xml_content.as_bytes())
+            .map_err(|e| ProcessorError::FeedParseError(e.to_string()))?;
+
+        let items = feed.entries
+            .into_iter()
+            .filter_map(|entry| {
+                // Atom entries may carry several links, the alternate one is the article
+                let link = entry.links.iter()
+                    .find(|link| link.rel.as_deref().is_none_or(|rel| rel == "alternate"))
+                    .or_else(|| entry.links.first())?;
+                let item_url = base_url.join(link.href.trim()).ok()?;
+                if !matches!(item_url.scheme(), "http" | "https") {
+                    return None;
+                }
+
+                Some(FeedItem {
+                    url: item_url.to_string(),
+                    title: entry.title.map(|t| t.content.trim().to_string()).unwrap_or_default(),
+                    published: entry.published.or(entry.updated),
+                    summary: entry.summary
+                        .map(|s| s.content.trim().to_string())
+                        .filter(|s| !s.is_empty()),
+                })
+            })
+            .collect();
+
+        Ok(items)
+    }
+}
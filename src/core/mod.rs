@@ -3,9 +3,19 @@
 pub mod crawler;
 pub mod url_frontier;
 pub mod page_processor;
+pub mod extractors;
+pub mod feed_processor;
 pub mod scheduler;
+pub mod opic;
+pub mod priority;
 mod tests;
 
 pub use url_frontier::UrlFrontier;
-pub use page_processor::PageProcessor;
-pub use scheduler::CrawlScheduler;
\ No newline at end of file
+pub use page_processor::{OpenGraphData, PageProcessor, StructuredData};
+pub use extractors::{PdfExtractor, PlainTextExtractor, XmlExtractor};
+pub use feed_processor::{FeedItem, FeedProcessor};
+pub use scheduler::{CircuitState, CrawlScheduler};
+pub use opic::OpicScorer;
+pub use priority::{
+    ComposedPriority, DepthDecayPriority, DomainBoostPriority, OpicPriority, PathPatternPriority, PriorityFunction,
+};
\ No newline at end of file
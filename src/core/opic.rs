@@ -0,0 +1,56 @@
+//! Online Page Importance Computation (Abiteboul et al., 2003)
+
+use dashmap::DashMap;
+
+/// Tracks OPIC cash per URL. Every seed starts with 1/N cash; crawling a page
+/// moves its cash into its history and splits it equally among its outgoing links.
+/// Pages holding more cash are more important and should be crawled sooner.
+#[derive(Debug, Default)]
+pub struct OpicScorer {
+    cash: DashMap<String, f64>,
+    history: DashMap<String, f64>,
+}
+
+impl OpicScorer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Give every seed an equal share of the initial cash
+    pub fn initialize(&self, seed_urls: &[String]) {
+        if seed_urls.is_empty() {
+            return;
+        }
+
+        let initial = 1.0 / seed_urls.len() as f64;
+        for url in seed_urls {
+            self.cash.insert(url.clone(), initial);
+        }
+    }
+
+    /// Current cash of a URL, used as its crawl priority
+    pub fn cash(&self, url: &str) -> f64 {
+        self.cash.get(url).map(|c| *c).unwrap_or(0.0)
+    }
+
+    /// Total cash the page has held when it was crawled
+    pub fn history(&self, url: &str) -> f64 {
+        self.history.get(url).map(|h| *h).unwrap_or(0.0)
+    }
+
+    /// Page `url` was crawled: distribute its cash over `links`
+    pub fn on_crawled(&self, url: &str, links: &[String]) {
+        let cash = self.cash.insert(url.to_string(), 0.0).unwrap_or(0.0);
+        *self.history.entry(url.to_string()).or_insert(0.0) += cash;
+
+        if links.is_empty() || cash == 0.0 {
+            return;
+        }
+
+        let share = cash / links.len() as f64;
+        for link in links {
+            *self.cash.entry(link.clone()).or_insert(0.0) += share;
+        }
+    }
+}
+
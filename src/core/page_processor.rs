@@ -1,20 +1,73 @@
 /// Process downloaded pages, extracts content and links
+use crate::core::extractors::{PdfExtractor, PlainTextExtractor, XmlExtractor};
+use crate::core::priority::{standard_priority, DomainBoostPriority, PriorityFunction};
 use crate::models::{CrawlUrl, PageData};
+use crate::network::HttpResponse;
+use crate::utils::registrable_domain;
+use bytes::Bytes;
+use futures_util::{Stream, StreamExt};
+use glob::Pattern;
 use scraper::{Html, Selector};
+use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
+use std::sync::Arc;
 use tracing::{debug, error};
 use url::Url;
 
+/// Quality boost for pages describing themselves with Open Graph tags
+const OPEN_GRAPH_QUALITY_BOOST: f64 = 0.1;
+
+/// Quality boost for pages with `<code>` or `<pre>` blocks
+const CODE_BLOCK_QUALITY_BOOST: f64 = 0.1;
+
+/// Pages with more words than this per heading lose part of the heading factor
+const MAX_WORDS_PER_HEADING: f64 = 300.0;
+
+/// Share of body words inside links above which a page looks like a link farm
+const MAX_LINK_DENSITY: f64 = 0.5;
+
+/// Meta descriptions shorter than this only get half the description factor
+const MIN_DESCRIPTION_CHARS: usize = 50;
+
+/// `<meta property="og:...">` tags of a page
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct OpenGraphData {
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub image: Option<String>,
+    pub og_type: Option<String>,
+    pub site_name: Option<String>,
+}
+
+impl OpenGraphData {
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+}
+
+/// Metadata a page embeds for social previews and search engines
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct StructuredData {
+    pub open_graph: OpenGraphData,
+    pub json_ld: Vec<serde_json::Value>,
+}
+
 /// Processes HTML pages and extracts useful information
 pub struct PageProcessor {
     /// Maximum number of links to extract per page
     max_links_per_page: usize,
 
-    /// Domains to prioritize for link extraction
-    priority_domains: HashSet<String>,
+    /// Scores extracted links
+    priority_function: Arc<dyn PriorityFunction>,
 
     /// File extensions to ignore
     ignored_extensions: HashSet<String>,
+
+    /// Only links to these domains are followed, empty allows all
+    allowed_domains: Vec<Pattern>,
+
+    /// Links to these domains are never followed
+    blocked_domains: Vec<Pattern>,
 }
 
 impl PageProcessor {
@@ -31,8 +84,10 @@ impl PageProcessor {
 
         Self {
             max_links_per_page: 1000,
-            priority_domains: HashSet::new(),
+            priority_function: Arc::new(standard_priority(Vec::new())),
             ignored_extensions,
+            allowed_domains: Vec::new(),
+            blocked_domains: Vec::new(),
         }
     }
 
@@ -51,25 +106,90 @@ impl PageProcessor {
         let keywords = self.extract_keywords(&document);
         let text_content = self.extract_text_content(&document);
 
-        // Extract outgoing links
-        let outgoing_links = self.extract_links(&document, url, depth + 1)?;
+        let feed_urls = Self::extract_feed_urls(&document, url);
+        let open_graph = Self::extract_open_graph(&document);
+        // a canonical on another site would let any page overwrite that site's pages
+        let canonical_url = Self::extract_canonical_url(&document, url)
+            .filter(|canonical| registrable_domain(canonical) == registrable_domain(url));
 
         // Calculate content metrics
         let word_count = text_content.split_whitespace().count();
-        let content_quality_score = self.calculate_content_quality(&text_content, &title);
+        let (content_quality_score, quality_factors) =
+            self.calculate_content_quality(&document, &text_content, &title, &description, &open_graph);
 
-        Ok(PageData {
+        let mut page_data = PageData {
             url: url.to_string(),
             title,
             description,
             keywords,
             content: text_content,
-            outgoing_links,
+            outgoing_links: Vec::new(),
             word_count,
             content_quality_score,
+            quality_factors,
             crawled_at: chrono::Utc::now(),
             depth,
-        })
+            etag: None,
+            last_modified: None,
+            feed_urls,
+            og_image: open_graph.image,
+            og_type: open_graph.og_type,
+            screenshot_path: None,
+            canonical_url,
+        };
+
+        // Extract outgoing links, scored with the page they were found on
+        page_data.outgoing_links = self.extract_links(&document, url, depth + 1, &page_data)?;
+
+        Ok(page_data)
+    }
+
+    /// Process HTML arriving in chunks, e.g. a response body stream, as a seed page at depth 0.
+    /// The HTML parser needs the whole document, so the chunks are still collected first;
+    /// invalid UTF-8 is replaced rather than rejected.
+    pub async fn process_page_streaming(
+        &self,
+        url: &str,
+        stream: impl Stream<Item = Bytes>,
+    ) -> Result<PageData, ProcessorError> {
+        let mut stream = std::pin::pin!(stream);
+        let mut html = Vec::new();
+        while let Some(chunk) = stream.next().await {
+            html.extend_from_slice(&chunk);
+        }
+
+        self.process_page(url, &String::from_utf8_lossy(&html), 0).await
+    }
+
+    /// Process a fetched response with the extractor for its content type: PDF, plain text
+    /// and XML get their text extracted, everything else is processed as HTML
+    pub async fn process_response(
+        &self,
+        url: &str,
+        response: &HttpResponse,
+        depth: u32,
+    ) -> Result<PageData, ProcessorError> {
+        let content_type = response.content_type.as_str();
+        let mut page_data = match content_type {
+            "application/pdf" => {
+                let bytes = response.raw_body.as_deref().unwrap_or(response.content.as_bytes());
+                PdfExtractor::extract(bytes)?
+            }
+            "text/plain" => PlainTextExtractor::extract(&response.content),
+            // RSS, Atom, sitemaps and other +xml types, XHTML is still HTML
+            "text/xml" | "application/xml" => XmlExtractor::extract(&response.content),
+            _ if content_type.ends_with("+xml") && content_type != "application/xhtml+xml" => {
+                XmlExtractor::extract(&response.content)
+            }
+            _ => return self.process_page(url, &response.content, depth).await,
+        };
+
+        let (content_quality_score, quality_factors) = Self::text_quality(&page_data.content, &page_data.title);
+        page_data.url = url.to_string();
+        page_data.depth = depth;
+        page_data.content_quality_score = content_quality_score;
+        page_data.quality_factors = quality_factors;
+        Ok(page_data)
     }
 
     /// Extract page title
@@ -142,6 +262,7 @@ impl PageProcessor {
         document: &Html,
         base_url: &str,
         next_depth: u32,
+        page_data: &PageData,
     ) -> Result<Vec<CrawlUrl>, ProcessorError> {
         let link_selector =
             Selector::parse("a[href]").map_err(|_| ProcessorError::SelectorParseError)?;
@@ -157,7 +278,7 @@ impl PageProcessor {
             }
 
             if let Some(href) = element.value().attr("href") {
-                match self.resolve_and_validate_url(&base_url_parsed, href, next_depth) {
+                match self.resolve_and_validate_url(&base_url_parsed, href, next_depth, page_data) {
                     Ok(Some(crawl_url)) => {
                         links.push(crawl_url);
                         link_count += 1;
@@ -173,12 +294,119 @@ impl PageProcessor {
         Ok(links)
     }
 
+    /// RSS and Atom feeds advertised with `<link rel="alternate">`, resolved against `base_url`
+    pub fn extract_feed_urls(document: &Html, base_url: &str) -> Vec<String> {
+        let Ok(base_url) = Url::parse(base_url) else {
+            return Vec::new();
+        };
+        let feed_selector = Selector::parse(
+            "link[rel~='alternate'][type='application/rss+xml'][href], \
+             link[rel~='alternate'][type='application/atom+xml'][href]",
+        ).unwrap();
+
+        let mut feed_urls: Vec<String> = Vec::new();
+        for element in document.select(&feed_selector) {
+            let Some(href) = element.value().attr("href") else {
+                continue;
+            };
+            if let Ok(feed_url) = base_url.join(href.trim())
+                && matches!(feed_url.scheme(), "http" | "https")
+                && !feed_urls.contains(&feed_url.to_string())
+            {
+                feed_urls.push(feed_url.to_string());
+            }
+        }
+        feed_urls
+    }
+
+    /// Target of `<link rel="canonical">` resolved against `base_url`, the first one wins
+    pub fn extract_canonical_url(document: &Html, base_url: &str) -> Option<String> {
+        let base_url = Url::parse(base_url).ok()?;
+        let canonical_selector = Selector::parse("link[rel~='canonical'][href]").unwrap();
+
+        let href = document.select(&canonical_selector).next()?.value().attr("href")?.trim();
+        if href.is_empty() {
+            return None;
+        }
+
+        base_url.join(href)
+            .ok()
+            .filter(|canonical| matches!(canonical.scheme(), "http" | "https"))
+            .map(|canonical| canonical.to_string())
+    }
+
+    /// Open Graph and JSON-LD metadata of the page
+    pub fn extract_structured_data(document: &Html) -> StructuredData {
+        StructuredData {
+            open_graph: Self::extract_open_graph(document),
+            json_ld: Self::extract_json_ld(document),
+        }
+    }
+
+    /// Open Graph properties, the first non-empty tag wins when one is repeated
+    pub fn extract_open_graph(document: &Html) -> OpenGraphData {
+        let og_selector = Selector::parse("meta[property^='og:'][content]").unwrap();
+
+        let mut data = OpenGraphData::default();
+        for element in document.select(&og_selector) {
+            let (Some(property), Some(content)) = (element.value().attr("property"), element.value().attr("content")) else {
+                continue;
+            };
+            let content = content.trim();
+            if content.is_empty() {
+                continue;
+            }
+
+            let slot = match property.trim().to_lowercase().as_str() {
+                "og:title" => &mut data.title,
+                "og:description" => &mut data.description,
+                "og:image" | "og:image:url" => &mut data.image,
+                "og:type" => &mut data.og_type,
+                "og:site_name" => &mut data.site_name,
+                _ => continue,
+            };
+            slot.get_or_insert_with(|| content.to_string());
+        }
+        data
+    }
+
+    /// JSON-LD objects from `<script type="application/ld+json">`, top-level arrays and
+    /// `@graph` containers are flattened, scripts that are not valid JSON are skipped
+    pub fn extract_json_ld(document: &Html) -> Vec<serde_json::Value> {
+        let script_selector = Selector::parse("script[type='application/ld+json']").unwrap();
+
+        let mut objects = Vec::new();
+        for element in document.select(&script_selector) {
+            let script = element.text().collect::<String>();
+            let value = match serde_json::from_str::<serde_json::Value>(script.trim()) {
+                Ok(value) => value,
+                Err(e) => {
+                    debug!("Skipping invalid JSON-LD: {}", e);
+                    continue;
+                }
+            };
+
+            let values = match value {
+                serde_json::Value::Array(values) => values,
+                value => vec![value],
+            };
+            for value in values {
+                match value.get("@graph").and_then(|graph| graph.as_array()) {
+                    Some(graph) => objects.extend(graph.iter().cloned()),
+                    None => objects.push(value),
+                }
+            }
+        }
+        objects
+    }
+
     /// Resolve relative URLs and validate
     fn resolve_and_validate_url(
         &self,
         base_url: &Url, // Fixed: Changed parameter type from &str to &Url
         href: &str,
         depth: u32, // Fixed: Changed parameter name from next_depth to depth for consistency
+        page_data: &PageData,
     ) -> Result<Option<CrawlUrl>, ProcessorError> {
         // Skip obvious non-web links
         if href.starts_with("mailto:") || href.starts_with("tel:") || href.starts_with("javascript:") {
@@ -204,8 +432,15 @@ impl PageProcessor {
             return Ok(None);
         }
 
+        // Respect domain allow/block lists
+        if let Some(host) = absolute_url.host_str()
+            && !self.is_domain_allowed(host)
+        {
+            return Ok(None);
+        }
+
         // Calculate priority based on domain and other factors
-        let priority = self.calculate_link_priority(&absolute_url, depth);
+        let priority = self.priority_function.priority(&absolute_url, depth, Some(page_data));
 
         Ok(Some(CrawlUrl {
             url: url_str,
@@ -215,67 +450,146 @@ impl PageProcessor {
         }))
     }
 
-    /// Calculate content quality score (0.0 to 1.0)
-    fn calculate_content_quality(&self, content: &str, title: &Option<String>) -> f64 {
-        let mut score = 0.0;
+    /// Calculate content quality score (0.0 to 1.0) as a weighted sum of the quality factors,
+    /// returned alongside the unweighted factor scores
+    pub(crate) fn calculate_content_quality(
+        &self,
+        document: &Html,
+        content: &str,
+        title: &Option<String>,
+        description: &Option<String>,
+        open_graph: &OpenGraphData,
+    ) -> (f64, Vec<(String, f64)>) {
+        let word_count = content.split_whitespace().count();
+
+        let factors = [
+            (Self::length_factor(word_count), 0.3),
+            (Self::title_factor(title), 0.15),
+            (Self::diversity_factor(content, word_count), 0.25),
+            (Self::heading_factor(document, word_count), 0.1),
+            (Self::link_density_factor(document), 0.1),
+            (Self::description_factor(description), 0.1),
+            // boosts on top of the base factors, the total is capped at 1.0
+            (Self::code_block_factor(document), CODE_BLOCK_QUALITY_BOOST),
+            (Self::open_graph_factor(open_graph), OPEN_GRAPH_QUALITY_BOOST),
+        ];
+
+        let score: f64 = factors.iter().map(|((_, score), weight)| score * weight).sum();
+        let factors = factors
+            .into_iter()
+            .map(|((name, score), _)| (name.to_string(), score))
+            .collect();
+
+        (score.min(1.0), factors)
+    }
 
-        // Length factor (optimal around 500-2000 words)
+    /// Quality of documents without markup, from the factors that only need their text
+    fn text_quality(content: &str, title: &Option<String>) -> (f64, Vec<(String, f64)>) {
         let word_count = content.split_whitespace().count();
-        let length_score = match word_count {
+        let factors = [
+            (Self::length_factor(word_count), 0.3),
+            (Self::title_factor(title), 0.15),
+            (Self::diversity_factor(content, word_count), 0.25),
+        ];
+
+        let score: f64 = factors.iter().map(|((_, score), weight)| score * weight).sum();
+        let factors = factors
+            .into_iter()
+            .map(|((name, score), _)| (name.to_string(), score))
+            .collect();
+
+        (score.min(1.0), factors)
+    }
+
+    /// Length factor (optimal around 500-2000 words)
+    fn length_factor(word_count: usize) -> (&'static str, f64) {
+        let score = match word_count {
             0..=50 => 0.1,
             51..=200 => 0.5,
             201..=500 => 0.8,
             501..=2000 => 1.0,
-            2001..=5000 => 0.9, // Fixed: Removed underscore from 0.9_
+            2001..=5000 => 0.9,
             _ => 0.7,
         };
+        ("length", score)
+    }
 
-        score += length_score * 0.4;
+    /// Title presence
+    fn title_factor(title: &Option<String>) -> (&'static str, f64) {
+        ("title", if title.is_some() { 1.0 } else { 0.0 })
+    }
 
-        // Title presence
-        if title.is_some() {
-            score += 0.2;
+    /// Content diversity, share of unique words
+    fn diversity_factor(content: &str, word_count: usize) -> (&'static str, f64) {
+        if word_count == 0 {
+            return ("diversity", 0.0);
         }
 
-        // Content diversity (simple heuristic)
-        let binding = content.to_lowercase();
+        let lowercase = content.to_lowercase();
+        let unique_words: HashSet<_> = lowercase.split_whitespace().collect();
+        ("diversity", (unique_words.len() as f64 / word_count as f64).min(1.0))
+    }
 
-        let unique_words: HashSet<_> = binding
-            .split_whitespace()
-            .collect(); // Fixed: count() -> collect()
+    /// Headings relative to content, pages without any headings score 0.0
+    fn heading_factor(document: &Html, word_count: usize) -> (&'static str, f64) {
+        let selector = Selector::parse("h1, h2, h3, h4, h5, h6").unwrap();
+        let headings = document.select(&selector).count();
+        if headings == 0 {
+            return ("headings", 0.0);
+        }
 
-        let diversity_score = if word_count > 0 {
-            (unique_words.len() as f64 / word_count as f64).min(1.0)
-        } else {
-            0.0
-        };
+        // one heading per few hundred words reads as structured text
+        let words_per_heading = word_count as f64 / headings as f64;
+        ("headings", (MAX_WORDS_PER_HEADING / words_per_heading.max(1.0)).min(1.0))
+    }
+
+    /// Share of body text inside links, link farms score 0.0
+    fn link_density_factor(document: &Html) -> (&'static str, f64) {
+        let body = Selector::parse("body").unwrap();
+        let anchors = Selector::parse("body a").unwrap();
+
+        let text_words: usize = document
+            .select(&body)
+            .map(|element| element.text().map(|text| text.split_whitespace().count()).sum::<usize>())
+            .sum();
+        if text_words == 0 {
+            return ("link_density", 0.0);
+        }
 
-        score += diversity_score * 0.4;
+        let anchor_words: usize = document
+            .select(&anchors)
+            .map(|element| element.text().map(|text| text.split_whitespace().count()).sum::<usize>())
+            .sum();
 
-        score.min(1.0)
+        let density = anchor_words as f64 / text_words as f64;
+        let score = if density <= MAX_LINK_DENSITY {
+            1.0
+        } else {
+            // falls to 0.0 as the whole page becomes anchor text
+            ((1.0 - density) / (1.0 - MAX_LINK_DENSITY)).max(0.0)
+        };
+        ("link_density", score)
     }
 
-    /// Calculate link priority
-    fn calculate_link_priority(&self, url: &Url, depth: u32) -> f64 {
-        let mut priority = 1.0 / (depth as f64 + 1.0);
-
-        // Domain priority boost
-        if let Some(host) = url.host_str() {
-            for priority_domain in &self.priority_domains {
-                if host.contains(priority_domain) {
-                    priority *= 2.0;
-                    break;
-                }
-            }
-        }
+    /// Code samples suggest technical content
+    fn code_block_factor(document: &Html) -> (&'static str, f64) {
+        let selector = Selector::parse("code, pre").unwrap();
+        ("code_blocks", if document.select(&selector).next().is_some() { 1.0 } else { 0.0 })
+    }
 
-        // Path-based priority adjustments
-        let path = url.path();
-        if path.contains("/article/") || path.contains("/post/") || path.contains("/blog/") {
-            priority *= 1.5;
-        }
+    /// Meta description length, missing or short descriptions are penalized
+    fn description_factor(description: &Option<String>) -> (&'static str, f64) {
+        let score = match description {
+            None => 0.0,
+            Some(description) if description.chars().count() < MIN_DESCRIPTION_CHARS => 0.5,
+            Some(_) => 1.0,
+        };
+        ("description", score)
+    }
 
-        priority
+    /// Open Graph tags suggest a curated page rather than boilerplate
+    fn open_graph_factor(open_graph: &OpenGraphData) -> (&'static str, f64) {
+        ("open_graph", if open_graph.is_empty() { 0.0 } else { 1.0 })
     }
 
     /// Get file extension from URL
@@ -288,9 +602,47 @@ impl PageProcessor {
             .map(|ext| ext.to_string()) // Convert to owned String
     }
 
-    /// Add priority domain
+    /// Boost links to `domain` on top of the current priority function
     pub fn add_priority_domain(&mut self, domain: String) {
-        self.priority_domains.insert(domain);
+        let base = self.priority_function.clone();
+        self.priority_function = Arc::new(DomainBoostPriority::new(vec![domain]).with_base(base));
+    }
+
+    /// Replace how extracted links are prioritized
+    pub fn set_priority_function(&mut self, priority_function: Arc<dyn PriorityFunction>) {
+        self.priority_function = priority_function;
+    }
+
+    /// Add a domain or glob pattern (e.g. `*.example.com`) to the allowlist
+    pub fn add_allowed_domain(&mut self, pattern: &str) -> Result<(), glob::PatternError> {
+        self.allowed_domains.push(Pattern::new(&pattern.to_lowercase())?);
+        Ok(())
+    }
+
+    /// Add a domain or glob pattern (e.g. `*.ads.example.com`) to the blocklist
+    pub fn add_blocked_domain(&mut self, pattern: &str) -> Result<(), glob::PatternError> {
+        self.blocked_domains.push(Pattern::new(&pattern.to_lowercase())?);
+        Ok(())
+    }
+
+    /// Check a host against the blocklist, then the allowlist if one is set
+    pub fn is_domain_allowed(&self, host: &str) -> bool {
+        let host = host.to_lowercase();
+
+        if self.blocked_domains.iter().any(|p| Self::domain_matches(p, &host)) {
+            return false;
+        }
+
+        self.allowed_domains.is_empty()
+            || self.allowed_domains.iter().any(|p| Self::domain_matches(p, &host))
+    }
+
+    /// A plain domain also matches its subdomains, wildcards follow glob rules
+    fn domain_matches(pattern: &Pattern, host: &str) -> bool {
+        pattern.matches(host)
+            || host
+                .strip_suffix(pattern.as_str())
+                .is_some_and(|prefix| prefix.ends_with('.'))
     }
 }
 
@@ -304,4 +656,13 @@ pub enum ProcessorError {
 
     #[error("URL resolution error")]
     UrlResolutionError, // Fixed: URLResolutionError -> UrlResolutionError (consistent naming)
+
+    #[error("Failed to parse feed: {0}")]
+    FeedParseError(String),
+
+    #[error("Text extraction failed: {0}")]
+    ExtractionError(String),
+
+    #[error("Unsupported content type: {0}")]
+    UnsupportedContentType(String),
 }
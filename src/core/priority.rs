@@ -0,0 +1,169 @@
+//! Pluggable crawl priority of discovered links
+
+use crate::config::{AlgorithmSettings, PriorityFunctionKind};
+use crate::core::OpicScorer;
+use crate::models::PageData;
+use std::sync::Arc;
+use url::Url;
+
+/// Scores a link found on a page, higher values are crawled sooner
+pub trait PriorityFunction: Send + Sync {
+    /// `depth` is the depth the link will be crawled at, `page_data` the page it was found on if known
+    fn priority(&self, url: &Url, depth: u32, page_data: Option<&PageData>) -> f64;
+
+    /// Weighted sum `weight_a * self + (1 - weight_a) * other`
+    fn compose<B>(self, other: B, weight_a: f64) -> ComposedPriority<Self, B>
+    where
+        Self: Sized,
+        B: PriorityFunction,
+    {
+        ComposedPriority { a: self, b: other, weight_a: weight_a.clamp(0.0, 1.0) }
+    }
+}
+
+impl<P: PriorityFunction + ?Sized> PriorityFunction for Arc<P> {
+    fn priority(&self, url: &Url, depth: u32, page_data: Option<&PageData>) -> f64 {
+        (**self).priority(url, depth, page_data)
+    }
+}
+
+/// `1 / (depth + 1)`: shallow pages first
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DepthDecayPriority;
+
+impl PriorityFunction for DepthDecayPriority {
+    fn priority(&self, _url: &Url, depth: u32, _page_data: Option<&PageData>) -> f64 {
+        1.0 / (depth as f64 + 1.0)
+    }
+}
+
+/// Multiplies the `base` priority for hosts containing one of `domains`
+#[derive(Debug, Clone)]
+pub struct DomainBoostPriority<P = DepthDecayPriority> {
+    base: P,
+    domains: Vec<String>,
+    boost: f64,
+}
+
+impl DomainBoostPriority {
+    pub fn new(domains: Vec<String>) -> Self {
+        Self { base: DepthDecayPriority, domains, boost: 2.0 }
+    }
+}
+
+impl<P> DomainBoostPriority<P> {
+    pub fn with_base<Q: PriorityFunction>(self, base: Q) -> DomainBoostPriority<Q> {
+        DomainBoostPriority { base, domains: self.domains, boost: self.boost }
+    }
+
+    pub fn with_boost(mut self, boost: f64) -> Self {
+        self.boost = boost;
+        self
+    }
+}
+
+impl<P: PriorityFunction> PriorityFunction for DomainBoostPriority<P> {
+    fn priority(&self, url: &Url, depth: u32, page_data: Option<&PageData>) -> f64 {
+        let priority = self.base.priority(url, depth, page_data);
+        let boosted = url.host_str()
+            .is_some_and(|host| self.domains.iter().any(|domain| host.contains(domain.as_str())));
+
+        if boosted { priority * self.boost } else { priority }
+    }
+}
+
+/// Multiplies the `base` priority for paths containing one of `patterns`
+#[derive(Debug, Clone)]
+pub struct PathPatternPriority<P = DepthDecayPriority> {
+    base: P,
+    patterns: Vec<String>,
+    boost: f64,
+}
+
+impl Default for PathPatternPriority {
+    fn default() -> Self {
+        Self::new(["/article/", "/post/", "/blog/", "/research/"].map(String::from).to_vec())
+    }
+}
+
+impl PathPatternPriority {
+    pub fn new(patterns: Vec<String>) -> Self {
+        Self { base: DepthDecayPriority, patterns, boost: 1.5 }
+    }
+}
+
+impl<P> PathPatternPriority<P> {
+    pub fn with_base<Q: PriorityFunction>(self, base: Q) -> PathPatternPriority<Q> {
+        PathPatternPriority { base, patterns: self.patterns, boost: self.boost }
+    }
+
+    pub fn with_boost(mut self, boost: f64) -> Self {
+        self.boost = boost;
+        self
+    }
+}
+
+impl<P: PriorityFunction> PriorityFunction for PathPatternPriority<P> {
+    fn priority(&self, url: &Url, depth: u32, page_data: Option<&PageData>) -> f64 {
+        let priority = self.base.priority(url, depth, page_data);
+        let path = url.path();
+
+        if self.patterns.iter().any(|pattern| path.contains(pattern.as_str())) {
+            priority * self.boost
+        } else {
+            priority
+        }
+    }
+}
+
+/// The OPIC cash the link holds, only moves when the frontier runs in OPIC mode
+#[derive(Debug, Clone)]
+pub struct OpicPriority {
+    scorer: Arc<OpicScorer>,
+}
+
+impl OpicPriority {
+    pub fn new(scorer: Arc<OpicScorer>) -> Self {
+        Self { scorer }
+    }
+}
+
+impl PriorityFunction for OpicPriority {
+    fn priority(&self, url: &Url, _depth: u32, _page_data: Option<&PageData>) -> f64 {
+        self.scorer.cash(url.as_str())
+    }
+}
+
+/// Result of `PriorityFunction::compose`
+#[derive(Debug, Clone)]
+pub struct ComposedPriority<A, B> {
+    a: A,
+    b: B,
+    weight_a: f64,
+}
+
+impl<A: PriorityFunction, B: PriorityFunction> PriorityFunction for ComposedPriority<A, B> {
+    fn priority(&self, url: &Url, depth: u32, page_data: Option<&PageData>) -> f64 {
+        self.weight_a * self.a.priority(url, depth, page_data)
+            + (1.0 - self.weight_a) * self.b.priority(url, depth, page_data)
+    }
+}
+
+/// Depth decay boosted by `domains` and article-like paths, the crawler's default
+pub fn standard_priority(domains: Vec<String>) -> impl PriorityFunction {
+    DomainBoostPriority::new(domains).with_base(PathPatternPriority::default())
+}
+
+/// The priority function selected in the config; `opic` is required for `PriorityFunctionKind::Opic`
+/// and falls back to the standard function without it
+pub fn priority_function_for(settings: &AlgorithmSettings, opic: Option<Arc<OpicScorer>>) -> Arc<dyn PriorityFunction> {
+    let domains = settings.priority_boost_domains.clone();
+
+    match (settings.priority_function, opic) {
+        (PriorityFunctionKind::DepthDecay, _) => Arc::new(DepthDecayPriority),
+        (PriorityFunctionKind::DomainBoost, _) => Arc::new(DomainBoostPriority::new(domains)),
+        (PriorityFunctionKind::PathPattern, _) => Arc::new(PathPatternPriority::default()),
+        (PriorityFunctionKind::Opic, Some(scorer)) => Arc::new(OpicPriority::new(scorer)),
+        (PriorityFunctionKind::Standard | PriorityFunctionKind::Opic, _) => Arc::new(standard_priority(domains)),
+    }
+}
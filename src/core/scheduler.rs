@@ -1,63 +1,359 @@
-use crate::config::CrawlerConfig;
-use std::sync::Arc;
+use crate::config::{CircuitBreakerConfig, CrawlerConfig, RetryConfig};
+use crate::network::NetworkError;
+use serde::Serialize;
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicU32, AtomicU8, AtomicUsize, Ordering};
 use tokio::sync::Semaphore;
 use tokio::time::{sleep, Duration, Instant};
 use tracing::{debug, warn};
 
+/// Upper bound for a delay grown by slow responses or rate limiting
+const MAX_ADAPTIVE_DELAY: Duration = Duration::from_secs(60);
+
+/// Smallest delay a back off starts from, so domains without a configured delay slow down too
+const MIN_BACKOFF_DELAY: Duration = Duration::from_millis(100);
+
+// weight of the newest sample in the response time moving average
+const RESPONSE_TIME_SMOOTHING: f64 = 0.3;
+
+/// Rate limiting state of a single domain
+#[derive(Debug, Clone)]
+struct DomainDelay {
+    last_request: Option<Instant>,
+    delay: Duration,
+    /// Lowest delay adaptation may shrink to, `request_delay` unless robots.txt asks for more
+    min_delay: Duration,
+    /// Exponential moving average of response times, None until the first response
+    avg_response_ms: Option<f64>,
+}
+
+/// Whether requests to a domain are let through
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+#[repr(u8)]
+pub enum CircuitState {
+    /// Requests go through, failures are being counted
+    Closed = 0,
+    /// Too many failures, requests are rejected until the open duration has passed
+    Open = 1,
+    /// The open duration has passed and a single trial request is under way, its failure
+    /// reopens the circuit and its success closes it
+    HalfOpen = 2,
+}
+
+impl CircuitState {
+    fn from_u8(value: u8) -> Self {
+        match value {
+            1 => CircuitState::Open,
+            2 => CircuitState::HalfOpen,
+            _ => CircuitState::Closed,
+        }
+    }
+}
+
+/// Consecutive failures of a single domain
+#[derive(Debug)]
+struct CircuitBreaker {
+    failures: AtomicU32,
+    state: AtomicU8,
+    last_failure: Mutex<Instant>,
+}
+
+impl CircuitBreaker {
+    fn new() -> Self {
+        Self {
+            failures: AtomicU32::new(0),
+            state: AtomicU8::new(CircuitState::Closed as u8),
+            last_failure: Mutex::new(Instant::now()),
+        }
+    }
+
+    fn state(&self) -> CircuitState {
+        CircuitState::from_u8(self.state.load(Ordering::SeqCst))
+    }
+
+    fn set_state(&self, state: CircuitState) {
+        self.state.store(state as u8, Ordering::SeqCst);
+    }
+
+    /// False while open and while the trial request is under way. Once `open_duration` has
+    /// passed since the last failure, the one request that moves the circuit to half-open is let through.
+    fn allows_request(&self, open_duration: Duration) -> bool {
+        match self.state() {
+            CircuitState::Closed => true,
+            CircuitState::HalfOpen => false,
+            CircuitState::Open => {
+                if self.last_failure.lock().unwrap().elapsed() < open_duration {
+                    return false;
+                }
+                self.state.compare_exchange(
+                    CircuitState::Open as u8,
+                    CircuitState::HalfOpen as u8,
+                    Ordering::SeqCst,
+                    Ordering::SeqCst,
+                ).is_ok()
+            }
+        }
+    }
+
+    /// Count a failure, true when it opened the circuit
+    fn record_failure(&self, open_threshold: u32) -> bool {
+        *self.last_failure.lock().unwrap() = Instant::now();
+        let failures = self.failures.fetch_add(1, Ordering::SeqCst).saturating_add(1);
+        if self.state() == CircuitState::HalfOpen || failures >= open_threshold {
+            self.set_state(CircuitState::Open);
+            return true;
+        }
+        false
+    }
+
+    fn record_success(&self) {
+        self.failures.store(0, Ordering::SeqCst);
+        self.set_state(CircuitState::Closed);
+    }
+}
+
 /// Manages crawling scheduling and rate limiting
 pub struct CrawlScheduler {
     /// Semaphore to limit concurrent requests
     semaphore: Arc<Semaphore>,
 
+    /// Permits the semaphore hands out in total, see `set_max_concurrent`
+    max_concurrent: AtomicUsize,
+
+    /// Permits still held by requests that a lowered limit retires once they are released
+    excess_permits: AtomicUsize,
+
     /// Delay between requests to the same domain
     request_delay: Duration,
 
-    /// Per-domain rate limiting
-    domain_delays: dashmap::DashMap<String, Instant>,
+    /// Per-domain rate limiting, adapted to how fast each server responds
+    domain_delays: dashmap::DashMap<String, DomainDelay>,
+
+    /// Average response time above which a domain's delay is doubled
+    slow_server_threshold: f64,
+
+    /// Average response time below which a domain's delay is halved back toward `request_delay`
+    fast_server_threshold: f64,
+
+    /// Backoff and retry limits for failed requests
+    retry: RetryConfig,
 
-    /// Maximum number of retries for failed requests
-    max_retries: u32,
+    /// Per-domain failure tracking, keeps consistently failing hosts from being retried
+    circuit_breakers: dashmap::DashMap<String, CircuitBreaker>,
+
+    /// When a domain's circuit opens and for how long
+    circuit_breaker: CircuitBreakerConfig,
 }
 
 impl CrawlScheduler {
     pub fn new(config: &CrawlerConfig) -> Self {
         Self {
             semaphore: Arc::new(Semaphore::new(config.crawler.concurrent_requests)),
+            max_concurrent: AtomicUsize::new(config.crawler.concurrent_requests),
+            excess_permits: AtomicUsize::new(0),
             request_delay: Duration::from_millis(config.network.request_delay_ms),
             domain_delays: dashmap::DashMap::new(),
-            max_retries: config.network.max_retries,
+            slow_server_threshold: config.network.slow_server_threshold_ms as f64,
+            fast_server_threshold: config.network.fast_server_threshold_ms as f64,
+            retry: config.network.retry_config(),
+            circuit_breakers: dashmap::DashMap::new(),
+            circuit_breaker: config.network.circuit_breaker.clone(),
         }
     }
 
     /// Acquire a permit for crawling (blocks if at limit)
     pub async fn acquire_permit(&self) -> tokio::sync::SemaphorePermit<'_> {
-        self.semaphore
-            .acquire()
-            .await
-            .expect("Semaphore should not be closed")
+        loop {
+            let permit = self.semaphore
+                .acquire()
+                .await
+                .expect("Semaphore should not be closed");
+
+            // a lowered limit retires released permits before handing them out again
+            if self.excess_permits.fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| n.checked_sub(1)).is_ok() {
+                permit.forget();
+                continue;
+            }
+            return permit;
+        }
+    }
+
+    /// Change how many requests may run at once, at least 1. Requests in flight keep
+    /// their permits, a lower limit takes effect as they finish.
+    pub fn set_max_concurrent(&self, limit: usize) {
+        let limit = limit.max(1);
+        let previous = self.max_concurrent.swap(limit, Ordering::SeqCst);
+
+        if limit > previous {
+            // cancel pending retirements before adding new permits
+            let growth = limit - previous;
+            let excess = self.excess_permits
+                .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| Some(n.saturating_sub(growth)))
+                .unwrap_or(0);
+            self.semaphore.add_permits(growth - excess.min(growth));
+        } else {
+            let shrink = previous - limit;
+            let forgotten = self.semaphore.forget_permits(shrink);
+            self.excess_permits.fetch_add(shrink - forgotten, Ordering::SeqCst);
+        }
+
+        debug!("Concurrent request limit changed from {} to {}", previous, limit);
+    }
+
+    /// Current limit on concurrent requests
+    pub fn max_concurrent(&self) -> usize {
+        self.max_concurrent.load(Ordering::SeqCst)
     }
 
     /// Check if we should delay before crawling this domain
     pub async fn respect_domain_delay(&self, domain: &str) {
-        if let Some(last_request_time) = self.domain_delays.get(domain) {
-            let elapsed = last_request_time.elapsed();
-            if elapsed < self.request_delay {
-                let remaining_delay = self.request_delay - elapsed;
-                debug!("Delaying {}ms for domain: {}", remaining_delay.as_millis(), domain);
-                sleep(remaining_delay).await;
-            }
+        let remaining_delay = self.domain_delays.get(domain).and_then(|state| {
+            state.last_request.map(|last_request_time| state.delay.saturating_sub(last_request_time.elapsed()))
+        });
+
+        if let Some(remaining_delay) = remaining_delay
+            && !remaining_delay.is_zero()
+        {
+            debug!("Delaying {}ms for domain: {}", remaining_delay.as_millis(), domain);
+            sleep(remaining_delay).await;
         }
 
         // Update last request time for this domain
-        self.domain_delays.insert(domain.to_string(), Instant::now());
+        self.domain_state(domain).last_request = Some(Instant::now());
+    }
+
+    /// Feed a response back into the domain's rate limit: slow servers get their delay
+    /// doubled, starting from at least 100ms, fast ones halved back toward the configured `request_delay_ms`, and a
+    /// 429 always backs off
+    pub fn adaptive_delay(&self, domain: &str, response_time_ms: u64, status_code: u16) {
+        let mut state = self.domain_state(domain);
+
+        let average = match state.avg_response_ms {
+            Some(average) => RESPONSE_TIME_SMOOTHING * response_time_ms as f64 + (1.0 - RESPONSE_TIME_SMOOTHING) * average,
+            None => response_time_ms as f64,
+        };
+        state.avg_response_ms = Some(average);
+
+        let previous = state.delay;
+        if status_code == 429 || average > self.slow_server_threshold {
+            state.delay = (state.delay * 2).max(MIN_BACKOFF_DELAY).min(MAX_ADAPTIVE_DELAY).max(previous);
+        } else if average < self.fast_server_threshold {
+            let halved = state.delay / 2;
+            // below the back off floor go straight back to the configured delay
+            state.delay = if halved < MIN_BACKOFF_DELAY { state.min_delay } else { halved.max(state.min_delay) };
+        }
+
+        if state.delay != previous {
+            debug!(
+                "Delay for {} changed from {}ms to {}ms (avg response {:.0}ms, status {})",
+                domain,
+                previous.as_millis(),
+                state.delay.as_millis(),
+                average,
+                status_code
+            );
+        }
+    }
+
+    /// Wait at least `retry_after` between requests to `domain`, as asked by a
+    /// `Retry-After` header on a 429 response
+    pub fn apply_retry_after(&self, domain: &str, retry_after: Duration) {
+        let mut state = self.domain_state(domain);
+        if retry_after > state.delay {
+            debug!("Honouring Retry-After of {}s for {}", retry_after.as_secs(), domain);
+            state.delay = retry_after;
+        }
+    }
+
+    /// Never wait less than `crawl_delay` between requests to `domain`, as asked by the
+    /// `Crawl-delay` directive of its robots.txt. Delays below `request_delay` are ignored.
+    pub fn set_crawl_delay(&self, domain: &str, crawl_delay: Duration) {
+        let mut state = self.domain_state(domain);
+        state.min_delay = crawl_delay.max(self.request_delay);
+        if state.delay < state.min_delay {
+            debug!("Using Crawl-delay of {}ms for {}", state.min_delay.as_millis(), domain);
+            state.delay = state.min_delay;
+        }
+    }
+
+    /// Current delay between requests to `domain`
+    pub fn get_domain_delay(&self, domain: &str) -> Duration {
+        self.domain_delays
+            .get(domain)
+            .map(|state| state.delay)
+            .unwrap_or(self.request_delay)
+    }
+
+    fn domain_state(&self, domain: &str) -> dashmap::mapref::one::RefMut<'_, String, DomainDelay> {
+        self.domain_delays
+            .entry(domain.to_string())
+            .or_insert_with(|| DomainDelay {
+                last_request: None,
+                delay: self.request_delay,
+                min_delay: self.request_delay,
+                avg_response_ms: None,
+            })
+    }
+
+    /// Circuit state of every domain that has had a retryable failure, sorted by domain
+    pub fn get_circuit_states(&self) -> Vec<(String, CircuitState)> {
+        let mut states: Vec<_> = self.circuit_breakers
+            .iter()
+            .map(|entry| (entry.key().clone(), entry.value().state()))
+            .collect();
+        states.sort_by(|a, b| a.0.cmp(&b.0));
+        states
+    }
+
+    fn check_circuit(&self, domain: &str) -> Result<(), SchedulerError> {
+        if self.circuit_breaker.open_threshold == 0 {
+            return Ok(());
+        }
+        let open_duration = Duration::from_secs(self.circuit_breaker.open_duration_secs);
+        match self.circuit_breakers.get(domain) {
+            Some(breaker) if !breaker.allows_request(open_duration) => Err(SchedulerError::CircuitOpen(domain.to_string())),
+            _ => Ok(()),
+        }
     }
 
-    /// Execute a crawling task with proper scheduling
+    /// Count a retryable failure against the domain, true when its circuit is now open
+    fn record_failure(&self, domain: &str) -> bool {
+        if self.circuit_breaker.open_threshold == 0 {
+            return false;
+        }
+        let breaker = self.circuit_breakers.entry(domain.to_string()).or_insert_with(CircuitBreaker::new);
+        let opened = breaker.record_failure(self.circuit_breaker.open_threshold);
+        if opened {
+            warn!(
+                "Circuit for {} opened after {} failures, skipping it for {}s",
+                domain,
+                breaker.failures.load(Ordering::SeqCst),
+                self.circuit_breaker.open_duration_secs
+            );
+        }
+        opened
+    }
+
+    fn record_success(&self, domain: &str) {
+        if self.circuit_breaker.open_threshold == 0 {
+            return;
+        }
+        // domains that never failed don't need a breaker
+        if let Some(breaker) = self.circuit_breakers.get(domain) {
+            breaker.record_success();
+        }
+    }
+
+    /// Execute a crawling task with proper scheduling. Fails with `CircuitOpen` without
+    /// running the task while the domain's circuit is open.
     pub async fn schedule_crawl<F, Fut, T>(&self, domain: &str, task: F) -> Result<T, SchedulerError>
     where
         F: Fn() -> Fut, // Changed: FnOnce -> Fn (allows multiple calls)
         Fut: std::future::Future<Output = Result<T, Box<dyn std::error::Error + Send + Sync>>>,
     {
+        self.check_circuit(domain)?;
+
         // Acquire semaphore permit (limits concurrency)
         let _permit = self.acquire_permit().await;
 
@@ -71,13 +367,28 @@ impl CrawlScheduler {
             attempts += 1;
 
             match task().await {
-                Ok(result) => return Ok(result),
+                Ok(result) => {
+                    self.record_success(domain);
+                    return Ok(result);
+                }
                 Err(e) => {
-                    if attempts >= self.max_retries {
-                        return Err(SchedulerError::MaxRetriesExceeded(e.to_string()));
+                    if !Self::is_retryable(e.as_ref()) {
+                        debug!("Request failed with non-retryable error: {}", e);
+                        // not a sign of an overloaded server, and it ends a half-open trial
+                        self.record_success(domain);
+                        return Err(SchedulerError::NonRetryable(e));
+                    }
+
+                    if self.record_failure(domain) {
+                        debug!("Giving up on {} after its circuit opened: {}", domain, e);
+                        return Err(SchedulerError::CircuitOpen(domain.to_string()));
                     }
 
-                    let delay = Duration::from_millis(1000 * attempts as u64);
+                    if attempts >= self.retry.max_retries {
+                        return Err(SchedulerError::MaxRetriesExceeded(e));
+                    }
+
+                    let delay = self.retry.backoff_delay(attempts).max(Self::retry_after(e.as_ref()));
                     warn!(
                         "Request failed (attempt {}), retrying in {}ms: {}",
                         attempts,
@@ -85,11 +396,32 @@ impl CrawlScheduler {
                         e
                     );
                     sleep(delay).await;
+
+                    // other requests to the domain may have opened its circuit meanwhile
+                    self.check_circuit(domain)?;
                 }
             }
         }
     }
 
+    /// Network errors decide for themselves, anything else is assumed transient
+    fn is_retryable(error: &(dyn std::error::Error + 'static)) -> bool {
+        match error.downcast_ref::<NetworkError>() {
+            Some(network_error) => network_error.is_retryable(),
+            None => true,
+        }
+    }
+
+    /// Retry-After of a rate limited request, capped so a retry doesn't sit on its permit for long
+    fn retry_after(error: &(dyn std::error::Error + 'static)) -> Duration {
+        match error.downcast_ref::<NetworkError>() {
+            Some(NetworkError::RateLimited { retry_after_secs: Some(secs), .. }) => {
+                Duration::from_secs(*secs).min(MAX_ADAPTIVE_DELAY)
+            }
+            _ => Duration::ZERO,
+        }
+    }
+
     /// Get current scheduler statistics
     pub fn get_stats(&self) -> SchedulerStats {
         SchedulerStats {
@@ -105,8 +437,15 @@ pub struct SchedulerStats {
     pub active_domains: usize,
 }
 
+/// Wraps the error of the last attempt, reachable through `source()`
 #[derive(Debug, thiserror::Error)]
 pub enum SchedulerError {
     #[error("Maximum retries exceeded: {0}")]
-    MaxRetriesExceeded(String)
+    MaxRetriesExceeded(#[source] Box<dyn std::error::Error + Send + Sync>),
+
+    #[error("Non-retryable error: {0}")]
+    NonRetryable(#[source] Box<dyn std::error::Error + Send + Sync>),
+
+    #[error("Circuit open for {0}, too many recent failures")]
+    CircuitOpen(String),
 }
@@ -0,0 +1,86 @@
+use crate::core::{PageProcessor, PdfExtractor, PlainTextExtractor, XmlExtractor};
+use crate::network::HttpResponse;
+
+fn response(content_type: &str, content: &str) -> HttpResponse {
+    HttpResponse {
+        url: "https://example.com/doc".to_string(),
+        final_url: "https://example.com/doc".to_string(),
+        status_code: 200,
+        headers: reqwest::header::HeaderMap::new(),
+        content: content.to_string(),
+        content_type: content_type.to_string(),
+        content_length: Some(content.len()),
+        encoding: "utf-8".to_string(),
+        fetch_time_ms: 1,
+        redirect_count: 0,
+        rendered_with_js: false,
+        raw_body: None,
+    }
+}
+
+#[test]
+fn test_plain_text_extractor() {
+    let page = PlainTextExtractor::extract("\n  Release notes\n\nFixed   the crawler\tretry loop.\n");
+
+    assert_eq!(page.title.as_deref(), Some("Release notes"));
+    assert_eq!(page.content, "Release notes Fixed the crawler retry loop.");
+    assert_eq!(page.word_count, 7);
+
+    let long_first_line = "word ".repeat(40);
+    assert_eq!(PlainTextExtractor::extract(&long_first_line).title, None);
+}
+
+#[test]
+fn test_xml_extractor() {
+    let xml = r#"<?xml version="1.0"?>
+        <!-- generated -->
+        <rss><channel>
+            <title>Monsoon &amp; Rivers</title>
+            <item><title>Second title</title><description><![CDATA[<b>Heavy</b> rain]]></description></item>
+            <empty/>
+            <note>caf&#233; &#x2014; &lt;ok&gt;</note>
+        </channel></rss>"#;
+
+    let page = XmlExtractor::extract(xml);
+    assert_eq!(page.title.as_deref(), Some("Monsoon & Rivers"));
+    assert_eq!(page.content, "Monsoon & Rivers Second title <b>Heavy</b> rain café — <ok>");
+    assert!(!page.content.contains("generated"));
+
+    let namespaced = XmlExtractor::extract("<doc><dc:title>Paper</dc:title><p>Body</p></doc>");
+    assert_eq!(namespaced.title.as_deref(), Some("Paper"));
+}
+
+#[cfg(not(feature = "pdf"))]
+#[test]
+fn test_pdf_extractor_needs_feature() {
+    assert!(PdfExtractor::extract(b"%PDF-1.4").is_err());
+}
+
+#[tokio::test]
+async fn test_process_response_routes_by_content_type() {
+    let processor = PageProcessor::new();
+
+    let text = processor
+        .process_response("https://example.com/notes.txt", &response("text/plain", "Notes\nplain text body"), 2)
+        .await
+        .unwrap();
+    assert_eq!(text.url, "https://example.com/notes.txt");
+    assert_eq!(text.depth, 2);
+    assert_eq!(text.title.as_deref(), Some("Notes"));
+    assert!(text.content_quality_score > 0.0);
+    assert_eq!(text.quality_factors.len(), 3);
+
+    let feed = processor
+        .process_response("https://example.com/feed", &response("application/atom+xml", "<feed><title>Feed</title></feed>"), 0)
+        .await
+        .unwrap();
+    assert_eq!(feed.title.as_deref(), Some("Feed"));
+
+    let html = "<html><head><title>Page</title></head><body><a href=\"/next\">next</a></body></html>";
+    let page = processor
+        .process_response("https://example.com/", &response("application/xhtml+xml", html), 0)
+        .await
+        .unwrap();
+    assert_eq!(page.title.as_deref(), Some("Page"));
+    assert_eq!(page.outgoing_links.len(), 1);
+}
@@ -0,0 +1,70 @@
+use crate::core::FeedProcessor;
+use crate::core::page_processor::ProcessorError;
+use chrono::{TimeZone, Utc};
+
+#[test]
+fn test_parse_rss_feed() {
+    let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+        <rss version="2.0">
+          <channel>
+            <title>Example News</title>
+            <link>https://example.com/</link>
+            <item>
+              <title>First story</title>
+              <link>https://example.com/news/first</link>
+              <description>What happened first</description>
+              <pubDate>Mon, 04 Mar 2024 10:00:00 GMT</pubDate>
+            </item>
+            <item>
+              <title>Relative link</title>
+              <link>/news/second</link>
+            </item>
+            <item>
+              <title>No link at all</title>
+            </item>
+          </channel>
+        </rss>"#;
+
+    let items = FeedProcessor::parse_feed("https://example.com/feed.xml", xml).unwrap();
+
+    assert_eq!(items.len(), 2);
+    assert_eq!(items[0].url, "https://example.com/news/first");
+    assert_eq!(items[0].title, "First story");
+    assert_eq!(items[0].summary.as_deref(), Some("What happened first"));
+    assert_eq!(items[0].published, Some(Utc.with_ymd_and_hms(2024, 3, 4, 10, 0, 0).unwrap()));
+    assert_eq!(items[1].url, "https://example.com/news/second");
+    assert_eq!(items[1].published, None);
+}
+
+#[test]
+fn test_parse_atom_feed() {
+    let xml = r#"<?xml version="1.0" encoding="utf-8"?>
+        <feed xmlns="http://www.w3.org/2005/Atom">
+          <title>Example Blog</title>
+          <id>urn:example:blog</id>
+          <updated>2024-03-05T08:00:00Z</updated>
+          <entry>
+            <title>Atom entry</title>
+            <id>urn:example:entry:1</id>
+            <link rel="edit" href="https://example.com/api/posts/1"/>
+            <link rel="alternate" href="https://example.com/posts/1"/>
+            <updated>2024-03-05T08:00:00Z</updated>
+            <summary>An entry summary</summary>
+          </entry>
+        </feed>"#;
+
+    let items = FeedProcessor::parse_feed("https://example.com/atom.xml", xml).unwrap();
+
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0].url, "https://example.com/posts/1");
+    assert_eq!(items[0].title, "Atom entry");
+    assert_eq!(items[0].summary.as_deref(), Some("An entry summary"));
+    // no <published>, falls back to <updated>
+    assert_eq!(items[0].published, Some(Utc.with_ymd_and_hms(2024, 3, 5, 8, 0, 0).unwrap()));
+}
+
+#[test]
+fn test_parse_invalid_feed() {
+    let result = FeedProcessor::parse_feed("https://example.com/feed.xml", "<html><body>not a feed</body></html>");
+    assert!(matches!(result, Err(ProcessorError::FeedParseError(_))));
+}
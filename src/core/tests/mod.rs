@@ -5,6 +5,14 @@ mod url_frontier_tests;
 #[cfg(test)]
 mod page_processor_tests;
 #[cfg(test)]
+mod feed_processor_tests;
+#[cfg(test)]
 mod scheduler_tests;
 #[cfg(test)]
 mod crawler_tests;
+#[cfg(test)]
+mod opic_tests;
+#[cfg(test)]
+mod priority_tests;
+#[cfg(test)]
+mod extractors_tests;
@@ -0,0 +1,20 @@
+use crate::core::OpicScorer;
+
+#[test]
+fn test_opic_cash_flow() {
+    let scorer = OpicScorer::new();
+    scorer.initialize(&["a".to_string(), "b".to_string()]);
+    assert_eq!(scorer.cash("a"), 0.5);
+
+    scorer.on_crawled("a", &["c".to_string(), "d".to_string()]);
+    scorer.on_crawled("b", &["c".to_string()]);
+
+    assert_eq!(scorer.cash("a"), 0.0);
+    assert_eq!(scorer.history("a"), 0.5);
+    assert_eq!(scorer.cash("c"), 0.75);
+    assert_eq!(scorer.cash("d"), 0.25);
+
+    // cash is only moved around, never created
+    let total: f64 = ["a", "b", "c", "d"].iter().map(|u| scorer.cash(u)).sum();
+    assert!((total - 1.0).abs() < 1e-12);
+}
@@ -1,4 +1,4 @@
-use crate::core::PageProcessor;
+use crate::core::{OpenGraphData, PageProcessor};
 
 #[tokio::test]
 async fn test_page_processor_html_parsing() {
@@ -88,3 +88,233 @@ fn test_priority_domains() {
     // This is a basic test - actual priority logic would be tested in integration
     assert!(true); // Placeholder
 }
+
+#[test]
+fn test_blocked_domain_wildcards() {
+    let mut processor = PageProcessor::new();
+    processor.add_blocked_domain("*.ads.example.com").unwrap();
+    processor.add_blocked_domain("tracker.net").unwrap();
+
+    assert!(!processor.is_domain_allowed("banner.ads.example.com"));
+    assert!(!processor.is_domain_allowed("a.b.ads.example.com"));
+    assert!(!processor.is_domain_allowed("tracker.net"));
+    assert!(!processor.is_domain_allowed("cdn.tracker.net"));
+    assert!(!processor.is_domain_allowed("CDN.Tracker.NET"));
+
+    assert!(processor.is_domain_allowed("example.com"));
+    assert!(processor.is_domain_allowed("www.example.com"));
+    assert!(processor.is_domain_allowed("nottracker.net"));
+}
+
+#[test]
+fn test_allowed_domain_wildcards() {
+    let mut processor = PageProcessor::new();
+    processor.add_allowed_domain("*.rust-lang.org").unwrap();
+    processor.add_allowed_domain("docs.rs").unwrap();
+    processor.add_blocked_domain("internals.rust-lang.org").unwrap();
+
+    assert!(processor.is_domain_allowed("doc.rust-lang.org"));
+    assert!(processor.is_domain_allowed("docs.rs"));
+    assert!(processor.is_domain_allowed("static.docs.rs"));
+
+    // blocklist wins over the allowlist
+    assert!(!processor.is_domain_allowed("internals.rust-lang.org"));
+    assert!(!processor.is_domain_allowed("example.com"));
+    assert!(!processor.is_domain_allowed("rust-lang.org.evil.com"));
+}
+
+#[tokio::test]
+async fn test_domain_filters_apply_to_extracted_links() {
+    let mut processor = PageProcessor::new();
+    processor.add_allowed_domain("example.com").unwrap();
+    processor.add_blocked_domain("*.ads.example.com").unwrap();
+
+    let html = r#"<html><body>
+        <a href="/about">About</a>
+        <a href="https://blog.example.com/post">Blog</a>
+        <a href="https://click.ads.example.com/x">Ad</a>
+        <a href="https://other.org/">Other</a>
+    </body></html>"#;
+
+    let page = processor.process_page("https://example.com/", html, 0).await.unwrap();
+    let urls: Vec<&str> = page.outgoing_links.iter().map(|l| l.url.as_str()).collect();
+
+    assert_eq!(urls, vec!["https://example.com/about", "https://blog.example.com/post"]);
+}
+
+#[test]
+fn test_invalid_domain_pattern() {
+    let mut processor = PageProcessor::new();
+    assert!(processor.add_blocked_domain("[unclosed").is_err());
+}
+
+#[test]
+fn test_extract_feed_urls() {
+    let html = r#"<html><head>
+        <link rel="alternate" type="application/rss+xml" title="RSS" href="/feed.xml">
+        <link rel="alternate" type="application/atom+xml" href="https://example.com/atom.xml">
+        <link rel="alternate" type="application/rss+xml" href="/feed.xml">
+        <link rel="alternate" hreflang="fr" href="/fr/">
+        <link rel="stylesheet" type="text/css" href="/style.css">
+    </head><body></body></html>"#;
+
+    let document = scraper::Html::parse_document(html);
+    let feeds = PageProcessor::extract_feed_urls(&document, "https://example.com/blog/");
+
+    assert_eq!(feeds, vec!["https://example.com/feed.xml", "https://example.com/atom.xml"]);
+}
+
+#[test]
+fn test_extract_canonical_url() {
+    let html = r#"<html><head>
+        <link rel="stylesheet" href="/style.css">
+        <link rel="canonical" href="/articles/rust">
+        <link rel="canonical" href="https://example.com/other">
+    </head><body></body></html>"#;
+
+    let document = scraper::Html::parse_document(html);
+    let canonical = PageProcessor::extract_canonical_url(&document, "http://www.example.com/articles/rust/?ref=feed");
+    assert_eq!(canonical.as_deref(), Some("http://www.example.com/articles/rust"));
+
+    let document = scraper::Html::parse_document(r#"<html><head><link rel="canonical" href="javascript:void(0)"></head></html>"#);
+    assert_eq!(PageProcessor::extract_canonical_url(&document, "https://example.com/"), None);
+
+    let document = scraper::Html::parse_document("<html><head><title>No canonical</title></head></html>");
+    assert_eq!(PageProcessor::extract_canonical_url(&document, "https://example.com/"), None);
+}
+
+#[tokio::test]
+async fn test_canonical_url_only_kept_on_same_site() {
+    let processor = PageProcessor::new();
+
+    let html = r#"<html><head><link rel="canonical" href="https://example.com/post"></head>
+        <body><p>Same post, other spelling</p></body></html>"#;
+    let page = processor.process_page("http://www.example.com/post/", html, 0).await.unwrap();
+    assert_eq!(page.canonical_url.as_deref(), Some("https://example.com/post"));
+    assert_eq!(page.storage_url(), "https://example.com/post");
+    assert_eq!(page.alias_url(), Some("http://www.example.com/post/"));
+
+    let html = r#"<html><head><link rel="canonical" href="https://other.org/post"></head>
+        <body><p>Claims to be someone else's page</p></body></html>"#;
+    let page = processor.process_page("https://example.com/post", html, 0).await.unwrap();
+    assert_eq!(page.canonical_url, None);
+    assert_eq!(page.storage_url(), "https://example.com/post");
+    assert_eq!(page.alias_url(), None);
+}
+
+#[test]
+fn test_extract_open_graph() {
+    let html = r#"<html><head>
+        <meta property="og:title" content="  Monsoon arrives in Kerala ">
+        <meta property="og:type" content="article">
+        <meta property="og:image" content="">
+        <meta property="og:image" content="https://example.com/rain.jpg">
+        <meta property="og:image" content="https://example.com/other.jpg">
+        <meta property="og:site_name" content="Example News">
+        <meta name="og:description" content="not an Open Graph property attribute">
+    </head><body></body></html>"#;
+
+    let document = scraper::Html::parse_document(html);
+    let og = PageProcessor::extract_open_graph(&document);
+
+    assert_eq!(og, OpenGraphData {
+        title: Some("Monsoon arrives in Kerala".to_string()),
+        description: None,
+        image: Some("https://example.com/rain.jpg".to_string()),
+        og_type: Some("article".to_string()),
+        site_name: Some("Example News".to_string()),
+    });
+    assert!(PageProcessor::extract_open_graph(&scraper::Html::parse_document("<p>plain</p>")).is_empty());
+}
+
+#[test]
+fn test_extract_json_ld() {
+    let html = r#"<html><head>
+        <script type="application/ld+json">{"@type": "NewsArticle", "headline": "Monsoon"}</script>
+        <script type="application/ld+json">[{"@type": "Person"}, {"@type": "Place"}]</script>
+        <script type="application/ld+json">{"@context": "https://schema.org", "@graph": [{"@type": "WebSite"}]}</script>
+        <script type="application/ld+json">{ not json </script>
+        <script type="text/javascript">{"@type": "Ignored"}</script>
+    </head><body></body></html>"#;
+
+    let document = scraper::Html::parse_document(html);
+    let types: Vec<_> = PageProcessor::extract_json_ld(&document)
+        .iter()
+        .map(|value| value["@type"].as_str().unwrap().to_string())
+        .collect();
+
+    assert_eq!(types, vec!["NewsArticle", "Person", "Place", "WebSite"]);
+}
+
+#[tokio::test]
+async fn test_open_graph_boosts_quality() {
+    let processor = PageProcessor::new();
+    let body = "<body><p>Heavy rain is expected across the coastal districts this week.</p></body>";
+    let plain = format!("<html><head><title>Rain</title></head>{}</html>", body);
+    let tagged = format!(
+        "<html><head><title>Rain</title><meta property=\"og:type\" content=\"article\">\
+         <meta property=\"og:image\" content=\"https://example.com/rain.jpg\"></head>{}</html>",
+        body,
+    );
+
+    let plain = processor.process_page("https://example.com/a", &plain, 0).await.unwrap();
+    let tagged = processor.process_page("https://example.com/a", &tagged, 0).await.unwrap();
+
+    assert!(tagged.content_quality_score > plain.content_quality_score);
+    assert_eq!(tagged.og_image.as_deref(), Some("https://example.com/rain.jpg"));
+    assert_eq!(tagged.og_type.as_deref(), Some("article"));
+    assert_eq!(plain.og_image, None);
+}
+
+#[tokio::test]
+async fn test_quality_factors() {
+    let processor = PageProcessor::new();
+    let paragraph = "Ownership rules let the compiler free memory without a garbage collector.";
+    let article = format!(
+        "<html><head><title>Ownership</title>\
+         <meta name=\"description\" content=\"How ownership and borrowing manage memory in Rust programs.\"></head>\
+         <body><h1>Ownership</h1><p>{}</p><pre><code>let s = String::new();</code></pre></body></html>",
+        paragraph,
+    );
+    let link_farm = format!(
+        "<html><head><title>Links</title></head><body><p>{}</p></body></html>",
+        (0..20).map(|i| format!("<a href=\"/p{}\">cheap offers here</a>", i)).collect::<String>(),
+    );
+
+    let article = processor.process_page("https://example.com/a", &article, 0).await.unwrap();
+    let link_farm = processor.process_page("https://example.com/b", &link_farm, 0).await.unwrap();
+
+    let factor = |page: &crate::models::PageData, name: &str| {
+        page.quality_factors.iter().find(|(factor, _)| factor == name).map(|(_, score)| *score)
+    };
+
+    assert_eq!(factor(&article, "headings"), Some(1.0));
+    assert_eq!(factor(&article, "code_blocks"), Some(1.0));
+    assert_eq!(factor(&article, "description"), Some(1.0));
+    assert_eq!(factor(&link_farm, "headings"), Some(0.0));
+    assert_eq!(factor(&link_farm, "link_density"), Some(0.0));
+    assert_eq!(factor(&link_farm, "description"), Some(0.0));
+    assert!(article.content_quality_score > link_farm.content_quality_score);
+}
+
+#[tokio::test]
+async fn test_process_page_streaming() {
+    let processor = PageProcessor::new();
+    let html = "<html><head><title>Caf\u{e9} menu</title></head><body><p>Soup of the day</p><a href=\"/next\">next</a></body></html>";
+
+    // a chunk boundary inside a multi-byte character must not garble it
+    let split = html.find('\u{e9}').unwrap() + 1;
+    let chunks = vec![
+        bytes::Bytes::copy_from_slice(&html.as_bytes()[..split]),
+        bytes::Bytes::copy_from_slice(&html.as_bytes()[split..]),
+    ];
+    let streamed = processor
+        .process_page_streaming("https://example.com/", futures_util::stream::iter(chunks))
+        .await
+        .unwrap();
+    let buffered = processor.process_page("https://example.com/", html, 0).await.unwrap();
+
+    assert_eq!(streamed.title.as_deref(), Some("Caf\u{e9} menu"));
+    assert_eq!(streamed.content, buffered.content);
+    assert_eq!(streamed.outgoing_links.len(), 1);
+}
@@ -0,0 +1,67 @@
+use crate::config::CrawlerConfig;
+use crate::config::PriorityFunctionKind;
+use crate::core::priority::{priority_function_for, standard_priority};
+use crate::core::{
+    DepthDecayPriority, DomainBoostPriority, OpicPriority, OpicScorer, PageProcessor, PathPatternPriority, PriorityFunction,
+};
+use std::sync::Arc;
+use url::Url;
+
+fn url(s: &str) -> Url {
+    Url::parse(s).unwrap()
+}
+
+#[test]
+fn test_builtin_priority_functions() {
+    let page = url("https://github.com/blog/release");
+
+    assert_eq!(DepthDecayPriority.priority(&page, 1, None), 0.5);
+    assert_eq!(DomainBoostPriority::new(vec!["github.com".to_string()]).priority(&page, 1, None), 1.0);
+    assert_eq!(DomainBoostPriority::new(vec!["gitlab.com".to_string()]).priority(&page, 1, None), 0.5);
+    assert_eq!(PathPatternPriority::default().priority(&page, 1, None), 0.75);
+    assert_eq!(PathPatternPriority::default().priority(&url("https://github.com/about"), 1, None), 0.5);
+
+    // the default keeps the original depth decay * domain boost * path boost
+    assert_eq!(standard_priority(vec!["github.com".to_string()]).priority(&page, 1, None), 1.5);
+}
+
+#[test]
+fn test_compose_weights_priorities() {
+    let page = url("https://example.com/research/paper");
+
+    let composed = PriorityFunction::compose(DepthDecayPriority, PathPatternPriority::default(), 0.25);
+    // 0.25 * 1.0 + 0.75 * 1.5
+    assert!((composed.priority(&page, 0, None) - 1.375).abs() < 1e-12);
+
+    let scorer = Arc::new(OpicScorer::new());
+    scorer.initialize(&[page.to_string()]);
+    let with_opic = DepthDecayPriority.compose(OpicPriority::new(scorer), 0.5);
+    assert_eq!(with_opic.priority(&page, 1, None), 0.75);
+}
+
+#[test]
+fn test_priority_function_from_config() {
+    let mut config = CrawlerConfig::default();
+    let page = url("https://en.wikipedia.org/wiki/Crawler");
+
+    assert_eq!(priority_function_for(&config.algorithms, None).priority(&page, 0, None), 2.0);
+
+    config.algorithms.priority_function = PriorityFunctionKind::DepthDecay;
+    assert_eq!(priority_function_for(&config.algorithms, None).priority(&page, 0, None), 1.0);
+
+    config.algorithms.priority_function = PriorityFunctionKind::Opic;
+    let scorer = Arc::new(OpicScorer::new());
+    scorer.initialize(&[page.to_string()]);
+    assert_eq!(priority_function_for(&config.algorithms, Some(scorer)).priority(&page, 0, None), 1.0);
+}
+
+#[tokio::test]
+async fn test_processor_uses_priority_function() {
+    let mut processor = PageProcessor::new();
+    processor.set_priority_function(Arc::new(DepthDecayPriority));
+
+    let html = r#"<html><body><a href="/blog/post">Post</a></body></html>"#;
+    let page = processor.process_page("https://example.com/", html, 0).await.unwrap();
+
+    assert_eq!(page.outgoing_links[0].priority, 0.5);
+}
@@ -170,3 +170,388 @@ async fn test_scheduler_different_domains() {
     // Different domains should be fast
     assert!(elapsed < Duration::from_millis(200));
 }
+
+#[test]
+fn test_retry_backoff_is_exponential_and_capped() {
+    let retry = crate::config::RetryConfig {
+        base_delay_ms: 100,
+        max_delay_ms: 1000,
+        jitter_fraction: 0.0,
+        max_retries: 10,
+    };
+
+    assert_eq!(retry.backoff_delay(1), Duration::from_millis(100));
+    assert_eq!(retry.backoff_delay(2), Duration::from_millis(200));
+    assert_eq!(retry.backoff_delay(3), Duration::from_millis(400));
+    assert_eq!(retry.backoff_delay(4), Duration::from_millis(800));
+    assert_eq!(retry.backoff_delay(5), Duration::from_millis(1000));
+    assert_eq!(retry.backoff_delay(100), Duration::from_millis(1000));
+}
+
+#[test]
+fn test_retry_backoff_jitter_bounds() {
+    let retry = crate::config::RetryConfig {
+        base_delay_ms: 1000,
+        max_delay_ms: 60_000,
+        jitter_fraction: 0.25,
+        max_retries: 5,
+    };
+
+    for attempt in 1..=4 {
+        let base = retry.base_delay(attempt);
+        for _ in 0..50 {
+            let delay = retry.backoff_delay(attempt);
+            assert!(delay >= base.mul_f64(0.75) && delay <= base.mul_f64(1.25), "{:?} outside jitter of {:?}", delay, base);
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_scheduler_does_not_retry_non_retryable_errors() {
+    let mut config = CrawlerConfig::default();
+    config.network.retry = Some(crate::config::RetryConfig {
+        base_delay_ms: 10,
+        max_delay_ms: 50,
+        jitter_fraction: 0.0,
+        max_retries: 5,
+    });
+    let scheduler = CrawlScheduler::new(&config);
+
+    for error in [
+        crate::network::NetworkError::Http { status: 404, message: "Not Found".to_string() },
+        crate::network::NetworkError::RobotsDisallowed("https://example.com/private".to_string()),
+    ] {
+        let attempt_count = Arc::new(AtomicUsize::new(0));
+        let error = Arc::new(std::sync::Mutex::new(Some(error)));
+
+        let result = scheduler.schedule_crawl("example.com", {
+            let attempt_count = Arc::clone(&attempt_count);
+            move || {
+                attempt_count.fetch_add(1, Ordering::Relaxed);
+                let error = error.lock().unwrap().take();
+                async move {
+                    Err::<String, Box<dyn std::error::Error + Send + Sync>>(Box::new(error.unwrap()))
+                }
+            }
+        }).await;
+
+        assert!(matches!(result, Err(crate::core::scheduler::SchedulerError::NonRetryable(_))));
+        assert_eq!(attempt_count.load(Ordering::Relaxed), 1);
+    }
+}
+
+#[tokio::test]
+async fn test_scheduler_retries_server_errors() {
+    let mut config = CrawlerConfig::default();
+    config.network.retry = Some(crate::config::RetryConfig {
+        base_delay_ms: 10,
+        max_delay_ms: 50,
+        jitter_fraction: 0.1,
+        max_retries: 4,
+    });
+    let scheduler = CrawlScheduler::new(&config);
+    let attempt_count = Arc::new(AtomicUsize::new(0));
+
+    let result = scheduler.schedule_crawl("example.com", {
+        let attempt_count = Arc::clone(&attempt_count);
+        move || {
+            attempt_count.fetch_add(1, Ordering::Relaxed);
+            async move {
+                Err::<String, Box<dyn std::error::Error + Send + Sync>>(Box::new(
+                    crate::network::NetworkError::Http { status: 503, message: "Service Unavailable".to_string() }
+                ))
+            }
+        }
+    }).await;
+
+    assert!(matches!(result, Err(crate::core::scheduler::SchedulerError::MaxRetriesExceeded(_))));
+    assert_eq!(attempt_count.load(Ordering::Relaxed), 4);
+}
+
+#[test]
+fn test_adaptive_delay_backs_off_slow_servers_and_recovers() {
+    let config = CrawlerConfig::default();
+    let scheduler = CrawlScheduler::new(&config);
+    let base = Duration::from_millis(config.network.request_delay_ms);
+
+    assert_eq!(scheduler.get_domain_delay("slow.com"), base);
+
+    scheduler.adaptive_delay("slow.com", 5000, 200);
+    assert_eq!(scheduler.get_domain_delay("slow.com"), base * 2);
+    scheduler.adaptive_delay("slow.com", 6000, 200);
+    assert_eq!(scheduler.get_domain_delay("slow.com"), base * 4);
+
+    // an average between the thresholds leaves the delay alone
+    scheduler.adaptive_delay("between.com", 1000, 200);
+    scheduler.adaptive_delay("between.com", 2000, 200);
+    assert_eq!(scheduler.get_domain_delay("between.com"), base);
+
+    // the moving average has to drop below the fast threshold before the delay shrinks
+    for _ in 0..20 {
+        scheduler.adaptive_delay("slow.com", 100, 200);
+    }
+    assert_eq!(scheduler.get_domain_delay("slow.com"), base);
+
+    // other domains keep the configured delay
+    assert_eq!(scheduler.get_domain_delay("other.com"), base);
+}
+
+#[test]
+fn test_crawl_delay_is_a_floor_for_adaptation() {
+    let config = CrawlerConfig::default();
+    let scheduler = CrawlScheduler::new(&config);
+    let base = Duration::from_millis(config.network.request_delay_ms);
+
+    scheduler.set_crawl_delay("polite.com", base * 3);
+    assert_eq!(scheduler.get_domain_delay("polite.com"), base * 3);
+
+    // fast responses no longer shrink the delay below the Crawl-delay
+    for _ in 0..20 {
+        scheduler.adaptive_delay("polite.com", 10, 200);
+    }
+    assert_eq!(scheduler.get_domain_delay("polite.com"), base * 3);
+
+    // a Crawl-delay shorter than the configured delay changes nothing
+    scheduler.set_crawl_delay("eager.com", base / 4);
+    assert_eq!(scheduler.get_domain_delay("eager.com"), base);
+}
+
+#[test]
+fn test_adaptive_delay_rate_limiting() {
+    let config = CrawlerConfig::default();
+    let scheduler = CrawlScheduler::new(&config);
+    let base = Duration::from_millis(config.network.request_delay_ms);
+
+    scheduler.adaptive_delay("limited.com", 50, 429);
+    assert_eq!(scheduler.get_domain_delay("limited.com"), base * 2);
+
+    scheduler.apply_retry_after("limited.com", Duration::from_secs(120));
+    assert_eq!(scheduler.get_domain_delay("limited.com"), Duration::from_secs(120));
+
+    // a shorter Retry-After never shortens the delay
+    scheduler.apply_retry_after("limited.com", Duration::from_secs(1));
+    assert_eq!(scheduler.get_domain_delay("limited.com"), Duration::from_secs(120));
+
+    for _ in 0..10 {
+        scheduler.adaptive_delay("capped.com", 10_000, 200);
+    }
+    assert_eq!(scheduler.get_domain_delay("capped.com"), Duration::from_secs(60));
+}
+
+#[test]
+fn test_adaptive_delay_backs_off_without_a_configured_delay() {
+    let mut config = CrawlerConfig::default();
+    config.network.request_delay_ms = 0;
+    let scheduler = CrawlScheduler::new(&config);
+
+    scheduler.adaptive_delay("zero.com", 50, 429);
+    assert_eq!(scheduler.get_domain_delay("zero.com"), Duration::from_millis(100));
+    scheduler.adaptive_delay("zero.com", 50, 429);
+    assert_eq!(scheduler.get_domain_delay("zero.com"), Duration::from_millis(200));
+
+    // fast responses bring it back to no delay at all
+    for _ in 0..20 {
+        scheduler.adaptive_delay("zero.com", 10, 200);
+    }
+    assert_eq!(scheduler.get_domain_delay("zero.com"), Duration::ZERO);
+}
+
+#[tokio::test]
+async fn test_respect_domain_delay_uses_adapted_delay() {
+    let mut config = CrawlerConfig::default();
+    config.network.request_delay_ms = 50;
+    let scheduler = CrawlScheduler::new(&config);
+
+    // learning the response time before the first request doesn't delay it
+    scheduler.adaptive_delay("adaptive.com", 4000, 200);
+    let start = Instant::now();
+    scheduler.respect_domain_delay("adaptive.com").await;
+    assert!(start.elapsed() < Duration::from_millis(50));
+
+    let start = Instant::now();
+    scheduler.respect_domain_delay("adaptive.com").await;
+    assert!(start.elapsed() >= Duration::from_millis(90));
+}
+
+#[tokio::test]
+async fn test_set_max_concurrent_resizes_permits() {
+    let mut config = CrawlerConfig::default();
+    config.crawler.concurrent_requests = 3;
+    let scheduler = CrawlScheduler::new(&config);
+
+    let first = scheduler.acquire_permit().await;
+    let second = scheduler.acquire_permit().await;
+
+    // one permit is free, the other held one is retired when released
+    scheduler.set_max_concurrent(1);
+    assert_eq!(scheduler.max_concurrent(), 1);
+    assert_eq!(scheduler.get_stats().available_permits, 0);
+
+    drop(first);
+    drop(second);
+    let only = scheduler.acquire_permit().await;
+    assert_eq!(scheduler.get_stats().available_permits, 0);
+    assert!(tokio::time::timeout(Duration::from_millis(50), scheduler.acquire_permit()).await.is_err());
+    drop(only);
+
+    scheduler.set_max_concurrent(4);
+    let mut permits = Vec::new();
+    for _ in 0..4 {
+        permits.push(scheduler.acquire_permit().await);
+    }
+    assert_eq!(scheduler.get_stats().available_permits, 0);
+}
+
+fn failing_task(attempt_count: &Arc<AtomicUsize>) -> impl Fn() -> std::future::Ready<Result<String, Box<dyn std::error::Error + Send + Sync>>> {
+    let attempt_count = Arc::clone(attempt_count);
+    move || {
+        attempt_count.fetch_add(1, Ordering::Relaxed);
+        std::future::ready(Err(Box::new(crate::network::NetworkError::Http {
+            status: 503,
+            message: "Service Unavailable".to_string(),
+        }) as Box<dyn std::error::Error + Send + Sync>))
+    }
+}
+
+#[tokio::test]
+async fn test_circuit_breaker_opens_after_repeated_failures() {
+    let mut config = CrawlerConfig::default();
+    config.network.request_delay_ms = 0;
+    config.network.retry = Some(crate::config::RetryConfig {
+        base_delay_ms: 1,
+        max_delay_ms: 5,
+        jitter_fraction: 0.0,
+        max_retries: 2,
+    });
+    config.network.circuit_breaker = crate::config::CircuitBreakerConfig { open_threshold: 3, open_duration_secs: 60 };
+    let scheduler = CrawlScheduler::new(&config);
+    let attempt_count = Arc::new(AtomicUsize::new(0));
+
+    let result = scheduler.schedule_crawl("down.example.com", failing_task(&attempt_count)).await;
+    assert!(matches!(result, Err(crate::core::scheduler::SchedulerError::MaxRetriesExceeded(_))));
+    assert_eq!(scheduler.get_circuit_states(), vec![("down.example.com".to_string(), crate::core::CircuitState::Closed)]);
+
+    // the third failure opens the circuit and stops the retries
+    let result = scheduler.schedule_crawl("down.example.com", failing_task(&attempt_count)).await;
+    assert!(matches!(result, Err(crate::core::scheduler::SchedulerError::CircuitOpen(ref domain)) if domain == "down.example.com"));
+    assert_eq!(attempt_count.load(Ordering::Relaxed), 3);
+
+    // rejected without running the task
+    let result = scheduler.schedule_crawl("down.example.com", failing_task(&attempt_count)).await;
+    assert!(matches!(result, Err(crate::core::scheduler::SchedulerError::CircuitOpen(_))));
+    assert_eq!(attempt_count.load(Ordering::Relaxed), 3);
+    assert_eq!(scheduler.get_circuit_states(), vec![("down.example.com".to_string(), crate::core::CircuitState::Open)]);
+
+    // other domains are unaffected
+    let result = scheduler.schedule_crawl("up.example.com", || async {
+        Ok::<_, Box<dyn std::error::Error + Send + Sync>>("ok".to_string())
+    }).await;
+    assert_eq!(result.unwrap(), "ok");
+}
+
+#[tokio::test]
+async fn test_circuit_breaker_half_open_trial_closes_on_success() {
+    let mut config = CrawlerConfig::default();
+    config.network.request_delay_ms = 0;
+    config.network.retry = Some(crate::config::RetryConfig {
+        base_delay_ms: 1,
+        max_delay_ms: 5,
+        jitter_fraction: 0.0,
+        max_retries: 5,
+    });
+    config.network.circuit_breaker = crate::config::CircuitBreakerConfig { open_threshold: 2, open_duration_secs: 0 };
+    let scheduler = CrawlScheduler::new(&config);
+    let attempt_count = Arc::new(AtomicUsize::new(0));
+
+    let result = scheduler.schedule_crawl("flaky.example.com", failing_task(&attempt_count)).await;
+    assert!(matches!(result, Err(crate::core::scheduler::SchedulerError::CircuitOpen(_))));
+    assert_eq!(attempt_count.load(Ordering::Relaxed), 2);
+
+    // the open duration has passed, a single failed trial reopens the circuit
+    let result = scheduler.schedule_crawl("flaky.example.com", failing_task(&attempt_count)).await;
+    assert!(matches!(result, Err(crate::core::scheduler::SchedulerError::CircuitOpen(_))));
+    assert_eq!(attempt_count.load(Ordering::Relaxed), 3);
+
+    let result = scheduler.schedule_crawl("flaky.example.com", || async {
+        Ok::<_, Box<dyn std::error::Error + Send + Sync>>("ok".to_string())
+    }).await;
+    assert_eq!(result.unwrap(), "ok");
+    assert_eq!(scheduler.get_circuit_states(), vec![("flaky.example.com".to_string(), crate::core::CircuitState::Closed)]);
+}
+
+#[tokio::test]
+async fn test_circuit_breaker_half_open_lets_a_single_trial_through() {
+    let mut config = CrawlerConfig::default();
+    config.network.request_delay_ms = 0;
+    config.network.max_retries = 1;
+    config.network.circuit_breaker = crate::config::CircuitBreakerConfig { open_threshold: 1, open_duration_secs: 0 };
+    let scheduler = Arc::new(CrawlScheduler::new(&config));
+    let attempt_count = Arc::new(AtomicUsize::new(0));
+
+    let result = scheduler.schedule_crawl("flaky.example.com", failing_task(&attempt_count)).await;
+    assert!(matches!(result, Err(crate::core::scheduler::SchedulerError::CircuitOpen(_))));
+
+    // the trial request waits until released, meanwhile other requests are rejected
+    let release = Arc::new(tokio::sync::Notify::new());
+    let trial = tokio::spawn({
+        let scheduler = Arc::clone(&scheduler);
+        let release = Arc::clone(&release);
+        async move {
+            scheduler.schedule_crawl("flaky.example.com", || {
+                let release = Arc::clone(&release);
+                async move {
+                    release.notified().await;
+                    Ok::<_, Box<dyn std::error::Error + Send + Sync>>("ok".to_string())
+                }
+            }).await
+        }
+    });
+    while scheduler.get_circuit_states()[0].1 != crate::core::CircuitState::HalfOpen {
+        tokio::task::yield_now().await;
+    }
+
+    let result = scheduler.schedule_crawl("flaky.example.com", failing_task(&attempt_count)).await;
+    assert!(matches!(result, Err(crate::core::scheduler::SchedulerError::CircuitOpen(_))));
+    assert_eq!(attempt_count.load(Ordering::Relaxed), 1);
+
+    release.notify_one();
+    assert_eq!(trial.await.unwrap().unwrap(), "ok");
+    assert_eq!(scheduler.get_circuit_states(), vec![("flaky.example.com".to_string(), crate::core::CircuitState::Closed)]);
+}
+
+#[tokio::test]
+async fn test_circuit_breaker_only_tracks_failing_domains() {
+    let mut config = CrawlerConfig::default();
+    config.network.request_delay_ms = 0;
+    let scheduler = CrawlScheduler::new(&config);
+
+    for domain in ["a.example.com", "b.example.com"] {
+        let result = scheduler.schedule_crawl(domain, || async {
+            Ok::<_, Box<dyn std::error::Error + Send + Sync>>("ok".to_string())
+        }).await;
+        assert_eq!(result.unwrap(), "ok");
+    }
+    assert!(scheduler.get_circuit_states().is_empty());
+}
+
+#[tokio::test]
+async fn test_circuit_breaker_disabled_with_zero_threshold() {
+    let mut config = CrawlerConfig::default();
+    config.network.request_delay_ms = 0;
+    config.network.retry = Some(crate::config::RetryConfig {
+        base_delay_ms: 1,
+        max_delay_ms: 5,
+        jitter_fraction: 0.0,
+        max_retries: 3,
+    });
+    config.network.circuit_breaker.open_threshold = 0;
+    let scheduler = CrawlScheduler::new(&config);
+    let attempt_count = Arc::new(AtomicUsize::new(0));
+
+    for _ in 0..3 {
+        let result = scheduler.schedule_crawl("down.example.com", failing_task(&attempt_count)).await;
+        assert!(matches!(result, Err(crate::core::scheduler::SchedulerError::MaxRetriesExceeded(_))));
+    }
+    assert_eq!(attempt_count.load(Ordering::Relaxed), 9);
+    assert!(scheduler.get_circuit_states().is_empty());
+}
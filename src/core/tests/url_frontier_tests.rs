@@ -89,3 +89,275 @@ async fn test_url_frontier_capacity_limits() {
     // Should reject when at capacity
     assert!(!frontier.add_url(url3).await);
 }
+
+// Drain the frontier over a synthetic link graph, returning the crawl order
+async fn simulate_crawl(frontier: &UrlFrontier, graph: &[(&str, &[&str])]) -> Vec<String> {
+    let mut order = Vec::new();
+
+    while let Some(current) = frontier.next_url().await {
+        frontier.mark_crawled(&current.url);
+        order.push(current.url.clone());
+
+        let links: Vec<CrawlUrl> = graph.iter()
+            .find(|(url, _)| *url == current.url)
+            .map(|(_, links)| links.iter().map(|l| CrawlUrl {
+                url: l.to_string(),
+                priority: 1.0,
+                depth: current.depth + 1,
+                discovered_at: 0,
+            }).collect())
+            .unwrap_or_default();
+
+        frontier.record_crawl(&current.url, &links);
+        frontier.add_urls(links).await;
+    }
+
+    order
+}
+
+#[tokio::test]
+async fn test_opic_crawls_hub_before_bfs_would() {
+    // s1 fans out to six leaves, s2 feeds all of its cash through a1/a2 into the hub
+    let graph: &[(&str, &[&str])] = &[
+        ("s1", &["l1", "l2", "l3", "l4", "l5", "l6"]),
+        ("s2", &["a1", "a2"]),
+        ("a1", &["hub"]),
+        ("a2", &["hub"]),
+    ];
+    let seeds = ["s1", "s2"];
+
+    let seed_urls = |frontier: &UrlFrontier| {
+        let urls: Vec<CrawlUrl> = seeds.iter().map(|s| CrawlUrl {
+            url: s.to_string(),
+            priority: 1.0,
+            depth: 0,
+            discovered_at: 0,
+        }).collect();
+        if let Some(opic) = frontier.opic() {
+            opic.initialize(&urls.iter().map(|u| u.url.clone()).collect::<Vec<_>>());
+        }
+        urls
+    };
+
+    let bfs = UrlFrontier::new(100);
+    bfs.add_urls(seed_urls(&bfs)).await;
+    let bfs_order = simulate_crawl(&bfs, graph).await;
+
+    let opic = UrlFrontier::new(100).with_opic_mode();
+    opic.add_urls(seed_urls(&opic)).await;
+    let opic_order = simulate_crawl(&opic, graph).await;
+
+    let position = |order: &[String], url: &str| order.iter().position(|u| u == url).unwrap();
+
+    assert_eq!(bfs_order.len(), 11);
+    assert_eq!(opic_order.len(), 11);
+
+    // BFS reaches the depth-2 hub only after every depth-1 leaf
+    assert_eq!(position(&bfs_order, "hub"), 10);
+
+    // OPIC pulls the hub ahead of all low-cash leaves
+    let first_leaf = (1..=6).map(|i| position(&opic_order, &format!("l{}", i))).min().unwrap();
+    assert!(position(&opic_order, "hub") < first_leaf);
+    assert!((opic.opic().unwrap().history("hub") - 0.5).abs() < 1e-12);
+}
+
+#[tokio::test]
+async fn test_max_depth_is_enforced() {
+    // two link chains five pages deep, deep.org may go one level further than the rest
+    let graph: &[(&str, &[&str])] = &[
+        ("https://example.com/0", &["https://example.com/1", "https://deep.org/0"]),
+        ("https://example.com/1", &["https://example.com/2"]),
+        ("https://example.com/2", &["https://example.com/3"]),
+        ("https://example.com/3", &["https://example.com/4"]),
+        ("https://deep.org/0", &["https://www.deep.org/1"]),
+        ("https://www.deep.org/1", &["https://deep.org/2"]),
+        ("https://deep.org/2", &["https://deep.org/3"]),
+        ("https://deep.org/3", &["https://deep.org/4"]),
+    ];
+
+    let overrides = std::collections::HashMap::from([("deep.org".to_string(), 3)]);
+    let frontier = UrlFrontier::new(100)
+        .with_max_depth(2)
+        .with_domain_depth_overrides(overrides);
+
+    assert!(frontier.add_url(CrawlUrl {
+        url: "https://example.com/0".to_string(),
+        priority: 1.0,
+        depth: 0,
+        discovered_at: 0,
+    }).await);
+
+    let crawled = simulate_crawl(&frontier, graph).await;
+    let depth_of = |url: &str| -> u32 { url.rsplit('/').next().unwrap().parse::<u32>().unwrap() + u32::from(url.contains("deep.org")) };
+
+    for url in &crawled {
+        let limit = if url.contains("deep.org") { 3 } else { 2 };
+        assert!(depth_of(url) <= limit, "{} crawled beyond max depth", url);
+    }
+
+    assert!(crawled.contains(&"https://example.com/2".to_string()));
+    assert!(!crawled.contains(&"https://example.com/3".to_string()));
+    assert!(crawled.contains(&"https://deep.org/2".to_string()));
+    assert!(!crawled.contains(&"https://deep.org/3".to_string()));
+
+    assert!(!frontier.add_url(CrawlUrl {
+        url: "https://example.com/deep".to_string(),
+        priority: 1.0,
+        depth: 3,
+        discovered_at: 0,
+    }).await);
+}
+
+#[tokio::test]
+async fn test_add_url_dedupes_canonical_variants() {
+    let frontier = UrlFrontier::new(100);
+    let variants = [
+        "https://example.com/page",
+        "http://example.com/page",
+        "https://example.com/page?utm_source=twitter",
+        "https://example.com/page#section",
+    ];
+
+    let added = frontier.add_urls(variants.iter().map(|url| CrawlUrl {
+        url: url.to_string(),
+        priority: 1.0,
+        depth: 0,
+        discovered_at: 0,
+    }).collect()).await;
+
+    assert_eq!(added, 1);
+    assert_eq!(frontier.next_url().await.unwrap().url, "https://example.com/page");
+    frontier.mark_crawled("http://example.com/page/");
+    assert!(frontier.is_crawled("https://example.com/page"));
+}
+
+#[tokio::test]
+async fn test_add_url_queues_the_url_as_given() {
+    let frontier = UrlFrontier::new(100);
+    let url = |url: &str| CrawlUrl { url: url.to_string(), priority: 1.0, depth: 0, discovered_at: 0 };
+
+    assert!(frontier.add_url(url("http://127.0.0.1:8080/page?utm_source=feed")).await);
+    assert!(!frontier.add_url(url("https://127.0.0.1:8080/page")).await);
+
+    // only the dedup key is canonical, the queued URL keeps its scheme and parameters
+    assert_eq!(frontier.next_url().await.unwrap().url, "http://127.0.0.1:8080/page?utm_source=feed");
+}
+
+#[tokio::test]
+async fn test_checkpoint_round_trip() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("frontier.json");
+
+    let frontier = UrlFrontier::new(50);
+    for (url, priority) in [("https://example.com/a", 1.0), ("https://example.com/b", 5.0), ("https://example.com/c", 3.0)] {
+        frontier.add_url(CrawlUrl { url: url.to_string(), priority, depth: 1, discovered_at: 0 }).await;
+    }
+    let first = frontier.next_url().await.unwrap();
+    frontier.mark_crawled(&first.url);
+    frontier.save_checkpoint(&path).await.unwrap();
+
+    let restored = UrlFrontier::load_checkpoint(&path).unwrap();
+    let stats = restored.get_stats().await;
+    assert_eq!(stats.queue_size, 2);
+    assert_eq!(stats.seen_count, 3);
+    assert!(restored.is_crawled("https://example.com/b"));
+
+    // seen URLs stay deduplicated and the queue keeps its priority order
+    assert!(!restored.add_url(CrawlUrl { url: "https://example.com/b".to_string(), priority: 1.0, depth: 1, discovered_at: 0 }).await);
+    assert_eq!(restored.next_url().await.unwrap().url, "https://example.com/c");
+    assert_eq!(restored.next_url().await.unwrap().url, "https://example.com/a");
+
+    assert!(UrlFrontier::load_checkpoint(&dir.path().join("missing.json")).is_err());
+}
+
+#[tokio::test]
+async fn test_held_urls_are_released_and_checkpointed() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("frontier.json");
+
+    let frontier = UrlFrontier::new(100);
+    let link = |path: &str| CrawlUrl { url: format!("https://example.com{}", path), priority: 1.0, depth: 1, discovered_at: 0 };
+    frontier.add_url(link("/seen")).await;
+    // seen and already held URLs are skipped, by their canonical form
+    assert_eq!(frontier.hold_urls(vec![link("/held"), link("/seen"), link("/held?utm_source=feed")]).await, 1);
+    assert_eq!(frontier.hold_urls(vec![link("/a"), link("/b")]).await, 2);
+    assert!(frontier.held_bytes().await > 0);
+
+    // released in batches, the longest held first
+    assert_eq!(frontier.release_held(1).await, 1);
+    assert_eq!(frontier.held_count().await, 2);
+    frontier.save_checkpoint(&path).await.unwrap();
+
+    let restored = UrlFrontier::load_checkpoint(&path).unwrap();
+    assert_eq!(restored.held_count().await, 2);
+    assert_eq!(restored.hold_urls(vec![link("/b")]).await, 0);
+    assert_eq!(restored.release_held(10).await, 2);
+    assert_eq!(restored.held_count().await, 0);
+    let mut queued: Vec<String> = restored.peek_top_n(10).await.into_iter().map(|url| url.url).collect();
+    queued.sort();
+    assert_eq!(queued, ["https://example.com/a", "https://example.com/b", "https://example.com/held", "https://example.com/seen"]);
+}
+
+#[tokio::test]
+async fn test_held_urls_are_capped_at_max_queue_size() {
+    let frontier = UrlFrontier::new(2);
+    let links = ["/a", "/b", "/c"]
+        .map(|path| CrawlUrl { url: format!("https://example.com{}", path), priority: 1.0, depth: 1, discovered_at: 0 });
+
+    assert_eq!(frontier.hold_urls(links.to_vec()).await, 2);
+    assert_eq!(frontier.held_count().await, 2);
+}
+
+#[tokio::test]
+async fn test_peek_top_n_and_remove_url() {
+    let frontier = UrlFrontier::new(100);
+    for (i, priority) in [3.0, 9.0, 1.0, 7.0, 5.0].into_iter().enumerate() {
+        frontier.add_url(CrawlUrl {
+            url: format!("https://example.com/{}", i),
+            priority,
+            depth: 1,
+            discovered_at: 0,
+        }).await;
+    }
+
+    let top: Vec<f64> = frontier.peek_top_n(3).await.iter().map(|u| u.priority).collect();
+    assert_eq!(top, vec![9.0, 7.0, 5.0]);
+    assert_eq!(frontier.peek_top_n(10).await.len(), 5);
+    assert!(frontier.peek_top_n(0).await.is_empty());
+    assert_eq!(frontier.get_stats().await.queue_size, 5);
+
+    // canonical variants name the same queued URL
+    assert!(frontier.remove_url("HTTPS://example.com/1#top").await);
+    assert!(!frontier.remove_url("https://example.com/1").await);
+    assert!(!frontier.add_url(CrawlUrl {
+        url: "https://example.com/1".to_string(),
+        priority: 9.0,
+        depth: 1,
+        discovered_at: 0,
+    }).await);
+
+    assert_eq!(frontier.next_url().await.unwrap().priority, 7.0);
+    assert_eq!(frontier.get_stats().await.queue_size, 3);
+}
+
+#[tokio::test]
+async fn test_reprioritize() {
+    let frontier = UrlFrontier::new(100);
+    for (url, priority) in [("https://a.com/1", 0.5), ("https://b.com/1", 0.9), ("https://a.com/2", 0.2)] {
+        frontier.add_url(CrawlUrl { url: url.to_string(), priority, depth: 1, discovered_at: 0 }).await;
+    }
+
+    let changed = frontier.reprioritize(|url| {
+        if url.url.starts_with("https://a.com") { url.priority + 1.0 } else { url.priority }
+    }).await;
+    assert_eq!(changed, 2);
+    assert_eq!(frontier.get_stats().await.queue_size, 3);
+
+    // the heap is rebuilt in the new order
+    assert_eq!(frontier.next_url().await.unwrap().url, "https://a.com/1");
+    assert_eq!(frontier.next_url().await.unwrap().url, "https://a.com/2");
+
+    // changes up to 0.01 aren't counted
+    assert_eq!(frontier.reprioritize(|url| url.priority + 0.005).await, 0);
+    assert_eq!(frontier.reprioritize(|_| 0.0).await, 1);
+}
@@ -1,11 +1,41 @@
 //! Manages the URL queue with prioritization
 
+use crate::core::OpicScorer;
 use crate::models::CrawlUrl;
+use crate::utils::{canonicalize_with, default_strip_params};
 use dashmap::DashSet;
-use std::collections::BinaryHeap;
+use serde::{Deserialize, Serialize};
+use std::collections::{BinaryHeap, HashMap, HashSet};
+use std::path::Path;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
 use tokio::sync::Mutex; // Changed: std::sync::Mutex -> tokio::sync::Mutex (for async)
-use tracing::{debug};
+use tracing::{debug, info};
+
+/// On-disk snapshot of the frontier, written by `save_checkpoint`
+#[derive(Debug, Serialize, Deserialize)]
+struct FrontierCheckpoint {
+    max_queue_size: usize,
+    queue: Vec<CrawlUrl>,
+    seen_urls: Vec<String>,
+    crawled_urls: Vec<String>,
+    #[serde(default)]
+    held: Vec<CrawlUrl>,
+}
+
+/// URLs kept out of the queue by `hold_urls`, with their canonical form so each is held once
+#[derive(Debug, Default)]
+struct HeldUrls {
+    urls: Vec<CrawlUrl>,
+    keys: HashSet<String>,
+}
+
+impl HeldUrls {
+    fn new(urls: Vec<CrawlUrl>, strip_params: &[String]) -> Self {
+        let keys = urls.iter().map(|url| canonicalize_with(&url.url, strip_params)).collect();
+        Self { urls, keys }
+    }
+}
 
 /// Thread-safe URL frontier that manages crawling queue with prioritization
 pub struct UrlFrontier {
@@ -18,8 +48,26 @@ pub struct UrlFrontier {
     /// Set of URLs already crawled
     crawled_urls: Arc<DashSet<String>>, // Fixed: crwaled_urls -> crawled_urls
 
+    /// URLs held back while memory is short, queued by `release_held`
+    held: Arc<Mutex<HeldUrls>>,
+
     /// Maximum queue size to prevent memory issues
     max_queue_size: usize,
+
+    /// OPIC cash per URL, replaces the caller's priority when enabled
+    opic: Option<Arc<OpicScorer>>,
+
+    /// Queued URLs received cash since the heap was last ordered
+    priorities_stale: AtomicBool,
+
+    /// URLs deeper than this are never enqueued
+    max_depth: Option<u32>,
+
+    /// Domain -> max depth, overriding `max_depth` for the domain and its subdomains
+    domain_depth_overrides: HashMap<String, u32>,
+
+    /// Query parameters removed when canonicalizing URLs
+    strip_params: Vec<String>,
 }
 
 impl UrlFrontier {
@@ -28,13 +76,88 @@ impl UrlFrontier {
             queue: Arc::new(Mutex::new(BinaryHeap::new())),
             seen_urls: Arc::new(DashSet::new()),
             crawled_urls: Arc::new(DashSet::new()), // Fixed: crwaled_urls -> crawled_urls
+            held: Arc::new(Mutex::new(HeldUrls::default())),
             max_queue_size,
+            opic: None,
+            priorities_stale: AtomicBool::new(false),
+            max_depth: None,
+            domain_depth_overrides: HashMap::new(),
+            strip_params: default_strip_params(),
+        }
+    }
+
+    /// Reject URLs discovered deeper than `max_depth` links from a seed
+    pub fn with_max_depth(mut self, max_depth: u32) -> Self {
+        self.max_depth = Some(max_depth);
+        self
+    }
+
+    pub fn with_domain_depth_overrides(mut self, overrides: HashMap<String, u32>) -> Self {
+        self.domain_depth_overrides = overrides;
+        self
+    }
+
+    /// Replace the tracking parameters stripped from queued URLs
+    pub fn with_strip_params(mut self, strip_params: Vec<String>) -> Self {
+        self.strip_params = strip_params;
+        self
+    }
+
+    /// Depth limit for a URL, the most specific domain override wins
+    pub fn depth_limit(&self, url: &str) -> Option<u32> {
+        if !self.domain_depth_overrides.is_empty()
+            && let Some(host) = url::Url::parse(url).ok().and_then(|u| u.host_str().map(str::to_lowercase))
+        {
+            let limit = self.domain_depth_overrides.iter()
+                .filter(|(domain, _)| host == **domain || host.ends_with(&format!(".{}", domain)))
+                .max_by_key(|(domain, _)| domain.len())
+                .map(|(_, depth)| *depth);
+
+            if limit.is_some() {
+                return limit;
+            }
+        }
+
+        self.max_depth
+    }
+
+    /// Prioritize URLs by their OPIC cash instead of the priority they were added with
+    pub fn with_opic_mode(mut self) -> Self {
+        self.opic = Some(Arc::new(OpicScorer::new()));
+        self
+    }
+
+    pub fn opic(&self) -> Option<&OpicScorer> {
+        self.opic.as_deref()
+    }
+
+    /// Shared handle to the OPIC scorer, for priority functions that read the cash
+    pub fn opic_scorer(&self) -> Option<Arc<OpicScorer>> {
+        self.opic.clone()
+    }
+
+    /// Distribute a crawled page's OPIC cash to its outgoing links (no-op without OPIC)
+    pub fn record_crawl(&self, url: &str, links: &[CrawlUrl]) {
+        if let Some(opic) = &self.opic {
+            let links: Vec<String> = links.iter().map(|l| canonicalize_with(&l.url, &self.strip_params)).collect();
+            opic.on_crawled(&canonicalize_with(url, &self.strip_params), &links);
+            self.priorities_stale.store(true, Ordering::Release);
         }
     }
 
-    /// Add URL to frontier if not already seen
-    pub async fn add_url(&self, url: CrawlUrl) -> bool {
-        if self.seen_urls.contains(&url.url) {
+    /// Add URL to frontier if not already seen. Duplicates are detected by the canonical
+    /// form of the URL, the URL is queued and fetched as given.
+    pub async fn add_url(&self, mut url: CrawlUrl) -> bool {
+        let key = canonicalize_with(&url.url, &self.strip_params);
+
+        if self.seen_urls.contains(&key) {
+            return false;
+        }
+
+        if let Some(limit) = self.depth_limit(&url.url)
+            && url.depth > limit
+        {
+            debug!("URL beyond max depth {} ({}), dropping: {}", limit, url.depth, url.url);
             return false;
         }
 
@@ -46,7 +169,11 @@ impl UrlFrontier {
             return false;
         }
 
-        self.seen_urls.insert(url.url.clone());
+        if let Some(opic) = &self.opic {
+            url.priority = opic.cash(&key);
+        }
+
+        self.seen_urls.insert(key);
         queue.push(url);
         true
     }
@@ -62,20 +189,138 @@ impl UrlFrontier {
         added
     }
 
+    /// Keep URLs out of the queue until `release_held`, skipping those already seen or held.
+    /// At most `max_queue_size` URLs are held, like the queue itself. Returns how many were held.
+    pub async fn hold_urls(&self, urls: Vec<CrawlUrl>) -> usize {
+        let mut held = self.held.lock().await;
+        let before = held.urls.len();
+        let mut dropped = 0;
+
+        for url in urls {
+            let key = canonicalize_with(&url.url, &self.strip_params);
+            if self.seen_urls.contains(&key) || held.keys.contains(&key) {
+                continue;
+            }
+            if held.urls.len() >= self.max_queue_size {
+                dropped += 1;
+                continue;
+            }
+            held.keys.insert(key);
+            held.urls.push(url);
+        }
+
+        if dropped > 0 {
+            debug!("Held URL limit reached, dropping {} URLs", dropped);
+        }
+        held.urls.len() - before
+    }
+
+    /// Queue up to `limit` of the URLs kept back by `hold_urls`, the longest held first.
+    /// Returns how many were new to the queue.
+    pub async fn release_held(&self, limit: usize) -> usize {
+        let released: Vec<CrawlUrl> = {
+            let mut held = self.held.lock().await;
+            let count = limit.min(held.urls.len());
+            let released: Vec<CrawlUrl> = held.urls.drain(..count).collect();
+            for url in &released {
+                held.keys.remove(&canonicalize_with(&url.url, &self.strip_params));
+            }
+            released
+        };
+        self.add_urls(released).await
+    }
+
+    /// Number of URLs held back by `hold_urls`
+    pub async fn held_count(&self) -> usize {
+        self.held.lock().await.urls.len()
+    }
+
     /// Get next URL to crawl (highest priority)
     pub async fn next_url(&self) -> Option<CrawlUrl> {
         let mut queue = self.queue.lock().await;
+        self.rerank_if_stale(&mut queue);
         queue.pop()
     }
 
+    /// Put a URL taken with `next_url` back in the queue to crawl it later. It was seen
+    /// before, so neither the seen set nor the queue limit apply.
+    pub async fn requeue(&self, url: CrawlUrl) {
+        self.queue.lock().await.push(url);
+    }
+
+    /// The `n` URLs `next_url` would return next, in that order, leaving the queue untouched.
+    /// Copies the whole queue to select them, so this is O(queue size) and holds the queue
+    /// lock meanwhile; meant for inspection, not for the crawl loop.
+    pub async fn peek_top_n(&self, n: usize) -> Vec<CrawlUrl> {
+        let mut queue = self.queue.lock().await;
+        self.rerank_if_stale(&mut queue);
+
+        let mut urls = queue.clone().into_vec();
+        if n < urls.len() {
+            urls.select_nth_unstable_by(n, |a, b| b.cmp(a));
+            urls.truncate(n);
+        }
+        urls.sort_by(|a, b| b.cmp(a));
+        urls
+    }
+
+    /// Drop a queued URL so it is not crawled, returns false if it wasn't queued.
+    /// The URL stays seen, rediscovering it doesn't queue it again. O(queue size).
+    pub async fn remove_url(&self, url: &str) -> bool {
+        let url = canonicalize_with(url, &self.strip_params);
+        let mut queue = self.queue.lock().await;
+
+        let before = queue.len();
+        queue.retain(|queued| canonicalize_with(&queued.url, &self.strip_params) != url);
+        before != queue.len()
+    }
+
+    /// Re-score every queued URL with `f` and rebuild the heap, returns how many priorities
+    /// moved by more than 0.01. Drains and re-inserts the whole queue, so this is O(queue size)
+    /// and holds the queue lock meanwhile. In OPIC mode the cash replaces these priorities
+    /// again once it next flows.
+    pub async fn reprioritize<F>(&self, f: F) -> usize
+    where
+        F: Fn(&CrawlUrl) -> f64,
+    {
+        let mut queue = self.queue.lock().await;
+
+        let mut urls = std::mem::take(&mut *queue).into_vec();
+        let mut changed = 0;
+        for url in &mut urls {
+            let priority = f(url);
+            if (priority - url.priority).abs() > 0.01 {
+                changed += 1;
+            }
+            url.priority = priority;
+        }
+        *queue = BinaryHeap::from(urls);
+
+        debug!("Reprioritized {} queued URLs, {} changed", queue.len(), changed);
+        changed
+    }
+
+    // cash may have flowed to URLs already queued, re-rank them before reading the top
+    fn rerank_if_stale(&self, queue: &mut BinaryHeap<CrawlUrl>) {
+        if let Some(opic) = &self.opic
+            && self.priorities_stale.swap(false, Ordering::AcqRel)
+        {
+            let mut urls = std::mem::take(queue).into_vec();
+            for url in &mut urls {
+                url.priority = opic.cash(&canonicalize_with(&url.url, &self.strip_params));
+            }
+            *queue = BinaryHeap::from(urls);
+        }
+    }
+
     /// Mark URL as crawled
     pub fn mark_crawled(&self, url: &str) {
-        self.crawled_urls.insert(url.to_string()); // Fixed: crwaled_urls -> crawled_urls
+        self.crawled_urls.insert(canonicalize_with(url, &self.strip_params)); // Fixed: crwaled_urls -> crawled_urls
     }
 
     /// Check if URL is already crawled
     pub fn is_crawled(&self, url: &str) -> bool {
-        self.crawled_urls.contains(url) // Fixed: crwaled_urls -> crawled_urls
+        self.crawled_urls.contains(&canonicalize_with(url, &self.strip_params)) // Fixed: crwaled_urls -> crawled_urls
     }
 
     /// Get queue statistics
@@ -88,6 +333,66 @@ impl UrlFrontier {
         }
     }
 
+    /// Estimated heap size of the seen and crawled URL sets
+    pub fn seen_urls_bytes(&self) -> usize {
+        [&self.seen_urls, &self.crawled_urls].iter()
+            .flat_map(|urls| urls.iter())
+            .map(|url| std::mem::size_of::<String>() + url.capacity())
+            .sum()
+    }
+
+    /// Estimated heap size of the queued URLs
+    pub async fn queue_bytes(&self) -> usize {
+        self.queue.lock().await.iter()
+            .map(|queued| std::mem::size_of::<CrawlUrl>() + queued.url.capacity())
+            .sum()
+    }
+
+    /// Estimated heap size of the URLs held back by `hold_urls` and their canonical forms
+    pub async fn held_bytes(&self) -> usize {
+        let held = self.held.lock().await;
+        held.urls.iter()
+            .map(|url| std::mem::size_of::<CrawlUrl>() + url.url.capacity())
+            .chain(held.keys.iter().map(|key| std::mem::size_of::<String>() + key.capacity()))
+            .sum()
+    }
+
+    /// Write the queued, held, seen and crawled URLs to `path` as JSON.
+    /// The file is replaced atomically so a crash mid-write keeps the previous checkpoint.
+    pub async fn save_checkpoint(&self, path: &Path) -> crate::Result<()> {
+        let queue = self.queue.lock().await.clone().into_vec();
+        let checkpoint = FrontierCheckpoint {
+            max_queue_size: self.max_queue_size,
+            queue,
+            seen_urls: self.seen_urls.iter().map(|url| url.clone()).collect(),
+            crawled_urls: self.crawled_urls.iter().map(|url| url.clone()).collect(),
+            held: self.held.lock().await.urls.clone(),
+        };
+
+        let tmp_path = path.with_extension("tmp");
+        std::fs::write(&tmp_path, serde_json::to_vec(&checkpoint)?)?;
+        std::fs::rename(&tmp_path, path)?;
+
+        debug!("Saved frontier checkpoint with {} queued URLs to {}", checkpoint.queue.len(), path.display());
+        Ok(())
+    }
+
+    /// Restore a frontier written by `save_checkpoint`; depth limits, strip params
+    /// and OPIC mode are configuration and have to be applied again with the builders
+    pub fn load_checkpoint(path: &Path) -> crate::Result<Self> {
+        let checkpoint: FrontierCheckpoint = serde_json::from_slice(&std::fs::read(path)?)?;
+
+        let mut frontier = Self::new(checkpoint.max_queue_size);
+        let queued = checkpoint.queue.len();
+        frontier.queue = Arc::new(Mutex::new(BinaryHeap::from(checkpoint.queue)));
+        frontier.held = Arc::new(Mutex::new(HeldUrls::new(checkpoint.held, &frontier.strip_params)));
+        checkpoint.seen_urls.into_iter().for_each(|url| { frontier.seen_urls.insert(url); });
+        checkpoint.crawled_urls.into_iter().for_each(|url| { frontier.crawled_urls.insert(url); });
+
+        info!("Loaded frontier checkpoint from {} ({} queued URLs)", path.display(), queued);
+        Ok(frontier)
+    }
+
     /// Check if frontier is empty
     pub async fn is_empty(&self) -> bool {
         let queue = self.queue.lock().await;
@@ -8,12 +8,13 @@ pub mod network;
 pub mod storage;
 pub mod search;
 pub mod algorithms;
+pub mod api;
 
 use chrono::offset;
 // Re-export commonly used types
 pub use config::CrawlerConfig;
 use tantivy::snippet;
-pub use crate::core::crawler::WebCrawler;
+pub use crate::core::crawler::{MemoryUsage, WebCrawler};
 pub use models::{CrawlUrl, PageData, CrawlResult, CrawlStatistics};
 pub use network::{HttpClient, NetworkError};
 
@@ -28,33 +29,133 @@ pub async fn init() -> Result<()> {
 }
 
 use std::path::Path;
+use std::sync::Arc;
 use crate::search::query::SearchQuery;
-use crate::search::filters::{self, SearchFilter, SortBy};
+use crate::search::caching::{CachingSearchQuery, SearchCacheStats, SearchResultCache};
+use crate::search::filters::SearchFilter;
+use crate::storage::cache::MemoryCache;
 
 // public search engine interface for adapters and integrations
 pub struct SearchEngine{
-    inner: SearchQuery,
+    inner: CachingSearchQuery,
+    index_path: std::path::PathBuf,
+    search_ttl: std::time::Duration,
 }
 
 impl  SearchEngine {
     // initialize search engine interface for adapters and integrations
     pub fn new(index_path: &Path) -> Result<Self>{
         let inner = SearchQuery::new(index_path)?;
-        Ok(Self { inner })
+        let search_ttl = crate::config::CacheSettings::default().search_ttl();
+        Ok(Self {
+            inner: CachingSearchQuery::with_ttl(inner, search_ttl),
+            index_path: index_path.to_path_buf(),
+            search_ttl,
+        })
+    }
+
+    // override ranking parameters (BM25 k1/b, score weights, url penalties)
+    pub fn with_config(self, config: crate::config::SearchConfig) -> Self {
+        let inner = self.inner.into_inner().with_config(config);
+        Self { inner: CachingSearchQuery::with_ttl(inner, self.search_ttl), ..self }
+    }
+
+    // cache results for search_ttl_secs, starts with an empty cache
+    pub fn with_cache_settings(self, settings: &crate::config::CacheSettings) -> Self {
+        let search_ttl = settings.search_ttl();
+        let inner = self.inner.into_inner();
+        Self { inner: CachingSearchQuery::with_ttl(inner, search_ttl), search_ttl, ..self }
+    }
+
+    // cache hit/miss counters for search()
+    pub fn cache_stats(&self) -> SearchCacheStats {
+        self.inner.stats()
+    }
+
+    // entry counts and hit rates of the cache behind search()
+    pub fn memory_cache(&self) -> Arc<MemoryCache> {
+        self.inner.result_cache().memory_cache().clone()
+    }
+
+    // drop every cached result, e.g. after reindexing
+    pub fn invalidate_cache(&self) {
+        self.inner.invalidate_all();
+    }
+
+    // hand to SearchIndexer::with_result_cache so indexing invalidates stale results
+    pub fn result_cache(&self) -> SearchResultCache {
+        self.inner.result_cache()
     }
     // execute search query
 
-    pub fn search(
+    pub fn search(&self, query: &str, options: &crate::search::SearchOptions) -> Result<Vec<crate::search::SearchResult>>{
+        let result = self.inner.search_with_filters(query, options)?;
+        Ok(result)
+    }
+
+    // many queries against one searcher, results in query order, not cached
+    pub fn batch_search(&self, queries: &[(String, crate::search::SearchOptions)]) -> Result<Vec<Vec<crate::search::SearchResult>>>{
+        let result = self.inner.inner().batch_search(queries)?;
+        Ok(result)
+    }
+
+    // typo tolerant search within max_distance edits per term
+    pub fn fuzzy_search(&self, query: &str, max_distance: u8, limit: usize) -> Result<Vec<crate::search::SearchResult>>{
+        let result = self.inner.inner().fuzzy_search(query, max_distance, limit)?;
+        Ok(result)
+    }
+
+    // search plus domain/language/quality facet counts, not cached
+    pub fn search_with_facets(&self, query: &str, request: crate::search::FacetedSearchRequest) -> Result<crate::search::FacetedSearchResponse>{
+        let result = self.inner.inner().search_with_facets(query, request)?;
+        Ok(result)
+    }
+
+    // title completions for a search box, highest PageRank first
+    pub fn suggest(&self, prefix: &str, limit: usize) -> Result<Vec<String>>{
+        let result = self.inner.inner().suggest(prefix, limit)?;
+        Ok(result)
+    }
+
+    // score breakdown of one page for a query, not cached
+    pub fn explain(&self, query: &str, url: &str) -> Result<crate::search::ScoreExplanation>{
+        let result = self.inner.inner().explain(query, url)?;
+        Ok(result)
+    }
+
+    // pages sharing the most distinctive terms with the page, not cached
+    pub fn search_similar(&self, url: &str, limit: usize) -> Result<Vec<crate::search::SearchResult>>{
+        let result = self.inner.inner().search_similar(url, limit)?;
+        Ok(result)
+    }
+
+    // search_similar for the page with this url hash
+    pub fn search_similar_by_hash(&self, url_hash: &str, limit: usize) -> Result<Vec<crate::search::SearchResult>>{
+        let result = self.inner.inner().search_similar_by_hash(url_hash, limit)?;
+        Ok(result)
+    }
+
+    // re-index pages crawled since `since` without a full Index run, returns how many were updated
+    pub async fn reindex_changed_pages(
+        &self,
+        repo: &crate::storage::repository::PageRepository,
+        since: chrono::DateTime<chrono::Utc>,
+    ) -> Result<u64>{
+        let indexer = crate::search::SearchIndexer::new(&self.index_path)?
+            .with_result_cache(self.result_cache());
+        indexer.reindex_changed_pages(repo, since).await
+    }
+
+    // cursor based pagination: continue after the last (score, url) seen
+    pub fn search_after(
         &self,
         query: &str,
         limit: usize,
-        offset: usize,
         filters: SearchFilter,
-        sort: SortBy,
-        snippets: bool,
-        highlight: bool,
+        score: f32,
+        url: &str,
     ) -> Result<Vec<crate::search::SearchResult>>{
-        let result = self.inner.search_with_filters(query, limit, filters, sort, offset, snippets, highlight,)?;
+        let result = self.inner.inner().search_after(query, limit, filters, score, url)?;
         Ok(result)
     }
 }
\ No newline at end of file
@@ -1,11 +1,15 @@
 use std::path::Path;
+use std::sync::Arc;
 use clap::Parser;
+use futures_util::StreamExt;
 use crawler::{init, CrawlerConfig, WebCrawler};
+use crawler::utils::CrawlMetrics;
 use tracing::{info, warn};
 use crawler::search::query::SearchQuery;
-use crawler::storage::database::{Database, DatabaseConfig};
+use crawler::storage::database::Database;
 use crawler::storage::repository::PageRepository;
-use crawler::algorithms::{LinkGraph, PageRankCalculator, TfIdfCalculator};
+use crawler::storage::domain_repository::DomainRepository;
+use crawler::algorithms::{HitsCalculator, LinkGraph, PageRankCalculator, TfIdfCalculator};
 use crawler::storage::models::PageFilter;
 use crate::Commands::CalculatePageRank;
 
@@ -16,6 +20,7 @@ struct Args {
     #[arg(short, long, default_value = "config/default.toml")]
     config: String,
 
+    /// Crawl and process pages without writing them to the database or search index
     #[arg(long)]
     dry_run: bool,
 
@@ -32,14 +37,50 @@ enum Commands {
         save_to_db: bool,
         #[arg(long, default_value = "10")]
         max_pages: usize,
+        /// Checkpoint the frontier to this file while crawling
+        #[arg(long)]
+        checkpoint: Option<String>,
+        /// Continue from the checkpoint file if it exists
+        #[arg(long)]
+        resume: bool,
+        /// Search index brought up to date after a crawl with --save-to-db
+        #[arg(long, default_value = "./search_index")]
+        index_path: String,
+        /// Write the final statistics as JSON to this file, e.g. stats.json
+        #[arg(long)]
+        stats_output: Option<String>,
+    },
+    /// Fetch a list of URLs again, bypassing the frontier
+    Recrawl {
+        /// File with one URL per line
+        urls_file: String,
+        #[arg(long)]
+        save_to_db: bool,
     },
     Index {
         #[arg(long, default_value = "./search_index")]
         index_path: String,
+
+        /// Merge the index into a single segment after indexing
+        #[arg(long)]
+        compact: bool,
+
+        /// Only re-index pages crawled since the last recorded indexing run
+        #[arg(long)]
+        incremental: bool,
+
+        /// Build documents in this many parallel workers instead of one after another
+        #[arg(long)]
+        workers: Option<usize>,
+
+        /// Pages per worker batch with --workers
+        #[arg(long, default_value = "1000")]
+        batch_size: usize,
     },
     Search {
         /// Search query string
-        query: String,
+        #[arg(required_unless_present = "similar_to")]
+        query: Option<String>,
 
         #[arg(long, default_value = "./search_index")]
         index_path: String,
@@ -64,6 +105,26 @@ enum Commands {
         #[arg(long)]
         max_quality: Option<f64>,
 
+        /// Only pages crawled since this date (YYYY-MM-DD, midnight UTC, or RFC 3339)
+        #[arg(long, value_parser = parse_date)]
+        after: Option<chrono::DateTime<chrono::Utc>>,
+
+        /// Only pages crawled until this date (YYYY-MM-DD, midnight UTC, or RFC 3339)
+        #[arg(long, value_parser = parse_date)]
+        before: Option<chrono::DateTime<chrono::Utc>>,
+
+        /// Only pages in these languages (ISO 639-1 codes, comma separated, e.g. hi,mr)
+        #[arg(long, value_delimiter = ',')]
+        language: Vec<String>,
+
+        /// Only pages with at least this many words
+        #[arg(long)]
+        min_words: Option<usize>,
+
+        /// Only pages with at most this many words
+        #[arg(long)]
+        max_words: Option<usize>,
+
         /// Sort by: relevance, quality, or date
         #[arg(long, default_value = "relevance")]
         sort: String,
@@ -75,10 +136,92 @@ enum Commands {
         /// Highlight matched terms
         #[arg(long)]
         highlight: bool,
+
+        /// Parse AND / OR / NOT, quoted phrases, parentheses and field:value prefixes
+        /// (title, content, url, domain, language), see `SearchQuery::parse_advanced_query`
+        #[arg(long)]
+        advanced: bool,
+
+        /// Tolerate typos in query terms
+        #[arg(long)]
+        fuzzy: bool,
+
+        /// Maximum edit distance per term for fuzzy search (0-2)
+        #[arg(long, default_value = "1")]
+        fuzzy_distance: u8,
+
+        /// Also show result counts per domain, language and quality bucket
+        #[arg(long)]
+        facets: bool,
+
+        /// Print how this url scored for the query instead of the results
+        #[arg(long)]
+        explain_url: Option<String>,
+
+        /// List the pages most similar in content to this indexed url instead of searching
+        #[arg(long)]
+        similar_to: Option<String>,
     },
     CalculatePageRank {
         #[arg(long, default_value = "10")]
         top: usize,
+
+        /// Probability of following a link instead of teleporting (0.0-1.0)
+        #[arg(long, default_value = "0.85")]
+        damping: f64,
+
+        /// Most power iterations to run
+        #[arg(long, default_value = "30")]
+        iterations: usize,
+
+        /// Stop once the ranks change by less than this in total between iterations
+        #[arg(long, default_value = "0.0001")]
+        tolerance: f64,
+
+        /// Bias teleportation towards these pages (comma separated) and store
+        /// the result as personalized_pagerank instead of pagerank
+        #[arg(long, value_delimiter = ',')]
+        personalized_seeds: Vec<String>,
+
+        /// Share of teleportation that goes to the seeds (0.0-1.0)
+        #[arg(long, default_value = "1.0")]
+        personalization_weight: f64,
+
+        /// Write the link graph to this file in Graphviz DOT format
+        #[arg(long)]
+        export_dot: Option<String>,
+
+        /// Highest ranked pages included in the DOT export
+        #[arg(long, default_value = "200")]
+        dot_max_nodes: usize,
+
+        /// Pages ranked lower are left out of the DOT export
+        #[arg(long, default_value = "0.0")]
+        dot_min_pagerank: f64,
+
+        /// Print connected component sizes and average degrees of the link graph
+        #[arg(long)]
+        graph_stats: bool,
+
+        /// Write Cypher statements for importing the link graph into Neo4j to this file
+        #[arg(long)]
+        export_neo4j: Option<String>,
+
+        /// Pages or links per statement in the Neo4j export
+        #[arg(long, default_value = "1000")]
+        neo4j_batch_size: usize,
+
+        /// Write the link graph to this file in GraphML format, for Gephi or Cytoscape
+        #[arg(long)]
+        export_graphml: Option<String>,
+    },
+
+    CalculateHits {
+        #[arg(long, default_value = "10")]
+        top: usize,
+
+        #[arg(long, default_value = "50")]
+        iterations: usize,
     },
 
     CalculateTfIdf{
@@ -86,15 +229,125 @@ enum Commands {
         // show up n terms for each document
         #[arg(long, default_value = "10")]
         top : Option<usize>,
+
+        /// Reuse the index saved at this path, updating it with the pages crawled or deleted
+        /// since it was written; without a saved index build one and save it there
+        #[arg(long)]
+        cache_path: Option<String>,
+    },
+
+    /// Recompute every stored page's quality score with the current scoring formula
+    RescoreQuality,
+
+    /// Group stored pages into topics by k-means over TF-IDF vectors and store each page's cluster_id
+    Cluster {
+        /// Number of clusters
+        #[arg(long, default_value = "10")]
+        k: usize,
+    },
+
+    Export {
+        /// Output file path
+        #[arg(short, long)]
+        output: String,
+
+        /// Export format: json, jsonl, csv, or warc
+        #[arg(long, default_value = "json")]
+        format: String,
+
+        /// Pretty-print each JSONL object
+        #[arg(long)]
+        pretty: bool,
+
+        /// CSV columns to write, e.g. url,title,pagerank; all columns by default
+        #[arg(long)]
+        columns: Option<String>,
+    },
+
+    /// Remove a page and its outgoing links from the database
+    Delete {
+        url: String,
+
+        /// Search index the page is also removed from, skipped if it doesn't exist
+        #[arg(long, default_value = "./search_index")]
+        index_path: String,
+    },
+
+    /// Keep only the best page of each group of pages with identical content
+    Deduplicate {
+        /// Smallest number of identical pages that counts as a duplicate group
+        #[arg(long, default_value = "2")]
+        min_group_size: usize,
+
+        /// Most groups to handle in one run
+        #[arg(long, default_value = "100")]
+        limit: usize,
+
+        /// List the groups without deleting anything
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Search index the deleted pages are also removed from, skipped if it doesn't exist
+        #[arg(long, default_value = "./search_index")]
+        index_path: String,
+    },
+
+    Import {
+        /// JSONL file written by `export --format jsonl`, a CSV file with a header row, or a WARC/1.0 file
+        #[arg(short, long)]
+        input: String,
+
+        /// Import format: jsonl, csv, or warc; guessed from the file extension by default
+        #[arg(long)]
+        format: Option<String>,
     },
 
     Api {
         #[arg(short, long, default_value = "3000")]
         port: u16,
+
+        #[arg(long, default_value = "./search_index")]
+        index_path: String,
     },
     Stats,
 }
 
+fn print_graph_stats(graph: &LinkGraph) {
+    let nodes = graph.node_count().max(1) as f64;
+    let out_degree: usize = graph.nodes.iter().map(|url| graph.outbound_count(url)).sum();
+    let in_degree: usize = graph.nodes.iter().map(|url| graph.inbound_count(url)).sum();
+
+    // the ten biggest components, then how many more there are
+    let sizes = |components: &[Vec<String>]| -> String {
+        let mut sizes: Vec<String> = components.iter().take(10).map(|c| c.len().to_string()).collect();
+        if components.len() > 10 {
+            sizes.push(format!("... ({} more)", components.len() - 10));
+        }
+        sizes.join(", ")
+    };
+    let components = graph.connected_components();
+    let strong_components = graph.strongly_connected_components();
+
+    println!("\nLink Graph Statistics:");
+    println!("{}", "=".repeat(80));
+    println!("Pages:                           {}", graph.node_count());
+    println!("Average out-degree:              {:.2}", out_degree as f64 / nodes);
+    println!("Average in-degree:               {:.2}", in_degree as f64 / nodes);
+    println!("Weakly connected components:     {} (sizes: {})", components.len(), sizes(&components));
+    println!("Strongly connected components:   {} (sizes: {})", strong_components.len(), sizes(&strong_components));
+    println!("Largest SCC fraction:            {:.2}%", graph.largest_scc_fraction() * 100.0);
+}
+
+// RFC 3339 timestamp or a plain date, taken as midnight UTC
+fn parse_date(value: &str) -> Result<chrono::DateTime<chrono::Utc>, String> {
+    if let Ok(date) = chrono::DateTime::parse_from_rfc3339(value) {
+        return Ok(date.with_timezone(&chrono::Utc));
+    }
+    chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .map(|date| date.and_time(chrono::NaiveTime::MIN).and_utc())
+        .map_err(|_| format!("expected YYYY-MM-DD or an RFC 3339 timestamp, got '{}'", value))
+}
+
 #[tokio::main]
 async fn main() -> crawler::Result<()> {
     let args = Args::parse();
@@ -103,12 +356,28 @@ async fn main() -> crawler::Result<()> {
     init().await?;
 
     // Load configuration
-    let config = CrawlerConfig::from_file(&args.config)?;
+    let mut config = CrawlerConfig::from_file_with_env_override(&args.config)?;
     info!("Loaded configuration from: {}", args.config);
+    match config.validate() {
+        Ok(warnings) => {
+            for warning in warnings {
+                warn!("Config: {}", warning);
+            }
+        }
+        Err(errors) => {
+            eprintln!("❌ Invalid configuration in {}:", args.config);
+            for error in errors {
+                eprintln!("  - {}", error);
+            }
+            std::process::exit(1);
+        }
+    }
+    config.crawler.dry_run |= args.dry_run;
 
     match args.command {
-        Some(Commands::Crawl { seed_urls, save_to_db, max_pages }) => {
+        Some(Commands::Crawl { seed_urls, save_to_db, max_pages, checkpoint, resume, index_path, stats_output }) => {
             let mut crawler_config = config;
+            let dry_run = crawler_config.crawler.dry_run;
 
             // Update seed URLs if provided
             if !seed_urls.is_empty() {
@@ -118,61 +387,172 @@ async fn main() -> crawler::Result<()> {
             // Update max pages if provided
             crawler_config.crawler.max_pages = max_pages;
 
+            if checkpoint.is_some() {
+                crawler_config.crawler.checkpoint_path = checkpoint;
+            }
+            crawler_config.crawler.resume = resume;
+
             // SIMPLE: Initialize database if save_to_db is true
-            let repository = if save_to_db {
+            let (repository, domain_repository) = if save_to_db {
                 info!("Database storage enabled - initializing PostgreSQL database");
 
-                let db_config = DatabaseConfig {
-                    database_url: crawler_config.storage.database_url.clone(),
-                    max_connections: crawler_config.storage.max_connections,
-                    enable_wal_mode: false,
-                    enable_foreign_keys: true,
-                };
-
                 // Connect and migrate database
-                let pool = Database::connect(&db_config).await?;
+                let storage = &crawler_config.storage;
+                let pool = Database::connect_with_retry(storage, storage.db_connect_retries, storage.db_connect_retry_delay_ms).await?;
                 Database::migrate(&pool).await?;
                 info!("Database initialized and migrations completed");
 
-                Some(PageRepository::new(pool))
+                (
+                    Some(PageRepository::new(pool.clone()).with_compression(crawler_config.storage.compress_content)),
+                    Some(DomainRepository::new(pool)),
+                )
             } else {
                 info!("Running crawler without database storage");
-                None
+                (None, None)
             };
 
             // 🔥 SIMPLE: Just create crawler normally
-            let crawler = WebCrawler::new(crawler_config).await?;
+            let mut crawler = WebCrawler::new(crawler_config, Arc::new(CrawlMetrics::new())).await?;
+            if let Some(domain_repository) = domain_repository {
+                crawler = crawler.with_domain_repository(domain_repository);
+            }
 
             // SIMPLE: Pass repository to the crawl method
-            crawler.start_crawling_with_repository(repository).await?;
+            let crawl_started = chrono::Utc::now();
+            let statistics = crawler.start_crawling_with_repository(repository.clone()).await?;
+            println!("{}", statistics);
+            if let Some(stats_output) = stats_output {
+                std::fs::write(&stats_output, serde_json::to_string_pretty(&statistics)?)?;
+                println!("Statistics written to {}", stats_output);
+            }
+
+            // Keep the search index in step with the pages just saved
+            if let Some(repository) = repository.filter(|_| !dry_run) {
+                let index_path = Path::new(&index_path);
+                let since = crawler::search::last_indexed_at(index_path).unwrap_or(crawl_started);
+                let reindexed = match crawler::search::SearchIndexer::new(index_path) {
+                    Ok(indexer) => indexer.reindex_changed_pages(&repository, since).await,
+                    Err(e) => Err(e.into()),
+                };
+                match reindexed {
+                    Ok(count) => {
+                        crawler::search::record_last_indexed(index_path, crawl_started)?;
+                        println!("Re-indexed {} pages", count);
+                    }
+                    Err(e) => warn!("Could not update the search index at {}: {}", index_path.display(), e),
+                }
+            }
         }
 
-        Some(Commands::Index { index_path }) => {
+        Some(Commands::Recrawl { urls_file, save_to_db }) => {
+            use crawler::CrawlResult;
+
+            let urls: Vec<String> = std::fs::read_to_string(&urls_file)?
+                .lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty() && !line.starts_with('#'))
+                .map(String::from)
+                .collect();
+
+            let repository = if save_to_db {
+                let pool = Database::connect(&config.storage).await?;
+                Database::migrate(&pool).await?;
+                Some(PageRepository::new(pool).with_compression(config.storage.compress_content))
+            } else {
+                None
+            };
+
+            let crawler = WebCrawler::new(config, Arc::new(CrawlMetrics::new())).await?;
+            let results = crawler.crawl_url_list(&urls).await?;
+
+            let mut refreshed = 0;
+            for result in &results {
+                match result {
+                    CrawlResult::Success(page) => {
+                        refreshed += 1;
+                        if let Some(repo) = &repository
+                            && let Err(e) = repo.save_page(page, 0).await
+                        {
+                            warn!("Failed to save {}: {}", page.url, e);
+                        }
+                        println!("✅ {}", page.url);
+                    }
+                    _ => println!("❌ {}: {}", result.url(), result.error().unwrap_or_default()),
+                }
+            }
+
+            println!("Recrawled {}/{} URLs from {}", refreshed, results.len(), urls_file);
+        }
+
+        Some(Commands::Index { index_path, compact, incremental, workers, batch_size }) => {
             use crawler::search::SearchIndexer;
-            use crawler::storage::database::{ Database, DatabaseConfig };
+            use crawler::storage::database::Database;
             use crawler::storage::repository::PageRepository;
 
             info!("Starting search indexing...");
 
             // connect to database
-            let db_config = DatabaseConfig::default();
-            let pool = Database::connect(&db_config).await?;
+            let storage = &config.storage;
+            let pool = Database::connect_with_retry(storage, storage.db_connect_retries, storage.db_connect_retry_delay_ms).await?;
             let repository = PageRepository::new(pool);
 
-            // create indexer and index all pages
-            let indexer = SearchIndexer::new(Path::new(&index_path))?;
-            let count= indexer.index_all_pages(&repository).await?;
+            let index_path = Path::new(&index_path);
+            let started = chrono::Utc::now();
+            let last_indexed = crawler::search::last_indexed_at(index_path);
+
+            match last_indexed {
+                Some(since) if incremental => {
+                    let count = SearchIndexer::new(index_path)?.reindex_changed_pages(&repository, since).await?;
+                    println!("Incremental indexing completed! {} pages crawled since {} re-indexed", count, since);
+                }
+                _ => {
+                    if incremental {
+                        println!("No previous indexing run recorded, indexing all pages");
+                    }
+
+                    // create indexer and index all pages
+                    let indexer = SearchIndexer::with_custom_fields(index_path, &config.search.custom_fields)?;
+                    match workers {
+                        Some(workers) => {
+                            let count = indexer.index_pages_parallel(&repository, workers, batch_size).await?;
+                            println!("Indexing completed! {} pages indexed by {} workers", count, workers);
+                        }
+                        None => {
+                            let count= indexer.index_all_pages(&repository).await?;
 
-            // TODO : Fix this bug count is acting as a fn convert it into integer
-            println!("Indexing completed! {:?} pages indexed", count);
+                            // TODO : Fix this bug count is acting as a fn convert it into integer
+                            println!("Indexing completed! {:?} pages indexed", count);
+                        }
+                    }
+                }
+            }
+            crawler::search::record_last_indexed(index_path, started)?;
+
+            if compact {
+                let segments = SearchIndexer::new(index_path)?.compact()?;
+                println!("Index compacted into {} segment(s)", segments);
+            }
         }
 
-        Some(Commands::CalculatePageRank { top }) => {
+        Some(Commands::CalculatePageRank {
+            top,
+            damping,
+            iterations,
+            tolerance,
+            personalized_seeds,
+            personalization_weight,
+            export_dot,
+            dot_max_nodes,
+            dot_min_pagerank,
+            graph_stats,
+            export_neo4j,
+            neo4j_batch_size,
+            export_graphml,
+        }) => {
             info!("Starting PageRank calculation...");
 
             // Initialize database connection
-            let db_config = DatabaseConfig::default();
-            let pool = Database::connect(&db_config).await?;
+            let pool = Database::connect(&config.storage).await?;
             let repository = PageRepository::new(pool);
 
             // Load graph from repository
@@ -182,8 +562,24 @@ async fn main() -> crawler::Result<()> {
         graph.dangling_nodes().len());
 
             // Calculate PageRank
-            let calculator = PageRankCalculator::new();
-            let ranks = calculator.calculate(&graph);
+            let calculator = PageRankCalculator::builder()
+                .with_damping_factor(damping)
+                .with_max_iterations(iterations)
+                .with_convergence_threshold(tolerance)
+                .build();
+            let personalized = !personalized_seeds.is_empty();
+            let ranks = if personalized {
+                calculator.calculate_personalized(&graph, &personalized_seeds, personalization_weight)
+            } else {
+                let (ranks, convergence) = calculator.calculate_with_convergence_info(&graph);
+                if convergence.converged {
+                    println!("Converged after {} iterations (delta {:.2e})", convergence.iterations_run, convergence.final_delta);
+                } else {
+                    println!("Stopped after {} iterations without converging (delta {:.2e}, tolerance {:.2e})",
+                             convergence.iterations_run, convergence.final_delta, tolerance);
+                }
+                ranks
+            };
 
             // Store PageRank values using batch update for efficiency
             info!("Storing PageRank values...");
@@ -191,7 +587,11 @@ async fn main() -> crawler::Result<()> {
                 .map(|(url, rank)| (url.clone(), *rank))
                 .collect();
 
-            repository.batch_update_pagerank(&ranks_vec).await?;
+            if personalized {
+                repository.batch_update_personalized_pagerank(&ranks_vec).await?;
+            } else {
+                repository.batch_update_pagerank(&ranks_vec).await?;
+            }
 
             // Display top pages
             let top_pages = calculator.get_top_pages(&ranks, top);
@@ -207,50 +607,208 @@ async fn main() -> crawler::Result<()> {
                 );
             }
 
+            if let Some(path) = export_dot {
+                std::fs::write(&path, graph.to_dot(dot_max_nodes, dot_min_pagerank, &ranks))?;
+                println!("\nLink graph written to {}", path);
+            }
+
+            if let Some(path) = export_neo4j {
+                std::fs::write(&path, graph.to_cypher_statements(neo4j_batch_size, &ranks).concat())?;
+                println!("\nCypher import written to {}", path);
+            }
+
+            if let Some(path) = export_graphml {
+                std::fs::write(&path, graph.to_graphml(&ranks))?;
+                println!("\nGraphML written to {}", path);
+            }
+
+            if graph_stats {
+                print_graph_stats(&graph);
+            }
+
             println!("\nPageRank calculation complete!");
         }
 
-        Some(Commands::CalculateTfIdf { top }) => {
+        Some(Commands::CalculateHits { top, iterations }) => {
+            info!("Starting HITS calculation...");
+
+            let pool = Database::connect(&config.storage).await?;
+            let repository = PageRepository::new(pool);
+
+            let graph = LinkGraph::from_repository(&repository).await?;
+            info!("Graph Stats: {} nodes", graph.node_count());
+
+            let result = HitsCalculator::new().calculate(&graph, iterations);
+
+            info!("Storing HITS scores...");
+            let scores: Vec<(String, f64, f64)> = result.hub_scores.iter()
+                .map(|(url, hub)| {
+                    let authority = result.authority_scores.get(url).copied().unwrap_or(0.0);
+                    (url.clone(), *hub, authority)
+                })
+                .collect();
+
+            repository.batch_update_hits_scores(&scores).await?;
+
+            println!("\nTop {} Authorities:\n", top);
+            println!("{:<6} {:<12} {}", "Rank", "Authority", "URL");
+            println!("{}", "=".repeat(80));
+            for (i, (url, score)) in result.top_authorities(top).iter().enumerate() {
+                println!("{:<6} {:<12.6} {}", format!("{}.", i + 1), score, url);
+            }
+
+            println!("\nTop {} Hubs:\n", top);
+            println!("{:<6} {:<12} {}", "Rank", "Hub", "URL");
+            println!("{}", "=".repeat(80));
+            for (i, (url, score)) in result.top_hubs(top).iter().enumerate() {
+                println!("{:<6} {:<12.6} {}", format!("{}.", i + 1), score, url);
+            }
+
+            println!("\nHITS calculation complete!");
+        }
+
+        Some(Commands::CalculateTfIdf { top, cache_path }) => {
             use crawler::algorithms::TfIdfCalculator;
             use crawler::storage::{database::Database, repository::PageRepository};
 
-            let db_config = DatabaseConfig::default();
-            let pool = Database::connect(&db_config).await?;
+            let pool = Database::connect(&config.storage).await?;
             let repository = PageRepository::new(pool);
 
-            let pages = repository.get_pages(&PageFilter::new()).await?;
+            let cache_path = cache_path.as_deref().map(Path::new);
+            let cached = cache_path.filter(|path| path.exists()).map(|path| {
+                let written = std::fs::metadata(path).and_then(|m| m.modified()).ok();
+                (TfIdfCalculator::load(path), written.map(chrono::DateTime::<chrono::Utc>::from))
+            });
+
+            // None rebuilds the index, Some(written) updates the saved one
+            let (mut tfidf, reuse) = match cached {
+                Some((Ok(tfidf), written)) => {
+                    println!("📂 Loaded TF-IDF index from {} ({} documents)", cache_path.unwrap().display(), tfidf.document_count());
+                    (tfidf, Some(written))
+                }
+                loaded => {
+                    if let Some((Err(e), _)) = loaded {
+                        println!("⚠️  Could not load cached TF-IDF index, rebuilding: {}", e);
+                    }
+                    (TfIdfCalculator::new(), None)
+                }
+            };
+
+            // pages are streamed into the index one at a time, only (url_hash, url) pairs are kept
+            let filter = PageFilter::new();
+            let mut stream = std::pin::pin!(repository.get_pages_stream(&filter));
+            let mut pages: Vec<(String, String)> = Vec::new();
+            let mut added = 0;
+            while let Some(page) = stream.next().await {
+                let page = page?;
+                // with a saved index, only pages crawled since it was written or missing from it
+                let stale = reuse.is_none_or(|written| {
+                    !tfidf.contains_document(&page.url_hash) || written.is_none_or(|written| page.crawled_at > written)
+                });
+                if stale {
+                    tfidf.add_document(&page.url_hash, &page.content);
+                    added += 1;
+                }
+                pages.push((page.url_hash, page.url));
+            }
             println!("📊 Loaded {} documents", pages.len());
 
-            // Build corpus: (doc_id=url_hash preferred for stability, content)
-            let corpus: Vec<(String, String)> = pages.iter()
-                .map(|p| (p.url_hash.clone(), p.content.clone()))
+            let current: std::collections::HashSet<&str> = pages.iter().map(|(url_hash, _)| url_hash.as_str()).collect();
+            let deleted: Vec<String> = tfidf.document_ids()
+                .filter(|id| !current.contains(id))
+                .map(String::from)
                 .collect();
+            for doc_id in &deleted {
+                tfidf.remove_document(doc_id);
+            }
 
-            let mut tfidf = TfIdfCalculator::new();
-            tfidf.build_from_corpus(&corpus);
+            if let Some(path) = cache_path {
+                if reuse.is_none() {
+                    tfidf.save(path)?;
+                    println!("💾 Saved TF-IDF index to {} ({} bytes)", path.display(), tfidf.get_stats().serialized_size_bytes);
+                } else if added > 0 || !deleted.is_empty() {
+                    tfidf.save(path)?;
+                    println!("🔄 Updated TF-IDF index: {} pages indexed, {} removed", added, deleted.len());
+                }
+            }
 
             // For each doc, compute a single “magnitude” score to store
             // Magnitude = sqrt(sum over terms of (tfidf(term, doc))^2)
-            for p in &pages {
-                let doc_id = &p.url_hash;
+            for (doc_id, url) in &pages {
                 let top_terms = tfidf.get_top_terms(doc_id, top.unwrap_or(256)); // sample top N
                 let magnitude = top_terms.iter().map(|(_, s)| s * s).sum::<f64>().sqrt();
 
                 repository.update_tfidf_score(doc_id, magnitude).await?;
-                println!("{} tfidf_score={:.6}", p.url, magnitude);
+                println!("{} tfidf_score={:.6}", url, magnitude);
             }
 
             println!("TF-IDF scores updated");
         }
 
+        Some(Commands::Cluster { k }) => {
+            use crawler::algorithms::{ContentCluster, TfIdfCalculator};
+
+            let pool = Database::connect(&config.storage).await?;
+            let repository = PageRepository::new(pool);
+
+            let mut tfidf = TfIdfCalculator::new();
+            let mut url_hashes = std::collections::HashMap::new();
+            let filter = PageFilter::new();
+            let mut stream = std::pin::pin!(repository.get_pages_stream(&filter));
+            while let Some(page) = stream.next().await {
+                let page = page?;
+                tfidf.add_document(&page.url, &page.content);
+                url_hashes.insert(page.url, page.url_hash);
+            }
+            println!("📊 Loaded {} documents", tfidf.document_count());
+
+            let clusters = ContentCluster::cluster_pages(&tfidf, k);
+            let mut assignments = Vec::new();
+            for (cluster_id, urls) in clusters.iter().enumerate() {
+                let labels = ContentCluster::label_cluster(urls, &tfidf);
+                println!("Cluster {}: {} pages [{}]", cluster_id, urls.len(), labels.join(", "));
+                assignments.extend(urls.iter().map(|url| (url_hashes[url].clone(), cluster_id as i32)));
+            }
+
+            let updated = repository.update_cluster_ids(&assignments).await?;
+            println!("Stored cluster ids of {} pages", updated);
+        }
+
+        Some(Commands::RescoreQuality) => {
+            use crawler::algorithms::QualityRescorer;
+            use crawler::core::PageProcessor;
+
+            let pool = Database::connect(&config.storage).await?;
+            let repository = PageRepository::new(pool);
+
+            let updated = QualityRescorer::new(PageProcessor::new()).rescore_all(&repository).await?;
+            println!("Updated {} quality scores, run `index` to refresh them in the search index", updated);
+        }
+
 
 
-        Some(Commands::Search { query, index_path, limit, domain, offset, min_quality, max_quality, sort, snippets, highlight }) => {
-            use crawler::search::{SearchQuery};
+        Some(Commands::Search { query, index_path, limit, domain, offset, min_quality, max_quality, after, before, language, min_words, max_words, sort, snippets, highlight, advanced, fuzzy, fuzzy_distance, facets, explain_url, similar_to }) => {
+            use crawler::search::{FacetedSearchRequest, SearchOptions, SearchQuery};
             use crawler::search::filters::{SearchFilter, SortBy};
             use std::path::Path;
             use std::str::FromStr;
 
+            if let Some(url) = similar_to {
+                let search_engine = SearchQuery::new(Path::new(&index_path))?
+                    .with_config(config.search.clone());
+                let results = search_engine.search_similar(&url, limit)?;
+
+                println!("\n Pages similar to {}\n", url);
+                for (i, result) in results.iter().enumerate() {
+                    println!(" {}. {} (similarity: {:.3})", i + 1, result.url, result.score);
+                    if let Some(ref title) = result.title {
+                        println!("Title: {}", title);
+                    }
+                }
+                return Ok(());
+            }
+            let query = query.unwrap_or_default();
+
             info!("Searching for : '{}'",query);
 
             // Build filters
@@ -268,6 +826,38 @@ async fn main() -> crawler::Result<()> {
                 filters = filters.with_max_quality(max_q);
                 info!("   Filter: max_quality = {}", max_q);
             }
+            if let Some(after) = after {
+                filters = filters.with_crawled_after(after);
+                info!("   Filter: crawled after {}", after);
+            }
+            if let Some(before) = before {
+                filters = filters.with_crawled_before(before);
+                info!("   Filter: crawled before {}", before);
+            }
+
+            if let Some(min_words) = min_words {
+                filters = filters.with_min_word_count(min_words);
+                info!("   Filter: at least {} words", min_words);
+            }
+            if let Some(max_words) = max_words {
+                filters = filters.with_max_word_count(max_words);
+                info!("   Filter: at most {} words", max_words);
+            }
+
+            if !language.is_empty() {
+                let languages: Vec<&str> = language.iter().map(String::as_str).collect();
+                filters = filters.with_languages(&languages);
+                info!("   Filter: language = {}", language.join(", "));
+            }
+
+            if advanced {
+                filters = filters.with_advanced_syntax(true);
+                info!("   Advanced query syntax");
+            }
+            if fuzzy {
+                filters = filters.with_fuzzy_distance(fuzzy_distance);
+                info!("   Fuzzy matching: distance = {}", fuzzy_distance);
+            }
 
             // Parse sort option
             let sort_by = SortBy::from_str(&sort)
@@ -278,10 +868,39 @@ async fn main() -> crawler::Result<()> {
 
 
             // create search query engine
-            let search_engine = SearchQuery::new(Path::new(&index_path))?;
+            let search_engine = SearchQuery::new(Path::new(&index_path))?
+                .with_config(config.search.clone());
+
+            if let Some(url) = explain_url {
+                let explanation = search_engine.explain(&query, &url)?;
+                println!("\n Score of {} for '{}'\n", explanation.url, query);
+                println!(" Combined score: {:.3}", explanation.combined_score);
+                println!(" Text (BM25):    {:.3}", explanation.tantivy_score);
+                println!(" PageRank:       {:.6}", explanation.pagerank);
+                println!(" TF-IDF:         {:.6}", explanation.tfidf);
+                println!(" Quality:        {:.3}", explanation.quality);
+                println!(" URL penalty:    {:.2}", explanation.url_penalty);
+                println!("\n Tantivy explanation:\n{}", explanation.tantivy_explanation);
+                return Ok(());
+            }
 
             // execute search
-            let results = search_engine.search_with_filters(&query, limit, filters, sort_by, offset, snippets, highlight)?;
+            let (results, facet_counts) = if facets {
+                let request = FacetedSearchRequest::new(limit)
+                    .with_offset(offset)
+                    .with_filters(filters)
+                    .with_sort(sort_by)
+                    .with_snippets(snippets, highlight);
+                let response = search_engine.search_with_facets(&query, request)?;
+                (response.results, Some(response.facets))
+            } else {
+                let options = SearchOptions::new(limit)
+                    .with_offset(offset)
+                    .with_filters(filters)
+                    .with_sort(sort_by)
+                    .with_snippets(snippets, highlight);
+                (search_engine.search_with_filters(&query, &options)?, None)
+            };
 
             // display results
             println!("\n Search results for : '{}'\n", query);
@@ -308,16 +927,175 @@ async fn main() -> crawler::Result<()> {
                 }
                 println!();
             }
+
+            if let Some(facet_counts) = facet_counts {
+                let print_facet = |name: &str, counts: &[(String, u64)]| {
+                    let values: Vec<String> = counts.iter().map(|(value, count)| format!("{} ({})", value, count)).collect();
+                    println!("{}: {}", name, values.join(", "));
+                };
+
+                println!("Facets:");
+                print_facet(" Domains", &facet_counts.domains);
+                print_facet(" Languages", &facet_counts.languages);
+                print_facet(" Quality", &facet_counts.quality_buckets);
+            }
+        }
+
+        Some(Commands::Export { output, format, pretty, columns }) => {
+            use crawler::storage::export::{CsvColumn, CsvExporter, DataExporter, JsonlExporter, WarcExporter};
+
+            let pool = Database::connect(&config.storage).await?;
+            let repository = PageRepository::new(pool);
+
+            match format.to_lowercase().as_str() {
+                "json" => {
+                    DataExporter::new(&repository).pages_to_json(&PageFilter::new(), &output).await?;
+                }
+                "jsonl" => {
+                    let mut exporter = JsonlExporter::new(Path::new(&output), pretty)?;
+                    let count = exporter.export_all(&repository, &PageFilter::new()).await?;
+                    println!("Exported {} pages", count);
+                }
+                "csv" => {
+                    let mut builder = CsvExporter::builder();
+                    if let Some(columns) = &columns {
+                        builder = builder.with_columns(CsvColumn::parse_list(columns)?);
+                    }
+                    let count = builder.build().export_all(&repository, &PageFilter::new(), Path::new(&output)).await?;
+                    println!("Exported {} pages", count);
+                }
+                "warc" => {
+                    let mut exporter = WarcExporter::new(Path::new(&output))?;
+                    let count = exporter.export_all(&repository).await?;
+                    println!("Exported {} pages", count);
+                }
+                other => {
+                    return Err(format!("Unknown export format '{}', expected json, jsonl, csv, or warc", other).into());
+                }
+            }
+
+            println!("Export written to {}", output);
         }
 
-        Some(Commands::Api { port }) => {
-            println!("API server not implemented yet. Port: {}", port);
+        Some(Commands::Delete { url, index_path }) => {
+            use crawler::search::SearchIndexer;
+
+            let pool = Database::connect(&config.storage).await?;
+            let mut repository = PageRepository::new(pool);
+            if Path::new(&index_path).exists() {
+                let indexer = SearchIndexer::new(Path::new(&index_path))?;
+                repository = repository.with_indexer(std::sync::Arc::new(indexer));
+            }
+
+            repository.delete_page(&url).await?;
+            println!("Deleted {}", url);
+        }
+
+        Some(Commands::Deduplicate { min_group_size, limit, dry_run, index_path }) => {
+            use crawler::search::SearchIndexer;
+
+            let pool = Database::connect(&config.storage).await?;
+            let mut repository = PageRepository::new(pool);
+            if !dry_run && Path::new(&index_path).exists() {
+                let indexer = SearchIndexer::new(Path::new(&index_path))?;
+                repository = repository.with_indexer(std::sync::Arc::new(indexer));
+            }
+
+            let groups = repository.get_duplicate_content_groups(min_group_size, limit).await?;
+            let mut deleted = 0;
+            for group in &groups {
+                let best = &group[0];
+                println!("{} copies of {} (quality {:.3}, pagerank {:.6})",
+                         group.len(), best.url, best.quality_score, best.pagerank.unwrap_or(0.0));
+                for duplicate in &group[1..] {
+                    println!("    {}", duplicate.url);
+                }
+
+                if !dry_run {
+                    deleted += repository.keep_best_duplicate(&best.content_hash).await?;
+                }
+            }
+
+            if dry_run {
+                println!("Found {} duplicate groups, nothing deleted (--dry-run)", groups.len());
+            } else {
+                println!("Deleted {} duplicate pages from {} groups", deleted, groups.len());
+            }
+        }
+
+        Some(Commands::Import { input, format }) => {
+            use crawler::storage::export::{CsvImporter, JsonlImporter, WarcImporter};
+
+            let pool = Database::connect(&config.storage).await?;
+            Database::migrate(&pool).await?;
+            let repository = PageRepository::new(pool).with_compression(config.storage.compress_content);
+
+            let path = Path::new(&input);
+            let format = format.unwrap_or_else(|| {
+                match path.extension().map(|ext| ext.to_string_lossy().to_lowercase()).as_deref() {
+                    Some("csv") => "csv",
+                    Some("warc") => "warc",
+                    _ => "jsonl",
+                }.to_string()
+            });
+
+            match format.to_lowercase().as_str() {
+                "csv" => {
+                    let stats = CsvImporter::import(path, &repository).await?;
+                    println!("Imported {} pages from {} ({} rows skipped)", stats.imported, input, stats.skipped);
+                    if !stats.unknown_columns.is_empty() {
+                        println!("Ignored unknown columns: {}", stats.unknown_columns.join(", "));
+                    }
+                }
+                "warc" => {
+                    let stats = WarcImporter::new(path)?.import_all(&repository).await?;
+                    println!("Imported {} pages from {} ({} records skipped)", stats.imported, input, stats.skipped);
+                }
+                "jsonl" => {
+                    let count = JsonlImporter::import(path, &repository).await?;
+                    println!("Imported {} pages from {}", count, input);
+                }
+                other => {
+                    return Err(format!("Unknown import format '{}', expected jsonl, csv, or warc", other).into());
+                }
+            }
+        }
+
+        Some(Commands::Api { port, index_path }) => {
+            use crawler::api::{self, ApiState};
+            use crawler::search::SearchIndexer;
+            use crawler::SearchEngine;
+
+            let pool = Database::connect(&config.storage).await?;
+            Database::migrate(&pool).await?;
+
+            let search_engine = SearchEngine::new(Path::new(&index_path))?
+                .with_cache_settings(&config.storage.cache)
+                .with_config(config.search.clone());
+            let indexer = SearchIndexer::with_custom_fields(Path::new(&index_path), &config.search.custom_fields)?
+                .with_result_cache(search_engine.result_cache());
+            let repository = PageRepository::new(pool)
+                .with_compression(config.storage.compress_content)
+                .with_indexer(Arc::new(indexer));
+            let crawler = WebCrawler::new(config, Arc::new(CrawlMetrics::new())).await?;
+
+            let state = ApiState::new(search_engine, repository, crawler);
+            api::serve(state, port).await?;
         }
         Some(Commands::Stats) => {
-            println!("Crawler Statistics:");
+            let pool = Database::connect(&config.storage).await?;
+            Database::migrate(&pool).await?;
+            let repository = PageRepository::new(pool);
+
+            let database = repository.get_stats().await?;
+            println!("{}", repository.get_cumulative_statistics().await?);
+            println!("{:<24}{:>20}", "Pages stored", database.total_pages);
+            println!("{:<24}{:>20}", "Links stored", database.total_links);
+            println!("{:<24}{:>20}", "Domains", database.total_domains);
+            println!("{:<24}{:>20}", "Crawl sessions", database.crawl_sessions);
         }
         None => {
-            let crawler = WebCrawler::new(config).await?;
+            let crawler = WebCrawler::new(config, Arc::new(CrawlMetrics::new())).await?;
             crawler.start_crawling().await?;
         }
         _ => {}
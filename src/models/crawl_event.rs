@@ -0,0 +1,23 @@
+use crate::models::statistics::CrawlStatistics;
+use serde::{Serialize, Deserialize};
+
+/// Progress notifications broadcast by `WebCrawler::subscribe`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum CrawlEvent {
+    PageCrawled {
+        url: String,
+        depth: u32,
+        quality: f64,
+    },
+
+    PageFailed {
+        url: String,
+        error: String,
+    },
+
+    FrontierUpdated {
+        queue_size: usize,
+    },
+
+    CrawlCompleted(CrawlStatistics),
+}
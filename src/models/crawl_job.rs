@@ -0,0 +1,86 @@
+use crate::config::{AlgorithmSettings, CrawlerConfig, CrawlerSettings, NetworkSettings};
+use crate::models::CrawlStatistics;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "state", rename_all = "lowercase")]
+pub enum CrawlJobStatus {
+    Running,
+    Completed { statistics: CrawlStatistics },
+    Failed { error: String },
+    Cancelled,
+}
+
+impl CrawlJobStatus {
+    /// Name stored in the `crawl_jobs.status` column
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Running => "running",
+            Self::Completed { .. } => "completed",
+            Self::Failed { .. } => "failed",
+            Self::Cancelled => "cancelled",
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        !matches!(self, Self::Running)
+    }
+}
+
+/// The settings a job crawls with. Storage settings are left out, they hold the
+/// database and Redis credentials.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CrawlJobConfig {
+    pub crawler: CrawlerSettings,
+    pub network: NetworkSettings,
+    pub algorithms: AlgorithmSettings,
+}
+
+impl From<&CrawlerConfig> for CrawlJobConfig {
+    fn from(config: &CrawlerConfig) -> Self {
+        Self {
+            crawler: config.crawler.clone(),
+            network: config.network.clone(),
+            algorithms: config.algorithms.clone(),
+        }
+    }
+}
+
+/// One crawl campaign over a set of seed URLs, see `api::CrawlJobManager`
+#[derive(Debug, Clone, Serialize)]
+pub struct CrawlJob {
+    pub id: Uuid,
+    pub seed_urls: Vec<String>,
+    pub config: CrawlJobConfig,
+    pub status: CrawlJobStatus,
+    /// Empty until the crawl completes
+    pub statistics: CrawlStatistics,
+    pub started_at: DateTime<Utc>,
+    /// When the job completed, failed or was cancelled
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+impl CrawlJob {
+    pub fn new(seed_urls: Vec<String>, config: &CrawlerConfig) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            seed_urls,
+            config: CrawlJobConfig::from(config),
+            status: CrawlJobStatus::Running,
+            statistics: CrawlStatistics::default(),
+            started_at: Utc::now(),
+            completed_at: None,
+        }
+    }
+
+    /// Record how the job ended, the statistics of a completed crawl are kept on the job too
+    pub fn finish(&mut self, status: CrawlJobStatus) {
+        if let CrawlJobStatus::Completed { statistics } = &status {
+            self.statistics = statistics.clone();
+        }
+        self.status = status;
+        self.completed_at = Some(Utc::now());
+    }
+}
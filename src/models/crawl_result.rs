@@ -14,4 +14,29 @@ pub enum CrawlResult {
         url: String,
         reason: String,
     },  
-}
\ No newline at end of file
+}
+
+impl CrawlResult {
+    pub fn url(&self) -> &str {
+        match self {
+            CrawlResult::Success(page) => &page.url,
+            CrawlResult::Failed { url, .. } | CrawlResult::Skipped { url, .. } => url,
+        }
+    }
+
+    pub fn page_data(&self) -> Option<&PageData> {
+        match self {
+            CrawlResult::Success(page) => Some(page),
+            _ => None,
+        }
+    }
+
+    /// Why the URL produced no page, for failed and skipped URLs
+    pub fn error(&self) -> Option<&str> {
+        match self {
+            CrawlResult::Success(_) => None,
+            CrawlResult::Failed { error, .. } => Some(error),
+            CrawlResult::Skipped { reason, .. } => Some(reason),
+        }
+    }
+}
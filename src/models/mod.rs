@@ -1,10 +1,14 @@
 mod crawl_url;
 mod page_data;
 mod crawl_result;
+mod crawl_event;
+mod crawl_job;
 mod statistics;
 mod tests;
 
 pub use crawl_url::CrawlUrl;
 pub use crawl_result::CrawlResult;
+pub use crawl_event::CrawlEvent;
+pub use crawl_job::{CrawlJob, CrawlJobConfig, CrawlJobStatus};
 pub use page_data::PageData;
 pub use statistics::CrawlStatistics;
\ No newline at end of file
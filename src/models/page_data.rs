@@ -11,6 +11,46 @@ pub struct PageData {
     pub outgoing_links: Vec<CrawlUrl>,
     pub word_count: usize,
     pub content_quality_score: f64,
+    // unweighted score of each factor behind content_quality_score, for debugging
+    #[serde(default)]
+    pub quality_factors: Vec<(String, f64)>,
     pub crawled_at : chrono::DateTime<chrono::Utc>,
     pub depth : u32,
+
+    // validators from the response, replayed on recrawl as conditional request headers
+    #[serde(default)]
+    pub etag: Option<String>,
+    #[serde(default)]
+    pub last_modified: Option<chrono::DateTime<chrono::Utc>>,
+
+    // RSS / Atom feeds the page links to
+    #[serde(default)]
+    pub feed_urls: Vec<String>,
+
+    // Open Graph image and type, if the page declares them
+    #[serde(default)]
+    pub og_image: Option<String>,
+    #[serde(default)]
+    pub og_type: Option<String>,
+
+    // Screenshot file name relative to the crawler's screenshot_dir, when captured
+    #[serde(default)]
+    pub screenshot_path: Option<String>,
+
+    // <link rel="canonical"> target on the same site, the page is stored under it
+    #[serde(default)]
+    pub canonical_url: Option<String>,
+}
+
+impl PageData {
+    /// URL the page is stored under, its canonical URL when it declares one
+    pub fn storage_url(&self) -> &str {
+        self.canonical_url.as_deref().unwrap_or(&self.url)
+    }
+
+    /// The crawled URL when it is another spelling of a different canonical URL
+    pub fn alias_url(&self) -> Option<&str> {
+        let canonical = self.canonical_url.as_deref()?;
+        (crate::utils::canonicalize(canonical) != crate::utils::canonicalize(&self.url)).then_some(self.url.as_str())
+    }
 }
\ No newline at end of file
@@ -1,7 +1,9 @@
 use serde::{Serialize, Deserialize};
+use std::collections::HashMap;
+use std::fmt;
 use std::time::Duration;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct CrawlStatistics {
     pub pages_crawled: usize,
     pub pages_failed: usize,
@@ -9,4 +11,128 @@ pub struct CrawlStatistics {
     pub urls_in_queue: usize,
     pub elapsed_time : Duration,
     pub crawl_rate : f64,
-}
\ No newline at end of file
+    #[serde(default)]
+    pub max_depth_reached: u32,
+    #[serde(default)]
+    pub resumed_from_checkpoint: bool,
+    #[serde(default)]
+    pub bytes_downloaded: u64,
+    #[serde(default)]
+    pub avg_fetch_time_ms: f64,
+    /// Recrawls answered with 304 Not Modified
+    #[serde(default)]
+    pub cache_hits: u64,
+    /// Recrawls that sent validators but got the full page back
+    #[serde(default)]
+    pub cache_misses: u64,
+    /// Failed pages per error type, e.g. `timeout` or `http_404`
+    #[serde(default)]
+    pub errors_by_type: HashMap<String, usize>,
+    /// Pages a dry run processed without saving them
+    #[serde(default)]
+    pub simulated_saves: usize,
+    /// Pages robots.txt disallowed, neither crawled nor failed
+    #[serde(default)]
+    pub pages_skipped: usize,
+}
+
+impl CrawlStatistics {
+    /// Fold in the statistics of another worker crawling at the same time: counters are summed,
+    /// elapsed time is the longer of the two and the rates are recomputed from the totals
+    pub fn merge(&mut self, other: &CrawlStatistics) {
+        let fetched = self.pages_crawled + other.pages_crawled;
+        if fetched > 0 {
+            self.avg_fetch_time_ms = (self.avg_fetch_time_ms * self.pages_crawled as f64
+                + other.avg_fetch_time_ms * other.pages_crawled as f64)
+                / fetched as f64;
+        }
+
+        self.pages_crawled = fetched;
+        self.pages_failed += other.pages_failed;
+        self.urls_discovered += other.urls_discovered;
+        self.urls_in_queue += other.urls_in_queue;
+        self.elapsed_time = self.elapsed_time.max(other.elapsed_time);
+        self.crawl_rate = rate(self.pages_crawled, self.elapsed_time);
+        self.max_depth_reached = self.max_depth_reached.max(other.max_depth_reached);
+        self.resumed_from_checkpoint |= other.resumed_from_checkpoint;
+        self.bytes_downloaded += other.bytes_downloaded;
+        self.cache_hits += other.cache_hits;
+        self.cache_misses += other.cache_misses;
+        self.simulated_saves += other.simulated_saves;
+        self.pages_skipped += other.pages_skipped;
+        for (error_type, count) in &other.errors_by_type {
+            *self.errors_by_type.entry(error_type.clone()).or_default() += count;
+        }
+    }
+}
+
+impl fmt::Display for CrawlStatistics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Crawl Statistics")?;
+        writeln!(f, "{}", "=".repeat(44))?;
+        writeln!(f, "{:<24}{:>20}", "Pages crawled", self.pages_crawled)?;
+        writeln!(f, "{:<24}{:>20}", "Pages failed", self.pages_failed)?;
+        writeln!(f, "{:<24}{:>20}", "URLs discovered", self.urls_discovered)?;
+        writeln!(f, "{:<24}{:>20}", "URLs in queue", self.urls_in_queue)?;
+        writeln!(f, "{:<24}{:>20}", "Max depth reached", self.max_depth_reached)?;
+        writeln!(f, "{:<24}{:>20}", "Downloaded", format_bytes(self.bytes_downloaded))?;
+        writeln!(f, "{:<24}{:>20}", "Avg fetch time", format!("{:.1} ms", self.avg_fetch_time_ms))?;
+        writeln!(f, "{:<24}{:>20}", "Cache hits / misses", format!("{} / {}", self.cache_hits, self.cache_misses))?;
+        writeln!(f, "{:<24}{:>20}", "Elapsed time", format_elapsed(self.elapsed_time))?;
+        writeln!(f, "{:<24}{:>20}", "Crawl rate", format!("{:.2} pages/sec", self.crawl_rate))?;
+        if self.pages_skipped > 0 {
+            writeln!(f, "{:<24}{:>20}", "Skipped (robots.txt)", self.pages_skipped)?;
+        }
+        if self.simulated_saves > 0 {
+            writeln!(f, "{:<24}{:>20}", "Saves skipped (dry run)", self.simulated_saves)?;
+        }
+        if self.resumed_from_checkpoint {
+            writeln!(f, "{:<24}{:>20}", "Resumed from checkpoint", "yes")?;
+        }
+
+        if !self.errors_by_type.is_empty() {
+            writeln!(f, "Errors by type")?;
+            let mut errors: Vec<_> = self.errors_by_type.iter().collect();
+            errors.sort_by(|a, b| b.1.cmp(a.1).then_with(|| a.0.cmp(b.0)));
+            for (error_type, count) in errors {
+                writeln!(f, "  {:<22}{:>20}", error_type, count)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Pages per second, 0.0 before any time has passed
+fn rate(pages: usize, elapsed: Duration) -> f64 {
+    if elapsed.is_zero() {
+        0.0
+    } else {
+        pages as f64 / elapsed.as_secs_f64()
+    }
+}
+
+/// Whole seconds as e.g. `1h 2m 5s`, `5m 23s` or `42s`
+fn format_elapsed(elapsed: Duration) -> String {
+    let secs = elapsed.as_secs();
+    let (hours, minutes, seconds) = (secs / 3600, secs / 60 % 60, secs % 60);
+    match (hours, minutes) {
+        (0, 0) => format!("{}s", seconds),
+        (0, _) => format!("{}m {}s", minutes, seconds),
+        _ => format!("{}h {}m {}s", hours, minutes, seconds),
+    }
+}
+
+fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KB", "MB", "GB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} B", bytes)
+    } else {
+        format!("{:.1} {}", value, UNITS[unit])
+    }
+}
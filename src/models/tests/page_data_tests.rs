@@ -12,8 +12,16 @@ fn test_page_data_creation() {
         outgoing_links: vec![],
         word_count: 10,
         content_quality_score: 0.75,
+        quality_factors: Vec::new(),
         crawled_at: Utc::now(),
         depth: 1,
+        etag: None,
+        last_modified: None,
+        feed_urls: vec![],
+        og_image: None,
+        og_type: None,
+        screenshot_path: None,
+        canonical_url: None,
     };
 
     assert_eq!(page_data.url, "https://example.com");
@@ -52,8 +60,16 @@ fn test_page_data_with_links() {
         outgoing_links: outgoing_links.clone(),
         word_count: 4,
         content_quality_score: 0.5,
+        quality_factors: Vec::new(),
         crawled_at: Utc::now(),
         depth: 1,
+        etag: None,
+        last_modified: None,
+        feed_urls: vec![],
+        og_image: None,
+        og_type: None,
+        screenshot_path: None,
+        canonical_url: None,
     };
 
     assert_eq!(page_data.outgoing_links.len(), 2);
@@ -74,8 +90,16 @@ fn test_page_data_empty_content() {
         outgoing_links: vec![],
         word_count: 0,
         content_quality_score: 0.0,
+        quality_factors: Vec::new(),
         crawled_at: Utc::now(),
         depth: 0,
+        etag: None,
+        last_modified: None,
+        feed_urls: vec![],
+        og_image: None,
+        og_type: None,
+        screenshot_path: None,
+        canonical_url: None,
     };
 
     assert!(page_data.title.is_none());
@@ -99,8 +123,16 @@ fn test_page_data_quality_score_range() {
         outgoing_links: vec![],
         word_count: 2,
         content_quality_score: 1.5, // Invalid: > 1.0
+        quality_factors: Vec::new(),
         crawled_at: Utc::now(),
         depth: 0,
+        etag: None,
+        last_modified: None,
+        feed_urls: vec![],
+        og_image: None,
+        og_type: None,
+        screenshot_path: None,
+        canonical_url: None,
     };
 
     // In a real implementation, you might have validation
@@ -123,8 +155,16 @@ fn test_page_data_serialization() {
         outgoing_links: vec![],
         word_count: 5,
         content_quality_score: 0.8,
+        quality_factors: Vec::new(),
         crawled_at: Utc::now(),
         depth: 1,
+        etag: None,
+        last_modified: None,
+        feed_urls: vec![],
+        og_image: None,
+        og_type: None,
+        screenshot_path: None,
+        canonical_url: None,
     };
 
     // Test JSON serialization if PageData derives Serialize
@@ -150,8 +190,16 @@ fn test_page_data_with_large_content() {
         outgoing_links: vec![],
         word_count: 1000,
         content_quality_score: 0.9,
+        quality_factors: Vec::new(),
         crawled_at: Utc::now(),
         depth: 2,
+        etag: None,
+        last_modified: None,
+        feed_urls: vec![],
+        og_image: None,
+        og_type: None,
+        screenshot_path: None,
+        canonical_url: None,
     };
 
     assert_eq!(page_data.content.len(), large_content.len());
@@ -1,4 +1,5 @@
 use crate::models::CrawlStatistics;
+use std::collections::HashMap;
 use std::time::Duration;
 
 #[test]
@@ -10,6 +11,15 @@ fn test_crawl_statistics_creation() {
         urls_in_queue: 25,
         elapsed_time: Duration::from_secs(300), // 5 minutes
         crawl_rate: 0.33, // pages per second
+        max_depth_reached: 0,
+        resumed_from_checkpoint: false,
+        bytes_downloaded: 0,
+        avg_fetch_time_ms: 0.0,
+        cache_hits: 0,
+        cache_misses: 0,
+        errors_by_type: HashMap::new(),
+        simulated_saves: 0,
+        pages_skipped: 0,
     };
 
     assert_eq!(stats.pages_crawled, 100);
@@ -29,6 +39,15 @@ fn test_crawl_statistics_initial_state() {
         urls_in_queue: 0,
         elapsed_time: Duration::from_secs(0),
         crawl_rate: 0.0,
+        max_depth_reached: 0,
+        resumed_from_checkpoint: false,
+        bytes_downloaded: 0,
+        avg_fetch_time_ms: 0.0,
+        cache_hits: 0,
+        cache_misses: 0,
+        errors_by_type: HashMap::new(),
+        simulated_saves: 0,
+        pages_skipped: 0,
     };
 
     assert_eq!(stats.pages_crawled, 0);
@@ -48,6 +67,15 @@ fn test_crawl_statistics_calculations() {
         urls_in_queue: 150,
         elapsed_time: Duration::from_secs(100),
         crawl_rate: 0.5, // 50 pages / 100 seconds
+        max_depth_reached: 0,
+        resumed_from_checkpoint: false,
+        bytes_downloaded: 0,
+        avg_fetch_time_ms: 0.0,
+        cache_hits: 0,
+        cache_misses: 0,
+        errors_by_type: HashMap::new(),
+        simulated_saves: 0,
+        pages_skipped: 0,
     };
 
     // Test total pages attempted
@@ -73,6 +101,15 @@ fn test_crawl_statistics_edge_cases() {
         urls_in_queue: 40,
         elapsed_time: Duration::ZERO,
         crawl_rate: f64::INFINITY, // or handle this case specially
+        max_depth_reached: 0,
+        resumed_from_checkpoint: false,
+        bytes_downloaded: 0,
+        avg_fetch_time_ms: 0.0,
+        cache_hits: 0,
+        cache_misses: 0,
+        errors_by_type: HashMap::new(),
+        simulated_saves: 0,
+        pages_skipped: 0,
     };
 
     assert!(stats_zero_time.crawl_rate.is_infinite() || stats_zero_time.crawl_rate.is_nan());
@@ -85,6 +122,15 @@ fn test_crawl_statistics_edge_cases() {
         urls_in_queue: 9,
         elapsed_time: Duration::from_secs(3600), // 1 hour
         crawl_rate: 1.0 / 3600.0, // Very slow rate
+        max_depth_reached: 0,
+        resumed_from_checkpoint: false,
+        bytes_downloaded: 0,
+        avg_fetch_time_ms: 0.0,
+        cache_hits: 0,
+        cache_misses: 0,
+        errors_by_type: HashMap::new(),
+        simulated_saves: 0,
+        pages_skipped: 0,
     };
 
     assert!(stats_long_time.crawl_rate < 0.001);
@@ -101,6 +147,15 @@ fn test_crawl_statistics_realistic_scenario() {
         urls_in_queue: 2500,
         elapsed_time: Duration::from_secs(3600), // 1 hour
         crawl_rate: 1500.0 / 3600.0, // ~0.42 pages/second
+        max_depth_reached: 0,
+        resumed_from_checkpoint: false,
+        bytes_downloaded: 0,
+        avg_fetch_time_ms: 0.0,
+        cache_hits: 0,
+        cache_misses: 0,
+        errors_by_type: HashMap::new(),
+        simulated_saves: 0,
+        pages_skipped: 0,
     };
 
     // Validate realistic ranges
@@ -120,6 +175,15 @@ fn test_crawl_statistics_debug_format() {
         urls_in_queue: 155,
         elapsed_time: Duration::from_secs(120),
         crawl_rate: 0.35,
+        max_depth_reached: 0,
+        resumed_from_checkpoint: false,
+        bytes_downloaded: 0,
+        avg_fetch_time_ms: 0.0,
+        cache_hits: 0,
+        cache_misses: 0,
+        errors_by_type: HashMap::new(),
+        simulated_saves: 0,
+        pages_skipped: 0,
     };
 
     // Test that Debug formatting works (if CrawlStatistics derives Debug)
@@ -137,6 +201,15 @@ fn test_crawl_statistics_performance_metrics() {
         urls_in_queue: 380,
         elapsed_time: Duration::from_secs(200),
         crawl_rate: 0.5,
+        max_depth_reached: 0,
+        resumed_from_checkpoint: false,
+        bytes_downloaded: 0,
+        avg_fetch_time_ms: 0.0,
+        cache_hits: 0,
+        cache_misses: 0,
+        errors_by_type: HashMap::new(),
+        simulated_saves: 0,
+        pages_skipped: 0,
     };
 
     // Calculate various performance metrics
@@ -150,3 +223,63 @@ fn test_crawl_statistics_performance_metrics() {
     assert_eq!(discovery_rate, 6.0); // 6 URLs per page crawled
     assert!((queue_utilization - 0.633).abs() < 0.001); // ~63.3% still in queue
 }
+
+#[test]
+fn test_crawl_statistics_display() {
+    let mut errors_by_type = HashMap::new();
+    errors_by_type.insert("timeout".to_string(), 3);
+    let stats = CrawlStatistics {
+        pages_crawled: 120,
+        elapsed_time: Duration::from_secs(323),
+        crawl_rate: 0.37,
+        bytes_downloaded: 3 * 1024 * 1024,
+        errors_by_type,
+        ..CrawlStatistics::default()
+    };
+
+    let table = stats.to_string();
+    assert!(table.lines().any(|line| line.starts_with("Pages crawled") && line.ends_with(" 120")));
+    assert!(table.contains("5m 23s"));
+    assert!(table.contains("0.37 pages/sec"));
+    assert!(table.contains("3.0 MB"));
+    assert!(table.lines().any(|line| line.trim_start().starts_with("timeout") && line.ends_with(" 3")));
+
+    let hours = CrawlStatistics { elapsed_time: Duration::from_secs(3725), ..CrawlStatistics::default() };
+    assert!(hours.to_string().contains("1h 2m 5s"));
+}
+
+#[test]
+fn test_crawl_statistics_merge_and_json_round_trip() {
+    let mut first = CrawlStatistics {
+        pages_crawled: 30,
+        pages_failed: 2,
+        elapsed_time: Duration::from_secs(10),
+        max_depth_reached: 2,
+        avg_fetch_time_ms: 100.0,
+        errors_by_type: HashMap::from([("timeout".to_string(), 2)]),
+        ..CrawlStatistics::default()
+    };
+    let second = CrawlStatistics {
+        pages_crawled: 10,
+        pages_failed: 1,
+        elapsed_time: Duration::from_secs(20),
+        max_depth_reached: 4,
+        avg_fetch_time_ms: 300.0,
+        errors_by_type: HashMap::from([("timeout".to_string(), 1)]),
+        ..CrawlStatistics::default()
+    };
+
+    first.merge(&second);
+    assert_eq!(first.pages_crawled, 40);
+    assert_eq!(first.pages_failed, 3);
+    assert_eq!(first.elapsed_time, Duration::from_secs(20));
+    assert_eq!(first.crawl_rate, 2.0);
+    assert_eq!(first.max_depth_reached, 4);
+    assert_eq!(first.avg_fetch_time_ms, 150.0);
+    assert_eq!(first.errors_by_type["timeout"], 3);
+
+    let json = serde_json::to_string(&first).unwrap();
+    let parsed: CrawlStatistics = serde_json::from_str(&json).unwrap();
+    assert_eq!(parsed.pages_crawled, 40);
+    assert_eq!(parsed.elapsed_time, Duration::from_secs(20));
+}
@@ -0,0 +1,82 @@
+//! DNS resolution for `HttpClient` with per-host overrides and a TTL cache, reqwest itself
+//! looks every host up again for each new connection
+
+use dashmap::DashMap;
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+use std::collections::HashMap;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+use tracing::debug;
+
+/// Installed in the reqwest client when it is built, so overrides and the cache TTL can be
+/// changed afterwards. Overridden hosts never reach the system resolver, e.g. `example.com`
+/// can point at `127.0.0.1` where a local copy of the site runs.
+#[derive(Debug, Default)]
+pub struct DnsResolver {
+    overrides: RwLock<HashMap<String, IpAddr>>,
+    // zero disables the cache
+    cache_ttl: RwLock<Duration>,
+    // host -> first resolved address and when it was looked up
+    cache: Arc<DashMap<String, (IpAddr, Instant)>>,
+}
+
+impl DnsResolver {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Replace the host to IP mapping, hosts are matched case-insensitively
+    pub fn set_overrides(&self, overrides: HashMap<String, IpAddr>) {
+        *self.overrides.write().unwrap() = overrides
+            .into_iter()
+            .map(|(host, ip)| (host.to_ascii_lowercase(), ip))
+            .collect();
+    }
+
+    /// Keep looked up addresses for `ttl`, `Duration::ZERO` turns caching off and empties the cache
+    pub fn set_cache_ttl(&self, ttl: Duration) {
+        *self.cache_ttl.write().unwrap() = ttl;
+        if ttl.is_zero() {
+            self.cache.clear();
+        }
+    }
+
+    /// The cached address of `host`, None when it isn't cached or has expired
+    pub fn cached_address(&self, host: &str) -> Option<IpAddr> {
+        let ttl = *self.cache_ttl.read().unwrap();
+        self.cache
+            .get(&host.to_ascii_lowercase())
+            .filter(|entry| entry.1.elapsed() < ttl)
+            .map(|entry| entry.0)
+    }
+
+    fn single(ip: IpAddr) -> Addrs {
+        // port 0 is replaced with the scheme's port or the one in the URL
+        Box::new(std::iter::once(SocketAddr::new(ip, 0)))
+    }
+}
+
+impl Resolve for DnsResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        let host = name.as_str().to_ascii_lowercase();
+
+        if let Some(ip) = self.overrides.read().unwrap().get(&host).copied() {
+            debug!("Resolved {} to {} from the DNS overrides", host, ip);
+            return Box::pin(std::future::ready(Ok(Self::single(ip))));
+        }
+        if let Some(ip) = self.cached_address(&host) {
+            return Box::pin(std::future::ready(Ok(Self::single(ip))));
+        }
+
+        let ttl = *self.cache_ttl.read().unwrap();
+        let cache = Arc::clone(&self.cache);
+        Box::pin(async move {
+            let addrs: Vec<SocketAddr> = tokio::net::lookup_host((host.as_str(), 0)).await?.collect();
+            if let Some(addr) = addrs.first().filter(|_| !ttl.is_zero()) {
+                cache.insert(host, (addr.ip(), Instant::now()));
+            }
+            Ok(Box::new(addrs.into_iter()) as Addrs)
+        })
+    }
+}
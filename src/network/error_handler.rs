@@ -28,8 +28,9 @@ pub enum NetworkError {
     #[error("Robots.txt disallows crawling: {0}")]
     RobotsDisallowed(String),
 
-    #[error("Rate limit exceeded for domain: {0}")]
-    RateLimited(String),
+    /// 429 response, `retry_after_secs` from its `Retry-After` header
+    #[error("Rate limit exceeded for domain: {domain}")]
+    RateLimited { domain: String, retry_after_secs: Option<u64> },
 
     #[error("DNS resolution failed: {0}")]
     DnsError(String),
@@ -43,6 +44,12 @@ pub enum NetworkError {
     #[error("Too many redirects: {count} (limit: {limit})")]
     TooManyRedirects { count: u32, limit: u32 },
 
+    #[error("Screenshot failed: {0}")]
+    Screenshot(String),
+
+    #[error("JavaScript rendering failed: {0}")]
+    JsRendering(String),
+
     #[error("Request error: {0}")]
     Request(#[from] reqwest::Error),
 
@@ -61,7 +68,7 @@ impl NetworkError {
                 // Retry on server errors, not client errors
                 *status >= 500 && *status < 600 || *status == 429  // Fixed: now status is u16, this works
             },
-            NetworkError::RateLimited(_) => true,
+            NetworkError::RateLimited { .. } => true,
             NetworkError::TlsError(_) => false,
             NetworkError::Request(e) => {
                 // Check reqwest error type
@@ -83,10 +90,86 @@ impl NetworkError {
                     _ => 1000,
                 }
             },
-            NetworkError::RateLimited(_) => 5000,
+            NetworkError::RateLimited { retry_after_secs, .. } => retry_after_secs.map_or(5000, |secs| secs * 1000),
             _ => 1000, // Fixed: changed default from 5000 to 1000 for consistency
         }
     }
+
+    /// HTTP status code of the response that caused the error, if there was one
+    pub fn http_status(&self) -> Option<u16> {
+        match self {
+            NetworkError::Http { status, .. } => Some(*status),
+            NetworkError::RateLimited { .. } => Some(429),
+            NetworkError::Request(e) => e.status().map(|status| status.as_u16()),
+            _ => None,
+        }
+    }
+
+    /// 4xx response
+    pub fn is_client_error(&self) -> bool {
+        self.http_status().is_some_and(|status| (400..500).contains(&status))
+    }
+
+    /// 5xx response
+    pub fn is_server_error(&self) -> bool {
+        self.http_status().is_some_and(|status| (500..600).contains(&status))
+    }
+
+    /// Host the failed request went to, when the error carries its URL or domain
+    pub fn domain(&self) -> Option<&str> {
+        match self {
+            NetworkError::RateLimited { domain, .. } => Some(domain.as_str()).filter(|d| !d.is_empty()),
+            NetworkError::Timeout(text)
+            | NetworkError::Connection(text)
+            | NetworkError::Http { message: text, .. }
+            | NetworkError::InvalidUrl(text)
+            | NetworkError::RobotsDisallowed(text)
+            | NetworkError::DnsError(text)
+            | NetworkError::TlsError(text)
+            | NetworkError::RedirectLoop(text)
+            | NetworkError::Screenshot(text)
+            | NetworkError::JsRendering(text) => host_in(text),
+            NetworkError::Request(e) => e.url().and_then(|url| url.host_str()),
+            _ => None,
+        }
+    }
+
+    /// Short name of the error variant, used as a label in logs and statistics
+    pub fn kind(&self) -> &'static str {
+        match self {
+            NetworkError::Timeout(_) => "timeout",
+            NetworkError::Connection(_) => "connection",
+            NetworkError::Http { .. } => "http",
+            NetworkError::InvalidUrl(_) => "invalid_url",
+            NetworkError::Encoding(_) => "encoding",
+            NetworkError::ContentTooLarge { .. } => "content_too_large",
+            NetworkError::UnsupportedContentType(_) => "unsupported_content_type",
+            NetworkError::RobotsDisallowed(_) => "robots_disallowed",
+            NetworkError::RateLimited { .. } => "rate_limited",
+            NetworkError::DnsError(_) => "dns",
+            NetworkError::TlsError(_) => "tls",
+            NetworkError::RedirectLoop(_) => "redirect_loop",
+            NetworkError::TooManyRedirects { .. } => "too_many_redirects",
+            NetworkError::Screenshot(_) => "screenshot",
+            NetworkError::JsRendering(_) => "js_rendering",
+            NetworkError::Request(_) => "request",
+            NetworkError::Io(_) => "io",
+        }
+    }
+}
+
+// Host of the first absolute URL in an error message
+fn host_in(text: &str) -> Option<&str> {
+    let (_, rest) = text.split_once("://")?;
+    let authority = rest
+        .split(|c: char| matches!(c, '/' | '?' | '#') || c.is_whitespace())
+        .next()?;
+    let host = authority.rsplit('@').next()?;
+    let host = match host.strip_prefix('[') {
+        Some(ipv6) => ipv6.split(']').next()?,
+        None => host.split(':').next()?,
+    };
+    Some(host).filter(|host| !host.is_empty())
 }
 
 /// Convert reqwest errors to NetworkError with context
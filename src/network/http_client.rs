@@ -1,12 +1,29 @@
 //! HTTP client with user agent rotation and robust error handling
 
-use crate::network::{NetworkError, HttpResponse, classify_reqwest_error, ResponseProcessor};
+use crate::network::{CachedHeaders, DnsResolver, HttpTraceLayer, NetworkError, HttpResponse, classify_reqwest_error, ResponseProcessor};
+use cookie_store::{Cookie, CookieStore, RawCookie};
+use reqwest::header::{HeaderName, HeaderValue};
 use reqwest::{Client, ClientBuilder, redirect::Policy};
-use std::sync::Arc;
-use std::sync::atomic::{AtomicUsize, Ordering}; // Fixed: removed duplicate and typo
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::sync::{Arc, RwLock};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering}; // Fixed: removed duplicate and typo
 use std::time::{Duration, Instant};
-use tracing::{debug, info}; // Fixed: removed duplicate debug import
+use tokio::sync::{mpsc, Semaphore};
+use tower::{Layer, ServiceExt};
+use tracing::{debug, warn}; // Fixed: removed duplicate debug import
 
+/// Outcome of a conditional GET
+#[derive(Debug, Clone)]
+#[allow(clippy::large_enum_variant)]
+pub enum ConditionalResponse {
+    /// 304, the copy from the previous crawl is still current
+    NotModified,
+    Modified(HttpResponse),
+}
+
+/// Cheap to clone, clones share the connection pool and cookie jar
+#[derive(Clone)]
 pub struct HttpClient {
     client: Client,
     response_processor: ResponseProcessor,
@@ -14,15 +31,74 @@ pub struct HttpClient {
     current_ua_index: Arc<AtomicUsize>,
     default_timeout: Duration,
     max_redirects: u32,
+    cookie_jar: Arc<CookieJar>,
+    // DNS overrides and cache, shared with the reqwest client
+    dns: Arc<DnsResolver>,
+    // request/response tracing around every request
+    trace: HttpTraceLayer,
+    // word count below which fetched HTML is rendered again in a browser, None when disabled
+    #[cfg(feature = "js-rendering")]
+    js_fallback_words: Option<usize>,
+    // launched for the first page that needs rendering, shared by clones
+    #[cfg(feature = "js-rendering")]
+    browser: Arc<std::sync::Mutex<Option<headless_chrome::Browser>>>,
+}
+
+/// Cookie jar shared with the underlying reqwest client.
+/// Disabled by default so stateless crawls don't carry sessions between sites.
+struct CookieJar {
+    enabled: AtomicBool,
+    store: RwLock<CookieStore>,
+}
+
+impl reqwest::cookie::CookieStore for CookieJar {
+    fn set_cookies(&self, cookie_headers: &mut dyn Iterator<Item = &HeaderValue>, url: &url::Url) {
+        if !self.enabled.load(Ordering::Relaxed) {
+            return;
+        }
+
+        let cookies = cookie_headers
+            .filter_map(|value| value.to_str().ok())
+            .filter_map(|value| RawCookie::parse(value.to_string()).ok());
+
+        self.store.write().unwrap().store_response_cookies(cookies, url);
+    }
+
+    fn cookies(&self, url: &url::Url) -> Option<HeaderValue> {
+        if !self.enabled.load(Ordering::Relaxed) {
+            return None;
+        }
+
+        let header = self.store.read().unwrap()
+            .get_request_values(url)
+            .map(|(name, value)| format!("{}={}", name, value))
+            .collect::<Vec<_>>()
+            .join("; ");
+
+        if header.is_empty() {
+            None
+        } else {
+            HeaderValue::from_str(&header).ok()
+        }
+    }
 }
 
 impl HttpClient {
     pub fn new() -> Result<Self, NetworkError> {
+        let cookie_jar = Arc::new(CookieJar {
+            enabled: AtomicBool::new(false),
+            store: RwLock::new(CookieStore::default()),
+        });
+
+        let dns = Arc::new(DnsResolver::new());
+
         let client = ClientBuilder::new()
             .timeout(Duration::from_secs(30)) // Fixed: 3 -> 30 seconds for more reasonable timeout
             .redirect(Policy::limited(10))
             .gzip(true)
             .brotli(true)
+            .cookie_provider(cookie_jar.clone())
+            .dns_resolver(dns.clone())
             .build()
             .map_err(|e| NetworkError::Request(e))?;
 
@@ -39,6 +115,13 @@ impl HttpClient {
             current_ua_index: Arc::new(AtomicUsize::new(0)),
             default_timeout: Duration::from_secs(30),
             max_redirects: 10,
+            cookie_jar,
+            dns,
+            trace: HttpTraceLayer::from_env(),
+            #[cfg(feature = "js-rendering")]
+            js_fallback_words: None,
+            #[cfg(feature = "js-rendering")]
+            browser: Arc::new(std::sync::Mutex::new(None)),
         })
     }
 
@@ -59,18 +142,225 @@ impl HttpClient {
         self
     }
 
+    /// Content types accepted by `fetch`, other responses fail with `UnsupportedContentType`
+    pub fn with_allowed_content_types(mut self, types: Vec<String>) -> Self {
+        self.response_processor = self.response_processor.with_allowed_content_types(types);
+        self
+    }
+
+    /// Enable or disable the persistent cookie jar for session-aware crawling
+    pub fn with_cookie_store(self, enabled: bool) -> Self {
+        self.cookie_jar.enabled.store(enabled, Ordering::Relaxed);
+        self
+    }
+
+    /// Connect to the given IP address instead of looking the host up, e.g. to crawl a local
+    /// copy of a site without editing `/etc/hosts`. Replaces earlier overrides.
+    pub fn with_dns_overrides(self, overrides: HashMap<String, IpAddr>) -> Self {
+        self.dns.set_overrides(overrides);
+        self
+    }
+
+    /// Cache looked up addresses for `ttl`, `Duration::ZERO` (the default) looks hosts up for every connection
+    pub fn with_dns_cache_ttl(self, ttl: Duration) -> Self {
+        self.dns.set_cache_ttl(ttl);
+        self
+    }
+
+    /// Trace every request in an `http_request` span, see `HttpTraceLayer`. Also turned on,
+    /// with info level logging, by `CRAWLER_LOG_HTTP=1`.
+    pub fn with_trace_layer(mut self, enabled: bool) -> Self {
+        self.trace = self.trace.enabled(enabled);
+        self
+    }
+
+    /// Send a fresh UUID in `header_name` with every request for log correlation.
+    /// An invalid header name is ignored with a warning.
+    pub fn with_request_id_header(mut self, header_name: &str) -> Self {
+        match HeaderName::from_bytes(header_name.as_bytes()) {
+            Ok(header) => self.trace = self.trace.with_request_id_header(header),
+            Err(_) => warn!("Ignoring invalid request id header name: {:?}", header_name),
+        }
+        self
+    }
+
+    /// Render HTML pages with fewer than `threshold_words` words of body text in a headless
+    /// browser, see `HttpResponse::needs_js_rendering`. Pages that fail to render keep the fetched HTML.
+    #[cfg(feature = "js-rendering")]
+    pub fn with_js_fallback(mut self, enabled: bool, threshold_words: usize) -> Self {
+        self.js_fallback_words = enabled.then_some(threshold_words);
+        self
+    }
+
+    /// Load `url` in a headless Chrome/Chromium and return the DOM once the page has loaded.
+    /// Headers and the real status code are not available, the response reports 200.
+    #[cfg(feature = "js-rendering")]
+    pub async fn fetch_with_js(&self, url: &str) -> Result<HttpResponse, NetworkError> {
+        let start_time = Instant::now();
+        let browser = self.browser.clone();
+        let target = url.to_string();
+
+        // the browser API blocks while the page loads
+        let (final_url, content) = tokio::task::spawn_blocking(move || Self::render(&browser, &target))
+            .await
+            .map_err(|e| NetworkError::JsRendering(format!("{}: {}", url, e)))?
+            .map_err(|e| NetworkError::JsRendering(format!("{}: {}", url, e)))?;
+
+        tracing::info!("Rendered {} with JavaScript ({} bytes)", url, content.len());
+        Ok(HttpResponse {
+            url: url.to_string(),
+            final_url,
+            status_code: 200,
+            headers: reqwest::header::HeaderMap::new(),
+            content_length: Some(content.len()),
+            content,
+            content_type: "text/html".to_string(),
+            encoding: "utf-8".to_string(),
+            fetch_time_ms: start_time.elapsed().as_millis() as u64,
+            redirect_count: 0,
+            rendered_with_js: true,
+            raw_body: None,
+        })
+    }
+
+    #[cfg(feature = "js-rendering")]
+    fn render(
+        browser: &std::sync::Mutex<Option<headless_chrome::Browser>>,
+        url: &str,
+    ) -> Result<(String, String), Box<dyn std::error::Error + Send + Sync>> {
+        let tab = {
+            let mut browser = browser.lock().unwrap();
+            if browser.is_none() {
+                *browser = Some(headless_chrome::Browser::default()?);
+            }
+            browser.as_ref().expect("browser launched above").new_tab()?
+        };
+
+        tab.navigate_to(url)?.wait_until_navigated()?;
+        let content = tab.get_content()?;
+        let final_url = tab.get_url();
+        // a failed close only leaks the tab until the browser exits
+        let _ = tab.close(false);
+        Ok((final_url, content))
+    }
+
+    // Swap in the rendered DOM when the fetched HTML looks like an empty script shell
+    #[cfg(feature = "js-rendering")]
+    async fn render_sparse_page(&self, mut response: HttpResponse) -> HttpResponse {
+        let Some(threshold) = self.js_fallback_words else { return response };
+        if !response.needs_js_rendering(threshold) {
+            return response;
+        }
+
+        match self.fetch_with_js(&response.final_url).await {
+            Ok(rendered) => {
+                response.content_length = Some(rendered.content.len());
+                response.content = rendered.content;
+                response.fetch_time_ms += rendered.fetch_time_ms;
+                response.rendered_with_js = true;
+            }
+            Err(e) => tracing::warn!("Keeping unrendered HTML: {}", e),
+        }
+        response
+    }
+
+    /// Export all unexpired cookies so they can be persisted between runs
+    pub fn export_cookies(&self) -> Vec<Cookie<'static>> {
+        self.cookie_jar.store.read().unwrap()
+            .iter_unexpired()
+            .cloned()
+            .collect()
+    }
+
+    /// Import previously exported cookies into the jar
+    pub fn import_cookies(&self, cookies: &[Cookie<'static>]) {
+        let mut store = self.cookie_jar.store.write().unwrap();
+
+        let merged: Vec<Cookie<'static>> = store
+            .iter_any()
+            .cloned()
+            .chain(cookies.iter().cloned())
+            .collect();
+
+        *store = CookieStore::from_cookies(merged.into_iter().map(Ok::<_, ()>), false)
+            .unwrap_or_default();
+
+        debug!("Imported {} cookies", cookies.len());
+    }
+
     /// Fetch a URL with automatic retries and error handling
     pub async fn fetch(&self, url: &str) -> Result<HttpResponse, NetworkError> {
         self.fetch_with_options(url, None).await
     }
 
+    /// Fetch `urls` with at most `concurrency` requests in flight, results are sent in
+    /// completion order. A fetch only starts once the channel has room for its result,
+    /// so a slow receiver holds back new requests.
+    pub fn batch_fetch(
+        &self,
+        urls: &[String],
+        concurrency: usize,
+    ) -> mpsc::Receiver<(String, Result<HttpResponse, NetworkError>)> {
+        let concurrency = concurrency.max(1);
+        let (sender, receiver) = mpsc::channel(concurrency);
+        let client = Arc::new(self.clone());
+        let urls = urls.to_vec();
+
+        tokio::spawn(async move {
+            let semaphore = Arc::new(Semaphore::new(concurrency));
+
+            for url in urls {
+                // the receiver was dropped, nobody wants the remaining pages
+                let Ok(slot) = sender.clone().reserve_owned().await else {
+                    break;
+                };
+                let Ok(permit) = semaphore.clone().acquire_owned().await else {
+                    break;
+                };
+
+                let client = client.clone();
+                tokio::spawn(async move {
+                    let result = client.fetch(&url).await;
+                    drop(permit);
+                    slot.send((url, result));
+                });
+            }
+        });
+
+        receiver
+    }
+
     /// Fetch URL with custom timeout
     pub async fn fetch_with_timeout(&self, url: &str, timeout: Duration) -> Result<HttpResponse, NetworkError> {
         self.fetch_with_options(url, Some(timeout)).await
     }
 
+    /// Fetch a previously crawled URL, sending its ETag / Last-Modified so unchanged
+    /// pages come back as a bodiless 304
+    pub async fn fetch_conditional(&self, url: &str, headers: Option<CachedHeaders>) -> Result<ConditionalResponse, NetworkError> {
+        let start_time = Instant::now();
+        let response = self.send_request(url, None, headers.as_ref()).await?;
+
+        if response.status() == reqwest::StatusCode::NOT_MODIFIED {
+            debug!("Not modified since last crawl: {}", url);
+            return Ok(ConditionalResponse::NotModified);
+        }
+
+        self.process(url, response, start_time).await.map(ConditionalResponse::Modified)
+    }
+
     async fn fetch_with_options(&self, url: &str, timeout: Option<Duration>) -> Result<HttpResponse, NetworkError> {
         let start_time = Instant::now();
+        let response = self.send_request(url, timeout, None).await?;
+        self.process(url, response, start_time).await
+    }
+
+    async fn send_request(
+        &self,
+        url: &str,
+        timeout: Option<Duration>,
+        conditional: Option<&CachedHeaders>,
+    ) -> Result<reqwest::Response, NetworkError> {
         let user_agent = self.get_next_user_agent();
         let timeout = timeout.unwrap_or(self.default_timeout);
 
@@ -103,12 +393,32 @@ impl HttpClient {
         // Add cache control
         request_builder = request_builder.header("Cache-Control", "no-cache");
 
+        // Validators from the previous crawl
+        if let Some(cached) = conditional {
+            if let Some(etag) = &cached.etag {
+                request_builder = request_builder.header("If-None-Match", etag);
+            }
+            if let Some(since) = cached.if_modified_since() {
+                request_builder = request_builder.header("If-Modified-Since", since);
+            }
+        }
+
         // Send request
-        let response = request_builder
-            .send()
+        self.execute(request_builder, url).await
+    }
+
+    // Send a request through the trace layer
+    async fn execute(&self, request_builder: reqwest::RequestBuilder, url: &str) -> Result<reqwest::Response, NetworkError> {
+        let request = request_builder.build().map_err(|e| classify_reqwest_error(e, url))?;
+
+        self.trace
+            .layer(self.client.clone())
+            .oneshot(request)
             .await
-            .map_err(|e| classify_reqwest_error(e, url))?;
+            .map_err(|e| classify_reqwest_error(e, url))
+    }
 
+    async fn process(&self, url: &str, response: reqwest::Response, start_time: Instant) -> Result<HttpResponse, NetworkError> {
         // Count redirects
         let redirect_count = self.count_redirects(&response);
         if redirect_count > self.max_redirects {
@@ -123,13 +433,16 @@ impl HttpClient {
             .process_response(response, start_time, redirect_count)
             .await?;
 
-        info!(
+        debug!(
             "Successfully fetched {} ({} bytes, {} ms)",
             url,
             http_response.content_length.unwrap_or(0),
             http_response.fetch_time_ms
         );
 
+        #[cfg(feature = "js-rendering")]
+        let http_response = self.render_sparse_page(http_response).await;
+
         Ok(http_response)
     }
 
@@ -148,13 +461,11 @@ impl HttpClient {
     pub async fn test_url(&self, url: &str) -> Result<u16, NetworkError> {
         let user_agent = self.get_next_user_agent();
 
-        let response = self.client
+        let request_builder = self.client
             .head(url)
             .header("User-Agent", &user_agent)
-            .timeout(Duration::from_secs(10))
-            .send()
-            .await
-            .map_err(|e| classify_reqwest_error(e, url))?;
+            .timeout(Duration::from_secs(10));
+        let response = self.execute(request_builder, url).await?;
 
         Ok(response.status().as_u16())
     }
@@ -166,6 +477,8 @@ impl HttpClient {
             total_user_agents: self.user_agents.len(),
             default_timeout_secs: self.default_timeout.as_secs(),
             max_redirects: self.max_redirects,
+            cookies_enabled: self.cookie_jar.enabled.load(Ordering::Relaxed),
+            bytes_discarded: self.response_processor.bytes_discarded(),
         }
     }
 }
@@ -176,6 +489,9 @@ pub struct HttpClientStats {
     pub total_user_agents: usize,
     pub default_timeout_secs: u64,
     pub max_redirects: u32,
+    pub cookies_enabled: bool,
+    /// Bytes downloaded and dropped by responses over the max content size
+    pub bytes_discarded: u64,
 }
 
 impl Default for HttpClient {
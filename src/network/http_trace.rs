@@ -0,0 +1,149 @@
+//! Structured request/response tracing for `HttpClient`, as a tower layer around the reqwest client
+
+use futures_util::future::BoxFuture;
+use reqwest::header::{HeaderName, HeaderValue, CONTENT_LENGTH, CONTENT_TYPE, USER_AGENT};
+use reqwest::{Request, Response};
+use std::task::{Context, Poll};
+use std::time::Instant;
+use tower::{Layer, Service};
+use tracing::{debug, field, info, info_span, warn, Instrument};
+use uuid::Uuid;
+
+/// Set to `1` to trace every request and log it at info level instead of debug
+pub const LOG_HTTP_ENV: &str = "CRAWLER_LOG_HTTP";
+
+/// Wraps each request in an `http_request` span recording method, URL, user agent, status,
+/// content length, content type and fetch time. Optionally tags requests with a UUID header
+/// so the crawler's logs can be matched with the server's.
+#[derive(Debug, Clone, Default)]
+pub struct HttpTraceLayer {
+    enabled: bool,
+    // log at info level, set by CRAWLER_LOG_HTTP=1
+    verbose: bool,
+    request_id_header: Option<HeaderName>,
+}
+
+impl HttpTraceLayer {
+    /// Enabled and verbose when `CRAWLER_LOG_HTTP=1`, otherwise disabled
+    pub fn from_env() -> Self {
+        Self::from_env_value(std::env::var(LOG_HTTP_ENV).ok().as_deref())
+    }
+
+    pub(crate) fn from_env_value(value: Option<&str>) -> Self {
+        let verbose = value.is_some_and(|value| value.trim() == "1");
+        Self { enabled: verbose, verbose, request_id_header: None }
+    }
+
+    pub fn enabled(mut self, enabled: bool) -> Self {
+        self.enabled = enabled;
+        self
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub fn is_verbose(&self) -> bool {
+        self.verbose
+    }
+
+    /// Send a fresh UUID in `header` with every request, even when tracing is disabled
+    pub fn with_request_id_header(mut self, header: HeaderName) -> Self {
+        self.request_id_header = Some(header);
+        self
+    }
+
+    pub fn request_id_header(&self) -> Option<&HeaderName> {
+        self.request_id_header.as_ref()
+    }
+}
+
+impl<S> Layer<S> for HttpTraceLayer {
+    type Service = HttpTrace<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        HttpTrace { inner, layer: self.clone() }
+    }
+}
+
+/// Service built by `HttpTraceLayer`
+#[derive(Debug, Clone)]
+pub struct HttpTrace<S> {
+    inner: S,
+    layer: HttpTraceLayer,
+}
+
+impl<S> Service<Request> for HttpTrace<S>
+where
+    S: Service<Request, Response = Response>,
+    S::Future: Send + 'static,
+    S::Error: std::fmt::Display,
+{
+    type Response = Response;
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<Response, S::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut request: Request) -> Self::Future {
+        let request_id = self.layer.request_id_header.as_ref().map(|header| {
+            let id = Uuid::new_v4().to_string();
+            request.headers_mut().insert(header.clone(), HeaderValue::from_str(&id).expect("a UUID is a valid header value"));
+            id
+        });
+
+        if !self.layer.enabled {
+            return Box::pin(self.inner.call(request));
+        }
+
+        let span = info_span!(
+            "http_request",
+            method = %request.method(),
+            url = %request.url(),
+            user_agent = request.headers().get(USER_AGENT).and_then(|ua| ua.to_str().ok()).unwrap_or(""),
+            request_id = request_id.as_deref().unwrap_or(""),
+            status = field::Empty,
+            content_length = field::Empty,
+            content_type = field::Empty,
+            fetch_time_ms = field::Empty,
+        );
+        let verbose = self.layer.verbose;
+        let response = self.inner.call(request);
+
+        Box::pin(
+            async move {
+                let start = Instant::now();
+                let result = response.await;
+                let fetch_time_ms = start.elapsed().as_millis() as u64;
+
+                let span = tracing::Span::current();
+                span.record("fetch_time_ms", fetch_time_ms);
+                match &result {
+                    Ok(response) => {
+                        let header = |name| response.headers().get(name).and_then(|value: &HeaderValue| value.to_str().ok());
+                        let content_length = header(CONTENT_LENGTH).and_then(|length| length.parse::<u64>().ok());
+                        let content_type = header(CONTENT_TYPE).unwrap_or("");
+
+                        span.record("status", response.status().as_u16());
+                        span.record("content_type", content_type);
+                        if let Some(content_length) = content_length {
+                            span.record("content_length", content_length);
+                        }
+
+                        if verbose {
+                            info!(status = response.status().as_u16(), ?content_length, content_type, fetch_time_ms, "HTTP response");
+                        } else {
+                            debug!(status = response.status().as_u16(), ?content_length, content_type, fetch_time_ms, "HTTP response");
+                        }
+                    }
+                    Err(e) => warn!(fetch_time_ms, "HTTP request failed: {}", e),
+                }
+                result
+            }
+            .instrument(span),
+        )
+    }
+}
+
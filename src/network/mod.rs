@@ -1,13 +1,24 @@
 //! Network module for HTTP requests and response handling
 
 pub mod http_client;
+pub mod http_trace;
+pub mod dns_resolver;
 pub mod response_handler;
 pub mod error_handler;
+pub mod screenshot;
+pub mod robots_txt;
 
 // Re-export the main types
-pub use http_client::{HttpClient, HttpClientStats};
-pub use response_handler::{HttpResponse, ResponseProcessor};
+pub use http_client::{ConditionalResponse, HttpClient, HttpClientStats};
+pub use http_trace::{HttpTraceLayer, LOG_HTTP_ENV};
+pub use dns_resolver::DnsResolver;
+pub use response_handler::{default_allowed_content_types, CachedHeaders, CrawlHints, HttpResponse, ResponseProcessor};
 pub use error_handler::{NetworkError, classify_reqwest_error};
+pub use screenshot::{ScreenshotCapture, screenshot_file_name};
+pub use robots_txt::{parse_crawl_delay, RobotsTxtRules};
+#[cfg(feature = "screenshots")]
+pub use screenshot::HeadlessBrowserCapture;
+pub use cookie_store::Cookie;
 
 // Tests module
 #[cfg(test)]
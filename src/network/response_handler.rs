@@ -1,8 +1,13 @@
 //! HTTP response processing and validation
 
 use crate::network::NetworkError;
+use chrono::{DateTime, Utc};
 use encoding_rs::{Encoding, UTF_8};
+use futures_util::StreamExt;
 use reqwest::{header::HeaderMap, Response};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::time::Instant;
 
 #[derive(Debug, Clone)]
@@ -17,22 +22,128 @@ pub struct HttpResponse {
     pub encoding: String,
     pub fetch_time_ms: u64,
     pub redirect_count: u32, // Fixed: f32 -> u32
+    /// `content` is the DOM after running the page's JavaScript in a headless browser
+    pub rendered_with_js: bool,
+    /// Undecoded body of binary responses such as PDFs, None for text
+    pub raw_body: Option<Vec<u8>>,
 }
 
+impl HttpResponse {
+    /// ETag and Last-Modified validators to send on the next fetch of this URL
+    pub fn cached_headers(&self) -> CachedHeaders {
+        CachedHeaders::from_headers(&self.headers)
+    }
+
+    /// HTML with a `<body>` holding fewer than `threshold_words` words of visible text,
+    /// typically a shell that scripts fill in after loading
+    pub fn needs_js_rendering(&self, threshold_words: usize) -> bool {
+        if !self.content_type.contains("html") || !self.content.to_ascii_lowercase().contains("<body") {
+            return false;
+        }
+
+        let document = scraper::Html::parse_document(&self.content);
+        let body = scraper::Selector::parse("body").expect("valid selector");
+        let Some(body) = document.select(&body).next() else {
+            return false;
+        };
+
+        let words: usize = body.descendants()
+            .filter_map(|node| {
+                let text = node.value().as_text()?;
+                let hidden = node.ancestors().any(|ancestor| {
+                    ancestor.value().as_element()
+                        .is_some_and(|e| matches!(e.name(), "script" | "style" | "noscript" | "template"))
+                });
+                (!hidden).then(|| text.split_whitespace().count())
+            })
+            .sum();
+
+        words < threshold_words
+    }
+}
+
+/// Validators from a previous fetch, sent back as If-None-Match / If-Modified-Since
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CachedHeaders {
+    pub etag: Option<String>,
+    pub last_modified: Option<DateTime<Utc>>,
+}
+
+impl CachedHeaders {
+    pub fn from_headers(headers: &HeaderMap) -> Self {
+        let header = |name: &str| {
+            headers
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty())
+        };
+
+        Self {
+            etag: header("etag"),
+            last_modified: header("last-modified")
+                .and_then(|s| DateTime::parse_from_rfc2822(&s).ok())
+                .map(|dt| dt.with_timezone(&Utc)),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.etag.is_none() && self.last_modified.is_none()
+    }
+
+    /// Last-Modified in HTTP-date format for If-Modified-Since
+    pub fn if_modified_since(&self) -> Option<String> {
+        self.last_modified
+            .map(|dt| dt.format("%a, %d %b %Y %H:%M:%S GMT").to_string())
+    }
+}
+
+/// Pacing a server asks for in its response headers
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CrawlHints {
+    /// `Retry-After`, given as seconds or as an HTTP date
+    pub retry_after_secs: Option<u64>,
+    /// `X-Crawl-Delay` in seconds, fractions rounded up
+    pub crawl_delay_secs: Option<u64>,
+    /// `max-age` directive of `Cache-Control`
+    pub cache_control_max_age: Option<u64>,
+}
+
+/// Content types fetched unless configured otherwise: HTML, plain text, XML and RSS/Atom feeds
+pub fn default_allowed_content_types() -> Vec<String> {
+    [
+        "text/html",
+        "application/xhtml+xml",
+        "text/plain",
+        "text/xml",
+        "application/xml",
+        "application/rss+xml",
+        "application/atom+xml",
+    ]
+        .iter()
+        .map(|s| s.to_string())
+        .collect()
+}
+
+// Bodies that don't survive decoding as text
+fn is_binary_content_type(content_type: &str) -> bool {
+    content_type == "application/pdf"
+}
+
+#[derive(Clone)]
 pub struct ResponseProcessor {
     max_content_size: usize,
     allowed_content_types: Vec<String>,
+    // bytes downloaded before a body was rejected as too large, shared by clones
+    bytes_discarded: Arc<AtomicU64>,
 }
 
 impl ResponseProcessor {
     pub fn new() -> Self {
         Self {
             max_content_size: 10 * 1024 * 1024,
-            allowed_content_types: vec![
-                "text/html".to_string(),
-                "application/xhtml+xml".to_string(),
-                "text/plain".to_string(),
-            ],
+            allowed_content_types: default_allowed_content_types(),
+            bytes_discarded: Arc::new(AtomicU64::new(0)),
         }
     }
 
@@ -46,6 +157,42 @@ impl ResponseProcessor {
         self
     }
 
+    /// Bytes downloaded and thrown away because their response outgrew the size limit
+    pub fn bytes_discarded(&self) -> u64 {
+        self.bytes_discarded.load(Ordering::Relaxed)
+    }
+
+    /// Retry-After, X-Crawl-Delay and Cache-Control max-age of a response, unparseable values are ignored
+    pub fn extract_crawl_hints(headers: &HeaderMap) -> CrawlHints {
+        let header = |name: &str| headers.get(name).and_then(|v| v.to_str().ok()).map(str::trim);
+
+        let retry_after_secs = header("retry-after").and_then(|value| {
+            value.parse().ok().or_else(|| {
+                // a date in the past means now
+                let date = DateTime::parse_from_rfc2822(value).ok()?;
+                Some((date.with_timezone(&Utc) - Utc::now()).num_seconds().max(0) as u64)
+            })
+        });
+
+        let crawl_delay_secs = header("x-crawl-delay")
+            .and_then(|value| value.parse::<f64>().ok())
+            .filter(|secs| secs.is_finite() && *secs >= 0.0)
+            .map(|secs| secs.ceil() as u64);
+
+        let cache_control_max_age = header("cache-control").and_then(|value| {
+            value.split(',').find_map(|directive| {
+                let (name, secs) = directive.split_once('=')?;
+                if name.trim().eq_ignore_ascii_case("max-age") {
+                    secs.trim().trim_matches('"').parse().ok()
+                } else {
+                    None
+                }
+            })
+        });
+
+        CrawlHints { retry_after_secs, crawl_delay_secs, cache_control_max_age }
+    }
+
     /// Process reqwest Response into our HttpResponse
     pub async fn process_response(
         &self,
@@ -58,6 +205,13 @@ impl ResponseProcessor {
         let headers = response.headers().clone();
 
         // Validate status code
+        if status_code == 429 {
+            let domain = response.url().host_str().unwrap_or_default().to_string();
+            return Err(NetworkError::RateLimited {
+                domain,
+                retry_after_secs: Self::extract_crawl_hints(&headers).retry_after_secs,
+            });
+        }
         if !response.status().is_success() {
             return Err(NetworkError::Http {
                 status: status_code,
@@ -73,20 +227,11 @@ impl ResponseProcessor {
             return Err(NetworkError::UnsupportedContentType(content_type));
         }
 
-        // Get response bytes
-        let bytes = response.bytes().await
-            .map_err(|e| NetworkError::Request(e))?;
-
-        // Check actual size
-        if bytes.len() > self.max_content_size {
-            return Err(NetworkError::ContentTooLarge {
-                size: bytes.len(),
-                limit: self.max_content_size,
-            });
-        }
+        let bytes = self.read_body(response).await?;
 
         // Detect and convert encoding
         let (content, encoding) = self.decode_content(&bytes, &content_type)?; // Fixed: Added ?
+        let raw_body = is_binary_content_type(&content_type).then(|| bytes.clone());
 
         let fetch_time_ms = start_time.elapsed().as_millis() as u64;
 
@@ -101,9 +246,34 @@ impl ResponseProcessor {
             encoding,
             fetch_time_ms,
             redirect_count,
+            rendered_with_js: false,
+            raw_body,
         })
     }
 
+    /// Download the body chunk by chunk, giving up as soon as it outgrows `max_content_size`.
+    /// `ContentTooLarge::size` is then the number of bytes received so far, not the full size.
+    async fn read_body(&self, response: Response) -> Result<Vec<u8>, NetworkError> {
+        let expected = response.content_length().unwrap_or(0) as usize;
+        let mut body = Vec::with_capacity(expected.min(self.max_content_size));
+
+        let mut stream = response.bytes_stream();
+        while let Some(chunk) = stream.next().await {
+            let chunk = chunk.map_err(NetworkError::Request)?;
+            let size = body.len() + chunk.len();
+            if size > self.max_content_size {
+                self.bytes_discarded.fetch_add(size as u64, Ordering::Relaxed);
+                return Err(NetworkError::ContentTooLarge {
+                    size,
+                    limit: self.max_content_size,
+                });
+            }
+            body.extend_from_slice(&chunk);
+        }
+
+        Ok(body)
+    }
+
     fn extract_content_type(&self, headers: &HeaderMap) -> String {
         headers
             .get("content-type")
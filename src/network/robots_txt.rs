@@ -0,0 +1,153 @@
+//! robots.txt directives the crawler honours
+
+use std::time::Duration;
+
+/// `Crawl-delay` of the group addressed to every user agent (`User-agent: *`), None if it has none.
+/// Fractional seconds are allowed, invalid and negative values are ignored.
+pub fn parse_crawl_delay(robots_txt: &str) -> Option<Duration> {
+    let mut agents: Vec<String> = Vec::new();
+    let mut previous_was_agent = false;
+
+    for line in robots_txt.lines() {
+        let line = line.split('#').next().unwrap_or_default().trim();
+        let Some((key, value)) = line.split_once(':') else { continue };
+        let value = value.trim();
+
+        match key.trim().to_ascii_lowercase().as_str() {
+            "user-agent" => {
+                // consecutive User-agent lines share one group
+                if !previous_was_agent {
+                    agents.clear();
+                }
+                agents.push(value.to_string());
+                previous_was_agent = true;
+            }
+            "crawl-delay" => {
+                previous_was_agent = false;
+                if agents.iter().any(|agent| agent == "*")
+                    && let Ok(seconds) = value.parse::<f64>()
+                    && seconds.is_finite()
+                    && seconds >= 0.0
+                {
+                    return Some(Duration::from_secs_f64(seconds));
+                }
+            }
+            _ => previous_was_agent = false,
+        }
+    }
+
+    None
+}
+
+/// `Allow` and `Disallow` rules of a robots.txt, grouped by the user agents they address
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RobotsTxtRules {
+    groups: Vec<RuleGroup>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct RuleGroup {
+    // lowercased User-agent values
+    agents: Vec<String>,
+    // (allow, path pattern)
+    rules: Vec<(bool, String)>,
+}
+
+impl RobotsTxtRules {
+    /// Lenient parse, lines that aren't directives are skipped. An empty or missing
+    /// robots.txt allows everything.
+    pub fn parse(robots_txt: &str) -> Self {
+        let mut groups: Vec<RuleGroup> = Vec::new();
+        let mut previous_was_agent = false;
+
+        for line in robots_txt.lines() {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            let Some((key, value)) = line.split_once(':') else { continue };
+            let value = value.trim();
+
+            match key.trim().to_ascii_lowercase().as_str() {
+                "user-agent" => {
+                    // consecutive User-agent lines share one group
+                    if !previous_was_agent || groups.is_empty() {
+                        groups.push(RuleGroup::default());
+                    }
+                    if let Some(group) = groups.last_mut() {
+                        group.agents.push(value.to_ascii_lowercase());
+                    }
+                    previous_was_agent = true;
+                }
+                directive @ ("allow" | "disallow") => {
+                    previous_was_agent = false;
+                    // an empty Disallow allows everything, rules before any User-agent are ignored
+                    if let Some(group) = groups.last_mut().filter(|_| !value.is_empty()) {
+                        group.rules.push((directive == "allow", value.to_string()));
+                    }
+                }
+                _ => previous_was_agent = false,
+            }
+        }
+
+        Self { groups }
+    }
+
+    /// Whether `user_agent` may fetch `path` (with its query string). The group naming the longest
+    /// part of the user agent applies, `*` otherwise. Within it the longest matching pattern
+    /// decides, `Allow` winning ties. Patterns may use `*` and a trailing `$`.
+    pub fn is_allowed(&self, user_agent: &str, path: &str) -> bool {
+        let user_agent = user_agent.to_ascii_lowercase();
+        let group = self.groups.iter()
+            .filter_map(|group| {
+                group.agents.iter()
+                    .filter(|agent| agent.as_str() != "*" && user_agent.contains(agent.as_str()))
+                    .map(String::len)
+                    .max()
+                    .map(|len| (len, group))
+            })
+            .max_by_key(|(len, _)| *len)
+            .map(|(_, group)| group)
+            .or_else(|| self.groups.iter().find(|group| group.agents.iter().any(|agent| agent == "*")));
+
+        let Some(group) = group else { return true };
+        group.rules.iter()
+            .filter(|(_, pattern)| Self::matches(pattern, path))
+            .max_by_key(|(allow, pattern)| (pattern.len(), *allow))
+            .is_none_or(|(allow, _)| *allow)
+    }
+
+    /// Rough heap size of the parsed rules, for `WebCrawler::get_memory_usage`
+    pub(crate) fn estimated_bytes(&self) -> usize {
+        let string_bytes = |s: &String| std::mem::size_of::<String>() + s.capacity();
+        self.groups.iter()
+            .map(|group| {
+                std::mem::size_of::<RuleGroup>()
+                    + group.agents.iter().map(string_bytes).sum::<usize>()
+                    + group.rules.iter().map(|(_, pattern)| 1 + string_bytes(pattern)).sum::<usize>()
+            })
+            .sum()
+    }
+
+    // `*` matches any run of characters, a trailing `$` anchors the pattern at the end of the path
+    fn matches(pattern: &str, path: &str) -> bool {
+        let (pattern, anchored) = match pattern.strip_suffix('$') {
+            Some(pattern) => (pattern, true),
+            None => (pattern, false),
+        };
+
+        let mut parts = pattern.split('*');
+        let first = parts.next().unwrap_or_default();
+        let Some(mut rest) = path.strip_prefix(first) else { return false };
+
+        let parts: Vec<&str> = parts.collect();
+        for (i, part) in parts.iter().enumerate() {
+            let last = i + 1 == parts.len();
+            if last && anchored {
+                return rest.ends_with(part);
+            }
+            match rest.find(part) {
+                Some(position) => rest = &rest[position + part.len()..],
+                None => return false,
+            }
+        }
+        !anchored || rest.is_empty()
+    }
+}
@@ -0,0 +1,65 @@
+//! Page screenshots, stored next to the crawled data as visual quality signals
+
+use async_trait::async_trait;
+use std::path::{Path, PathBuf};
+
+use crate::network::NetworkError;
+use crate::storage::repository::PageRepository;
+
+/// Renders a page and saves an image of it
+#[async_trait]
+pub trait ScreenshotCapture: Send + Sync {
+    /// Save a screenshot of `url` as `output_dir/<file name>` and return that path
+    async fn capture(&self, url: &str, output_dir: &Path) -> Result<PathBuf, NetworkError>;
+}
+
+/// `<url hash>.png`, the file a screenshot of `url` is stored under
+pub fn screenshot_file_name(url: &str) -> String {
+    format!("{}.png", PageRepository::calculate_url_hash(url))
+}
+
+/// Captures with a local Chrome or Chromium started once and reused for every page
+#[cfg(feature = "screenshots")]
+pub struct HeadlessBrowserCapture {
+    browser: headless_chrome::Browser,
+}
+
+#[cfg(feature = "screenshots")]
+impl HeadlessBrowserCapture {
+    /// Launch the browser, fails if no Chrome/Chromium binary is found
+    pub fn new() -> Result<Self, NetworkError> {
+        let browser = headless_chrome::Browser::default()
+            .map_err(|e| NetworkError::Screenshot(format!("failed to launch browser: {}", e)))?;
+        Ok(Self { browser })
+    }
+
+    fn capture_png(browser: &headless_chrome::Browser, url: &str) -> Result<Vec<u8>, Box<dyn std::error::Error + Send + Sync>> {
+        use headless_chrome::protocol::cdp::Page::CaptureScreenshotFormatOption;
+
+        let tab = browser.new_tab()?;
+        tab.navigate_to(url)?.wait_until_navigated()?;
+        let png = tab.capture_screenshot(CaptureScreenshotFormatOption::Png, None, None, true)?;
+        // a failed close only leaks the tab until the browser exits
+        let _ = tab.close(false);
+        Ok(png)
+    }
+}
+
+#[cfg(feature = "screenshots")]
+#[async_trait]
+impl ScreenshotCapture for HeadlessBrowserCapture {
+    async fn capture(&self, url: &str, output_dir: &Path) -> Result<PathBuf, NetworkError> {
+        // the browser API blocks while the page loads
+        let browser = self.browser.clone();
+        let target = url.to_string();
+        let png = tokio::task::spawn_blocking(move || Self::capture_png(&browser, &target))
+            .await
+            .map_err(|e| NetworkError::Screenshot(format!("{}: {}", url, e)))?
+            .map_err(|e| NetworkError::Screenshot(format!("{}: {}", url, e)))?;
+
+        tokio::fs::create_dir_all(output_dir).await?;
+        let path = output_dir.join(screenshot_file_name(url));
+        tokio::fs::write(&path, png).await?;
+        Ok(path)
+    }
+}
@@ -44,3 +44,35 @@ fn test_http_error_classification() {
     assert!(rate_limit_error.is_retryable());
     assert_eq!(rate_limit_error.retry_delay_ms(), 5000);
 }
+
+#[test]
+fn test_error_status_and_domain() {
+    let not_found = NetworkError::Http {
+        status: 404,
+        message: "404 Not Found: https://user@Example.com:8443/missing?q=1".to_string(),
+    };
+    assert_eq!(not_found.http_status(), Some(404));
+    assert!(not_found.is_client_error());
+    assert!(!not_found.is_server_error());
+    assert_eq!(not_found.domain(), Some("Example.com"));
+    assert_eq!(not_found.kind(), "http");
+
+    let unavailable = NetworkError::Http { status: 503, message: "Service Unavailable".to_string() };
+    assert!(unavailable.is_server_error());
+    assert!(!unavailable.is_client_error());
+    assert_eq!(unavailable.domain(), None);
+
+    let timeout = NetworkError::Timeout("https://[::1]:8080/page".to_string());
+    assert_eq!(timeout.http_status(), None);
+    assert!(!timeout.is_client_error() && !timeout.is_server_error());
+    assert_eq!(timeout.domain(), Some("::1"));
+
+    let connection = NetworkError::Connection("Failed to connect to https://news.example.org/".to_string());
+    assert_eq!(connection.domain(), Some("news.example.org"));
+    let rate_limited = NetworkError::RateLimited { domain: "example.com".to_string(), retry_after_secs: Some(120) };
+    assert_eq!(rate_limited.domain(), Some("example.com"));
+    assert_eq!(rate_limited.http_status(), Some(429));
+    assert!(rate_limited.is_retryable());
+    assert_eq!(rate_limited.retry_delay_ms(), 120_000);
+    assert_eq!(NetworkError::DnsError("DNS failed".to_string()).domain(), None);
+}
@@ -1,4 +1,4 @@
-use crate::network::{HttpClient, HttpClientStats};
+use crate::network::{CachedHeaders, ConditionalResponse, Cookie, HttpClient, HttpClientStats, HttpTraceLayer, NetworkError};
 use std::time::Duration;
 
 #[test]
@@ -31,3 +31,271 @@ fn test_http_client_configuration() {
     assert_eq!(stats.total_user_agents, 2);
     assert_eq!(stats.default_timeout_secs, 15);
 }
+
+#[test]
+fn test_http_client_cookie_round_trip() {
+    let client = HttpClient::new().unwrap();
+    assert!(!client.get_stats().cookies_enabled);
+
+    let client = client.with_cookie_store(true);
+    assert!(client.get_stats().cookies_enabled);
+
+    let url = url::Url::parse("https://example.com/").unwrap();
+    let cookie = Cookie::parse("session=abc123; Path=/", &url).unwrap().into_owned();
+    client.import_cookies(&[cookie]);
+
+    let exported = client.export_cookies();
+    assert_eq!(exported.len(), 1);
+    assert_eq!(exported[0].name(), "session");
+    assert_eq!(exported[0].value(), "abc123");
+}
+
+#[test]
+fn test_cached_headers_from_response_headers() {
+    let mut headers = reqwest::header::HeaderMap::new();
+    headers.insert("etag", "\"abc123\"".parse().unwrap());
+    headers.insert("last-modified", "Wed, 21 Oct 2015 07:28:00 GMT".parse().unwrap());
+
+    let cached = CachedHeaders::from_headers(&headers);
+    assert_eq!(cached.etag.as_deref(), Some("\"abc123\""));
+    assert_eq!(cached.if_modified_since().as_deref(), Some("Wed, 21 Oct 2015 07:28:00 GMT"));
+
+    assert!(CachedHeaders::from_headers(&reqwest::header::HeaderMap::new()).is_empty());
+}
+
+#[tokio::test]
+async fn test_fetch_conditional_not_modified() {
+    use axum::http::{HeaderMap, StatusCode, header};
+    use axum::response::IntoResponse;
+
+    // answers 304 only when the client replays the current ETag
+    async fn page(headers: HeaderMap) -> axum::response::Response {
+        if headers.get(header::IF_NONE_MATCH).is_some_and(|v| v == "\"v2\"") {
+            return StatusCode::NOT_MODIFIED.into_response();
+        }
+        (
+            [(header::ETAG, "\"v2\""), (header::CONTENT_TYPE, "text/html")],
+            "<html><body>fresh</body></html>",
+        ).into_response()
+    }
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}/page", listener.local_addr().unwrap());
+    tokio::spawn(async move {
+        axum::serve(listener, axum::Router::new().route("/page", axum::routing::get(page))).await.unwrap();
+    });
+
+    let client = HttpClient::new().unwrap();
+
+    let first = client.fetch_conditional(&url, None).await.unwrap();
+    let ConditionalResponse::Modified(response) = first else {
+        panic!("first fetch should return the page");
+    };
+    assert!(response.content.contains("fresh"));
+
+    let cached = response.cached_headers();
+    assert_eq!(cached.etag.as_deref(), Some("\"v2\""));
+    assert!(matches!(
+        client.fetch_conditional(&url, Some(cached)).await.unwrap(),
+        ConditionalResponse::NotModified
+    ));
+
+    let stale = CachedHeaders { etag: Some("\"v1\"".to_string()), last_modified: None };
+    assert!(matches!(
+        client.fetch_conditional(&url, Some(stale)).await.unwrap(),
+        ConditionalResponse::Modified(_)
+    ));
+}
+
+#[tokio::test]
+async fn test_batch_fetch_delivers_every_result() {
+    use axum::extract::{Path, State};
+    use axum::http::{StatusCode, header};
+    use axum::response::IntoResponse;
+    use std::collections::HashSet;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    async fn page(State(hits): State<Arc<AtomicUsize>>, Path(id): Path<u32>) -> axum::response::Response {
+        hits.fetch_add(1, Ordering::SeqCst);
+        if id == 0 {
+            return StatusCode::NOT_FOUND.into_response();
+        }
+        ([(header::CONTENT_TYPE, "text/html")], format!("<html><body>page {}</body></html>", id)).into_response()
+    }
+
+    let hits = Arc::new(AtomicUsize::new(0));
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let base = format!("http://{}", listener.local_addr().unwrap());
+    let app = axum::Router::new()
+        .route("/page/{id}", axum::routing::get(page))
+        .with_state(hits.clone());
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    let urls: Vec<String> = (0..10).map(|id| format!("{}/page/{}", base, id)).collect();
+    let client = HttpClient::new().unwrap();
+    let mut results = client.batch_fetch(&urls, 2);
+
+    // nothing is read yet, so only as many fetches as the channel holds get started
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    assert!(hits.load(Ordering::SeqCst) <= 2);
+
+    let mut received = HashSet::new();
+    while let Some((url, result)) = results.recv().await {
+        if url.ends_with("/page/0") {
+            assert!(result.is_err());
+        } else {
+            let response = result.unwrap();
+            assert!(response.content.contains(&url[url.rfind('/').unwrap() + 1..]));
+        }
+        assert!(received.insert(url));
+    }
+
+    assert_eq!(received, urls.into_iter().collect::<HashSet<_>>());
+    assert_eq!(hits.load(Ordering::SeqCst), 10);
+}
+
+#[tokio::test]
+async fn test_oversized_body_is_abandoned_mid_stream() {
+    use axum::body::Body;
+    use axum::http::header;
+
+    // a body far over the limit, sent in 1 KiB chunks without a Content-Length
+    async fn huge() -> axum::response::Response {
+        let chunks = (0..1024).map(|_| Ok::<_, std::io::Error>(vec![b'a'; 1024]));
+        axum::response::Response::builder()
+            .header(header::CONTENT_TYPE, "text/html")
+            .body(Body::from_stream(futures_util::stream::iter(chunks)))
+            .unwrap()
+    }
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        let app = axum::Router::new()
+            .route("/huge", axum::routing::get(huge))
+            .route("/small", axum::routing::get(|| async { ([(header::CONTENT_TYPE, "text/html")], "<p>ok</p>") }));
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    let client = HttpClient::new().unwrap().with_max_content_size(10 * 1024);
+    assert!(client.fetch(&format!("http://{}/small", addr)).await.is_ok());
+    assert_eq!(client.get_stats().bytes_discarded, 0);
+
+    match client.fetch(&format!("http://{}/huge", addr)).await {
+        Err(NetworkError::ContentTooLarge { size, limit }) => {
+            assert_eq!(limit, 10 * 1024);
+            assert!(size > limit && size < 1024 * 1024);
+        }
+        other => panic!("expected ContentTooLarge, got {:?}", other.map(|r| r.content_length)),
+    }
+    let discarded = client.get_stats().bytes_discarded;
+    assert!(discarded > 10 * 1024 && discarded < 1024 * 1024);
+}
+
+#[tokio::test]
+async fn test_too_many_requests_carries_retry_after() {
+    use axum::http::{StatusCode, header};
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}/page", listener.local_addr().unwrap());
+    tokio::spawn(async move {
+        let busy = || async { (StatusCode::TOO_MANY_REQUESTS, [(header::RETRY_AFTER, "120")], "slow down") };
+        axum::serve(listener, axum::Router::new().route("/page", axum::routing::get(busy))).await.unwrap();
+    });
+
+    match HttpClient::new().unwrap().fetch(&url).await {
+        Err(NetworkError::RateLimited { domain, retry_after_secs }) => {
+            assert_eq!(domain, "127.0.0.1");
+            assert_eq!(retry_after_secs, Some(120));
+        }
+        other => panic!("expected a rate limit error, got {:?}", other.map(|r| r.status_code)),
+    }
+}
+
+#[test]
+fn test_trace_layer_from_env_value() {
+    assert!(!HttpTraceLayer::from_env_value(None).is_enabled());
+    assert!(!HttpTraceLayer::from_env_value(Some("0")).is_enabled());
+
+    let layer = HttpTraceLayer::from_env_value(Some("1"));
+    assert!(layer.is_enabled() && layer.is_verbose());
+    assert!(!layer.enabled(false).is_enabled());
+}
+
+#[tokio::test]
+async fn test_request_id_header_is_sent_with_every_request() {
+    use axum::http::{HeaderMap, header};
+    use axum::response::IntoResponse;
+
+    // echoes the request id back as the page body
+    async fn page(headers: HeaderMap) -> axum::response::Response {
+        let id = headers.get("x-request-id").and_then(|v| v.to_str().ok()).unwrap_or("").to_string();
+        ([(header::CONTENT_TYPE, "text/plain")], id).into_response()
+    }
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}/page", listener.local_addr().unwrap());
+    tokio::spawn(async move {
+        axum::serve(listener, axum::Router::new().route("/page", axum::routing::get(page))).await.unwrap();
+    });
+
+    let client = HttpClient::new().unwrap()
+        .with_trace_layer(true)
+        .with_request_id_header("X-Request-Id");
+
+    let first = client.fetch(&url).await.unwrap().content;
+    let second = client.fetch(&url).await.unwrap().content;
+    assert!(uuid::Uuid::parse_str(first.trim()).is_ok());
+    assert_ne!(first, second);
+
+    // an invalid name leaves requests untagged
+    let untagged = HttpClient::new().unwrap().with_request_id_header("bad header");
+    assert_eq!(untagged.fetch(&url).await.unwrap().content.trim(), "");
+}
+
+#[tokio::test]
+async fn test_dns_overrides_route_hosts_to_local_server() {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    tokio::spawn(async move {
+        let app = axum::Router::new().route(
+            "/page",
+            axum::routing::get(|| async { ([(axum::http::header::CONTENT_TYPE, "text/html")], "<html><body>local copy</body></html>") }),
+        );
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    let overrides = std::collections::HashMap::from([("Crawler-Test.invalid".to_string(), "127.0.0.1".parse().unwrap())]);
+    let client = HttpClient::new().unwrap().with_dns_overrides(overrides);
+
+    let response = client.fetch(&format!("http://crawler-test.invalid:{}/page", port)).await.unwrap();
+    assert!(response.content.contains("local copy"));
+
+    // without the override the reserved .invalid domain never resolves
+    let client = HttpClient::new().unwrap();
+    assert!(client.fetch(&format!("http://crawler-test.invalid:{}/page", port)).await.is_err());
+}
+
+#[tokio::test]
+async fn test_dns_cache_keeps_addresses_for_ttl() {
+    use reqwest::dns::Resolve;
+
+    let resolver = crate::network::DnsResolver::new();
+    let addrs: Vec<_> = resolver.resolve("localhost".parse().unwrap()).await.unwrap().collect();
+    assert!(!addrs.is_empty());
+    assert_eq!(resolver.cached_address("localhost"), None);
+
+    resolver.set_cache_ttl(Duration::from_secs(60));
+    let _ = resolver.resolve("localhost".parse().unwrap()).await.unwrap();
+    let cached = resolver.cached_address("LOCALHOST").expect("address should be cached");
+    assert!(cached.is_loopback());
+
+    let addrs: Vec<_> = resolver.resolve("localhost".parse().unwrap()).await.unwrap().collect();
+    assert_eq!(addrs, vec![std::net::SocketAddr::new(cached, 0)]);
+
+    resolver.set_cache_ttl(Duration::ZERO);
+    assert_eq!(resolver.cached_address("localhost"), None);
+}
@@ -6,3 +6,5 @@ mod http_client_tests;
 mod response_handler_tests;
 #[cfg(test)]
 mod error_handler_tests;
+#[cfg(test)]
+mod robots_txt_tests;
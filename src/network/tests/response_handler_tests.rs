@@ -1,4 +1,5 @@
-use crate::network::ResponseProcessor;
+use crate::network::{CrawlHints, ResponseProcessor};
+use reqwest::header::{HeaderMap, HeaderValue};
 
 #[test]
 fn test_response_processor_creation() {
@@ -19,3 +20,67 @@ fn test_content_type_extraction() {
     let processor = ResponseProcessor::default();
     assert!(true); // Placeholder for actual content type testing
 }
+
+fn html_response(content: &str) -> crate::network::HttpResponse {
+    crate::network::HttpResponse {
+        url: "https://example.com/".to_string(),
+        final_url: "https://example.com/".to_string(),
+        status_code: 200,
+        headers: reqwest::header::HeaderMap::new(),
+        content: content.to_string(),
+        content_type: "text/html".to_string(),
+        content_length: Some(content.len()),
+        encoding: "utf-8".to_string(),
+        fetch_time_ms: 1,
+        redirect_count: 0,
+        rendered_with_js: false,
+        raw_body: None,
+    }
+}
+
+#[test]
+fn test_needs_js_rendering_detects_script_shells() {
+    let shell = html_response(
+        "<html><head><title>App</title></head><body><div id=\"root\"></div>\
+         <script>window.render(\"lots of words that are not visible text at all\")</script></body></html>"
+    );
+    assert!(shell.needs_js_rendering(50));
+
+    let article = html_response(&format!("<html><body><p>{}</p></body></html>", "word ".repeat(60)));
+    assert!(!article.needs_js_rendering(50));
+
+    // fragments without a body and non-HTML responses are left alone
+    assert!(!html_response("<p>short</p>").needs_js_rendering(50));
+    let mut json = html_response("<body></body>");
+    json.content_type = "application/json".to_string();
+    assert!(!json.needs_js_rendering(50));
+}
+
+#[test]
+fn test_extract_crawl_hints() {
+    let mut headers = HeaderMap::new();
+    assert_eq!(ResponseProcessor::extract_crawl_hints(&headers), CrawlHints::default());
+
+    headers.insert("retry-after", HeaderValue::from_static("120"));
+    headers.insert("x-crawl-delay", HeaderValue::from_static("2.5"));
+    headers.insert("cache-control", HeaderValue::from_static("public, Max-Age=3600, must-revalidate"));
+    assert_eq!(ResponseProcessor::extract_crawl_hints(&headers), CrawlHints {
+        retry_after_secs: Some(120),
+        crawl_delay_secs: Some(3),
+        cache_control_max_age: Some(3600),
+    });
+
+    // Retry-After may be a date, one in the past means retry right away
+    let in_a_minute = (chrono::Utc::now() + chrono::Duration::seconds(60)).format("%a, %d %b %Y %H:%M:%S GMT").to_string();
+    headers.insert("retry-after", HeaderValue::from_str(&in_a_minute).unwrap());
+    let retry_after = ResponseProcessor::extract_crawl_hints(&headers).retry_after_secs.unwrap();
+    assert!((58..=60).contains(&retry_after), "{}", retry_after);
+
+    headers.insert("retry-after", HeaderValue::from_static("Wed, 21 Oct 2015 07:28:00 GMT"));
+    headers.insert("x-crawl-delay", HeaderValue::from_static("soon"));
+    headers.insert("cache-control", HeaderValue::from_static("no-store"));
+    assert_eq!(ResponseProcessor::extract_crawl_hints(&headers), CrawlHints {
+        retry_after_secs: Some(0),
+        ..CrawlHints::default()
+    });
+}
@@ -0,0 +1,68 @@
+use crate::network::robots_txt::parse_crawl_delay;
+use std::time::Duration;
+
+#[test]
+fn test_parse_crawl_delay_for_all_agents() {
+    let robots = "\
+User-agent: Googlebot
+Crawl-delay: 30
+
+# everyone else
+User-agent: bingbot
+User-agent: *
+Disallow: /private
+Crawl-delay: 2.5
+";
+    assert_eq!(parse_crawl_delay(robots), Some(Duration::from_millis(2500)));
+}
+
+#[test]
+fn test_parse_crawl_delay_missing_or_invalid() {
+    assert_eq!(parse_crawl_delay("User-agent: *\nDisallow: /"), None);
+    assert_eq!(parse_crawl_delay("User-agent: *\nCrawl-delay: soon"), None);
+    assert_eq!(parse_crawl_delay("User-agent: *\nCrawl-delay: -1"), None);
+    assert_eq!(parse_crawl_delay("User-agent: Googlebot\nCrawl-delay: 5"), None);
+}
+
+#[test]
+fn test_robots_rules_pick_the_most_specific_group() {
+    use crate::network::RobotsTxtRules;
+
+    let rules = RobotsTxtRules::parse("\
+User-agent: *
+Disallow: /private
+Allow: /private/press
+
+User-agent: WebCrawler
+Disallow: /
+Allow: /public
+");
+
+    assert!(!rules.is_allowed("Mozilla/5.0 (compatible; WebCrawler/1.0)", "/about"));
+    assert!(rules.is_allowed("Mozilla/5.0 (compatible; WebCrawler/1.0)", "/public/page"));
+
+    assert!(rules.is_allowed("OtherBot/2.0", "/about"));
+    assert!(!rules.is_allowed("OtherBot/2.0", "/private/notes"));
+    // the longer Allow overrides the Disallow
+    assert!(rules.is_allowed("OtherBot/2.0", "/private/press/release"));
+}
+
+#[test]
+fn test_robots_rules_wildcards_and_anchors() {
+    use crate::network::RobotsTxtRules;
+
+    let rules = RobotsTxtRules::parse("\
+User-agent: *
+Disallow: /*.pdf$
+Disallow: /search*q=
+Disallow:
+");
+
+    assert!(!rules.is_allowed("bot", "/files/report.pdf"));
+    assert!(rules.is_allowed("bot", "/files/report.pdf?download=1"));
+    assert!(!rules.is_allowed("bot", "/search?page=2&q=rust"));
+    assert!(rules.is_allowed("bot", "/search"));
+
+    assert!(RobotsTxtRules::parse("").is_allowed("bot", "/anything"));
+    assert!(RobotsTxtRules::parse("User-agent: Googlebot\nDisallow: /").is_allowed("bot", "/"));
+}
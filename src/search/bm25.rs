@@ -0,0 +1,126 @@
+use tantivy::query::Query;
+use tantivy::schema::{IndexRecordOption, Term};
+use tantivy::postings::Postings;
+use tantivy::{DocAddress, DocSet, Searcher, TERMINATED};
+
+// Tantivy hardcodes k1 = 1.2 and b = 0.75 in its own scorer
+pub const DEFAULT_K1: f32 = 1.2;
+pub const DEFAULT_B: f32 = 0.75;
+
+struct TermStats {
+    term: Term,
+    idf: f32,
+    average_fieldnorm: f32,
+}
+
+/// Okapi BM25 with configurable k1 and b, used to rescore hits when the
+/// configured parameters differ from Tantivy's built-in ones
+pub struct Bm25Scorer {
+    k1: f32,
+    b: f32,
+    terms: Vec<TermStats>,
+}
+
+impl Bm25Scorer {
+    pub fn is_default(k1: f32, b: f32) -> bool {
+        k1 == DEFAULT_K1 && b == DEFAULT_B
+    }
+
+    // Collect corpus statistics for every term the query touches
+    pub fn for_query(searcher: &Searcher, query: &dyn Query, k1: f32, b: f32) -> tantivy::Result<Self> {
+        let mut query_terms: Vec<Term> = Vec::new();
+        query.query_terms(&mut |term, _| {
+            if !query_terms.contains(term) {
+                query_terms.push(term.clone());
+            }
+        });
+
+        let total_docs = searcher.num_docs().max(1) as f32;
+        let mut terms = Vec::with_capacity(query_terms.len());
+
+        for term in query_terms {
+            let doc_freq = searcher.doc_freq(&term)? as f32;
+            let idf = (1.0 + (total_docs - doc_freq + 0.5) / (doc_freq + 0.5)).ln();
+
+            let mut total_tokens = 0u64;
+            for segment_reader in searcher.segment_readers() {
+                total_tokens += segment_reader.inverted_index(term.field())?.total_num_tokens();
+            }
+            let average_fieldnorm = total_tokens as f32 / total_docs;
+
+            terms.push(TermStats { term, idf, average_fieldnorm });
+        }
+
+        Ok(Self { k1, b, terms })
+    }
+
+    // Sum of per-term BM25 contributions for a single document
+    pub fn score(&self, searcher: &Searcher, doc_address: DocAddress) -> tantivy::Result<f32> {
+        let segment_reader = searcher.segment_reader(doc_address.segment_ord);
+        let mut score = 0.0;
+
+        for stats in &self.terms {
+            let inverted_index = segment_reader.inverted_index(stats.term.field())?;
+            let Some(mut postings) = inverted_index.read_postings(&stats.term, IndexRecordOption::WithFreqs)? else {
+                continue;
+            };
+
+            if postings.seek(doc_address.doc_id) != doc_address.doc_id || postings.doc() == TERMINATED {
+                continue;
+            }
+
+            let tf = postings.term_freq() as f32;
+            let fieldnorm = segment_reader
+                .get_fieldnorms_reader(stats.term.field())?
+                .fieldnorm(doc_address.doc_id) as f32;
+
+            let norm = if stats.average_fieldnorm > 0.0 {
+                1.0 - self.b + self.b * fieldnorm / stats.average_fieldnorm
+            } else {
+                1.0
+            };
+
+            score += stats.idf * (tf * (self.k1 + 1.0)) / (tf + self.k1 * norm);
+        }
+
+        Ok(score)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::search::SearchSchema;
+    use tantivy::collector::TopDocs;
+    use tantivy::query::QueryParser;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_default_parameters_match_tantivy() {
+        let dir = tempdir().unwrap();
+        let index = SearchSchema::create_index(dir.path()).unwrap();
+        let schema = SearchSchema::build();
+
+        let mut writer: tantivy::IndexWriter = index.writer(15_000_000).unwrap();
+        for content in ["rust crawler", "rust rust web crawler in rust", "a page about bread", "crawler"] {
+            let mut doc = tantivy::TantivyDocument::default();
+            doc.add_text(schema.content_field, content);
+            writer.add_document(doc).unwrap();
+        }
+        writer.commit().unwrap();
+
+        let searcher = index.reader().unwrap().searcher();
+        let query = QueryParser::for_index(&index, vec![schema.content_field])
+            .parse_query("rust crawler")
+            .unwrap();
+
+        let scorer = Bm25Scorer::for_query(&searcher, query.as_ref(), DEFAULT_K1, DEFAULT_B).unwrap();
+        let top_docs = searcher.search(&query, &TopDocs::with_limit(10)).unwrap();
+        assert_eq!(top_docs.len(), 3);
+
+        for (tantivy_score, doc_address) in top_docs {
+            let score = scorer.score(&searcher, doc_address).unwrap();
+            assert!((score - tantivy_score).abs() < 1e-4, "{} vs {}", score, tantivy_score);
+        }
+    }
+}
@@ -0,0 +1,225 @@
+use dashmap::{DashMap, DashSet};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+use tracing::debug;
+
+use crate::storage::cache::MemoryCache;
+use crate::utils::MetricsHandle;
+use super::query::{SearchOptions, SearchQuery, SearchResult};
+
+/// Shared handle to cached search results, cloned into the indexer so that
+/// indexing a page drops the cached results for its domain
+#[derive(Clone)]
+pub struct SearchResultCache {
+    cache: Arc<MemoryCache>,
+    // every key we stored, for invalidate_all on a shared MemoryCache
+    keys: Arc<DashSet<String>>,
+    // domain -> keys whose filter or results touch that domain
+    domain_keys: Arc<DashMap<String, Vec<String>>>,
+    // keys without a domain filter, any newly indexed page can change their results
+    unfiltered_keys: Arc<DashSet<String>>,
+}
+
+impl SearchResultCache {
+    pub fn new(cache: Arc<MemoryCache>) -> Self {
+        Self {
+            cache,
+            keys: Arc::new(DashSet::new()),
+            domain_keys: Arc::new(DashMap::new()),
+            unfiltered_keys: Arc::new(DashSet::new()),
+        }
+    }
+
+    /// The underlying cache, for its hit rate statistics
+    pub fn memory_cache(&self) -> &Arc<MemoryCache> {
+        &self.cache
+    }
+
+    fn get(&self, key: &str) -> Option<Vec<SearchResult>> {
+        self.cache.get::<Vec<SearchResult>>(key).ok().flatten()
+    }
+
+    fn insert(&self, key: String, results: &[SearchResult], filter_domain: Option<&str>) {
+        if self.cache.set_with_ttl(&key, &results, self.cache.search_ttl()).is_err() {
+            return;
+        }
+
+        if filter_domain.is_none() {
+            self.unfiltered_keys.insert(key.clone());
+        }
+
+        let mut domains: Vec<&str> = results.iter().map(|r| r.domain.as_str()).collect();
+        domains.extend(filter_domain);
+        domains.sort_unstable();
+        domains.dedup();
+
+        for domain in domains {
+            self.domain_keys.entry(domain.to_string()).or_default().push(key.clone());
+        }
+        self.keys.insert(key);
+    }
+
+    /// Drop cached results that were filtered to or contained pages from `domain`, and
+    /// every search without a domain filter since a new page of `domain` may now match it
+    pub fn invalidate_domain(&self, domain: &str) {
+        let mut keys: Vec<String> = self.domain_keys.remove(domain).map(|(_, keys)| keys).unwrap_or_default();
+        keys.extend(self.unfiltered_keys.iter().map(|key| key.clone()));
+        self.unfiltered_keys.clear();
+
+        debug!("Invalidating {} cached searches for domain {}", keys.len(), domain);
+        for key in keys {
+            self.cache.invalidate(&key);
+            self.keys.remove(&key);
+        }
+    }
+
+    pub fn invalidate_all(&self) {
+        for key in self.keys.iter() {
+            self.cache.invalidate(key.key());
+        }
+        self.keys.clear();
+        self.domain_keys.clear();
+        self.unfiltered_keys.clear();
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct SearchCacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub hit_ratio: f64,
+}
+
+/// SearchQuery with a TTL cache in front of Tantivy
+pub struct CachingSearchQuery {
+    inner: SearchQuery,
+    results: SearchResultCache,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl CachingSearchQuery {
+    pub fn new(inner: SearchQuery, cache: Arc<MemoryCache>) -> Self {
+        Self {
+            inner,
+            results: SearchResultCache::new(cache),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    /// Cache results for `ttl` in a cache of its own
+    pub fn with_ttl(inner: SearchQuery, ttl: Duration) -> Self {
+        Self::new(inner, Arc::new(MemoryCache::new(10_000, ttl).with_search_ttl(ttl)))
+    }
+
+    pub fn inner(&self) -> &SearchQuery {
+        &self.inner
+    }
+
+    pub fn into_inner(self) -> SearchQuery {
+        self.inner
+    }
+
+    /// Handle for SearchIndexer::with_result_cache
+    pub fn result_cache(&self) -> SearchResultCache {
+        self.results.clone()
+    }
+
+    pub fn search_with_filters(&self, query_str: &str, options: &SearchOptions) -> tantivy::Result<Vec<SearchResult>> {
+        let key = Self::cache_key(query_str, options);
+
+        if let Some(results) = self.results.get(&key) {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+            MetricsHandle::global().cache_hits_total.inc();
+            return Ok(results);
+        }
+        self.misses.fetch_add(1, Ordering::Relaxed);
+        MetricsHandle::global().cache_misses_total.inc();
+
+        let results = self.inner.search_with_filters(query_str, options)?;
+
+        self.results.insert(key, &results, options.filters.domain.as_deref());
+        Ok(results)
+    }
+
+    pub fn invalidate_all(&self) {
+        self.results.invalidate_all();
+    }
+
+    pub fn stats(&self) -> SearchCacheStats {
+        let hits = self.hits.load(Ordering::Relaxed);
+        let misses = self.misses.load(Ordering::Relaxed);
+        let total = hits + misses;
+
+        SearchCacheStats {
+            hits,
+            misses,
+            hit_ratio: if total == 0 { 0.0 } else { hits as f64 / total as f64 },
+        }
+    }
+
+    // SHA-256 of every option that changes the result list
+    fn cache_key(query_str: &str, options: &SearchOptions) -> String {
+        let SearchOptions { limit, offset, ref filters, sort, snippets, highlight } = *options;
+        let filters_hash = hex::encode(Sha256::digest(serde_json::to_vec(filters).unwrap_or_default()));
+        let parts = serde_json::json!([query_str, limit, offset, filters_hash, sort, snippets, highlight]);
+
+        format!("search_query:{}", hex::encode(Sha256::digest(parts.to_string().as_bytes())))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::models::tests::page_data;
+    use crate::search::{SearchIndexer, SearchSchema};
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_cached_search_and_domain_invalidation() {
+        let dir = tempdir().unwrap();
+        SearchSchema::create_index(dir.path()).unwrap();
+
+        let indexer = SearchIndexer::new(dir.path()).unwrap();
+        indexer.index_page(&page_data("https://example.com/a", "rust crawler")).unwrap();
+
+        let search = CachingSearchQuery::with_ttl(SearchQuery::new(dir.path()).unwrap(), Duration::from_secs(60));
+        let indexer = indexer.with_result_cache(search.result_cache());
+
+        let run = || search.search_with_filters("rust", &SearchOptions::new(10)).unwrap();
+
+        let first = run();
+        let second = run();
+        assert_eq!(first.len(), 1);
+        assert_eq!(second.len(), 1);
+        assert_eq!(search.stats().hits, 1);
+        assert_eq!(search.stats().misses, 1);
+
+        // a different page size is a different cache entry
+        search.search_with_filters("rust", &SearchOptions::new(5)).unwrap();
+        assert_eq!(search.stats().misses, 2);
+
+        // indexing another page for the domain drops its cached results
+        indexer.index_page(&page_data("https://example.com/b", "more rust")).unwrap();
+        run();
+        assert_eq!(search.stats().misses, 3);
+
+        // a query that matched nothing is dropped once a page of any domain is indexed
+        let python = || search.search_with_filters("python", &SearchOptions::new(10)).unwrap();
+        assert!(python().is_empty());
+        python();
+        assert_eq!(search.stats().hits, 2);
+        indexer.index_page(&page_data("https://other.org/a", "python crawler")).unwrap();
+        python();
+        assert_eq!(search.stats().misses, 5);
+
+        search.invalidate_all();
+        run();
+        assert_eq!(search.stats().misses, 6);
+        assert!((search.stats().hit_ratio - 0.25).abs() < 1e-9);
+    }
+}
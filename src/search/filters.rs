@@ -6,8 +6,21 @@ pub struct SearchFilter {
     pub domain : Option<String>,
     pub min_quality: Option<f64>,
     pub max_quality: Option<f64>,
-    pub after: Option<DateTime<Utc>>,
-    pub before: Option<DateTime<Utc>>,
+    pub crawled_after: Option<DateTime<Utc>>,
+    pub crawled_before: Option<DateTime<Utc>>,
+    /// Match terms within this edit distance instead of exactly
+    pub fuzzy_distance: Option<u8>,
+    /// ISO 639-1 codes, pages in any of them match; empty allows every language
+    #[serde(default)]
+    pub languages: Vec<String>,
+    /// Parse the query with `SearchQuery::parse_advanced_query` instead of Tantivy's parser
+    #[serde(default)]
+    pub advanced_syntax: bool,
+    /// Inclusive bounds on the page's word count
+    #[serde(default)]
+    pub min_word_count: Option<usize>,
+    #[serde(default)]
+    pub max_word_count: Option<usize>,
 }
 
 impl SearchFilter {
@@ -16,8 +29,13 @@ impl SearchFilter {
             domain: None,
             max_quality: None,
             min_quality: None,
-            after: None,
-            before: None,
+            crawled_after: None,
+            crawled_before: None,
+            fuzzy_distance: None,
+            languages: Vec::new(),
+            advanced_syntax: false,
+            min_word_count: None,
+            max_word_count: None,
         }
     }
     pub fn with_domain(mut self, domain: String) -> Self {
@@ -35,22 +53,58 @@ impl SearchFilter {
         self
     }
     
-    pub fn with_after(mut self, date: DateTime<Utc>) -> Self {
-        self.after = Some(date);
+    pub fn with_crawled_after(mut self, date: DateTime<Utc>) -> Self {
+        self.crawled_after = Some(date);
         self
     }
     
-    pub fn with_before(mut self, date: DateTime<Utc>) -> Self {
-        self.before = Some(date);
+    pub fn with_crawled_before(mut self, date: DateTime<Utc>) -> Self {
+        self.crawled_before = Some(date);
         self
     }
     
+    pub fn with_fuzzy_distance(mut self, distance: u8) -> Self {
+        self.fuzzy_distance = Some(distance);
+        self
+    }
+
+    pub fn with_language(mut self, lang: &str) -> Self {
+        self.languages = vec![lang.to_string()];
+        self
+    }
+
+    /// Pages in any of `langs`
+    pub fn with_languages(mut self, langs: &[&str]) -> Self {
+        self.languages = langs.iter().map(|lang| lang.to_string()).collect();
+        self
+    }
+
+    pub fn with_advanced_syntax(mut self, advanced: bool) -> Self {
+        self.advanced_syntax = advanced;
+        self
+    }
+
+    pub fn with_min_word_count(mut self, words: usize) -> Self {
+        self.min_word_count = Some(words);
+        self
+    }
+
+    pub fn with_max_word_count(mut self, words: usize) -> Self {
+        self.max_word_count = Some(words);
+        self
+    }
+
+    /// Pages with `min..=max` words
+    pub fn with_word_count_range(self, min: usize, max: usize) -> Self {
+        self.with_min_word_count(min).with_max_word_count(max)
+    }
+
     pub fn has_filters(&self) -> bool {
         self.domain.is_some()
         ||self.min_quality.is_some()
         ||self.max_quality.is_some()
-        ||self.after.is_some()
-        ||self.min_quality.is_some()
+        ||self.crawled_after.is_some()
+        ||self.crawled_before.is_some()
     }
 }
 
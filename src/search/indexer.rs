@@ -1,45 +1,126 @@
+use crate::config::CustomFieldSpec;
 use crate::models::PageData;
+use chrono::{DateTime, Utc};
+use crate::storage::models::{PageFilter, StoredPage};
 use crate::storage::repository::PageRepository;
-use tantivy::{Index, IndexWriter, doc};
-use tantivy::collector::TopDocs;
-use tantivy::query::{QueryParser, };
+use crate::storage::search_index::merge_all_segments;
+use crate::utils::{detect_language, registrable_domain, MetricsHandle};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use tantivy::{Index, IndexWriter, Searcher, TantivyDocument, TantivyError, Term, doc};
+use tantivy::collector::{Count, TopDocs};
+use tantivy::query::{BooleanQuery, Occur, QueryParser, TermQuery};
+use tantivy::schema::{Field, IndexRecordOption, OwnedValue};
 use std::path::Path;
+use tokio::sync::Semaphore;
+use tokio::task::JoinHandle;
 use tracing::{info, warn};
 
+use super::caching::SearchResultCache;
 use super::schema::SearchSchema;
 
+// pages loaded from the database per round trip when re-indexing
+const REINDEX_BATCH_SIZE: usize = 500;
+
+/// Document counts of the search index
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IndexStats {
+    pub total_docs: u64,
+    pub unique_content_hashes: u64,
+    /// Share of documents whose content is also indexed under another url
+    pub duplicate_ratio: f64,
+}
+
+/// File in the index directory holding when the index was last brought up to date
+pub const LAST_INDEXED_FILE: &str = ".last_indexed";
+
+/// Time recorded by `record_last_indexed`, None if the index was never recorded or the file is unreadable
+pub fn last_indexed_at(index_path: &Path) -> Option<DateTime<Utc>> {
+    let text = std::fs::read_to_string(index_path.join(LAST_INDEXED_FILE)).ok()?;
+    DateTime::parse_from_rfc3339(text.trim()).ok().map(|at| at.with_timezone(&Utc))
+}
+
+/// Remember `at` as the time pages were last indexed, for `--incremental` runs
+pub fn record_last_indexed(index_path: &Path, at: DateTime<Utc>) -> std::io::Result<()> {
+    std::fs::create_dir_all(index_path)?;
+    std::fs::write(index_path.join(LAST_INDEXED_FILE), at.to_rfc3339())
+}
+
 pub struct SearchIndexer {
     index: Index,
     search_schema : SearchSchema,
+    // cached search results to drop when a domain's pages change
+    result_cache: Option<SearchResultCache>,
 }
 
 impl SearchIndexer {
     pub fn new(index_path: &Path) -> tantivy::Result<Self>{
-        let index = SearchSchema::open_or_create(index_path)?;
-        let search_schema = SearchSchema::build();
+        Self::with_custom_fields(index_path, &[])
+    }
+
+    /// Indexer whose schema has `custom_fields` after the built-in fields, for values
+    /// passed to `index_page_with_metadata`
+    pub fn with_custom_fields(index_path: &Path, custom_fields: &[CustomFieldSpec]) -> tantivy::Result<Self> {
+        let search_schema = SearchSchema::build_custom(custom_fields);
+        let index = search_schema.open_or_create_in(index_path)?;
 
         Ok(Self{
             index,
             search_schema,
+            result_cache: None,
         })
     }
 
+    pub fn with_result_cache(mut self, cache: SearchResultCache) -> Self {
+        self.result_cache = Some(cache);
+        self
+    }
+
     pub fn index_page(&self, page: &PageData) -> tantivy::Result<()> {
+        self.index_page_with_metadata(page, &HashMap::new())
+    }
+
+    /// Index `page` with values for the schema's custom fields, keyed by field name
+    pub fn index_page_with_metadata(&self, page: &PageData, extra: &HashMap<String, serde_json::Value>) -> tantivy::Result<()> {
         let mut index_writer = self.index.writer(50_000_000)?;
 
         let mut doc = tantivy::TantivyDocument::default();
-        doc.add_text(self.search_schema.url_field, &page.url);
+        self.search_schema.add_url(&mut doc, &page.url);
 
         if let Some(ref title) = page.title {
-            doc.add_text(self.search_schema.title_field, &title);
+            self.search_schema.add_title(&mut doc, title);
+        }
+
+        let domain = registrable_domain(&page.url);
+
+        if let Some(ref description) = page.description {
+            doc.add_text(self.search_schema.description_field, description);
         }
 
         doc.add_text(self.search_schema.content_field, &page.content);
-        doc.add_text(self.search_schema.domain_field, &self.extract_domain(&page.url));
+        doc.add_text(self.search_schema.domain_field, &domain);
         doc.add_f64(self.search_schema.quality_field, page.content_quality_score);
+        doc.add_u64(self.search_schema.word_count_field, page.word_count as u64);
+        self.search_schema.add_hashes(
+            &mut doc,
+            &PageRepository::calculate_url_hash(&page.url),
+            &PageRepository::calculate_content_hash(&page.content),
+        );
+        let language = detect_language(&page.content).primary;
+        self.search_schema.add_facets(&mut doc, &domain, language, page.content_quality_score);
+        self.search_schema.add_crawled_at(&mut doc, page.crawled_at);
+        self.search_schema.add_custom_values(&mut doc, extra);
 
+        // replace the document from an earlier crawl of the page
+        index_writer.delete_term(self.search_schema.url_term(&page.url));
         index_writer.add_document(doc)?;
         index_writer.commit()?;
+        MetricsHandle::global().index_documents_total.inc();
+
+        if let Some(cache) = &self.result_cache {
+            cache.invalidate_domain(&domain);
+        }
 
         info!("Indexed page: {}", page.url);
         Ok(())
@@ -48,39 +129,495 @@ impl SearchIndexer {
     pub async fn index_all_pages(&self, repository: &PageRepository) -> tantivy::Result<()> {
         info!("Starting full indexing of all pages...");
 
-        let mut index_writer = self.index.writer(50_000_000)?;
-        let mut count = 0;
-
         // get all pages from database
-        let filter = crate::storage::models::PageFilter::new().with_limit(10000);
+        let filter = PageFilter::new().with_limit(10000);
         let pages = repository.get_pages(&filter).await
-            .map_err(|e| tantivy::TantivyError::InternalError(e.to_string()))?;
+            .map_err(|e| TantivyError::InternalError(e.to_string()))?
+            .items;
+
+        self.index_stored_pages(&pages)?;
+        Ok(())
+    }
+
+    /// Index `pages` one after another in a single commit, returns how many documents were
+    /// added. Pages unchanged since they were indexed are skipped.
+    pub fn index_stored_pages(&self, pages: &[StoredPage]) -> tantivy::Result<u64> {
+        let mut index_writer: IndexWriter = self.index.writer(50_000_000)?;
+        let searcher = self.index.reader()?.searcher();
+
+        let documents = changed_documents(&self.search_schema, &searcher, pages)?;
+        let count = documents.len() as u64;
+        for (url_term, doc) in documents {
+            index_writer.delete_term(url_term);
+            index_writer.add_document(doc)?;
+        }
+        index_writer.commit()?;
+        MetricsHandle::global().index_documents_total.inc_by(count);
+        info!("Indexed {} pages successfully, {} unchanged pages skipped", count, pages.len() as u64 - count);
+
+        Ok(count)
+    }
+
+    /// Index every stored page, reading them in batches of `batch_size` and building their
+    /// documents in up to `worker_count` blocking tasks at once. Each worker adds its batch
+    /// to the shared writer under one lock, the whole run is a single commit.
+    /// Returns how many documents were added, unchanged pages are skipped.
+    pub async fn index_pages_parallel(
+        &self,
+        repo: &PageRepository,
+        worker_count: usize,
+        batch_size: usize,
+    ) -> tantivy::Result<u64> {
+        let mut workers = IndexWorkers::new(self, worker_count)?;
+        let mut filter = PageFilter::new().with_limit(batch_size.max(1)).with_cursor(0);
+
+        loop {
+            let batch = repo.get_pages(&filter).await
+                .map_err(|e| TantivyError::InternalError(e.to_string()))?;
+            workers.spawn(batch.items).await;
+
+            match batch.next_cursor {
+                Some(cursor) if batch.has_more => filter = filter.with_cursor(cursor),
+                _ => break,
+            }
+        }
+
+        workers.finish().await
+    }
 
-        for stored_pages in pages{
-            let mut doc = tantivy::TantivyDocument::default();
-            doc.add_text(self.search_schema.url_field, &stored_pages.url);
+    /// `index_pages_parallel` for pages already in memory
+    pub async fn index_stored_pages_parallel(
+        &self,
+        pages: Vec<StoredPage>,
+        worker_count: usize,
+        batch_size: usize,
+    ) -> tantivy::Result<u64> {
+        let mut workers = IndexWorkers::new(self, worker_count)?;
 
-            if let Some(ref title) = stored_pages.title{
-                doc.add_text(self.search_schema.title_field, &title);
+        let mut pages = pages.into_iter();
+        loop {
+            let batch: Vec<StoredPage> = pages.by_ref().take(batch_size.max(1)).collect();
+            if batch.is_empty() {
+                break;
             }
-                doc.add_text(self.search_schema.content_field, &stored_pages.content);
-                doc.add_text(self.search_schema.domain_field, &stored_pages.domain);
-                doc.add_f64(self.search_schema.quality_field, stored_pages.quality_score);
-                doc.add_f64(self.search_schema.pagerank_field, stored_pages.pagerank.unwrap_or(0.0));
-                doc.add_f64(self.search_schema.tfidf_field, stored_pages.tfidf_score.unwrap_or(0.0));
-                index_writer.add_document(doc)?;
+            workers.spawn(batch).await;
+        }
+
+        workers.finish().await
+    }
+
+    /// Whether a document with this url hash and content hash is in the index,
+    /// i.e. the page has not changed since it was indexed
+    pub fn is_indexed(&self, url_hash: &str, content_hash: &str) -> tantivy::Result<bool> {
+        let searcher = self.index.reader()?.searcher();
+        has_document(&self.search_schema, &searcher, url_hash, content_hash)
+    }
+
+    /// Document count and how much of the indexed content is duplicated across urls
+    pub fn get_index_stats(&self) -> tantivy::Result<IndexStats> {
+        let searcher = self.index.reader()?.searcher();
+        let mut content_hashes = HashSet::new();
+
+        for segment_reader in searcher.segment_readers() {
+            let Some(column) = segment_reader.fast_fields().str("content_hash")? else {
+                continue;
+            };
+            let mut hash = String::new();
+            for doc_id in segment_reader.doc_ids_alive() {
+                for ord in column.term_ords(doc_id) {
+                    if column.ord_to_str(ord, &mut hash)? {
+                        content_hashes.insert(hash.clone());
+                    }
+                }
+            }
+        }
+
+        let total_docs = searcher.num_docs();
+        let unique_content_hashes = content_hashes.len() as u64;
+        let duplicate_ratio = if total_docs == 0 {
+            0.0
+        } else {
+            1.0 - unique_content_hashes as f64 / total_docs as f64
+        };
+
+        Ok(IndexStats { total_docs, unique_content_hashes, duplicate_ratio })
+    }
+
+    /// Re-index a stored page, replacing its previous document
+    pub fn update_page(&self, page: &StoredPage) -> tantivy::Result<()> {
+        let mut index_writer: IndexWriter = self.index.writer(50_000_000)?;
+
+        index_writer.delete_term(self.search_schema.url_term(&page.url));
+        index_writer.add_document(stored_page_document(&self.search_schema, page))?;
+        index_writer.commit()?;
+        MetricsHandle::global().index_documents_total.inc();
+
+        if let Some(cache) = &self.result_cache {
+            cache.invalidate_domain(&page.domain);
+        }
+
+        info!("Updated indexed page: {}", page.url);
+        Ok(())
+    }
+
+    /// Re-index the pages crawled since `since`, reading them in batches, returns how many
+    /// were updated
+    pub async fn reindex_changed_pages(&self, repository: &PageRepository, since: DateTime<Utc>) -> crate::Result<u64> {
+        let mut filter = PageFilter::crawled_after(since)
+            .with_limit(REINDEX_BATCH_SIZE)
+            .with_cursor(0);
+        let mut count = 0u64;
+
+        loop {
+            let batch = repository.get_pages(&filter).await?;
+            for page in &batch.items {
+                self.update_page(page)?;
                 count += 1;
+            }
+            match batch.next_cursor {
+                Some(cursor) if batch.has_more => filter = filter.with_cursor(cursor),
+                _ => break,
+            }
+        }
+
+        info!("Re-indexed {} pages crawled since {}", count, since);
+        Ok(count)
+    }
+
+    /// Remove the document for `url`, a no-op if it was never indexed
+    pub fn delete_page(&self, url: &str) -> tantivy::Result<()> {
+        self.delete_pages(&[url.to_string()])
+    }
+
+    /// Remove the documents for `urls` in a single commit
+    pub fn delete_pages(&self, urls: &[String]) -> tantivy::Result<()> {
+        if urls.is_empty() {
+            return Ok(());
+        }
+
+        let mut index_writer: IndexWriter = self.index.writer(50_000_000)?;
+        for url in urls {
+            index_writer.delete_term(self.search_schema.url_term(url));
         }
         index_writer.commit()?;
-        info!("Indexed {} pages successfully", count);
 
+        if let Some(cache) = &self.result_cache {
+            for url in urls {
+                cache.invalidate_domain(&registrable_domain(url));
+            }
+        }
+
+        info!("Removed {} pages from the index", urls.len());
         Ok(())
     }
 
-    fn extract_domain(&self, url: &str) -> String{
-        url::Url::parse(url)
-            .ok()
-            .and_then(|u| u.host_str().map(String::from))
-            .unwrap_or_else(|| "unknown".to_string())
+    /// Merge the index into a single segment, returns the number of segments left
+    pub fn compact(&self) -> tantivy::Result<usize> {
+        let mut index_writer: IndexWriter = self.index.writer(50_000_000)?;
+        merge_all_segments(&self.index, &mut index_writer)?;
+        index_writer.wait_merging_threads()?;
+
+        Ok(self.index.searchable_segment_ids()?.len())
     }
-}
\ No newline at end of file
+}
+
+fn stored_page_document(schema: &SearchSchema, page: &StoredPage) -> TantivyDocument {
+    let mut doc = TantivyDocument::default();
+    schema.add_url(&mut doc, &page.url);
+
+    if let Some(ref title) = page.title {
+        schema.add_title(&mut doc, title);
+    }
+    if let Some(ref description) = page.description {
+        doc.add_text(schema.description_field, description);
+    }
+    doc.add_text(schema.content_field, &page.content);
+    doc.add_text(schema.domain_field, &page.domain);
+    doc.add_f64(schema.quality_field, page.quality_score);
+    doc.add_u64(schema.word_count_field, page.word_count.max(0) as u64);
+    schema.add_hashes(&mut doc, &page.url_hash, &page.content_hash);
+    schema.add_facets(&mut doc, &page.domain, &page.language, page.quality_score);
+    schema.add_crawled_at(&mut doc, page.crawled_at);
+    doc.add_f64(schema.pagerank_field, page.pagerank.unwrap_or(0.0));
+    doc.add_f64(schema.tfidf_field, page.tfidf_score.unwrap_or(0.0));
+    doc
+}
+
+fn has_document(schema: &SearchSchema, searcher: &Searcher, url_hash: &str, content_hash: &str) -> tantivy::Result<bool> {
+    Ok(searcher.search(&hash_query(schema, url_hash, content_hash), &Count)? > 0)
+}
+
+// Documents indexed with both hashes
+fn hash_query(schema: &SearchSchema, url_hash: &str, content_hash: &str) -> BooleanQuery {
+    let term_query = |field, value: &str| -> Box<dyn tantivy::query::Query> {
+        Box::new(TermQuery::new(Term::from_field_text(field, value), IndexRecordOption::Basic))
+    };
+    BooleanQuery::new(vec![
+        (Occur::Must, term_query(schema.url_hash_field, url_hash)),
+        (Occur::Must, term_query(schema.content_hash_field, content_hash)),
+    ])
+}
+
+// Whether the page is indexed as `document` would index it: same hashes and the same value
+// in every stored field, the fields that are only indexed are built from those
+fn is_unchanged(schema: &SearchSchema, searcher: &Searcher, page: &StoredPage, document: &TantivyDocument) -> tantivy::Result<bool> {
+    let query = hash_query(schema, &page.url_hash, &page.content_hash);
+    let Some((_, address)) = searcher.search(&query, &TopDocs::with_limit(1))?.into_iter().next() else {
+        return Ok(false);
+    };
+    let indexed: TantivyDocument = searcher.doc(address)?;
+    Ok(stored_values(schema, &indexed) == stored_values(schema, document))
+}
+
+// Stored field values of `doc`, ordered by field
+fn stored_values(schema: &SearchSchema, doc: &TantivyDocument) -> Vec<(Field, OwnedValue)> {
+    let mut values: Vec<(Field, OwnedValue)> = doc.field_values()
+        .filter(|(field, _)| schema.schema.get_field_entry(*field).is_stored())
+        .map(|(field, value)| (field, OwnedValue::from(value)))
+        .collect();
+    values.sort_by_key(|(field, _)| field.field_id());
+    values
+}
+
+// Documents of the pages that changed since they were indexed, including their scores and
+// other metadata, with the url term replacing their old ones
+fn changed_documents(
+    schema: &SearchSchema,
+    searcher: &Searcher,
+    pages: &[StoredPage],
+) -> tantivy::Result<Vec<(Term, TantivyDocument)>> {
+    let mut documents = Vec::with_capacity(pages.len());
+    for page in pages {
+        let document = stored_page_document(schema, page);
+        if !is_unchanged(schema, searcher, page, &document)? {
+            documents.push((schema.url_term(&page.url), document));
+        }
+    }
+    Ok(documents)
+}
+
+// Blocking tasks building documents for one shared writer, at most `worker_count` at a time
+struct IndexWorkers {
+    schema: Arc<SearchSchema>,
+    searcher: Searcher,
+    writer: Arc<Mutex<IndexWriter>>,
+    semaphore: Arc<Semaphore>,
+    tasks: Vec<JoinHandle<tantivy::Result<(u64, usize)>>>,
+}
+
+impl IndexWorkers {
+    fn new(indexer: &SearchIndexer, worker_count: usize) -> tantivy::Result<Self> {
+        Ok(Self {
+            schema: Arc::new(indexer.search_schema.clone()),
+            searcher: indexer.index.reader()?.searcher(),
+            writer: Arc::new(Mutex::new(indexer.index.writer(50_000_000)?)),
+            semaphore: Arc::new(Semaphore::new(worker_count.max(1))),
+            tasks: Vec::new(),
+        })
+    }
+
+    // Waits for a free worker, so the caller doesn't load batches faster than they are indexed
+    async fn spawn(&mut self, pages: Vec<StoredPage>) {
+        let permit = self.semaphore.clone().acquire_owned().await.expect("Semaphore should not be closed");
+        let (schema, searcher, writer) = (self.schema.clone(), self.searcher.clone(), self.writer.clone());
+
+        self.tasks.push(tokio::task::spawn_blocking(move || {
+            let _permit = permit;
+            let documents = changed_documents(&schema, &searcher, &pages)?;
+            let added = documents.len() as u64;
+
+            // documents are built outside the lock, only handing them to the writer is serialized
+            let writer = writer.lock().unwrap();
+            for (url_term, doc) in documents {
+                writer.delete_term(url_term);
+                writer.add_document(doc)?;
+            }
+            Ok((added, pages.len()))
+        }));
+    }
+
+    async fn finish(self) -> tantivy::Result<u64> {
+        let (mut count, mut total) = (0, 0);
+        for task in self.tasks {
+            let (added, pages) = task.await.map_err(|e| TantivyError::InternalError(e.to_string()))??;
+            count += added;
+            total += pages;
+        }
+
+        self.writer.lock().unwrap().commit()?;
+        MetricsHandle::global().index_documents_total.inc_by(count);
+        info!("Indexed {} pages in parallel, {} unchanged pages skipped", count, total as u64 - count);
+
+        Ok(count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::search::SearchQuery;
+    use crate::storage::models::tests::page_data;
+    use tempfile::tempdir;
+
+    fn page(url: &str, content: &str) -> PageData {
+        PageData { title: Some("Crawler notes".to_string()), ..page_data(url, content) }
+    }
+
+    fn urls_matching(dir: &Path, query: &str) -> Vec<String> {
+        let results = SearchQuery::new(dir).unwrap().search(query, 10).unwrap();
+        results.into_iter().map(|r| r.url).collect()
+    }
+
+    #[test]
+    fn test_update_and_delete_page() {
+        let dir = tempdir().unwrap();
+        let indexer = SearchIndexer::new(dir.path()).unwrap();
+
+        indexer.index_page(&page("https://example.com/a", "tokio runtime")).unwrap();
+        indexer.index_page(&page("https://example.com/b", "tokio channels")).unwrap();
+        // indexing a page again replaces its document
+        indexer.index_page(&page("https://example.com/a", "tokio runtime")).unwrap();
+        assert_eq!(urls_matching(dir.path(), "tokio").len(), 2);
+
+        let updated = page("https://example.com/a", "async executors");
+        let stored = StoredPage::from_page_data(&updated, String::new(), String::new());
+        indexer.update_page(&stored).unwrap();
+        assert_eq!(urls_matching(dir.path(), "tokio"), vec!["https://example.com/b"]);
+        assert_eq!(urls_matching(dir.path(), "executors"), vec!["https://example.com/a"]);
+
+        // any spelling of the url finds the document
+        indexer.delete_page("http://example.com/a#intro").unwrap();
+        assert!(urls_matching(dir.path(), "executors").is_empty());
+
+        indexer.delete_page("https://example.com/never-indexed").unwrap();
+        assert_eq!(urls_matching(dir.path(), "tokio").len(), 1);
+    }
+
+    #[test]
+    fn test_is_indexed_and_index_stats() {
+        let dir = tempdir().unwrap();
+        let indexer = SearchIndexer::new(dir.path()).unwrap();
+
+        indexer.index_page(&page("https://example.com/a", "tokio runtime")).unwrap();
+        indexer.index_page(&page("https://example.com/b", "tokio runtime")).unwrap();
+        indexer.index_page(&page("https://example.com/c", "tokio channels")).unwrap();
+
+        let url_hash = PageRepository::calculate_url_hash("https://example.com/a");
+        let content_hash = PageRepository::calculate_content_hash("tokio runtime");
+        assert!(indexer.is_indexed(&url_hash, &content_hash).unwrap());
+        assert!(!indexer.is_indexed(&url_hash, &PageRepository::calculate_content_hash("changed")).unwrap());
+
+        let stats = indexer.get_index_stats().unwrap();
+        assert_eq!(stats.total_docs, 3);
+        assert_eq!(stats.unique_content_hashes, 2);
+        assert!((stats.duplicate_ratio - 1.0 / 3.0).abs() < 1e-9);
+
+        // a changed page replaces its old hashes
+        let changed = page("https://example.com/b", "async executors");
+        let stored = StoredPage::from_page_data(
+            &changed,
+            PageRepository::calculate_url_hash(&changed.url),
+            PageRepository::calculate_content_hash(&changed.content),
+        );
+        indexer.update_page(&stored).unwrap();
+        assert!(indexer.is_indexed(&stored.url_hash, &stored.content_hash).unwrap());
+
+        let stats = indexer.get_index_stats().unwrap();
+        assert_eq!(stats.total_docs, 3);
+        assert_eq!(stats.unique_content_hashes, 3);
+        assert_eq!(stats.duplicate_ratio, 0.0);
+    }
+
+    #[test]
+    fn test_index_stored_pages_reindexes_changed_metadata() {
+        let dir = tempdir().unwrap();
+        let indexer = SearchIndexer::new(dir.path()).unwrap();
+        let data = page("https://example.com/a", "tokio runtime");
+        let stored = StoredPage::from_page_data(
+            &data,
+            PageRepository::calculate_url_hash(&data.url),
+            PageRepository::calculate_content_hash(&data.content),
+        );
+
+        assert_eq!(indexer.index_stored_pages(std::slice::from_ref(&stored)).unwrap(), 1);
+        assert_eq!(indexer.index_stored_pages(std::slice::from_ref(&stored)).unwrap(), 0);
+
+        // same content, new scores
+        let rescored = StoredPage { quality_score: 0.9, pagerank: Some(0.25), ..stored.clone() };
+        assert_eq!(indexer.index_stored_pages(std::slice::from_ref(&rescored)).unwrap(), 1);
+        let relabelled = StoredPage { language: "de".to_string(), ..rescored };
+        assert_eq!(indexer.index_stored_pages(std::slice::from_ref(&relabelled)).unwrap(), 1);
+        assert_eq!(indexer.get_index_stats().unwrap().total_docs, 1);
+    }
+
+    #[test]
+    fn test_last_indexed_round_trip() {
+        let dir = tempdir().unwrap();
+        assert_eq!(last_indexed_at(dir.path()), None);
+
+        let at = DateTime::parse_from_rfc3339("2025-03-01T12:30:00Z").unwrap().with_timezone(&Utc);
+        record_last_indexed(dir.path(), at).unwrap();
+        assert_eq!(last_indexed_at(dir.path()), Some(at));
+
+        std::fs::write(dir.path().join(LAST_INDEXED_FILE), "yesterday").unwrap();
+        assert_eq!(last_indexed_at(dir.path()), None);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_parallel_indexing_matches_sequential() {
+        let pages: Vec<StoredPage> = (0..50)
+            .map(|i| {
+                let page = page(&format!("https://example.com/{}", i), &format!("tokio runtime page{}", i));
+                StoredPage::from_page_data(
+                    &page,
+                    PageRepository::calculate_url_hash(&page.url),
+                    PageRepository::calculate_content_hash(&page.content),
+                )
+            })
+            .collect();
+
+        let sequential_dir = tempdir().unwrap();
+        let sequential = SearchIndexer::new(sequential_dir.path()).unwrap();
+        assert_eq!(sequential.index_stored_pages(&pages).unwrap(), 50);
+
+        let parallel_dir = tempdir().unwrap();
+        let parallel = SearchIndexer::new(parallel_dir.path()).unwrap();
+        assert_eq!(parallel.index_stored_pages_parallel(pages[..30].to_vec(), 4, 7).await.unwrap(), 30);
+        // pages indexed by the first run are skipped
+        assert_eq!(parallel.index_stored_pages_parallel(pages.clone(), 4, 7).await.unwrap(), 20);
+
+        assert_eq!(parallel.get_index_stats().unwrap().total_docs, 50);
+        assert_eq!(urls_matching(parallel_dir.path(), "page42"), urls_matching(sequential_dir.path(), "page42"));
+    }
+
+    #[test]
+    fn test_index_page_with_metadata() {
+        let dir = tempdir().unwrap();
+        let custom_fields = [CustomFieldSpec::text("author", true, true), CustomFieldSpec::numeric("price", true, true)];
+        let indexer = SearchIndexer::with_custom_fields(dir.path(), &custom_fields).unwrap();
+
+        let extra: HashMap<String, serde_json::Value> = serde_json::from_str(
+            r#"{"author": ["Ada Lovelace", "Grace Hopper"], "price": "12.5", "unknown": "ignored"}"#,
+        ).unwrap();
+        indexer.index_page_with_metadata(&page("https://example.com/a", "tokio runtime"), &extra).unwrap();
+        indexer.index_page(&page("https://example.com/b", "tokio channels")).unwrap();
+
+        let schema = &indexer.search_schema;
+        let author = schema.custom_field("author").unwrap();
+        let price = schema.custom_field("price").unwrap();
+        let searcher = indexer.index.reader().unwrap().searcher();
+        let query = QueryParser::for_index(&indexer.index, vec![author]).parse_query("hopper").unwrap();
+        let hits = searcher.search(&query, &TopDocs::with_limit(10)).unwrap();
+        assert_eq!(hits.len(), 1);
+
+        let doc: TantivyDocument = searcher.doc(hits[0].1).unwrap();
+        assert_eq!(doc.get_first(price).and_then(|v| tantivy::schema::Value::as_f64(&v)), Some(12.5));
+        // built-in fields keep their ids, plain searches still work
+        assert_eq!(urls_matching(dir.path(), "tokio").len(), 2);
+
+        // an index without the custom fields has to be rebuilt
+        let plain_dir = tempdir().unwrap();
+        SearchIndexer::new(plain_dir.path()).unwrap();
+        assert!(SearchIndexer::with_custom_fields(plain_dir.path(), &custom_fields).is_err());
+    }
+}
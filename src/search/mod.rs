@@ -2,10 +2,14 @@ pub mod schema;
 pub mod indexer;
 pub mod query;
 pub mod filters;
+pub mod bm25;
+pub mod caching;
 mod snippets;
+mod query_syntax;
 
 pub use schema::SearchSchema;
-pub use indexer::SearchIndexer;
-pub use query::{SearchQuery, SearchResult};
+pub use indexer::{IndexStats, SearchIndexer, last_indexed_at, record_last_indexed};
+pub use query::{FacetCounts, FacetedSearchRequest, FacetedSearchResponse, ScoreExplanation, SearchOptions, SearchQuery, SearchResult};
+pub use caching::{CachingSearchQuery, SearchCacheStats, SearchResultCache};
 pub use filters::{ SearchFilter, SortBy};
-pub use snippets::{ SnippetGenerator };
\ No newline at end of file
+pub use snippets::{ HighlightSpan, SnippetGenerator, StructuredSnippet };
\ No newline at end of file
@@ -1,14 +1,30 @@
-use tantivy::{Index, IndexReader, ReloadPolicy, Document};
-use tantivy::collector::TopDocs;
-use tantivy::query::QueryParser;
+use tantivy::{Index, IndexReader, Order, ReloadPolicy, TantivyError, Document};
+use tantivy::collector::{Count, FacetCollector, TopDocs};
+use tantivy::query::{AllQuery, BooleanQuery, BoostQuery, ConstScoreQuery, EmptyQuery, FuzzyTermQuery, Occur, PhraseQuery, Query, QueryParser, RangeQuery, TermQuery};
+use tantivy::schema::{Field, IndexRecordOption, Term};
+use std::collections::HashMap;
+use std::ops::Bound;
 use std::path::Path;
+use std::sync::Arc;
 use serde::{Serialize, Deserialize};
 use tantivy::schema::Value;
-use tracing::info;
+use tracing::{info, warn};
 
-use super::schema::SearchSchema;
+use crate::algorithms::TfIdfCalculator;
+use crate::config::{SearchConfig, SearchSchemaBoosts};
+use crate::storage::repository::PageRepository;
+use super::bm25::Bm25Scorer;
+use super::schema::{SearchSchema, AUTOCOMPLETE_MAX_GRAM};
+use percent_encoding::percent_decode_str;
 use super::filters::{SearchFilter, SortBy};
-use super::snippets::SnippetGenerator;
+use super::query_syntax::{self, QueryNode};
+use super::snippets::{HighlightSpan, SnippetGenerator, StructuredSnippet};
+
+/// Passages returned in `SearchResult::snippets`
+const MAX_SNIPPETS: usize = 3;
+
+/// Top TF-IDF terms of the source page `search_similar` looks for
+const SIMILAR_TERMS: usize = 10;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct SearchResult {
@@ -21,17 +37,159 @@ pub struct SearchResult {
     pub tfidf: f64,
     pub crawled_at: Option<String>,
     pub snippet: Option<String>,
+    /// Passages matching different query terms, in document order, see `SnippetGenerator::generate_multi_query`
+    #[serde(default)]
+    pub snippets: Vec<String>,
+    /// Query term matches in `snippet`, as byte offsets into it, inside any `**` highlighting
+    #[serde(default)]
+    pub highlight_positions: Vec<HighlightSpan>,
+    /// Title, description and content snippet kept apart, set along with `snippet`
+    #[serde(default)]
+    pub structured_snippet: Option<StructuredSnippet>,
+}
+
+/// Why a page scored what it did for a query, see `SearchQuery::explain`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScoreExplanation {
+    pub url: String,
+    /// BM25 score, 0.0 when the query doesn't match the page
+    pub tantivy_score: f32,
+    pub pagerank: f64,
+    pub tfidf: f64,
+    pub quality: f64,
+    /// Multiplier of the matching `url_penalties` pattern, 1.0 without one
+    pub url_penalty: f64,
+    /// The score `search` ranks by
+    pub combined_score: f32,
+    /// Tantivy's scoring tree as pretty JSON, or why the query didn't match
+    pub tantivy_explanation: String,
+}
+
+/// What `search_with_facets` should return besides the facet counts
+#[derive(Debug)]
+pub struct FacetedSearchRequest {
+    pub limit: usize,
+    pub offset: usize,
+    pub filters: SearchFilter,
+    pub sort_by: SortBy,
+    pub snippets: bool,
+    pub highlight: bool,
+    /// Most frequent domains / languages to report
+    pub facet_limit: usize,
+}
+
+impl FacetedSearchRequest {
+    pub fn new(limit: usize) -> Self {
+        Self {
+            limit,
+            offset: 0,
+            filters: SearchFilter::new(),
+            sort_by: SortBy::Relevance,
+            snippets: false,
+            highlight: false,
+            facet_limit: 10,
+        }
+    }
+
+    pub fn with_offset(mut self, offset: usize) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    pub fn with_filters(mut self, filters: SearchFilter) -> Self {
+        self.filters = filters;
+        self
+    }
+
+    pub fn with_sort(mut self, sort_by: SortBy) -> Self {
+        self.sort_by = sort_by;
+        self
+    }
+
+    pub fn with_snippets(mut self, snippets: bool, highlight: bool) -> Self {
+        self.snippets = snippets;
+        self.highlight = highlight;
+        self
+    }
+
+    pub fn with_facet_limit(mut self, facet_limit: usize) -> Self {
+        self.facet_limit = facet_limit;
+        self
+    }
+}
+
+/// Paging, filtering, sorting and snippet settings of one query
+#[derive(Debug)]
+pub struct SearchOptions {
+    pub limit: usize,
+    pub offset: usize,
+    pub filters: SearchFilter,
+    pub sort: SortBy,
+    pub snippets: bool,
+    pub highlight: bool,
+}
+
+impl SearchOptions {
+    pub fn new(limit: usize) -> Self {
+        Self {
+            limit,
+            offset: 0,
+            filters: SearchFilter::new(),
+            sort: SortBy::Relevance,
+            snippets: false,
+            highlight: false,
+        }
+    }
+
+    pub fn with_offset(mut self, offset: usize) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    pub fn with_filters(mut self, filters: SearchFilter) -> Self {
+        self.filters = filters;
+        self
+    }
+
+    pub fn with_sort(mut self, sort: SortBy) -> Self {
+        self.sort = sort;
+        self
+    }
+
+    pub fn with_snippets(mut self, snippets: bool, highlight: bool) -> Self {
+        self.snippets = snippets;
+        self.highlight = highlight;
+        self
+    }
+}
+
+/// Number of matching documents per facet value, most frequent first
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct FacetCounts {
+    pub domains: Vec<(String, u64)>,
+    pub languages: Vec<(String, u64)>,
+    /// Always low, medium, high in that order
+    pub quality_buckets: Vec<(String, u64)>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FacetedSearchResponse {
+    pub results: Vec<SearchResult>,
+    pub facets: FacetCounts,
 }
 
 pub struct SearchQuery {
     index: Index,
     reader: IndexReader,
     search_schema: SearchSchema,
+    config: SearchConfig,
+    // corpus keyed by url hash, as built by `calculate-tfidf`
+    tfidf_reranker: Option<Arc<TfIdfCalculator>>,
 }
 
 impl SearchQuery {
     pub fn new(index_path: &Path) -> tantivy::Result<Self> {
-        let index = Index::open_in_dir(index_path)?;
+        let index = SearchSchema::open(index_path)?;
         let reader = index
             .reader_builder()
             .reload_policy(ReloadPolicy::OnCommitWithDelay)
@@ -43,44 +201,59 @@ impl SearchQuery {
             index,
             reader,
             search_schema,
+            config: SearchConfig::default(),
+            tfidf_reranker: None,
         })
     }
 
+    /// Use custom BM25 parameters, score weights and URL penalties. With a `tfidf_index_path`
+    /// the TF-IDF index saved there becomes the reranker, see `with_tfidf_reranker`
+    pub fn with_config(mut self, config: SearchConfig) -> Self {
+        if let Some(path) = &config.tfidf_index_path {
+            match TfIdfCalculator::load(Path::new(path)) {
+                Ok(calculator) => self.tfidf_reranker = Some(Arc::new(calculator)),
+                Err(e) => warn!("Searching without TF-IDF reranking, could not load {}: {}", path, e),
+            }
+        }
+        self.config = config;
+        self
+    }
+
+    /// Rerank retrieved candidates by their query-time TF-IDF similarity.
+    /// The calculator's documents must be keyed by url hash.
+    pub fn with_tfidf_reranker(mut self, calculator: Arc<TfIdfCalculator>) -> Self {
+        self.tfidf_reranker = Some(calculator);
+        self
+    }
+
     pub fn search(&self, query_str: &str, limit: usize) -> tantivy::Result<Vec<SearchResult>> {
-        self.search_with_filters(
-            query_str,
-            limit,
-            SearchFilter::new(),
-            SortBy::Relevance,
-            0,
-            false,
-            false
-        )
+        self.search_with_filters(query_str, &SearchOptions::new(limit))
+    }
+
+    pub fn search_with_filters(&self, query_str: &str, options: &SearchOptions) -> tantivy::Result<Vec<SearchResult>> {
+        self.search_with_searcher(&self.reader.searcher(), query_str, options)
+    }
+
+    /// Run every query against one searcher, results in the order of `queries`. Saves the
+    /// per-query reader setup of `search_with_filters` for bulk workloads like evaluations.
+    pub fn batch_search(&self, queries: &[(String, SearchOptions)]) -> tantivy::Result<Vec<Vec<SearchResult>>> {
+        let searcher = self.reader.searcher();
+        queries
+            .iter()
+            .map(|(query_str, options)| self.search_with_searcher(&searcher, query_str, options))
+            .collect()
     }
 
-    pub fn search_with_filters(
+    fn search_with_searcher(
         &self,
+        searcher: &tantivy::Searcher,
         query_str: &str,
-        limit: usize,
-        filters: SearchFilter,
-        sort_by: SortBy,
-        offset: usize,
-        generate_snippets: bool,
-        highlight: bool,
+        options: &SearchOptions,
     ) -> tantivy::Result<Vec<SearchResult>> {
-        let searcher = self.reader.searcher();
-
-        // Create query parser
-        let query_parser = QueryParser::for_index(
-            &self.index,
-            vec![
-                self.search_schema.title_field,
-                self.search_schema.content_field,
-                self.search_schema.url_field,
-            ],
-        );
+        let SearchOptions { limit, offset, ref filters, sort: sort_by, snippets: generate_snippets, highlight } = *options;
 
-        let query = query_parser.parse_query(query_str)?;
+        let query = self.with_languages(self.with_date_range(self.build_query(query_str, filters)?, filters), filters);
+        let query = self.with_word_count_range(query, filters);
 
         // Fetch more results for filtering
         let fetch_limit = if filters.has_filters() {
@@ -92,6 +265,14 @@ impl SearchQuery {
         // Search and get top results
         let top_docs = searcher.search(&query, &TopDocs::with_limit(fetch_limit))?;
 
+        // Tantivy retrieves candidates with its fixed BM25 parameters,
+        // custom k1/b only change how those candidates are scored
+        let bm25 = if Bm25Scorer::is_default(self.config.bm25_k1, self.config.bm25_b) {
+            None
+        } else {
+            Some(Bm25Scorer::for_query(searcher, query.as_ref(), self.config.bm25_k1, self.config.bm25_b)?)
+        };
+
         // Prepare snippet generator
         let snippet_gen = SnippetGenerator::new();
         let query_terms = SnippetGenerator::extract_terms(query_str);
@@ -122,6 +303,12 @@ impl SearchQuery {
                 .and_then(|v| v.as_f64())
                 .unwrap_or(0.0);
 
+            let crawled_at = retrieved_doc
+                .get_first(self.search_schema.crawled_at_field)
+                .and_then(|v| v.as_u64())
+                .and_then(|timestamp| chrono::DateTime::from_timestamp(timestamp as i64, 0))
+                .map(|date| date.to_rfc3339());
+
             // 🔥 NEW: Extract PageRank from index
             let pagerank = retrieved_doc
                 .get_first(self.search_schema.pagerank_field)
@@ -129,7 +316,7 @@ impl SearchQuery {
                 .unwrap_or(0.0);
 
             // Generate snippet if requested
-            let snippet = if generate_snippets {
+            let (snippet, snippets, highlight_positions, structured_snippet) = if generate_snippets {
                 let content_opt = retrieved_doc
                     .get_first(self.search_schema.content_field)
                     .and_then(|v| v.as_str());
@@ -137,17 +324,22 @@ impl SearchQuery {
                 match content_opt {
                     Some(content) => {
                         eprintln!("Content retrieved, length: {}", content.len());
-                        let snippet_text = snippet_gen.generate(content, &query_terms, highlight);
+                        let (snippet_text, spans) = snippet_gen.generate_with_positions(content, &query_terms, highlight);
                         eprintln!("Snippet generated, length: {}", snippet_text.len());
-                        Some(snippet_text)
+                        let passages = snippet_gen.generate_multi_query(content, &query_terms, MAX_SNIPPETS, highlight);
+                        let description = retrieved_doc
+                            .get_first(self.search_schema.description_field)
+                            .and_then(|v| v.as_str());
+                        let structured = snippet_gen.structured(title.as_deref(), description, snippet_text.clone(), &query_terms, highlight);
+                        (Some(snippet_text), passages, spans, Some(structured))
                     }
                     None => {
                         eprintln!("Content field is EMPTY or not stored in index!");
-                        None
+                        (None, Vec::new(), Vec::new(), None)
                     }
                 }
             } else {
-                None
+                (None, Vec::new(), Vec::new(), None)
             };
 
             // Apply filters
@@ -169,21 +361,17 @@ impl SearchQuery {
                 }
             }
 
-            // NEW: Calculate combined score
-            // Formula: 60% relevance + 40% PageRank (scaled)
-            // Note: PageRank is typically 0.0-0.2, so we scale it by 100
-            let pagerank_scaled = pagerank * 100.0;
-
             let tfidf = retrieved_doc
                 .get_first(self.search_schema.tfidf_field)
                 .and_then(|v| v.as_f64())
                 .unwrap_or(0.0);
 
-            let tfidf_sealed = tfidf * 100.0;
-
-            let penalty = SearchQuery::utility_penalty(&url);
+            let text_score = match &bm25 {
+                Some(scorer) => scorer.score(searcher, doc_address)?,
+                None => tantivy_score,
+            };
 
-            let combined_score = ((tantivy_score as f64 * 0.6) + (pagerank_scaled * 0.25) + (tfidf_sealed * 0.15)) * penalty;
+            let combined_score = self.combined_score(query_str, &url, text_score, pagerank, tfidf, quality_score);
 
             results.push(SearchResult {
                 url,
@@ -193,8 +381,11 @@ impl SearchQuery {
                 score: combined_score as f32,  // Use combined score
                 pagerank,  // Store PageRank separately
                 tfidf,
-                crawled_at: None,
+                crawled_at,
                 snippet,
+                snippets,
+                highlight_positions,
+                structured_snippet,
             });
         }
 
@@ -202,11 +393,7 @@ impl SearchQuery {
         match sort_by {
             SortBy::Relevance => {
                 // Sort by combined score (already calculated above)
-                results.sort_by(|a, b| {
-                    b.score
-                        .partial_cmp(&a.score)
-                        .unwrap_or(std::cmp::Ordering::Equal)
-                });
+                results.sort_by(Self::relevance_order);
             }
             SortBy::Quality => {
                 // Sort by quality_score descending
@@ -229,7 +416,8 @@ impl SearchQuery {
             }
 
             SortBy::Date => {
-                // TODO: Sort by crawled_at when we add it to index
+                // newest first, RFC 3339 UTC timestamps sort chronologically as strings
+                results.sort_by(|a, b| b.crawled_at.cmp(&a.crawled_at));
             }
         }
 
@@ -244,28 +432,1348 @@ impl SearchQuery {
         Ok(paginated)
     }
 
-    fn utility_penalty(url: &str) -> f64 {
-        if url.contains("action=edit") || url.contains("action=history") || url.contains("/Special:") {
-            0.85 // stronger penalty
-        } else if url.contains("#") {
-            0.95 // mild penalty for section anchors
+    /// Search plus domain, language and quality-bucket counts over every match of the query.
+    /// Facets ignore the request's filters so they show what narrowing would leave.
+    pub fn search_with_facets(&self, query_str: &str, request: FacetedSearchRequest) -> tantivy::Result<FacetedSearchResponse> {
+        let searcher = self.reader.searcher();
+        let query = self.build_query(query_str, &request.filters)?;
+
+        let facet_collector = |name: &str| {
+            let mut collector = FacetCollector::for_field(name);
+            collector.add_facet("/");
+            collector
+        };
+        let (domain_counts, language_counts, quality_counts) = searcher.search(
+            &query,
+            &(facet_collector("domain_facet"), facet_collector("language_facet"), facet_collector("quality_facet")),
+        )?;
+
+        let top = |counts: &tantivy::collector::FacetCounts| -> Vec<(String, u64)> {
+            counts
+                .top_k("/", request.facet_limit)
+                .into_iter()
+                .map(|(facet, count)| (Self::facet_label(facet), count))
+                .collect()
+        };
+
+        let facets = FacetCounts {
+            domains: top(&domain_counts),
+            languages: top(&language_counts),
+            quality_buckets: ["low", "medium", "high"]
+                .iter()
+                .map(|bucket| {
+                    let count = quality_counts
+                        .get("/")
+                        .find(|(facet, _)| Self::facet_label(facet) == *bucket)
+                        .map(|(_, count)| count)
+                        .unwrap_or(0);
+                    (bucket.to_string(), count)
+                })
+                .collect(),
+        };
+
+        let options = SearchOptions::new(request.limit)
+            .with_offset(request.offset)
+            .with_filters(request.filters)
+            .with_sort(request.sort_by)
+            .with_snippets(request.snippets, request.highlight);
+        let results = self.search_with_filters(query_str, &options)?;
+
+        Ok(FacetedSearchResponse { results, facets })
+    }
+
+    fn facet_label(facet: &tantivy::schema::Facet) -> String {
+        facet.to_path().last().map(|s| s.to_string()).unwrap_or_default()
+    }
+
+    /// Titles starting with `prefix`, most authoritative (highest PageRank) first
+    pub fn suggest(&self, prefix: &str, limit: usize) -> tantivy::Result<Vec<String>> {
+        let prefix: String = prefix.trim_start().to_lowercase().chars().take(AUTOCOMPLETE_MAX_GRAM).collect();
+        if prefix.is_empty() || limit == 0 {
+            return Ok(Vec::new());
+        }
+
+        let searcher = self.reader.searcher();
+        let term = Term::from_field_text(self.search_schema.title_autocomplete_field, &prefix);
+        let query = TermQuery::new(term, IndexRecordOption::Basic);
+
+        // pages often share a title, so look past `limit` to fill it with distinct ones
+        let collector = TopDocs::with_limit(limit * 4).order_by_fast_field::<f64>("pagerank", Order::Desc);
+        let top_docs = searcher.search(&query, &collector)?;
+
+        let mut suggestions: Vec<String> = Vec::with_capacity(limit);
+        for (_pagerank, doc_address) in top_docs {
+            let doc: tantivy::TantivyDocument = searcher.doc(doc_address)?;
+            let Some(title) = doc.get_first(self.search_schema.title_field).and_then(|v| v.as_str()) else {
+                continue;
+            };
+
+            let title = percent_decode_str(title).decode_utf8_lossy().trim().to_string();
+            if !suggestions.contains(&title) {
+                suggestions.push(title);
+            }
+            if suggestions.len() == limit {
+                break;
+            }
+        }
+
+        Ok(suggestions)
+    }
+
+    /// Typo-tolerant search: every query token matches terms within `max_distance` edits
+    pub fn fuzzy_search(&self, query_str: &str, max_distance: u8, limit: usize) -> tantivy::Result<Vec<SearchResult>> {
+        let options = SearchOptions::new(limit).with_filters(SearchFilter::new().with_fuzzy_distance(max_distance));
+        self.search_with_filters(query_str, &options)
+    }
+
+    // Parse the query string, or build a fuzzy query when the filter asks for one
+    fn build_query(&self, query_str: &str, filters: &SearchFilter) -> tantivy::Result<Box<dyn Query>> {
+        if let Some(distance) = filters.fuzzy_distance {
+            return Ok(self.build_fuzzy_query(query_str, distance));
+        }
+
+        if filters.advanced_syntax {
+            return self.parse_advanced_query(query_str);
+        }
+
+        if query_str.contains('"') {
+            return Ok(self.build_phrase_query(query_str));
+        }
+
+        if !Self::uses_parser_syntax(query_str) {
+            return Ok(self.build_boosted_query(query_str, &self.config.boosts));
+        }
+
+        // Tantivy's syntax (+must, -not, field:term, ranges), with the same field boosts
+        let boosts = &self.config.boosts;
+        let fields = self.boosted_fields(boosts);
+        let mut query_parser = QueryParser::for_index(&self.index, fields.iter().map(|(field, _)| *field).collect());
+        for (field, boost) in fields {
+            query_parser.set_field_boost(field, boost);
+        }
+
+        Ok(query_parser.parse_query(query_str)?)
+    }
+
+    /// Match any query word in the title, content, url or description. Each field's matches
+    /// are scored times its boost, so with the default boosts a word in the title outweighs
+    /// the same word in the content. Words are split by each field's tokenizer.
+    pub fn build_boosted_query(&self, query_str: &str, boosts: &SearchSchemaBoosts) -> Box<dyn Query> {
+        let mut clauses: Vec<(Occur, Box<dyn Query>)> = Vec::new();
+
+        for (field, boost) in self.boosted_fields(boosts) {
+            let Ok(mut tokenizer) = self.index.tokenizer_for_field(field) else {
+                continue;
+            };
+            let mut stream = tokenizer.token_stream(query_str);
+            while stream.advance() {
+                let term = Term::from_field_text(field, &stream.token().text);
+                let query = TermQuery::new(term, IndexRecordOption::WithFreqs);
+                clauses.push((Occur::Should, Box::new(BoostQuery::new(Box::new(query), boost))));
+            }
+        }
+
+        if clauses.is_empty() {
+            return Box::new(EmptyQuery);
+        }
+        Box::new(BooleanQuery::new(clauses))
+    }
+
+    // Searched text fields and their boosts, fields boosted to 0 are left out
+    fn boosted_fields(&self, boosts: &SearchSchemaBoosts) -> Vec<(Field, f32)> {
+        let schema = &self.search_schema;
+        [
+            (schema.title_field, boosts.title_boost),
+            (schema.content_field, boosts.content_boost),
+            (schema.url_field, boosts.url_boost),
+            (schema.description_field, boosts.description_boost),
+        ]
+        .into_iter()
+        .filter(|(_, boost)| *boost > 0.0)
+        .collect()
+    }
+
+    // Queries with operators or field prefixes go to Tantivy's QueryParser
+    fn uses_parser_syntax(query_str: &str) -> bool {
+        query_str.contains([':', '+', '-', '*', '^', '[', '{', '('])
+            || query_str.split_whitespace().any(|word| matches!(word, "AND" | "OR" | "NOT" | "IN"))
+    }
+
+    /// Parse a query written in the boolean syntax below. Queries that aren't valid syntax,
+    /// e.g. with an unclosed parenthesis, fall back to matching any of their words.
+    ///
+    /// # Query syntax guide
+    ///
+    /// | Syntax | Matches |
+    /// |---|---|
+    /// | `rust crawler`, `rust AND crawler` | pages with both words |
+    /// | `rust OR go` | pages with either word |
+    /// | `rust NOT go`, `rust AND NOT go` | pages with `rust` but without `go` |
+    /// | `"web crawler"` | the words next to each other, in this order |
+    /// | `(rust OR go) AND crawler` | parentheses group sub-queries |
+    /// | `title:rust`, `title:"web crawler"`, `title:(rust OR go)` | only in that field |
+    ///
+    /// Operators must be upper case, `and` is searched for as a word. `NOT` binds tighter
+    /// than `AND`, which binds tighter than `OR`. Words without a field are looked up in the
+    /// title, content and url. Fields are `title`, `content`, `url`, `domain` (e.g.
+    /// `domain:example.com`) and `language` or `lang` (an ISO 639-1 code like `language:hi`);
+    /// any other `name:value` is searched for as ordinary text.
+    pub fn parse_advanced_query(&self, query_str: &str) -> tantivy::Result<Box<dyn Query>> {
+        match query_syntax::parse(query_str) {
+            Ok(node) => self.node_query(&node),
+            Err(reason) => {
+                info!("Falling back to a plain word search for '{}': {}", query_str, reason);
+                let words: Vec<&str> = query_str
+                    .split(|c: char| c.is_whitespace() || matches!(c, '"' | '(' | ')'))
+                    .filter(|word| !matches!(*word, "AND" | "OR" | "NOT"))
+                    .collect();
+                Ok(self.build_phrase_query(&words.join(" ")))
+            }
+        }
+    }
+
+    fn node_query(&self, node: &QueryNode) -> tantivy::Result<Box<dyn Query>> {
+        Ok(match node {
+            QueryNode::Term { field, text } | QueryNode::Phrase { field, text } => self.field_text_query(field.as_deref(), text)?,
+            QueryNode::And(nodes) => {
+                let mut clauses = Vec::with_capacity(nodes.len());
+                for node in nodes {
+                    clauses.push(match node {
+                        QueryNode::Not(negated) => (Occur::MustNot, self.node_query(negated)?),
+                        _ => (Occur::Must, self.node_query(node)?),
+                    });
+                }
+                // a BooleanQuery of only MustNot clauses matches nothing
+                if clauses.iter().all(|(occur, _)| *occur == Occur::MustNot) {
+                    clauses.push((Occur::Must, Box::new(AllQuery)));
+                }
+                Box::new(BooleanQuery::new(clauses))
+            }
+            QueryNode::Or(nodes) => {
+                let mut clauses = Vec::with_capacity(nodes.len());
+                for node in nodes {
+                    clauses.push((Occur::Should, self.node_query(node)?));
+                }
+                Box::new(BooleanQuery::new(clauses))
+            }
+            QueryNode::Not(negated) => Box::new(BooleanQuery::new(vec![
+                (Occur::Must, Box::new(AllQuery) as Box<dyn Query>),
+                (Occur::MustNot, self.node_query(negated)?),
+            ])),
+        })
+    }
+
+    // The text tokenized like the field was indexed, several tokens have to match as a phrase
+    fn field_text_query(&self, field_name: Option<&str>, text: &str) -> tantivy::Result<Box<dyn Query>> {
+        let schema = &self.search_schema;
+        let (fields, text) = match field_name {
+            None => (vec![schema.title_field, schema.content_field, schema.url_field], text.to_string()),
+            Some("title") => (vec![schema.title_field], text.to_string()),
+            Some("content") => (vec![schema.content_field], text.to_string()),
+            Some("url") => (vec![schema.url_field], text.to_string()),
+            Some("domain") => (vec![schema.domain_field], text.to_string()),
+            // stored lower case but not tokenized
+            Some("language" | "lang") => (vec![schema.language_field], text.to_lowercase()),
+            Some(other) => (vec![schema.title_field, schema.content_field, schema.url_field], format!("{}:{}", other, text)),
+        };
+
+        let mut per_field: Vec<(Occur, Box<dyn Query>)> = Vec::new();
+        for field in fields {
+            let mut tokenizer = self.index.tokenizer_for_field(field)?;
+            let mut stream = tokenizer.token_stream(&text);
+            let mut words = Vec::new();
+            while stream.advance() {
+                words.push(stream.token().text.clone());
+            }
+            if !words.is_empty() {
+                per_field.push((Occur::Should, Self::phrase_for_field(field, &words, 0)));
+            }
+        }
+
+        Ok(match per_field.len() {
+            0 => Box::new(EmptyQuery),
+            1 => per_field.remove(0).1,
+            _ => Box::new(BooleanQuery::new(per_field)),
+        })
+    }
+
+    // Keep only pages crawled within the filter's date range, without changing scores
+    fn with_date_range(&self, query: Box<dyn Query>, filters: &SearchFilter) -> Box<dyn Query> {
+        if filters.crawled_after.is_none() && filters.crawled_before.is_none() {
+            return query;
+        }
+
+        let bound = |date: Option<chrono::DateTime<chrono::Utc>>| match date {
+            Some(date) => Bound::Included(Term::from_field_u64(
+                self.search_schema.crawled_at_field,
+                SearchSchema::timestamp(date),
+            )),
+            None => Bound::Unbounded,
+        };
+        let range = RangeQuery::new(bound(filters.crawled_after), bound(filters.crawled_before));
+
+        Box::new(BooleanQuery::new(vec![
+            (Occur::Must, query),
+            (Occur::Must, Box::new(ConstScoreQuery::new(Box::new(range), 0.0))),
+        ]))
+    }
+
+    // Keep only pages within the filter's word counts, without changing scores
+    fn with_word_count_range(&self, query: Box<dyn Query>, filters: &SearchFilter) -> Box<dyn Query> {
+        if filters.min_word_count.is_none() && filters.max_word_count.is_none() {
+            return query;
+        }
+
+        let bound = |words: Option<usize>| match words {
+            Some(words) => Bound::Included(Term::from_field_u64(self.search_schema.word_count_field, words as u64)),
+            None => Bound::Unbounded,
+        };
+        let range = RangeQuery::new(bound(filters.min_word_count), bound(filters.max_word_count));
+
+        Box::new(BooleanQuery::new(vec![
+            (Occur::Must, query),
+            (Occur::Must, Box::new(ConstScoreQuery::new(Box::new(range), 0.0))),
+        ]))
+    }
+
+    // Keep only pages in one of the filter's languages, without changing scores
+    fn with_languages(&self, query: Box<dyn Query>, filters: &SearchFilter) -> Box<dyn Query> {
+        if filters.languages.is_empty() {
+            return query;
+        }
+
+        let languages: Vec<(Occur, Box<dyn Query>)> = filters.languages
+            .iter()
+            .map(|language| {
+                let term = Term::from_field_text(self.search_schema.language_field, &language.to_lowercase());
+                (Occur::Should, Box::new(TermQuery::new(term, IndexRecordOption::Basic)) as Box<dyn Query>)
+            })
+            .collect();
+
+        Box::new(BooleanQuery::new(vec![
+            (Occur::Must, query),
+            (Occur::Must, Box::new(ConstScoreQuery::new(Box::new(BooleanQuery::new(languages)), 0.0))),
+        ]))
+    }
+
+    /// Match `phrase` as consecutive words, allowing up to `slop` positions between them
+    pub fn phrase_search(&self, phrase: &str, slop: u32, limit: usize) -> tantivy::Result<Vec<SearchResult>> {
+        let query_str = format!("\"{}\"~{}", phrase.replace('"', ""), slop);
+        self.search(&query_str, limit)
+    }
+
+    // Quoted sections (optionally followed by ~slop) must match as phrases,
+    // the remaining words are optional terms that only improve the score
+    fn build_phrase_query(&self, query_str: &str) -> Box<dyn Query> {
+        let (phrases, free_text) = Self::split_phrases(query_str);
+        let mut clauses: Vec<(Occur, Box<dyn Query>)> = Vec::new();
+
+        for (phrase, slop) in phrases {
+            let words: Vec<String> = SnippetGenerator::extract_terms(&phrase)
+                .into_iter()
+                .map(|w| w.to_lowercase())
+                .collect();
+            if words.is_empty() {
+                continue;
+            }
+
+            let per_field: Vec<(Occur, Box<dyn Query>)> = self.text_fields()
+                .into_iter()
+                .map(|field| (Occur::Should, Self::phrase_for_field(field, &words, slop)))
+                .collect();
+            clauses.push((Occur::Must, Box::new(BooleanQuery::new(per_field))));
+        }
+
+        for word in SnippetGenerator::extract_terms(&free_text) {
+            let word = word.to_lowercase();
+            for field in self.text_fields() {
+                let term = Term::from_field_text(field, &word);
+                clauses.push((Occur::Should, Box::new(TermQuery::new(term, IndexRecordOption::WithFreqs))));
+            }
+        }
+
+        Box::new(BooleanQuery::new(clauses))
+    }
+
+    fn phrase_for_field(field: Field, words: &[String], slop: u32) -> Box<dyn Query> {
+        let terms: Vec<Term> = words.iter()
+            .map(|w| Term::from_field_text(field, w))
+            .collect();
+
+        // PhraseQuery needs at least two terms
+        if terms.len() == 1 {
+            return Box::new(TermQuery::new(terms[0].clone(), IndexRecordOption::WithFreqs));
+        }
+
+        let mut phrase = PhraseQuery::new(terms);
+        phrase.set_slop(slop);
+        Box::new(phrase)
+    }
+
+    // Split `"web crawler"~1 rust` into ([("web crawler", 1)], " rust")
+    fn split_phrases(query_str: &str) -> (Vec<(String, u32)>, String) {
+        let mut phrases = Vec::new();
+        let mut free_text = String::new();
+        let mut rest = query_str;
+
+        while let Some(start) = rest.find('"') {
+            free_text.push_str(&rest[..start]);
+            let after_quote = &rest[start + 1..];
+
+            let Some(end) = after_quote.find('"') else {
+                // unbalanced quote, treat the remainder as plain words
+                free_text.push(' ');
+                free_text.push_str(after_quote);
+                rest = "";
+                break;
+            };
+
+            let phrase = after_quote[..end].to_string();
+            rest = &after_quote[end + 1..];
+
+            let mut slop = 0;
+            if let Some(tail) = rest.strip_prefix('~') {
+                let digits: String = tail.chars().take_while(|c| c.is_ascii_digit()).collect();
+                slop = digits.parse().unwrap_or(0);
+                rest = &tail[digits.len()..];
+            }
+
+            phrases.push((phrase, slop));
+            free_text.push(' ');
+        }
+        free_text.push_str(rest);
+
+        (phrases, free_text)
+    }
+
+    fn text_fields(&self) -> Vec<Field> {
+        vec![self.search_schema.title_field, self.search_schema.content_field]
+    }
+
+    // One FuzzyTermQuery per token and field, any of which may match
+    fn build_fuzzy_query(&self, query_str: &str, max_distance: u8) -> Box<dyn Query> {
+        // Tantivy only ships Levenshtein automata up to distance 2
+        let distance = max_distance.min(2);
+        let fields = [
+            self.search_schema.title_field,
+            self.search_schema.content_field,
+            self.search_schema.url_field,
+        ];
+
+        let clauses: Vec<(Occur, Box<dyn Query>)> = SnippetGenerator::extract_terms(query_str)
+            .into_iter()
+            .map(|token| token.to_lowercase())
+            .flat_map(|token| {
+                fields.iter().map(move |&field| {
+                    let term = Term::from_field_text(field, &token);
+                    let fuzzy: Box<dyn Query> = Box::new(FuzzyTermQuery::new(term, distance, true));
+                    (Occur::Should, fuzzy)
+                })
+            })
+            .collect();
+
+        Box::new(BooleanQuery::new(clauses))
+    }
+
+    /// Cursor-based alternative to offset pagination, like Elasticsearch's search_after.
+    /// Returns the results ranked strictly after the (score, url) of the last hit seen.
+    pub fn search_after(
+        &self,
+        query_str: &str,
+        limit: usize,
+        filters: SearchFilter,
+        score: f32,
+        url: &str,
+    ) -> tantivy::Result<Vec<SearchResult>> {
+        // Combined scores are computed after retrieval, so rank every match
+        let query = self.build_query(query_str, &filters)?;
+        let total_hits = self.reader.searcher().search(&query, &Count)?;
+
+        let results = self.search_with_filters(query_str, &SearchOptions::new(total_hits.max(1)).with_filters(filters))?;
+
+        Ok(results
+            .into_iter()
+            .filter(|r| r.score < score || (r.score == score && r.url.as_str() > url))
+            .take(limit)
+            .collect())
+    }
+
+    /// Score breakdown of the page at `url` for `query_str`, as `search` ranks it without filters.
+    /// Fails if the page isn't indexed; a page the query doesn't match is explained with a zero text score.
+    pub fn explain(&self, query_str: &str, url: &str) -> tantivy::Result<ScoreExplanation> {
+        let searcher = self.reader.searcher();
+        let query = self.build_query(query_str, &SearchFilter::new())?;
+
+        let url_query = TermQuery::new(self.search_schema.url_term(url), IndexRecordOption::Basic);
+        let doc_address = searcher
+            .search(&url_query, &TopDocs::with_limit(1))?
+            .into_iter()
+            .next()
+            .map(|(_, address)| address)
+            .ok_or_else(|| TantivyError::InvalidArgument(format!("'{}' is not indexed", url)))?;
+        let doc: tantivy::TantivyDocument = searcher.doc(doc_address)?;
+
+        let (tantivy_score, tantivy_explanation, matched) = match query.explain(&searcher, doc_address) {
+            Ok(explanation) => (explanation.value(), explanation.to_pretty_json(), true),
+            // tantivy reports a document the query doesn't match as an invalid argument
+            Err(TantivyError::InvalidArgument(reason)) => (0.0, reason, false),
+            Err(e) => return Err(e),
+        };
+
+        let text_score = if matched && !Bm25Scorer::is_default(self.config.bm25_k1, self.config.bm25_b) {
+            Bm25Scorer::for_query(&searcher, query.as_ref(), self.config.bm25_k1, self.config.bm25_b)?
+                .score(&searcher, doc_address)?
         } else {
-            1.0
+            tantivy_score
+        };
+
+        let field = |field| doc.get_first(field).and_then(|v| v.as_f64()).unwrap_or(0.0);
+        let pagerank = field(self.search_schema.pagerank_field);
+        let tfidf = field(self.search_schema.tfidf_field);
+        let quality = field(self.search_schema.quality_field);
+        let indexed_url = doc
+            .get_first(self.search_schema.url_field)
+            .and_then(|v| v.as_str())
+            .unwrap_or(url)
+            .to_string();
+
+        Ok(ScoreExplanation {
+            combined_score: self.combined_score(query_str, &indexed_url, text_score, pagerank, tfidf, quality) as f32,
+            url_penalty: self.utility_penalty(&indexed_url),
+            url: indexed_url,
+            tantivy_score,
+            pagerank,
+            tfidf,
+            quality,
+            tantivy_explanation,
+        })
+    }
+
+    /// Pages sharing the most distinctive vocabulary with the indexed page at `url`, most similar first.
+    /// Its top TF-IDF terms come from the reranker corpus when it has the page, otherwise from a
+    /// corpus built over every indexed document. Fails if the page isn't indexed.
+    pub fn search_similar(&self, url: &str, limit: usize) -> tantivy::Result<Vec<SearchResult>> {
+        self.similar_to(self.search_schema.url_term(url), url, limit)
+    }
+
+    /// `search_similar` for the page with this `StoredPage::url_hash`
+    pub fn search_similar_by_hash(&self, url_hash: &str, limit: usize) -> tantivy::Result<Vec<SearchResult>> {
+        self.similar_to(Term::from_field_text(self.search_schema.url_hash_field, url_hash), url_hash, limit)
+    }
+
+    // More-like-this query from the source page's top terms, the page itself excluded
+    fn similar_to(&self, source: Term, label: &str, limit: usize) -> tantivy::Result<Vec<SearchResult>> {
+        let searcher = self.reader.searcher();
+        let source_address = searcher
+            .search(&TermQuery::new(source.clone(), IndexRecordOption::Basic), &TopDocs::with_limit(1))?
+            .into_iter()
+            .next()
+            .map(|(_, address)| address)
+            .ok_or_else(|| TantivyError::InvalidArgument(format!("'{}' is not indexed", label)))?;
+        let source_doc: tantivy::TantivyDocument = searcher.doc(source_address)?;
+        let source_url = source_doc
+            .get_first(self.search_schema.url_field)
+            .and_then(|v| v.as_str())
+            .unwrap_or("")
+            .to_string();
+
+        let top_terms = self.similarity_terms(&searcher, &source_doc, &source_url)?;
+        let Some(max_score) = top_terms.first().map(|(_, score)| *score) else {
+            return Ok(Vec::new());
+        };
+
+        // rarer, more frequent terms of the source weigh more
+        let mut clauses: Vec<(Occur, Box<dyn Query>)> = Vec::new();
+        for (word, score) in &top_terms {
+            for field in self.text_fields() {
+                let Ok(mut tokenizer) = self.index.tokenizer_for_field(field) else {
+                    continue;
+                };
+                let mut stream = tokenizer.token_stream(word);
+                while stream.advance() {
+                    let query = TermQuery::new(Term::from_field_text(field, &stream.token().text), IndexRecordOption::WithFreqs);
+                    clauses.push((Occur::Should, Box::new(BoostQuery::new(Box::new(query), (score / max_score) as f32))));
+                }
+            }
+        }
+        if clauses.is_empty() {
+            return Ok(Vec::new());
+        }
+        clauses.push((Occur::MustNot, Box::new(TermQuery::new(source, IndexRecordOption::Basic))));
+
+        let top_docs = searcher.search(&BooleanQuery::new(clauses), &TopDocs::with_limit(limit))?;
+        let mut results = Vec::with_capacity(top_docs.len());
+        for (score, doc_address) in top_docs {
+            let doc: tantivy::TantivyDocument = searcher.doc(doc_address)?;
+            results.push(self.plain_result(&doc, score));
         }
+
+        info!("🔍 Found {} pages similar to {}", results.len(), source_url);
+        Ok(results)
+    }
+
+    // Top TF-IDF terms of the page, IDF taken from the index's document frequencies
+    fn similarity_terms(&self, searcher: &tantivy::Searcher, source_doc: &tantivy::TantivyDocument, url: &str) -> tantivy::Result<Vec<(String, f64)>> {
+        let doc_id = PageRepository::calculate_url_hash(url);
+        if let Some(tfidf) = self.tfidf_reranker.as_ref().filter(|tfidf| tfidf.contains_document(&doc_id)) {
+            return Ok(tfidf.get_top_terms(&doc_id, SIMILAR_TERMS));
+        }
+
+        let field = self.search_schema.content_field;
+        let content = source_doc.get_first(field).and_then(|v| v.as_str()).unwrap_or("");
+        let mut tokenizer = self.index.tokenizer_for_field(field)?;
+        let mut counts: HashMap<String, usize> = HashMap::new();
+        let mut total = 0usize;
+        let mut stream = tokenizer.token_stream(content);
+        while stream.advance() {
+            *counts.entry(stream.token().text.clone()).or_insert(0) += 1;
+            total += 1;
+        }
+
+        let num_docs = searcher.num_docs() as f64;
+        let mut scores = Vec::with_capacity(counts.len());
+        for (term, count) in counts {
+            let doc_freq = searcher.doc_freq(&Term::from_field_text(field, &term))?;
+            if doc_freq == 0 {
+                continue;
+            }
+            let score = count as f64 / total as f64 * (num_docs / doc_freq as f64).ln();
+            if score > 0.0 {
+                scores.push((term, score));
+            }
+        }
+
+        scores.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        scores.truncate(SIMILAR_TERMS);
+        Ok(scores)
+    }
+
+    // Result with the indexed fields as they are, scored by `score`
+    fn plain_result(&self, doc: &tantivy::TantivyDocument, score: f32) -> SearchResult {
+        let text = |field| doc.get_first(field).and_then(|v| v.as_str()).map(String::from);
+        let number = |field| doc.get_first(field).and_then(|v| v.as_f64()).unwrap_or(0.0);
+
+        SearchResult {
+            url: text(self.search_schema.url_field).unwrap_or_default(),
+            title: text(self.search_schema.title_field),
+            domain: text(self.search_schema.domain_field).unwrap_or_default(),
+            quality_score: number(self.search_schema.quality_field),
+            score,
+            pagerank: number(self.search_schema.pagerank_field),
+            tfidf: number(self.search_schema.tfidf_field),
+            crawled_at: doc
+                .get_first(self.search_schema.crawled_at_field)
+                .and_then(|v| v.as_u64())
+                .and_then(|timestamp| chrono::DateTime::from_timestamp(timestamp as i64, 0))
+                .map(|date| date.to_rfc3339()),
+            snippet: None,
+            snippets: Vec::new(),
+            highlight_positions: Vec::new(),
+            structured_snippet: None,
+        }
+    }
+
+    // Combined ranking score from the configured weights
+    // (default: 60% relevance + 25% PageRank + 15% TF-IDF), times the url penalty
+    fn combined_score(&self, query_str: &str, url: &str, text_score: f32, pagerank: f64, tfidf: f64, quality: f64) -> f64 {
+        // Note: PageRank is typically 0.0-0.2, so we scale it by 100
+        let pagerank_scaled = pagerank * 100.0;
+        let tfidf_scaled = tfidf * 100.0;
+
+        // quality is 0.0-1.0, bring it to the same range as BM25
+        let quality_scaled = quality * 10.0;
+
+        // cosine similarity to this query is 0.0-1.0, scaled like quality
+        let query_tfidf_scaled = self.query_tfidf_similarity(query_str, url) * 10.0;
+
+        ((text_score as f64 * self.config.text_weight())
+            + (pagerank_scaled * self.config.pagerank_weight)
+            + (tfidf_scaled * self.config.tfidf_weight)
+            + (query_tfidf_scaled * self.config.query_tfidf_weight)
+            + (quality_scaled * self.config.quality_weight)) * self.utility_penalty(url)
+    }
+
+    // score descending, url ascending as a stable tie-breaker for search_after
+    fn relevance_order(a: &SearchResult, b: &SearchResult) -> std::cmp::Ordering {
+        b.score
+            .partial_cmp(&a.score)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then_with(|| a.url.cmp(&b.url))
+    }
+
+    // multiplier of the first configured pattern the url contains
+    fn utility_penalty(&self, url: &str) -> f64 {
+        self.config.url_penalties
+            .iter()
+            .find(|(pattern, _)| url.contains(pattern.as_str()))
+            .map(|(_, penalty)| *penalty)
+            .unwrap_or(1.0)
+    }
+
+    // 0.0 without a reranker or when the page isn't in its corpus
+    fn query_tfidf_similarity(&self, query_str: &str, url: &str) -> f64 {
+        self.tfidf_reranker
+            .as_ref()
+            .map(|tfidf| tfidf.query_document_similarity(query_str, &PageRepository::calculate_url_hash(url)))
+            .unwrap_or(0.0)
     }
 
     pub fn search_by_domain(&self, query_str: &str, domain: &str, limit: usize) -> tantivy::Result<Vec<SearchResult>> {
         let filters = SearchFilter::new().with_domain(domain.to_string());
-        self.search_with_filters(
-            query_str,
-            limit,
-            filters,
-            SortBy::Relevance,
-            0,
-            false,
-            false
-        )
+        self.search_with_filters(query_str, &SearchOptions::new(limit).with_filters(filters))
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_search_after_pages_through_results() {
+        let dir = tempdir().unwrap();
+        let index = SearchSchema::create_index(dir.path()).unwrap();
+        let schema = SearchSchema::build();
+
+        let mut writer: tantivy::IndexWriter = index.writer(15_000_000).unwrap();
+        for i in 0..5 {
+            let mut doc = tantivy::TantivyDocument::default();
+            doc.add_text(schema.url_field, format!("https://example.com/{}", i));
+            doc.add_text(schema.content_field, "rust crawler ".repeat(i + 1));
+            writer.add_document(doc).unwrap();
+        }
+        writer.commit().unwrap();
+
+        let search = SearchQuery::new(dir.path()).unwrap();
+        let all = search.search("rust", 10).unwrap();
+        assert_eq!(all.len(), 5);
+
+        let first_page = search.search("rust", 2).unwrap();
+        let last = first_page.last().unwrap();
+        let next_page = search
+            .search_after("rust", 2, SearchFilter::new(), last.score, &last.url)
+            .unwrap();
+
+        assert_eq!(next_page.len(), 2);
+        assert_eq!(next_page[0].url, all[2].url);
+        assert_eq!(next_page[1].url, all[3].url);
+    }
+
+    #[test]
+    fn test_search_results_carry_structured_snippets() {
+        let dir = tempdir().unwrap();
+        let index = SearchSchema::create_index(dir.path()).unwrap();
+        let schema = SearchSchema::build();
+
+        let mut writer: tantivy::IndexWriter = index.writer(15_000_000).unwrap();
+        let mut doc = tantivy::TantivyDocument::default();
+        schema.add_url(&mut doc, "https://example.com/guide");
+        schema.add_title(&mut doc, "Crawler guide");
+        doc.add_text(schema.description_field, "Everything about a crawler");
+        doc.add_text(schema.content_field, "A crawler follows links from page to page.");
+        writer.add_document(doc).unwrap();
+        writer.commit().unwrap();
+
+        let search = SearchQuery::new(dir.path()).unwrap();
+        let results = search
+            .search_with_filters("crawler", &SearchOptions::new(10).with_snippets(true, true))
+            .unwrap();
+        assert_eq!(results[0].structured_snippet, Some(StructuredSnippet {
+            title: Some("**Crawler** guide".to_string()),
+            description: Some("Everything about a **crawler**".to_string()),
+            body_snippet: "A **crawler** follows links from page to page.".to_string(),
+        }));
+
+        let results = search
+            .search_with_filters("crawler", &SearchOptions::new(10))
+            .unwrap();
+        assert_eq!(results[0].structured_snippet, None);
+    }
+
+    #[test]
+    fn test_batch_search_matches_single_queries() {
+        let dir = tempdir().unwrap();
+        let index = SearchSchema::create_index(dir.path()).unwrap();
+        let schema = SearchSchema::build();
+
+        let mut writer: tantivy::IndexWriter = index.writer(15_000_000).unwrap();
+        for i in 0..6 {
+            let mut doc = tantivy::TantivyDocument::default();
+            doc.add_text(schema.url_field, format!("https://example.com/{}", i));
+            doc.add_text(schema.content_field, format!("{} {}", "rust crawler ".repeat(i + 1), if i % 2 == 0 { "tantivy" } else { "" }));
+            writer.add_document(doc).unwrap();
+        }
+        writer.commit().unwrap();
+
+        let search = SearchQuery::new(dir.path()).unwrap();
+        let batch = search.batch_search(&[
+            ("rust".to_string(), SearchOptions::new(3)),
+            ("tantivy".to_string(), SearchOptions::new(10).with_offset(1)),
+            ("missing".to_string(), SearchOptions::new(10)),
+        ]).unwrap();
+
+        let urls = |results: &[SearchResult]| results.iter().map(|r| r.url.clone()).collect::<Vec<_>>();
+        assert_eq!(batch.len(), 3);
+        assert_eq!(urls(&batch[0]), urls(&search.search("rust", 3).unwrap()));
+        let single = search
+            .search_with_filters("tantivy", &SearchOptions::new(10).with_offset(1))
+            .unwrap();
+        assert_eq!(batch[1].len(), 2);
+        assert_eq!(urls(&batch[1]), urls(&single));
+        assert!(batch[2].is_empty());
+        assert!(search.batch_search(&[]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_fuzzy_search_tolerates_typos() {
+        let dir = tempdir().unwrap();
+        let index = SearchSchema::create_index(dir.path()).unwrap();
+        let schema = SearchSchema::build();
+
+        let mut writer: tantivy::IndexWriter = index.writer(15_000_000).unwrap();
+        let mut doc = tantivy::TantivyDocument::default();
+        doc.add_text(schema.url_field, "https://example.com/crawler");
+        doc.add_text(schema.content_field, "a fast web crawler written in rust");
+        writer.add_document(doc).unwrap();
+        writer.commit().unwrap();
+
+        let search = SearchQuery::new(dir.path()).unwrap();
+        assert!(search.search("crawlr", 10).unwrap().is_empty());
+
+        let results = search.fuzzy_search("crawlr", 1, 10).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].url, "https://example.com/crawler");
+    }
+
+    #[test]
+    fn test_phrase_search_respects_word_order() {
+        let dir = tempdir().unwrap();
+        let index = SearchSchema::create_index(dir.path()).unwrap();
+        let schema = SearchSchema::build();
+
+        let mut writer: tantivy::IndexWriter = index.writer(15_000_000).unwrap();
+        for (url, content) in [
+            ("https://example.com/a", "a web crawler written in rust"),
+            ("https://example.com/b", "the crawler walks the web"),
+            ("https://example.com/c", "a web scale crawler"),
+        ] {
+            let mut doc = tantivy::TantivyDocument::default();
+            doc.add_text(schema.url_field, url);
+            doc.add_text(schema.content_field, content);
+            writer.add_document(doc).unwrap();
+        }
+        writer.commit().unwrap();
+
+        let search = SearchQuery::new(dir.path()).unwrap();
+        assert_eq!(search.search("web crawler", 10).unwrap().len(), 3);
 
+        let exact = search.phrase_search("web crawler", 0, 10).unwrap();
+        assert_eq!(exact.len(), 1);
+        assert_eq!(exact[0].url, "https://example.com/a");
+
+        let sloppy = search.phrase_search("web crawler", 1, 10).unwrap();
+        let mut urls: Vec<&str> = sloppy.iter().map(|r| r.url.as_str()).collect();
+        urls.sort();
+        assert_eq!(urls, vec!["https://example.com/a", "https://example.com/c"]);
+
+        // Quoted phrase combined with a free term
+        let mixed = search.search("\"web crawler\" rust", 10).unwrap();
+        assert_eq!(mixed.len(), 1);
+    }
+
+    #[test]
+    fn test_split_phrases() {
+        let (phrases, free) = SearchQuery::split_phrases("\"web crawler\"~2 rust \"open source\"");
+        assert_eq!(phrases, vec![("web crawler".to_string(), 2), ("open source".to_string(), 0)]);
+        assert_eq!(free.split_whitespace().collect::<Vec<_>>(), vec!["rust"]);
+    }
+
+    #[test]
+    fn test_search_config_changes_ranking() {
+        let dir = tempdir().unwrap();
+        let index = SearchSchema::create_index(dir.path()).unwrap();
+        let schema = SearchSchema::build();
+
+        let mut writer: tantivy::IndexWriter = index.writer(15_000_000).unwrap();
+        for (url, content) in [
+            ("https://example.com/short", "rust crawler"),
+            ("https://example.com/long", "rust rust rust crawler with a much longer body of text about many other things"),
+        ] {
+            let mut doc = tantivy::TantivyDocument::default();
+            doc.add_text(schema.url_field, url);
+            doc.add_text(schema.content_field, content);
+            writer.add_document(doc).unwrap();
+        }
+        writer.commit().unwrap();
+
+        let default = SearchQuery::new(dir.path()).unwrap();
+        assert_eq!(default.search("rust", 10).unwrap()[0].url, "https://example.com/short");
+
+        // without length normalization the long document's term frequency wins
+        let no_length_norm = SearchQuery::new(dir.path()).unwrap().with_config(SearchConfig {
+            bm25_b: 0.0,
+            url_penalties: vec![("/short".to_string(), 0.5)],
+            ..SearchConfig::default()
+        });
+        let results = no_length_norm.search("rust", 10).unwrap();
+        assert_eq!(results[0].url, "https://example.com/long");
+    }
+
+    #[test]
+    fn test_title_match_outranks_content_match() {
+        let dir = tempdir().unwrap();
+        let index = SearchSchema::create_index(dir.path()).unwrap();
+        let schema = SearchSchema::build();
+
+        let mut writer: tantivy::IndexWriter = index.writer(15_000_000).unwrap();
+        for (url, title, content) in [
+            ("https://example.com/title", "Tantivy guide", "a guide to a search library written in rust"),
+            ("https://example.com/content", "Search notes", "tantivy tantivy is a search library written in rust"),
+        ] {
+            let mut doc = tantivy::TantivyDocument::default();
+            schema.add_url(&mut doc, url);
+            schema.add_title(&mut doc, title);
+            doc.add_text(schema.content_field, content);
+            writer.add_document(doc).unwrap();
+        }
+        writer.commit().unwrap();
+
+        // the word only appears in the title of the first page
+        let boosted = SearchQuery::new(dir.path()).unwrap();
+        let results = boosted.search("tantivy", 10).unwrap();
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].url, "https://example.com/title");
+
+        let unboosted = SearchQuery::new(dir.path()).unwrap().with_config(SearchConfig {
+            boosts: SearchSchemaBoosts { title_boost: 1.0, content_boost: 1.0, url_boost: 1.0, description_boost: 1.0 },
+            ..SearchConfig::default()
+        });
+        assert_eq!(unboosted.search("tantivy", 10).unwrap()[0].url, "https://example.com/content");
+
+        let without_titles = SearchQuery::new(dir.path()).unwrap().with_config(SearchConfig {
+            boosts: SearchSchemaBoosts { title_boost: 0.0, ..SearchSchemaBoosts::default() },
+            ..SearchConfig::default()
+        });
+        let results = without_titles.search("tantivy", 10).unwrap();
+        assert_eq!(results.iter().map(|r| r.url.as_str()).collect::<Vec<_>>(), vec!["https://example.com/content"]);
+    }
+
+    #[test]
+    fn test_explain_matches_search_score() {
+        let dir = tempdir().unwrap();
+        let index = SearchSchema::create_index(dir.path()).unwrap();
+        let schema = SearchSchema::build();
+
+        let mut writer: tantivy::IndexWriter = index.writer(15_000_000).unwrap();
+        for (url, content, pagerank) in [
+            ("https://example.com/rust", "a web crawler written in rust", 0.05),
+            ("https://example.com/tag/bread", "sourdough bread", 0.01),
+        ] {
+            let mut doc = tantivy::TantivyDocument::default();
+            schema.add_url(&mut doc, url);
+            doc.add_text(schema.content_field, content);
+            doc.add_f64(schema.pagerank_field, pagerank);
+            doc.add_f64(schema.quality_field, 0.5);
+            writer.add_document(doc).unwrap();
+        }
+        writer.commit().unwrap();
+
+        let search = SearchQuery::new(dir.path()).unwrap().with_config(SearchConfig {
+            url_penalties: vec![("/tag/".to_string(), 0.5)],
+            ..SearchConfig::default()
+        });
+
+        let result = &search.search("crawler", 10).unwrap()[0];
+        let explanation = search.explain("crawler", "https://example.com/rust").unwrap();
+        assert_eq!(explanation.combined_score, result.score);
+        assert!(explanation.tantivy_score > 0.0);
+        assert_eq!(explanation.pagerank, 0.05);
+        assert_eq!(explanation.url_penalty, 1.0);
+        assert!(explanation.tantivy_explanation.contains("TermQuery"));
+
+        // not matched, still explained from its stored signals
+        let unmatched = search.explain("crawler", "https://example.com/tag/bread").unwrap();
+        assert_eq!(unmatched.tantivy_score, 0.0);
+        assert_eq!(unmatched.url_penalty, 0.5);
+        assert!(unmatched.combined_score > 0.0);
+
+        assert!(search.explain("crawler", "https://example.com/missing").is_err());
+    }
+
+    #[test]
+    fn test_tfidf_reranker_uses_query_similarity() {
+        let dir = tempdir().unwrap();
+        let index = SearchSchema::create_index(dir.path()).unwrap();
+        let schema = SearchSchema::build();
+
+        let mut writer: tantivy::IndexWriter = index.writer(15_000_000).unwrap();
+        for (url, content) in [
+            ("https://example.com/repeated", "rust crawler rust crawler"),
+            ("https://example.com/focused", "rust crawler with a longer body of unrelated text"),
+        ] {
+            let mut doc = tantivy::TantivyDocument::default();
+            doc.add_text(schema.url_field, url);
+            doc.add_text(schema.content_field, content);
+            writer.add_document(doc).unwrap();
+        }
+        writer.commit().unwrap();
+
+        let plain = SearchQuery::new(dir.path()).unwrap();
+        assert_eq!(plain.search("rust crawler", 10).unwrap()[0].url, "https://example.com/repeated");
+
+        let mut tfidf = TfIdfCalculator::new();
+        tfidf.build_from_corpus(&[
+            (PageRepository::calculate_url_hash("https://example.com/repeated"), "gardening flowers seeds".to_string()),
+            (PageRepository::calculate_url_hash("https://example.com/focused"), "rust crawler internals".to_string()),
+        ]);
+
+        let saved = dir.path().join("tfidf.idx");
+        tfidf.save(&saved).unwrap();
+
+        let reranked = SearchQuery::new(dir.path()).unwrap()
+            .with_config(SearchConfig { query_tfidf_weight: 1.0, ..SearchConfig::default() })
+            .with_tfidf_reranker(Arc::new(tfidf));
+        let results = reranked.search("rust crawler", 10).unwrap();
+        assert_eq!(results[0].url, "https://example.com/focused");
+        assert!(results[0].score > results[1].score);
+
+        // the saved index is picked up from the config, a missing one leaves the ranking alone
+        let configured = |path: &Path| SearchQuery::new(dir.path()).unwrap().with_config(SearchConfig {
+            query_tfidf_weight: 1.0,
+            tfidf_index_path: Some(path.display().to_string()),
+            ..SearchConfig::default()
+        });
+        assert_eq!(configured(&saved).search("rust crawler", 10).unwrap()[0].url, "https://example.com/focused");
+        assert_eq!(
+            configured(&dir.path().join("missing.idx")).search("rust crawler", 10).unwrap()[0].url,
+            "https://example.com/repeated"
+        );
+    }
+
+    #[test]
+    fn test_search_similar_finds_shared_vocabulary() {
+        let dir = tempdir().unwrap();
+        let index = SearchSchema::create_index(dir.path()).unwrap();
+        let schema = SearchSchema::build();
+
+        let mut writer: tantivy::IndexWriter = index.writer(15_000_000).unwrap();
+        for (url, content) in [
+            ("https://example.com/borrow", "rust ownership borrowing lifetimes checker ownership borrowing"),
+            ("https://example.com/lifetimes", "explaining lifetimes ownership borrowing compiler errors"),
+            ("https://example.com/bread", "sourdough bread starter flour hydration oven"),
+            ("https://example.com/garden", "tomato garden soil compost watering"),
+        ] {
+            let mut doc = tantivy::TantivyDocument::default();
+            schema.add_url(&mut doc, url);
+            schema.add_hashes(&mut doc, &PageRepository::calculate_url_hash(url), "content");
+            doc.add_text(schema.content_field, content);
+            writer.add_document(doc).unwrap();
+        }
+        writer.commit().unwrap();
+
+        let search = SearchQuery::new(dir.path()).unwrap();
+        let similar = search.search_similar("https://example.com/borrow", 10).unwrap();
+        let urls: Vec<&str> = similar.iter().map(|r| r.url.as_str()).collect();
+        assert_eq!(urls, vec!["https://example.com/lifetimes"]);
+
+        let by_hash = search
+            .search_similar_by_hash(&PageRepository::calculate_url_hash("https://example.com/lifetimes"), 10)
+            .unwrap();
+        assert_eq!(by_hash[0].url, "https://example.com/borrow");
+
+        assert!(search.search_similar("https://example.com/missing", 10).is_err());
+    }
+
+    #[test]
+    fn test_suggest_orders_completions_by_pagerank() {
+        let dir = tempdir().unwrap();
+        let index = SearchSchema::create_index(dir.path()).unwrap();
+        let schema = SearchSchema::build();
+
+        let mut writer: tantivy::IndexWriter = index.writer(15_000_000).unwrap();
+        for i in 0..100 {
+            // a third of the titles start with "Rust", the rest are noise
+            let title = match i % 3 {
+                0 => format!("Rust Guide {}", i),
+                1 => format!("Python Guide {}", i),
+                _ => format!("Trusty Notes {}", i),
+            };
+            let mut doc = tantivy::TantivyDocument::default();
+            doc.add_text(schema.url_field, format!("https://example.com/{}", i));
+            schema.add_title(&mut doc, &title);
+            doc.add_f64(schema.pagerank_field, ((i * 37) % 100) as f64 / 1000.0);
+            writer.add_document(doc).unwrap();
+        }
+        // same title twice only suggests once, the percent-encoded one is decoded
+        for (title, pagerank) in [("Rust%20Book", 0.5), ("Rust Book", 0.4)] {
+            let mut doc = tantivy::TantivyDocument::default();
+            schema.add_title(&mut doc, title);
+            doc.add_f64(schema.pagerank_field, pagerank);
+            writer.add_document(doc).unwrap();
+        }
+        writer.commit().unwrap();
+
+        let search = SearchQuery::new(dir.path()).unwrap();
+        let suggestions = search.suggest("rust g", 5).unwrap();
+        assert_eq!(suggestions.len(), 5);
+
+        let mut expected: Vec<(f64, String)> = (0..100)
+            .filter(|i| i % 3 == 0)
+            .map(|i| (((i * 37) % 100) as f64 / 1000.0, format!("Rust Guide {}", i)))
+            .collect();
+        expected.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap());
+        let expected: Vec<String> = expected.into_iter().take(5).map(|(_, title)| title).collect();
+        assert_eq!(suggestions, expected);
+
+        let ru = search.suggest("RU", 3).unwrap();
+        assert_eq!(ru[0], "Rust Book");
+        assert_eq!(ru.iter().filter(|t| *t == "Rust Book").count(), 1);
+        assert!(ru.iter().all(|t| t.starts_with("Rust")));
+
+        assert!(search.suggest("", 5).unwrap().is_empty());
+        assert!(search.suggest("go", 5).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_search_with_facets_counts_matches() {
+        let dir = tempdir().unwrap();
+        let index = SearchSchema::create_index(dir.path()).unwrap();
+        let schema = SearchSchema::build();
+
+        let mut writer: tantivy::IndexWriter = index.writer(15_000_000).unwrap();
+        for (i, (domain, language, quality, content)) in [
+            ("rust-lang.org", "en", 0.9, "rust crawler"),
+            ("rust-lang.org", "en", 0.5, "rust book"),
+            ("docs.rs", "en", 0.2, "rust docs"),
+            ("beispiel.de", "de", 0.8, "rust anleitung"),
+            ("example.com", "en", 0.9, "python tutorial"),
+        ].into_iter().enumerate() {
+            let mut doc = tantivy::TantivyDocument::default();
+            doc.add_text(schema.url_field, format!("https://{}/{}", domain, i));
+            doc.add_text(schema.domain_field, domain);
+            doc.add_text(schema.content_field, content);
+            doc.add_f64(schema.quality_field, quality);
+            schema.add_facets(&mut doc, domain, language, quality);
+            writer.add_document(doc).unwrap();
+        }
+        writer.commit().unwrap();
+
+        let search = SearchQuery::new(dir.path()).unwrap();
+        let response = search
+            .search_with_facets("rust", FacetedSearchRequest::new(2).with_facet_limit(2))
+            .unwrap();
+
+        assert_eq!(response.results.len(), 2);
+        assert_eq!(response.facets.domains.len(), 2);
+        assert_eq!(response.facets.domains[0], ("rust-lang.org".to_string(), 2));
+        assert_eq!(response.facets.languages, vec![("en".to_string(), 3), ("de".to_string(), 1)]);
+        assert_eq!(response.facets.quality_buckets, vec![
+            ("low".to_string(), 1),
+            ("medium".to_string(), 1),
+            ("high".to_string(), 2),
+        ]);
+
+        // filters narrow the results but not the facet counts
+        let filtered = search
+            .search_with_facets("rust", FacetedSearchRequest::new(10).with_filters(SearchFilter::new().with_domain("docs.rs".to_string())))
+            .unwrap();
+        assert_eq!(filtered.results.len(), 1);
+        assert_eq!(filtered.facets.domains.iter().map(|(_, c)| c).sum::<u64>(), 4);
+    }
+
+    #[test]
+    fn test_date_range_filter_and_date_sort() {
+        use chrono::{TimeZone, Utc};
+
+        let dir = tempdir().unwrap();
+        let index = SearchSchema::create_index(dir.path()).unwrap();
+        let schema = SearchSchema::build();
+
+        let mut writer: tantivy::IndexWriter = index.writer(15_000_000).unwrap();
+        for (url, month) in [
+            ("https://example.com/jan", 1),
+            ("https://example.com/mar", 3),
+            ("https://example.com/may", 5),
+        ] {
+            let mut doc = tantivy::TantivyDocument::default();
+            doc.add_text(schema.url_field, url);
+            doc.add_text(schema.content_field, "rust crawler");
+            schema.add_crawled_at(&mut doc, Utc.with_ymd_and_hms(2024, month, 1, 0, 0, 0).unwrap());
+            writer.add_document(doc).unwrap();
+        }
+        writer.commit().unwrap();
+
+        let search = SearchQuery::new(dir.path()).unwrap();
+        let urls = |filters: SearchFilter| -> Vec<String> {
+            search
+                .search_with_filters("rust", &SearchOptions::new(10).with_filters(filters).with_sort(SortBy::Date))
+                .unwrap()
+                .into_iter()
+                .map(|r| r.url)
+                .collect()
+        };
+
+        assert_eq!(urls(SearchFilter::new()), vec![
+            "https://example.com/may",
+            "https://example.com/mar",
+            "https://example.com/jan",
+        ]);
+        assert_eq!(
+            urls(SearchFilter::new().with_crawled_after(Utc.with_ymd_and_hms(2024, 2, 1, 0, 0, 0).unwrap())),
+            vec!["https://example.com/may", "https://example.com/mar"]
+        );
+        assert_eq!(
+            urls(SearchFilter::new()
+                .with_crawled_after(Utc.with_ymd_and_hms(2024, 2, 1, 0, 0, 0).unwrap())
+                .with_crawled_before(Utc.with_ymd_and_hms(2024, 4, 1, 0, 0, 0).unwrap())),
+            vec!["https://example.com/mar"]
+        );
+
+        let result = &search.search("rust", 1).unwrap()[0];
+        assert!(result.crawled_at.as_deref().unwrap().starts_with("2024-0"));
+    }
+
+    #[test]
+    fn test_word_count_filter() {
+        let dir = tempdir().unwrap();
+        let index = SearchSchema::create_index(dir.path()).unwrap();
+        let schema = SearchSchema::build();
+
+        let mut writer: tantivy::IndexWriter = index.writer(15_000_000).unwrap();
+        for (url, words) in [
+            ("https://example.com/summary", 80),
+            ("https://example.com/article", 600),
+            ("https://example.com/longread", 4000),
+        ] {
+            let mut doc = tantivy::TantivyDocument::default();
+            doc.add_text(schema.url_field, url);
+            doc.add_text(schema.content_field, "rust crawler");
+            doc.add_u64(schema.word_count_field, words);
+            writer.add_document(doc).unwrap();
+        }
+        writer.commit().unwrap();
+
+        let search = SearchQuery::new(dir.path()).unwrap();
+        let urls = |filters: SearchFilter| -> Vec<String> {
+            let mut urls: Vec<String> = search
+                .search_with_filters("rust", &SearchOptions::new(10).with_filters(filters))
+                .unwrap()
+                .into_iter()
+                .map(|r| r.url)
+                .collect();
+            urls.sort();
+            urls
+        };
+
+        assert_eq!(urls(SearchFilter::new()).len(), 3);
+        assert_eq!(urls(SearchFilter::new().with_min_word_count(500)), vec![
+            "https://example.com/article",
+            "https://example.com/longread",
+        ]);
+        assert_eq!(urls(SearchFilter::new().with_max_word_count(600)), vec![
+            "https://example.com/article",
+            "https://example.com/summary",
+        ]);
+        assert_eq!(urls(SearchFilter::new().with_word_count_range(100, 1000)), vec!["https://example.com/article"]);
+    }
+
+    #[test]
+    fn test_language_filter() {
+        let dir = tempdir().unwrap();
+        let index = SearchSchema::create_index(dir.path()).unwrap();
+        let schema = SearchSchema::build();
+
+        let mut writer: tantivy::IndexWriter = index.writer(15_000_000).unwrap();
+        for (url, language, content) in [
+            ("https://example.in/hi-1", "hi", "मानसून बारिश crawler"),
+            ("https://example.in/hi-2", "hi", "बारिश का मौसम crawler"),
+            ("https://example.in/mr", "mr", "पाऊस crawler"),
+            ("https://example.com/en", "en", "monsoon rain crawler"),
+        ] {
+            let mut doc = tantivy::TantivyDocument::default();
+            doc.add_text(schema.url_field, url);
+            doc.add_text(schema.content_field, content);
+            schema.add_facets(&mut doc, "example.in", language, 0.5);
+            writer.add_document(doc).unwrap();
+        }
+        writer.commit().unwrap();
+
+        let search = SearchQuery::new(dir.path()).unwrap();
+        let urls = |filters: SearchFilter| -> Vec<String> {
+            let mut urls: Vec<String> = search
+                .search_with_filters("crawler", &SearchOptions::new(10).with_filters(filters))
+                .unwrap()
+                .into_iter()
+                .map(|r| r.url)
+                .collect();
+            urls.sort();
+            urls
+        };
+
+        assert_eq!(urls(SearchFilter::new().with_language("hi")), vec![
+            "https://example.in/hi-1",
+            "https://example.in/hi-2",
+        ]);
+        assert_eq!(urls(SearchFilter::new().with_languages(&["mr", "en"])), vec![
+            "https://example.com/en",
+            "https://example.in/mr",
+        ]);
+        assert_eq!(urls(SearchFilter::new()).len(), 4);
+    }
+
+    #[test]
+    fn test_parse_advanced_query_operators() {
+        let dir = tempdir().unwrap();
+        let index = SearchSchema::create_index(dir.path()).unwrap();
+        let schema = SearchSchema::build();
+
+        let mut writer: tantivy::IndexWriter = index.writer(15_000_000).unwrap();
+        for (url, domain, language, title, content) in [
+            ("https://example.com/rust", "example.com", "en", "Rust crawler", "a web crawler written in rust"),
+            ("https://example.com/go", "example.com", "en", "Go crawler", "a crawler for the web written in go"),
+            ("https://docs.test.org/rust", "docs.test.org", "en", "Rust book", "learning rust ownership"),
+            ("https://example.in/hi", "example.in", "hi", "Crawler", "मानसून crawler written in rust"),
+        ] {
+            let mut doc = tantivy::TantivyDocument::default();
+            doc.add_text(schema.url_field, url);
+            doc.add_text(schema.domain_field, domain);
+            doc.add_text(schema.title_field, title);
+            doc.add_text(schema.content_field, content);
+            schema.add_facets(&mut doc, domain, language, 0.5);
+            writer.add_document(doc).unwrap();
+        }
+        writer.commit().unwrap();
+
+        let search = SearchQuery::new(dir.path()).unwrap();
+        let urls = |query: &str| -> Vec<String> {
+            let mut urls: Vec<String> = search
+                .search_with_filters(query, &SearchOptions::new(10).with_filters(SearchFilter::new().with_advanced_syntax(true)))
+                .unwrap()
+                .into_iter()
+                .map(|r| r.url)
+                .collect();
+            urls.sort();
+            urls
+        };
+
+        assert_eq!(urls("rust AND crawler"), vec!["https://example.com/rust", "https://example.in/hi"]);
+        assert_eq!(urls("rust crawler"), urls("rust AND crawler"));
+        assert_eq!(urls("ownership OR go"), vec!["https://docs.test.org/rust", "https://example.com/go"]);
+        assert_eq!(urls("crawler NOT rust"), vec!["https://example.com/go"]);
+        assert_eq!(urls("NOT crawler"), vec!["https://docs.test.org/rust"]);
+
+        // fields
+        assert_eq!(urls("title:rust"), vec!["https://docs.test.org/rust", "https://example.com/rust"]);
+        assert_eq!(urls("domain:example.com"), vec!["https://example.com/go", "https://example.com/rust"]);
+        assert_eq!(urls("language:HI"), vec!["https://example.in/hi"]);
+
+        // phrases and grouping
+        assert_eq!(urls("\"web crawler\""), vec!["https://example.com/rust"]);
+        assert_eq!(urls("title:\"rust book\""), vec!["https://docs.test.org/rust"]);
+        assert_eq!(urls("(go OR ownership) AND NOT domain:example.com"), vec!["https://docs.test.org/rust"]);
+        assert_eq!(urls("title:(go OR book)"), vec!["https://docs.test.org/rust", "https://example.com/go"]);
+
+        // invalid syntax matches any of the words instead of failing
+        assert_eq!(urls("(ownership OR"), vec!["https://docs.test.org/rust"]);
+    }
+}
@@ -0,0 +1,195 @@
+//! Parser for the boolean query syntax of `SearchQuery::parse_advanced_query`
+
+/// A parsed query, `field` is the name before a `field:` prefix if there was one
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum QueryNode {
+    Term { field: Option<String>, text: String },
+    Phrase { field: Option<String>, text: String },
+    And(Vec<QueryNode>),
+    Or(Vec<QueryNode>),
+    Not(Box<QueryNode>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Word(String),
+    Phrase(String),
+    /// `name:` directly followed by a word, phrase or group
+    Field(String),
+    Open,
+    Close,
+    And,
+    Or,
+    Not,
+}
+
+/// Parse `query`, the error says why it isn't valid syntax
+pub(crate) fn parse(query: &str) -> Result<QueryNode, String> {
+    let tokens = tokenize(query)?;
+    if tokens.is_empty() {
+        return Err("empty query".to_string());
+    }
+
+    let mut parser = Parser { tokens, pos: 0 };
+    let node = parser.or(None)?;
+    match parser.tokens.get(parser.pos) {
+        None => Ok(node),
+        Some(token) => Err(format!("unexpected {:?}", token)),
+    }
+}
+
+fn tokenize(query: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = query.char_indices().peekable();
+
+    while let Some(&(start, c)) = chars.peek() {
+        match c {
+            _ if c.is_whitespace() => {
+                chars.next();
+            }
+            '(' => {
+                chars.next();
+                tokens.push(Token::Open);
+            }
+            ')' => {
+                chars.next();
+                tokens.push(Token::Close);
+            }
+            '"' => {
+                chars.next();
+                let rest = &query[start + 1..];
+                let end = rest.find('"').ok_or("unbalanced quote")?;
+                tokens.push(Token::Phrase(rest[..end].to_string()));
+                // skip the phrase and its closing quote
+                while chars.next_if(|&(i, _)| i <= start + 1 + end).is_some() {}
+            }
+            _ => {
+                let mut end = query.len();
+                while let Some(&(i, c)) = chars.peek() {
+                    if c.is_whitespace() || matches!(c, '(' | ')' | '"') {
+                        end = i;
+                        break;
+                    }
+                    chars.next();
+                }
+                tokens.extend(word_tokens(&query[start..end]));
+            }
+        }
+    }
+
+    Ok(tokens)
+}
+
+// Operators are only recognised in upper case, `and` is an ordinary word
+fn word_tokens(word: &str) -> Vec<Token> {
+    match word {
+        "AND" => vec![Token::And],
+        "OR" => vec![Token::Or],
+        "NOT" => vec![Token::Not],
+        _ => match word.split_once(':') {
+            Some((field, value)) if !field.is_empty() && field.chars().all(|c| c.is_ascii_alphabetic()) => {
+                let mut tokens = vec![Token::Field(field.to_lowercase())];
+                if !value.is_empty() {
+                    tokens.push(Token::Word(value.to_string()));
+                }
+                tokens
+            }
+            _ => vec![Token::Word(word.to_string())],
+        },
+    }
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn or(&mut self, field: Option<&str>) -> Result<QueryNode, String> {
+        let mut nodes = vec![self.and(field)?];
+        while self.next_if(&Token::Or) {
+            nodes.push(self.and(field)?);
+        }
+        Ok(Self::group(nodes, QueryNode::Or))
+    }
+
+    // Adjacent terms without an operator are ANDed as well
+    fn and(&mut self, field: Option<&str>) -> Result<QueryNode, String> {
+        let mut nodes = vec![self.unary(field)?];
+        while self.next_if(&Token::And)
+            || matches!(self.tokens.get(self.pos), Some(token) if *token != Token::Or && *token != Token::Close)
+        {
+            nodes.push(self.unary(field)?);
+        }
+        Ok(Self::group(nodes, QueryNode::And))
+    }
+
+    fn unary(&mut self, field: Option<&str>) -> Result<QueryNode, String> {
+        if self.next_if(&Token::Not) {
+            return Ok(QueryNode::Not(Box::new(self.unary(field)?)));
+        }
+        self.primary(field)
+    }
+
+    fn primary(&mut self, field: Option<&str>) -> Result<QueryNode, String> {
+        let token = self.tokens.get(self.pos).cloned().ok_or("query ends after an operator")?;
+        self.pos += 1;
+
+        match token {
+            Token::Word(text) => Ok(QueryNode::Term { field: field.map(String::from), text }),
+            Token::Phrase(text) => Ok(QueryNode::Phrase { field: field.map(String::from), text }),
+            Token::Field(name) => self.primary(Some(&name)),
+            Token::Open => {
+                let node = self.or(field)?;
+                if !self.next_if(&Token::Close) {
+                    return Err("unclosed parenthesis".to_string());
+                }
+                Ok(node)
+            }
+            other => Err(format!("unexpected {:?}", other)),
+        }
+    }
+
+    fn next_if(&mut self, expected: &Token) -> bool {
+        let matched = self.tokens.get(self.pos) == Some(expected);
+        if matched {
+            self.pos += 1;
+        }
+        matched
+    }
+
+    fn group(mut nodes: Vec<QueryNode>, combine: fn(Vec<QueryNode>) -> QueryNode) -> QueryNode {
+        if nodes.len() == 1 { nodes.remove(0) } else { combine(nodes) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn term(field: Option<&str>, text: &str) -> QueryNode {
+        QueryNode::Term { field: field.map(String::from), text: text.to_string() }
+    }
+
+    #[test]
+    fn test_parse_precedence_and_fields() {
+        // AND binds tighter than OR, NOT tighter than AND
+        assert_eq!(parse("rust AND NOT go OR python").unwrap(), QueryNode::Or(vec![
+            QueryNode::And(vec![term(None, "rust"), QueryNode::Not(Box::new(term(None, "go")))]),
+            term(None, "python"),
+        ]));
+
+        // a field prefix applies to a whole group
+        assert_eq!(parse("title:(rust OR \"web crawler\") domain:example.com").unwrap(), QueryNode::And(vec![
+            QueryNode::Or(vec![
+                term(Some("title"), "rust"),
+                QueryNode::Phrase { field: Some("title".to_string()), text: "web crawler".to_string() },
+            ]),
+            term(Some("domain"), "example.com"),
+        ]));
+
+        for invalid in ["", "rust AND", "(rust", "rust)", "\"rust", "OR rust"] {
+            assert!(parse(invalid).is_err(), "{:?} should not parse", invalid);
+        }
+    }
+}
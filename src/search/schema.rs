@@ -1,17 +1,46 @@
+use crate::config::{CustomFieldKind, CustomFieldSpec};
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
 use tantivy::schema::*;
+use tantivy::tokenizer::{LowerCaser, NgramTokenizer, TextAnalyzer};
 use tantivy::Index;
 use std::path::Path;
+use tracing::warn;
 
-#[derive(Debug)]
+/// Edge n-gram tokenizer behind `title_autocomplete`
+pub const AUTOCOMPLETE_TOKENIZER: &str = "autocomplete";
+
+/// Longest title prefix indexed for auto-complete
+pub const AUTOCOMPLETE_MAX_GRAM: usize = 40;
+
+#[derive(Debug, Clone)]
 pub struct SearchSchema {
     pub schema: Schema,
     pub url_field: Field,
+    /// Canonical URL as a single term, identifies a page's document for deletes
+    pub url_key_field: Field,
     pub title_field: Field,
     pub content_field: Field,
     pub domain_field: Field,
     pub quality_field: Field,
     pub pagerank_field: Field,
     pub tfidf_field: Field,
+    pub title_autocomplete_field: Field,
+    pub language_field: Field,
+    pub domain_facet_field: Field,
+    pub language_facet_field: Field,
+    pub quality_facet_field: Field,
+    /// `StoredPage::url_hash` and `content_hash`, unchanged pages are not re-indexed
+    pub url_hash_field: Field,
+    pub content_hash_field: Field,
+    /// Unix timestamp of the crawl, for date range filters and date sorting
+    pub crawled_at_field: Field,
+    /// Meta description, searched but not stored
+    pub description_field: Field,
+    /// Words in the page content, for word count range filters
+    pub word_count_field: Field,
+    /// Site specific fields after the built-in ones, in the order they were added
+    pub custom_fields: Vec<(CustomFieldSpec, Field)>,
 }
 
 impl SearchSchema {
@@ -21,6 +50,9 @@ impl SearchSchema {
         // url field - stored index
         let url_field = schema_builder.add_text_field("url", TEXT | STORED);
 
+        // untokenized copy of the url, Term lookups on `url` would only hit single tokens
+        let url_key_field = schema_builder.add_text_field("url_key", STRING | FAST);
+
         // title field searchable with high boost
         let title_field = schema_builder.add_text_field("title", TEXT | STORED);
 
@@ -37,36 +69,270 @@ impl SearchSchema {
 
         let tfidf_field = schema_builder.add_f64_field("tfidf", FAST | STORED);
 
+        // every lowercased prefix of the title, for query suggestions
+        let autocomplete_indexing = TextFieldIndexing::default()
+            .set_tokenizer(AUTOCOMPLETE_TOKENIZER)
+            .set_index_option(IndexRecordOption::Basic);
+        let title_autocomplete_field = schema_builder.add_text_field(
+            "title_autocomplete",
+            TextOptions::default().set_indexing_options(autocomplete_indexing),
+        );
+
+        // ISO 639-1 code of the page language
+        let language_field = schema_builder.add_text_field("language", STRING | STORED);
+
+        // facet counterparts of domain, language and quality for FacetCollector
+        let domain_facet_field = schema_builder.add_facet_field("domain_facet", FacetOptions::default());
+        let language_facet_field = schema_builder.add_facet_field("language_facet", FacetOptions::default());
+        let quality_facet_field = schema_builder.add_facet_field("quality_facet", FacetOptions::default());
+
+        // hashes of the indexed url and content, compared before re-indexing a page
+        let url_hash_field = schema_builder.add_text_field("url_hash", STRING | FAST);
+        let content_hash_field = schema_builder.add_text_field("content_hash", STRING | FAST);
+
+        let crawled_at_field = schema_builder.add_u64_field(
+            "crawled_at",
+            NumericOptions::default().set_indexed().set_fast().set_stored(),
+        );
+
+        // added after the other fields so their ids stay the same, older indexes need a reindex
+        let description_field = schema_builder.add_text_field("description", TEXT | STORED);
+        let word_count_field = schema_builder.add_u64_field(
+            "word_count",
+            NumericOptions::default().set_indexed().set_fast().set_stored(),
+        );
+
         let schema = schema_builder.build();
 
 
         Self{
             schema,
             url_field,
+            url_key_field,
             title_field,
             content_field,
             domain_field,
             quality_field,
             pagerank_field,
             tfidf_field,
+            title_autocomplete_field,
+            language_field,
+            domain_facet_field,
+            language_facet_field,
+            quality_facet_field,
+            url_hash_field,
+            content_hash_field,
+            crawled_at_field,
+            description_field,
+            word_count_field,
+            custom_fields: Vec::new(),
+        }
+    }
+
+    /// The built-in fields followed by `fields`, see `add_custom_field`
+    pub fn build_custom(fields: &[CustomFieldSpec]) -> Self {
+        let mut search_schema = Self::build();
+        for spec in fields {
+            search_schema.add_custom_field(spec.clone());
+        }
+        search_schema
+    }
+
+    pub fn with_custom_text_field(&mut self, name: &str, stored: bool, indexed: bool) -> &mut Self {
+        self.add_custom_field(CustomFieldSpec::text(name, stored, indexed))
+    }
+
+    pub fn with_custom_numeric_field(&mut self, name: &str, stored: bool, fast: bool) -> &mut Self {
+        self.add_custom_field(CustomFieldSpec::numeric(name, stored, fast))
+    }
+
+    /// Append a field to the schema, built-in field ids don't change. Names that are taken
+    /// or that tantivy rejects are skipped with a warning. An index created before the field
+    /// was added has to be rebuilt to hold it.
+    pub fn add_custom_field(&mut self, spec: CustomFieldSpec) -> &mut Self {
+        if !is_valid_field_name(&spec.name) || self.schema.get_field(&spec.name).is_ok() {
+            warn!("Skipping custom search field {:?}: invalid or already defined", spec.name);
+            return self;
+        }
+
+        // a built schema is immutable, rebuild it with the same fields in the same order
+        let mut schema_builder = Schema::builder();
+        for (_, entry) in self.schema.fields() {
+            schema_builder.add_field(entry.clone());
+        }
+
+        let field = match spec.kind {
+            CustomFieldKind::Text => {
+                let mut options = TextOptions::default();
+                if spec.indexed {
+                    options = options.set_indexing_options(
+                        TextFieldIndexing::default().set_index_option(IndexRecordOption::WithFreqsAndPositions),
+                    );
+                }
+                if spec.stored {
+                    options = options.set_stored();
+                }
+                schema_builder.add_text_field(&spec.name, options)
+            }
+            CustomFieldKind::Numeric => {
+                let mut options = NumericOptions::default().set_indexed();
+                if spec.stored {
+                    options = options.set_stored();
+                }
+                if spec.fast {
+                    options = options.set_fast();
+                }
+                schema_builder.add_f64_field(&spec.name, options)
+            }
+        };
+
+        self.schema = schema_builder.build();
+        self.custom_fields.push((spec, field));
+        self
+    }
+
+    pub fn custom_field(&self, name: &str) -> Option<Field> {
+        self.custom_fields.iter().find(|(spec, _)| spec.name == name).map(|(_, field)| *field)
+    }
+
+    /// Add the `extra` values of custom fields to `doc`. Arrays add every element, text
+    /// fields take numbers and booleans as text, numeric fields parse strings. Keys
+    /// without a custom field and values that don't fit are ignored.
+    pub fn add_custom_values(&self, doc: &mut TantivyDocument, extra: &HashMap<String, serde_json::Value>) {
+        for (spec, field) in &self.custom_fields {
+            let Some(value) = extra.get(&spec.name) else { continue };
+            let values = match value {
+                serde_json::Value::Array(values) => values.iter().collect(),
+                value => vec![value],
+            };
+
+            for value in values {
+                match (spec.kind, value) {
+                    (CustomFieldKind::Text, serde_json::Value::String(text)) => doc.add_text(*field, text),
+                    (CustomFieldKind::Text, serde_json::Value::Number(_) | serde_json::Value::Bool(_)) => {
+                        doc.add_text(*field, value.to_string())
+                    }
+                    (CustomFieldKind::Numeric, serde_json::Value::Number(number)) => {
+                        if let Some(number) = number.as_f64() {
+                            doc.add_f64(*field, number);
+                        }
+                    }
+                    (CustomFieldKind::Numeric, serde_json::Value::String(text)) => {
+                        if let Ok(number) = text.trim().parse::<f64>() {
+                            doc.add_f64(*field, number);
+                        }
+                    }
+                    _ => {}
+                }
+            }
         }
     }
 
+    /// Quality bucket label for a 0.0-1.0 quality score: low (<0.3), medium (<0.7) or high
+    pub fn quality_bucket(quality: f64) -> &'static str {
+        if quality < 0.3 {
+            "low"
+        } else if quality < 0.7 {
+            "medium"
+        } else {
+            "high"
+        }
+    }
+
+    /// Add the language and the facet values search_with_facets counts
+    pub fn add_facets(&self, doc: &mut TantivyDocument, domain: &str, language: &str, quality: f64) {
+        doc.add_text(self.language_field, language);
+        doc.add_facet(self.domain_facet_field, Facet::from_path([domain]));
+        doc.add_facet(self.language_facet_field, Facet::from_path([language]));
+        doc.add_facet(self.quality_facet_field, Facet::from_path([Self::quality_bucket(quality)]));
+    }
+
+    /// Register the custom tokenizers the schema refers to, needed on every opened index
+    pub fn register_tokenizers(index: &Index) {
+        let edge_ngrams = NgramTokenizer::prefix_only(1, AUTOCOMPLETE_MAX_GRAM)
+            .expect("valid n-gram bounds");
+        index.tokenizers().register(
+            AUTOCOMPLETE_TOKENIZER,
+            TextAnalyzer::builder(edge_ngrams).filter(LowerCaser).build(),
+        );
+    }
+
+    /// Add a url to both the searchable and the exact key field
+    pub fn add_url(&self, doc: &mut TantivyDocument, url: &str) {
+        doc.add_text(self.url_field, url);
+        doc.add_text(self.url_key_field, crate::utils::canonicalize(url));
+    }
+
+    /// Term matching the document indexed for `url`, under any spelling of it
+    pub fn url_term(&self, url: &str) -> Term {
+        Term::from_field_text(self.url_key_field, &crate::utils::canonicalize(url))
+    }
+
+    /// Record the hashes `SearchIndexer::is_indexed` compares against
+    pub fn add_hashes(&self, doc: &mut TantivyDocument, url_hash: &str, content_hash: &str) {
+        doc.add_text(self.url_hash_field, url_hash);
+        doc.add_text(self.content_hash_field, content_hash);
+    }
+
+    /// Record when the page was crawled, pages from before 1970 count as crawled at the epoch
+    pub fn add_crawled_at(&self, doc: &mut TantivyDocument, crawled_at: DateTime<Utc>) {
+        doc.add_u64(self.crawled_at_field, Self::timestamp(crawled_at));
+    }
+
+    /// `crawled_at` value of a point in time
+    pub fn timestamp(date: DateTime<Utc>) -> u64 {
+        date.timestamp().max(0) as u64
+    }
+
+    /// Add a title to both the searchable and the auto-complete field
+    pub fn add_title(&self, doc: &mut TantivyDocument, title: &str) {
+        doc.add_text(self.title_field, title);
+        doc.add_text(self.title_autocomplete_field, title);
+    }
+
     pub fn create_index(index_path : &Path) -> tantivy::Result<Index> {
-        let search_schema = Self::build();
+        Self::build().create_index_in(index_path)
+    }
 
+    /// Create an index with this schema, custom fields included
+    pub fn create_index_in(&self, index_path: &Path) -> tantivy::Result<Index> {
         if !index_path.exists() {
             std::fs::create_dir_all(index_path)?;
         }
 
-        Index::create_in_dir(index_path, search_schema.schema)
+        let index = Index::create_in_dir(index_path, self.schema.clone())?;
+        Self::register_tokenizers(&index);
+        Ok(index)
+    }
+
+    pub fn open(index_path: &Path) -> tantivy::Result<Index> {
+        let index = Index::open_in_dir(index_path)?;
+        Self::register_tokenizers(&index);
+        Ok(index)
     }
     
     pub fn open_or_create(index_path: &Path) -> tantivy::Result<Index> {
-        if index_path.exists() && index_path.read_dir()?.next().is_some() {
-            Index::open_in_dir(index_path)
-        }else { 
-            Self::create_index(index_path)
+        Self::build().open_or_create_in(index_path)
+    }
+
+    /// Open the index at `index_path` or create it with this schema. An existing index
+    /// missing one of the custom fields is an error, it needs to be rebuilt.
+    pub fn open_or_create_in(&self, index_path: &Path) -> tantivy::Result<Index> {
+        if !(index_path.exists() && index_path.read_dir()?.next().is_some()) {
+            return self.create_index_in(index_path);
+        }
+
+        let index = Self::open(index_path)?;
+        let index_schema = index.schema();
+        for (spec, field) in &self.custom_fields {
+            if index_schema.get_field(&spec.name).ok() != Some(*field) {
+                return Err(tantivy::TantivyError::SchemaError(format!(
+                    "index at {} has no custom field {:?}, rebuild it to add the field",
+                    index_path.display(),
+                    spec.name
+                )));
+            }
         }
+        Ok(index)
     }
 }
\ No newline at end of file
@@ -1,3 +1,26 @@
+use serde::{Deserialize, Serialize};
+use crate::storage::models::StoredPage;
+
+/// Where a query term matched, as byte offsets into the snippet returned with it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HighlightSpan {
+    /// Document field the snippet was taken from
+    pub field: String,
+    /// The query term that matched
+    pub term: String,
+    pub start_byte: usize,
+    pub end_byte: usize,
+}
+
+/// Title, description and body excerpt of a result, for pages that show them separately
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StructuredSnippet {
+    pub title: Option<String>,
+    pub description: Option<String>,
+    /// Taken from the page content only, see `SnippetGenerator::generate`
+    pub body_snippet: String,
+}
+
 /// Generate search result snippets with context around matched terms
 pub struct SnippetGenerator {
     max_length: usize,
@@ -60,6 +83,141 @@ impl SnippetGenerator {
         let start_char = target_char_idx.saturating_sub(self.context_chars / 2);
         let end_char = (start_char + self.context_chars).min(chars.len());
 
+        self.excerpt(content, &chars, start_char, end_char, query_terms, highlight)
+    }
+
+    /// The page's title and description as they are, with matched terms highlighted if
+    /// requested, and a snippet of its content. Blank titles and descriptions become None.
+    pub fn generate_structured(&self, page: &StoredPage, query_terms: &[String], highlight: bool) -> StructuredSnippet {
+        let body_snippet = self.generate(&page.content, query_terms, highlight);
+        self.structured(page.title.as_deref(), page.description.as_deref(), body_snippet, query_terms, highlight)
+    }
+
+    // `generate_structured` for fields read from the search index, with the content
+    // snippet already generated
+    pub(crate) fn structured(
+        &self,
+        title: Option<&str>,
+        description: Option<&str>,
+        body_snippet: String,
+        query_terms: &[String],
+        highlight: bool,
+    ) -> StructuredSnippet {
+        let field = |text: Option<&str>| {
+            text.map(str::trim)
+                .filter(|text| !text.is_empty())
+                .map(|text| if highlight { self.highlight_terms(text, query_terms) } else { text.to_string() })
+        };
+
+        StructuredSnippet {
+            title: field(title),
+            description: field(description),
+            body_snippet,
+        }
+    }
+
+    /// Snippet like `generate`, plus the byte span of every case-insensitive match of a query
+    /// term in it, ordered by position, for clients that render highlights themselves. With
+    /// `highlight` the spans cover the terms inside the `**` markup of the returned snippet.
+    pub fn generate_with_positions(&self, content: &str, query_terms: &[String], highlight: bool) -> (String, Vec<HighlightSpan>) {
+        let snippet = self.generate(content, query_terms, highlight);
+        let spans = Self::term_spans(&snippet, query_terms, "content");
+        (snippet, spans)
+    }
+
+    /// Up to `max_snippets` passages that together cover the most query terms, in document order.
+    /// Windows of `context_chars` around each match are scored by the number of distinct terms
+    /// they contain and taken best first; a window overlapping one already taken is merged into it
+    /// while the result stays within `max_length`.
+    pub fn generate_multi_query(
+        &self,
+        content: &str,
+        query_terms: &[String],
+        max_snippets: usize,
+        highlight: bool,
+    ) -> Vec<String> {
+        if content.is_empty() || max_snippets == 0 {
+            return Vec::new();
+        }
+
+        let chars: Vec<(usize, char)> = content.char_indices().collect();
+        let terms: Vec<String> = query_terms.iter().map(|term| term.to_lowercase()).collect();
+        let matches = Self::term_matches(&chars, content, &terms);
+        if matches.is_empty() {
+            return vec![self.generate(content, query_terms, highlight)];
+        }
+
+        // one candidate window centred on every match, scored by distinct terms inside it
+        let mut candidates: Vec<(usize, usize, usize)> = matches.iter()
+            .map(|&(position, _)| {
+                let start = position.saturating_sub(self.context_chars / 2);
+                let end = (start + self.context_chars).min(chars.len());
+                let mut found: Vec<usize> = matches.iter()
+                    .filter(|(pos, _)| (start..end).contains(pos))
+                    .map(|&(_, term)| term)
+                    .collect();
+                found.sort_unstable();
+                found.dedup();
+                (found.len(), start, end)
+            })
+            .collect();
+        candidates.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+
+        let mut windows: Vec<(usize, usize)> = Vec::new();
+        for (_, start, end) in candidates {
+            let full = windows.len() >= max_snippets;
+            match windows.iter_mut().find(|(s, e)| start < *e && *s < end) {
+                Some(window) => {
+                    let merged = (window.0.min(start), window.1.max(end));
+                    if merged.1 - merged.0 <= self.max_length {
+                        *window = merged;
+                    }
+                }
+                None if !full => windows.push((start, end)),
+                None => {}
+            }
+        }
+        windows.sort_unstable();
+
+        windows.into_iter()
+            .map(|(start, end)| self.excerpt(content, &chars, start, end, query_terms, highlight))
+            .collect()
+    }
+
+    // (char index, term index) of every word containing one of the lowercased `terms`
+    fn term_matches(chars: &[(usize, char)], content: &str, terms: &[String]) -> Vec<(usize, usize)> {
+        let mut matches = Vec::new();
+        let mut word_start = None;
+
+        for i in 0..=chars.len() {
+            let in_word = chars.get(i).is_some_and(|(_, c)| c.is_alphanumeric());
+            match (word_start, in_word) {
+                (None, true) => word_start = Some(i),
+                (Some(start), false) => {
+                    let end_byte = chars.get(i).map_or(content.len(), |(byte, _)| *byte);
+                    let word = content[chars[start].0..end_byte].to_lowercase();
+                    if let Some(term) = terms.iter().position(|term| !term.is_empty() && word.contains(term.as_str())) {
+                        matches.push((start, term));
+                    }
+                    word_start = None;
+                }
+                _ => {}
+            }
+        }
+
+        matches
+    }
+
+    // Text between two char indices trimmed to whole words, with "..." where content was cut
+    fn excerpt(
+        &self,
+        content: &str,
+        chars: &[(usize, char)],
+        start_char: usize,
+        end_char: usize,
+        query_terms: &[String],
+        highlight: bool,
+    ) -> String {
         // Get byte positions from char indices
         let start_byte = if start_char > 0 {
             chars[start_char].0
@@ -129,6 +287,55 @@ impl SnippetGenerator {
         result
     }
 
+    // Case-insensitive matches of each term in `text`. Compares char by char instead of
+    // searching a lowercased copy, whose byte offsets can differ from the original
+    fn term_spans(text: &str, query_terms: &[String], field: &str) -> Vec<HighlightSpan> {
+        let mut spans = Vec::new();
+
+        for term in query_terms {
+            let term_lower: Vec<char> = term.chars().flat_map(char::to_lowercase).collect();
+            if term_lower.is_empty() {
+                continue;
+            }
+
+            let mut from = 0;
+            while from < text.len() {
+                match Self::match_len(&text[from..], &term_lower) {
+                    Some(len) => {
+                        spans.push(HighlightSpan {
+                            field: field.to_string(),
+                            term: term.clone(),
+                            start_byte: from,
+                            end_byte: from + len,
+                        });
+                        from += len;
+                    }
+                    None => from += text[from..].chars().next().map_or(1, char::len_utf8),
+                }
+            }
+        }
+
+        spans.sort_by_key(|span| (span.start_byte, span.end_byte));
+        spans
+    }
+
+    // Bytes of `text` matched by the lowercased `term` at its start, if it matches there
+    fn match_len(text: &str, term: &[char]) -> Option<usize> {
+        let mut remaining = term;
+        for (byte, c) in text.char_indices() {
+            for lower in c.to_lowercase() {
+                match remaining.split_first() {
+                    Some((&expected, rest)) if expected == lower => remaining = rest,
+                    _ => return None,
+                }
+            }
+            if remaining.is_empty() {
+                return Some(byte + c.len_utf8());
+            }
+        }
+        None
+    }
+
     /// Extract query terms from query string
     pub fn extract_terms(query: &str) -> Vec<String> {
         query
@@ -149,6 +356,7 @@ impl Default for SnippetGenerator {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::storage::models::tests::stored_page;
 
     #[test]
     fn test_extract_terms() {
@@ -177,6 +385,81 @@ mod tests {
         assert!(snippet.contains("**crawler**"));
     }
 
+    #[test]
+    fn test_multi_query_snippets_cover_distant_terms() {
+        let generator = SnippetGenerator::new();
+        let filler = "lorem ipsum dolor sit amet ".repeat(10);
+        let content = format!("Rust crawlers fetch pages. {}Tantivy builds the search index. {}The end.", filler, filler);
+        let terms = vec!["crawler".to_string(), "tantivy".to_string(), "index".to_string()];
+
+        let snippets = generator.generate_multi_query(&content, &terms, 3, true);
+        assert_eq!(snippets.len(), 2);
+        assert!(snippets[0].contains("**crawler**s"));
+        // both nearby terms end up in one window
+        assert!(snippets[1].contains("**Tantivy**") && snippets[1].contains("**index**"));
+
+        assert_eq!(generator.generate_multi_query(&content, &terms, 1, false).len(), 1);
+        assert!(generator.generate_multi_query("", &terms, 3, false).is_empty());
+    }
+
+    #[test]
+    fn test_multi_query_overlapping_windows_merge() {
+        let generator = SnippetGenerator::new();
+        let content = "web crawler notes: a crawler visits pages and a crawler follows links";
+        let terms = vec!["crawler".to_string()];
+
+        let snippets = generator.generate_multi_query(content, &terms, 3, false);
+        assert_eq!(snippets, vec![content.to_string()]);
+    }
+
+    #[test]
+    fn test_generate_with_positions() {
+        let generator = SnippetGenerator::new();
+        let content = "Ünïcode Crawler: a web crawler visits ÜNÏCODE pages.";
+        let terms = vec!["crawler".to_string(), "ünïcode".to_string()];
+
+        let (snippet, spans) = generator.generate_with_positions(content, &terms, false);
+        assert_eq!(snippet, content);
+
+        let matched: Vec<&str> = spans.iter().map(|span| &snippet[span.start_byte..span.end_byte]).collect();
+        assert_eq!(matched, vec!["Ünïcode", "Crawler", "crawler", "ÜNÏCODE"]);
+        assert!(spans.iter().all(|span| span.field == "content"));
+        assert_eq!(spans[1].term, "crawler");
+
+        let (_, spans) = generator.generate_with_positions(content, &["spider".to_string()], false);
+        assert!(spans.is_empty());
+
+        // highlighted, the spans point into the returned markup
+        let (snippet, spans) = generator.generate_with_positions(content, &terms[..1], true);
+        assert_eq!(snippet, "Ünïcode **Crawler**: a web **crawler** visits ÜNÏCODE pages.");
+        let matched: Vec<&str> = spans.iter().map(|span| &snippet[span.start_byte..span.end_byte]).collect();
+        assert_eq!(matched, vec!["Crawler", "crawler"]);
+        assert_eq!(&snippet[spans[0].start_byte - 2..spans[0].end_byte + 2], "**Crawler**");
+    }
+
+    #[test]
+    fn test_generate_structured_keeps_fields_apart() {
+        let generator = SnippetGenerator::new();
+        let page = StoredPage {
+            title: Some("Web Crawler Guide".to_string()),
+            description: Some("  ".to_string()),
+            content: "A crawler downloads pages and follows their links.".to_string(),
+            ..stored_page(1, "https://example.com/guide")
+        };
+        let terms = vec!["crawler".to_string()];
+
+        let snippet = generator.generate_structured(&page, &terms, true);
+        assert_eq!(snippet.title.as_deref(), Some("Web **Crawler** Guide"));
+        assert_eq!(snippet.description, None);
+        assert_eq!(snippet.body_snippet, "A **crawler** downloads pages and follows their links.");
+
+        let page = StoredPage { description: Some("How crawlers work".to_string()), ..page };
+        let snippet = generator.generate_structured(&page, &terms, false);
+        assert_eq!(snippet.title.as_deref(), Some("Web Crawler Guide"));
+        assert_eq!(snippet.description.as_deref(), Some("How crawlers work"));
+        assert!(!snippet.body_snippet.contains("Guide"));
+    }
+
     #[test]
     fn test_unicode_content() {
         let generator = SnippetGenerator::new();
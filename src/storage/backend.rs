@@ -0,0 +1,269 @@
+//! Storage backend abstraction so the crawler is not tied to PostgreSQL
+
+use crate::models::{CrawlUrl, PageData};
+use crate::network::{CachedHeaders, Cookie};
+use crate::storage::Result;
+use crate::storage::database::{BackendKind, Database, DatabasePool};
+use crate::storage::models::{DatabaseStats, PageFilter, PagePage, PageVersion, SaveResult, StoredPage, StoredPageSummary};
+use crate::storage::repository::PageRepository;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+
+/// Page storage operations shared by every database backend.
+/// Mirrors the public API of `PageRepository`.
+#[async_trait]
+pub trait StorageBackend: Send + Sync {
+    /// Which database this backend talks to
+    fn kind(&self) -> BackendKind;
+
+    /// Create or upgrade the schema
+    async fn migrate(&self) -> Result<()>;
+
+    async fn health_check(&self) -> bool;
+
+    async fn save_page(&self, page: &PageData, session_id: i64) -> Result<SaveResult>;
+
+    async fn get_cached_headers(&self, url: &str) -> Result<Option<CachedHeaders>>;
+
+    async fn get_page_history(&self, url: &str) -> Result<Vec<PageVersion>>;
+
+    async fn get_page_at_version(&self, url: &str, version: u32) -> Result<Option<StoredPage>>;
+
+    async fn save_links(&self, page_id: i64, links: &[CrawlUrl]) -> Result<()>;
+
+    async fn get_page_by_id(&self, page_id: i64) -> Result<Option<StoredPage>>;
+
+    async fn get_page_by_url(&self, url: &str) -> Result<Option<StoredPage>>;
+
+    async fn url_exists(&self, url: &str) -> Result<bool>;
+
+    async fn get_pages(&self, filter: &PageFilter) -> Result<PagePage>;
+
+    /// Number of pages per language code, most common first
+    async fn get_language_distribution(&self) -> Result<Vec<(String, i64)>>;
+
+    async fn get_all_links(&self) -> Result<Vec<(String, String)>>;
+
+    /// Links found on the page at `url`, in page order
+    async fn get_outbound_links(&self, url: &str, limit: usize) -> Result<Vec<String>>;
+
+    /// Pages linking to `url`, highest PageRank first
+    async fn get_inbound_links(&self, url: &str, limit: usize) -> Result<Vec<StoredPageSummary>>;
+
+    async fn update_pagerank(&self, url: &str, pagerank: f64) -> Result<()>;
+
+    async fn batch_update_pagerank(&self, ranks: &[(String, f64)]) -> Result<()>;
+
+    async fn batch_update_personalized_pagerank(&self, ranks: &[(String, f64)]) -> Result<()>;
+
+    async fn batch_update_hits_scores(&self, scores: &[(String, f64, f64)]) -> Result<()>;
+
+    async fn get_top_pages_by_pagerank(&self, limit: usize) -> Result<Vec<StoredPage>>;
+
+    async fn get_pages_by_domain(&self, domain: &str, limit: usize) -> Result<Vec<StoredPage>> {
+        let filter = PageFilter::new().with_domain(domain.to_string()).with_limit(limit);
+        Ok(self.get_pages(&filter).await?.items)
+    }
+
+    async fn find_near_duplicates(&self, url: &str, threshold: u32) -> Result<Vec<StoredPage>>;
+
+    async fn search_pages(&self, q: &str, limit: usize) -> Result<Vec<StoredPage>>;
+
+    async fn batch_save_pages(&self, pages: &[PageData], session_id: i64) -> Result<Vec<i64>>;
+
+    async fn update_tfidf_score(&self, url_hash: &str, tfidf: f64) -> Result<()>;
+
+    async fn delete_page(&self, url: &str) -> Result<()>;
+
+    async fn delete_pages_by_domain(&self, domain: &str) -> Result<u64>;
+
+    async fn purge_stale_pages(&self, older_than: DateTime<Utc>) -> Result<u64>;
+
+    async fn create_crawl_session(
+        &self,
+        seed_urls: &[String],
+        config: &crate::config::CrawlerConfig,
+    ) -> Result<i64>;
+
+    async fn update_crawl_session(&self, session_id: i64, crawled: i32, failed: i32) -> Result<()>;
+
+    async fn complete_crawl_session(&self, session_id: i64, status: &str) -> Result<()>;
+
+    async fn get_stats(&self) -> Result<DatabaseStats>;
+
+    async fn save_cookies(&self, cookies: &[Cookie<'static>]) -> Result<()>;
+
+    async fn load_cookies(&self) -> Result<Vec<Cookie<'static>>>;
+}
+
+/// PostgreSQL backend, delegating to `PageRepository`
+pub struct PostgresBackend {
+    pool: DatabasePool,
+    repository: PageRepository,
+}
+
+impl PostgresBackend {
+    pub fn new(pool: DatabasePool) -> Self {
+        Self {
+            repository: PageRepository::new(pool.clone()),
+            pool,
+        }
+    }
+
+    /// Store saved pages zstd-compressed, see `PageRepository::with_compression`
+    pub fn with_compression(mut self, enabled: bool) -> Self {
+        self.repository = self.repository.with_compression(enabled);
+        self
+    }
+
+    pub fn pool(&self) -> &DatabasePool {
+        &self.pool
+    }
+
+    pub fn repository(&self) -> &PageRepository {
+        &self.repository
+    }
+}
+
+#[async_trait]
+impl StorageBackend for PostgresBackend {
+    fn kind(&self) -> BackendKind {
+        BackendKind::Postgres
+    }
+
+    async fn migrate(&self) -> Result<()> {
+        Database::migrate(&self.pool).await
+    }
+
+    async fn health_check(&self) -> bool {
+        Database::health_check(&self.pool).await
+    }
+
+    async fn save_page(&self, page: &PageData, session_id: i64) -> Result<SaveResult> {
+        self.repository.save_page(page, session_id).await
+    }
+
+    async fn get_cached_headers(&self, url: &str) -> Result<Option<CachedHeaders>> {
+        self.repository.get_cached_headers(url).await
+    }
+
+    async fn get_page_history(&self, url: &str) -> Result<Vec<PageVersion>> {
+        self.repository.get_page_history(url).await
+    }
+
+    async fn get_page_at_version(&self, url: &str, version: u32) -> Result<Option<StoredPage>> {
+        self.repository.get_page_at_version(url, version).await
+    }
+
+    async fn save_links(&self, page_id: i64, links: &[CrawlUrl]) -> Result<()> {
+        self.repository.save_links(page_id, links).await
+    }
+
+    async fn get_page_by_id(&self, page_id: i64) -> Result<Option<StoredPage>> {
+        self.repository.get_page_by_id(page_id).await
+    }
+
+    async fn get_page_by_url(&self, url: &str) -> Result<Option<StoredPage>> {
+        self.repository.get_page_by_url(url).await
+    }
+
+    async fn url_exists(&self, url: &str) -> Result<bool> {
+        self.repository.url_exists(url).await
+    }
+
+    async fn get_pages(&self, filter: &PageFilter) -> Result<PagePage> {
+        self.repository.get_pages(filter).await
+    }
+
+    async fn get_language_distribution(&self) -> Result<Vec<(String, i64)>> {
+        self.repository.get_language_distribution().await
+    }
+
+    async fn get_all_links(&self) -> Result<Vec<(String, String)>> {
+        self.repository.get_all_links().await
+    }
+
+    async fn get_outbound_links(&self, url: &str, limit: usize) -> Result<Vec<String>> {
+        self.repository.get_outbound_links(url, limit).await
+    }
+
+    async fn get_inbound_links(&self, url: &str, limit: usize) -> Result<Vec<StoredPageSummary>> {
+        self.repository.get_inbound_links(url, limit).await
+    }
+
+    async fn update_pagerank(&self, url: &str, pagerank: f64) -> Result<()> {
+        self.repository.update_pagerank(url, pagerank).await
+    }
+
+    async fn batch_update_pagerank(&self, ranks: &[(String, f64)]) -> Result<()> {
+        self.repository.batch_update_pagerank(ranks).await
+    }
+
+    async fn batch_update_personalized_pagerank(&self, ranks: &[(String, f64)]) -> Result<()> {
+        self.repository.batch_update_personalized_pagerank(ranks).await
+    }
+
+    async fn batch_update_hits_scores(&self, scores: &[(String, f64, f64)]) -> Result<()> {
+        self.repository.batch_update_hits_scores(scores).await
+    }
+
+    async fn get_top_pages_by_pagerank(&self, limit: usize) -> Result<Vec<StoredPage>> {
+        self.repository.get_top_pages_by_pagerank(limit).await
+    }
+
+    async fn find_near_duplicates(&self, url: &str, threshold: u32) -> Result<Vec<StoredPage>> {
+        self.repository.find_near_duplicates(url, threshold).await
+    }
+
+    async fn search_pages(&self, q: &str, limit: usize) -> Result<Vec<StoredPage>> {
+        self.repository.search_pages(q, limit).await
+    }
+
+    async fn batch_save_pages(&self, pages: &[PageData], session_id: i64) -> Result<Vec<i64>> {
+        self.repository.batch_save_pages(pages, session_id).await
+    }
+
+    async fn update_tfidf_score(&self, url_hash: &str, tfidf: f64) -> Result<()> {
+        self.repository.update_tfidf_score(url_hash, tfidf).await
+    }
+
+    async fn delete_page(&self, url: &str) -> Result<()> {
+        self.repository.delete_page(url).await
+    }
+
+    async fn delete_pages_by_domain(&self, domain: &str) -> Result<u64> {
+        self.repository.delete_pages_by_domain(domain).await
+    }
+
+    async fn purge_stale_pages(&self, older_than: DateTime<Utc>) -> Result<u64> {
+        self.repository.purge_stale_pages(older_than).await
+    }
+
+    async fn create_crawl_session(
+        &self,
+        seed_urls: &[String],
+        config: &crate::config::CrawlerConfig,
+    ) -> Result<i64> {
+        self.repository.create_crawl_session(seed_urls, config).await
+    }
+
+    async fn update_crawl_session(&self, session_id: i64, crawled: i32, failed: i32) -> Result<()> {
+        self.repository.update_crawl_session(session_id, crawled, failed).await
+    }
+
+    async fn complete_crawl_session(&self, session_id: i64, status: &str) -> Result<()> {
+        self.repository.complete_crawl_session(session_id, status).await
+    }
+
+    async fn get_stats(&self) -> Result<DatabaseStats> {
+        self.repository.get_stats().await
+    }
+
+    async fn save_cookies(&self, cookies: &[Cookie<'static>]) -> Result<()> {
+        self.repository.save_cookies(cookies).await
+    }
+
+    async fn load_cookies(&self) -> Result<Vec<Cookie<'static>>> {
+        self.repository.load_cookies().await
+    }
+}
@@ -1,83 +1,161 @@
 // in memory caching implementaion
 
+use async_trait::async_trait;
+use moka::Expiry;
 use moka::sync::Cache as MokaCache;
+use redis::AsyncCommands;
+use redis::aio::MultiplexedConnection;
 use serde::{Serialize, Deserialize};
-use std::time::Duration;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
 use tracing::{info, debug};
+use crate::config::{CacheSettings, StorageSettings};
 use crate::storage::{ Result, StorageError};
 use crate::storage::models::{ StoredPage};
 
+// A cached value and when it expires, so every entry can have its own TTL
+#[derive(Clone)]
+struct Expiring<V> {
+    value: V,
+    expires_at: Instant,
+}
+
+impl<V> Expiring<V> {
+    fn new(value: V, ttl: Duration) -> Self {
+        Self { value, expires_at: Instant::now() + ttl }
+    }
+}
+
+// Moka has no per-insert TTL on the sync cache, its expiry policy reads it from the entry instead
+struct EntryExpiry;
+
+impl<V> Expiry<String, Expiring<V>> for EntryExpiry {
+    fn expire_after_create(&self, _key: &String, value: &Expiring<V>, created_at: Instant) -> Option<Duration> {
+        Some(value.expires_at.saturating_duration_since(created_at))
+    }
+
+    fn expire_after_update(
+        &self,
+        _key: &String,
+        value: &Expiring<V>,
+        updated_at: Instant,
+        _duration_until_expiry: Option<Duration>,
+    ) -> Option<Duration> {
+        Some(value.expires_at.saturating_duration_since(updated_at))
+    }
+}
+
 // In memory cache implementaion using Moka
 pub struct MemoryCache{
     // Page cache
-    page_cache: MokaCache<String, StoredPage>,
+    page_cache: MokaCache<String, Expiring<StoredPage>>,
 
     // Search result cache
-    search_cache: MokaCache<String, Vec<StoredPage>>,
+    search_cache: MokaCache<String, Expiring<Vec<StoredPage>>>,
 
     // URL existance cache (for duplocate detection)
-    url_cache: MokaCache<String, bool>,
+    url_cache: MokaCache<String, Expiring<bool>>,
 
     // General purpose cache for serializable data
-    general_cache: MokaCache<String, String>,  // json string
+    general_cache: MokaCache<String, Expiring<String>>,  // json string
 
     // Configuration
     default_ttl: Duration,
+    search_ttl: Duration,
+    url_exists_ttl: Duration,
+
+    // Lookup counters since creation or the last reset_stats
+    hits: AtomicU64,
+    misses: AtomicU64,
+    // ring of the latest lookups for get_windowed_stats, written without locking: nanoseconds
+    // since `created` shifted left by one, the low bit set for a hit
+    samples: Box<[AtomicU64]>,
+    // lookups sampled since creation or the last reset_stats, the next slot is this modulo the ring size
+    sampled: AtomicU64,
+    created: Instant,
 }
 
+/// Lookups kept for windowed statistics, older ones are dropped first
+const MAX_LOOKUP_SAMPLES: usize = 100_000;
+
 impl MemoryCache {
-    // Create a new memory cache
+    // Create a new memory cache, search results and URL existence use the CacheSettings defaults
     pub fn new(max_capaciity : u64, default_ttl : Duration) -> Self {
         info!("Initializing memory cache with capacity : {}, TTL: {:?}", max_capaciity, default_ttl);
+        let settings = CacheSettings::default();
 
         Self{
-            page_cache:MokaCache::builder()
-                .max_capacity(max_capaciity/4)
-                .time_to_live(default_ttl)
-                .build(),
-
-            search_cache: MokaCache::builder()
-                .max_capacity(max_capaciity/4)
-                .time_to_live(Duration::from_secs(300))
-                .build(),
-
-            url_cache: MokaCache::builder()
-                .max_capacity(max_capaciity/2)
-                .time_to_live(default_ttl)
-                .build(),
-
-            general_cache: MokaCache::builder()
-                .max_capacity(max_capaciity/4)
-                .time_to_live(default_ttl)
-                .build(),
-
+            page_cache: Self::build_cache(max_capaciity/4),
+            search_cache: Self::build_cache(max_capaciity/4),
+            url_cache: Self::build_cache(max_capaciity/2),
+            general_cache: Self::build_cache(max_capaciity/4),
             default_ttl,
+            search_ttl: settings.search_ttl(),
+            url_exists_ttl: settings.url_exists_ttl(),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+            samples: (0..MAX_LOOKUP_SAMPLES).map(|_| AtomicU64::new(0)).collect(),
+            sampled: AtomicU64::new(0),
+            created: Instant::now(),
         }
     }
 
+    /// Cache sized and timed by `settings`
+    pub fn from_settings(settings: &CacheSettings) -> Self {
+        Self::new(settings.max_capacity, settings.default_ttl())
+            .with_search_ttl(settings.search_ttl())
+            .with_url_exists_ttl(settings.url_exists_ttl())
+    }
+
+    fn build_cache<V: Clone + Send + Sync + 'static>(max_capacity: u64) -> MokaCache<String, Expiring<V>> {
+        MokaCache::builder()
+            .max_capacity(max_capacity)
+            .expire_after(EntryExpiry)
+            .build()
+    }
+
+    /// TTL of cached search results, also used by `SearchResultCache`
+    pub fn with_search_ttl(mut self, ttl: Duration) -> Self {
+        self.search_ttl = ttl;
+        self
+    }
+
+    pub fn with_url_exists_ttl(mut self, ttl: Duration) -> Self {
+        self.url_exists_ttl = ttl;
+        self
+    }
+
+    pub fn search_ttl(&self) -> Duration {
+        self.search_ttl
+    }
+
     // create a cache with default settings
     pub fn default() -> Self{
-        Self::new(10_000, Duration::from_secs(3600)) // 10k entries, 1 hr ttl
+        Self::from_settings(&CacheSettings::default()) // 10k entries, 1 hr ttl
     }
 
     // page cache methods
 
     // cache a page
     pub fn cache_page(&self, page: &StoredPage){
-        let key = format!("Page: {}", page.id);
-        self.page_cache.insert(key.clone(), page.clone());
+        self.cache_page_with_ttl(page, self.default_ttl);
+    }
+
+    /// Cache a page for `ttl` instead of the default TTL
+    pub fn cache_page_with_ttl(&self, page: &StoredPage, ttl: Duration){
+        let entry = Expiring::new(page.clone(), ttl);
+        self.page_cache.insert(page_id_key(page.id), entry.clone());
 
         // Also cache URL -> ID mapping
-        let url_key = format!("URL: {}", page.url);
-        self.page_cache.insert(url_key, page.clone());
+        self.page_cache.insert(page_url_key(&page.url), entry);
 
-        debug!("Cached page: {} (ID : {})", page.url, page.id);
+        debug!("Cached page: {} (ID : {}) for {:?}", page.url, page.id, ttl);
     }
 
     // Get a page by ID from cache
     pub fn get_page_by_id(&self, page_id: i64)-> Option<StoredPage>{
-        let key = format!("Page: {}", page_id);
-        let result = self.page_cache.get(&key);
+        let result = self.page_cache.get(&page_id_key(page_id)).map(|entry| entry.value);
+        self.record_lookup(result.is_some());
 
         if result.is_some(){
             debug!("Cache hit for page ID: {}", page_id);
@@ -87,8 +165,8 @@ impl MemoryCache {
 
     // get a page by URL from cache
     pub fn get_page_by_url(&self, url: &str)-> Option<StoredPage>{
-        let key = format!("url : {}", url);
-        let result = self.page_cache.get(&key);
+        let result = self.page_cache.get(&page_url_key(url)).map(|entry| entry.value);
+        self.record_lookup(result.is_some());
 
         if result.is_some(){
             debug!("Cache hit for page URL: {}", url);
@@ -101,13 +179,13 @@ impl MemoryCache {
 
     // Cache url existance
     pub fn cache_url_exists(&self, url:&str, exists: bool){
-        self.url_cache.insert(url.to_string(), exists);
+        self.url_cache.insert(url.to_string(), Expiring::new(exists, self.url_exists_ttl));
         debug!("Cached URL existance {}: {}", url, exists);
     }
 
     // check if URL existance is  cached
     pub fn get_url_exists(&self, url:&str)-> Option<bool>{
-        self.url_cache.get(url)
+        self.url_cache.get(url).map(|entry| entry.value)
     }
 
     // search result caching
@@ -115,14 +193,15 @@ impl MemoryCache {
     // caching search results
     pub fn cache_search_results(&self, query: &str, limit:usize, offset: usize, results: &[StoredPage]){
         let key = format!("Search : {} : {} : {}", query, limit, offset);
-        self.search_cache.insert(key, results.to_vec());
+        self.search_cache.insert(key, Expiring::new(results.to_vec(), self.search_ttl));
         debug!("Cache search results for query : {} ({} results)", query, results.len());
     }
 
     // Get cached search results
     pub fn get_search_results(&self, query:&str, limit: usize, offset: usize) -> Option<Vec<StoredPage>> {
         let key = format!("Search : {} : {} : {}", query, limit, offset);
-        let result = self.search_cache.get(&key);
+        let result = self.search_cache.get(&key).map(|entry| entry.value);
+        self.record_lookup(result.is_some());
 
         if result.is_some(){
             debug!("Cache hit for search: {}", query);
@@ -135,18 +214,26 @@ impl MemoryCache {
 
     // set a value in general cahce
     pub fn set<T: Serialize>(&self, key: &str, value: &T) -> Result<()> {
+        self.set_with_ttl(key, value, self.default_ttl)
+    }
+
+    /// Set a value in the general cache that expires after `ttl` instead of the default TTL
+    pub fn set_with_ttl<T: Serialize>(&self, key: &str, value: &T, ttl: Duration) -> Result<()> {
         let json_value = serde_json::to_string(value)
             .map_err(|e| StorageError::Serialization(e))?;
 
-        self.general_cache.insert(key.to_string(), json_value);
-        debug!("Cached value for key: {}", key);
+        self.general_cache.insert(key.to_string(), Expiring::new(json_value, ttl));
+        debug!("Cached value for key: {} for {:?}", key, ttl);
         Ok(())
     }
 
     /// Get a value from the general cache
     pub fn get<T: for<'de> Deserialize<'de>>(&self, key: &str) -> Result<Option<T>> {
-        if let Some(json_value) = self.general_cache.get(key) {
-            let value = serde_json::from_str(&json_value)
+        let entry = self.general_cache.get(key);
+        self.record_lookup(entry.is_some());
+
+        if let Some(entry) = entry {
+            let value = serde_json::from_str(&entry.value)
                 .map_err(|e| StorageError::Serialization(e))?;
             debug!("Cache hit for key: {}", key);
             Ok(Some(value))
@@ -155,6 +242,16 @@ impl MemoryCache {
         }
     }
 
+    /// Time left before `key` expires, looked up in the same caches as `invalidate`
+    pub fn get_remaining_ttl(&self, key: &str) -> Option<Duration> {
+        let expires_at = self.general_cache.get(key).map(|entry| entry.expires_at)
+            .or_else(|| self.page_cache.get(key).map(|entry| entry.expires_at))
+            .or_else(|| self.search_cache.get(key).map(|entry| entry.expires_at))
+            .or_else(|| self.url_cache.get(key).map(|entry| entry.expires_at))?;
+
+        Some(expires_at.saturating_duration_since(Instant::now()))
+    }
+
     /// Remove a value from all caches
     pub fn invalidate(&self, key: &str) {
         // Try to remove from all caches
@@ -176,6 +273,10 @@ impl MemoryCache {
 
     /// Get cache statistics
     pub fn get_stats(&self) -> CacheStats {
+        let total_hits = self.hits.load(Ordering::Relaxed);
+        let total_requests = total_hits + self.misses.load(Ordering::Relaxed);
+        let (hit_rate, miss_rate) = hit_and_miss_rate(total_hits, total_requests);
+
         CacheStats {
             page_cache_size: self.page_cache.entry_count(),
             search_cache_size: self.search_cache.entry_count(),
@@ -185,9 +286,59 @@ impl MemoryCache {
                 self.search_cache.entry_count() +
                 self.url_cache.entry_count() +
                 self.general_cache.entry_count(),
+            hit_rate,
+            miss_rate,
+            total_requests,
+            total_hits,
+        }
+    }
+
+    /// Hits and misses of the lookups in the last `window`, only the latest
+    /// MAX_LOOKUP_SAMPLES lookups are remembered
+    pub fn get_windowed_stats(&self, window: Duration) -> WindowedCacheStats {
+        let now = self.created.elapsed().as_nanos() as u64;
+        let window_nanos = window.as_nanos().min(u64::MAX as u128) as u64;
+        let sampled = self.sampled.load(Ordering::Acquire);
+
+        // concurrent lookups may land out of order, so every remembered slot is checked
+        let (mut hits, mut misses) = (0, 0);
+        for slot in &self.samples[..sampled.min(MAX_LOOKUP_SAMPLES as u64) as usize] {
+            let sample = slot.load(Ordering::Relaxed);
+            if now.saturating_sub(sample >> 1) <= window_nanos {
+                if sample & 1 == 1 { hits += 1 } else { misses += 1 }
+            }
+        }
+
+        let (hit_rate, miss_rate) = hit_and_miss_rate(hits, hits + misses);
+        WindowedCacheStats {
+            window_secs: window.as_secs(),
+            requests: hits + misses,
+            hits,
+            misses,
+            hit_rate,
+            miss_rate,
         }
     }
 
+    /// Forget all hits and misses, cached entries are kept
+    pub fn reset_stats(&self) {
+        self.hits.store(0, Ordering::Relaxed);
+        self.misses.store(0, Ordering::Relaxed);
+        self.sampled.store(0, Ordering::Release);
+    }
+
+    fn record_lookup(&self, hit: bool) {
+        if hit {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+        } else {
+            self.misses.fetch_add(1, Ordering::Relaxed);
+        }
+
+        let at = self.created.elapsed().as_nanos() as u64;
+        let slot = self.sampled.fetch_add(1, Ordering::AcqRel) % MAX_LOOKUP_SAMPLES as u64;
+        self.samples[slot as usize].store(at << 1 | hit as u64, Ordering::Relaxed);
+    }
+
     /// Run cache maintenance (cleanup expired entries)
     pub fn run_pending_tasks(&self) {
         self.page_cache.run_pending_tasks();
@@ -198,6 +349,199 @@ impl MemoryCache {
 
 }
 
+/// Page, URL existence and search result caching, shared by the in-process and Redis caches
+#[async_trait]
+pub trait CacheBackend: Send + Sync {
+    async fn cache_page(&self, page: &StoredPage) -> Result<()>;
+    async fn get_page_by_id(&self, page_id: i64) -> Result<Option<StoredPage>>;
+    async fn get_page_by_url(&self, url: &str) -> Result<Option<StoredPage>>;
+    async fn cache_url_exists(&self, url: &str, exists: bool) -> Result<()>;
+    async fn get_url_exists(&self, url: &str) -> Result<Option<bool>>;
+    async fn cache_search_results(&self, query: &str, limit: usize, offset: usize, results: &[StoredPage]) -> Result<()>;
+    async fn get_search_results(&self, query: &str, limit: usize, offset: usize) -> Result<Option<Vec<StoredPage>>>;
+}
+
+#[async_trait]
+impl CacheBackend for MemoryCache {
+    async fn cache_page(&self, page: &StoredPage) -> Result<()> {
+        MemoryCache::cache_page(self, page);
+        Ok(())
+    }
+
+    async fn get_page_by_id(&self, page_id: i64) -> Result<Option<StoredPage>> {
+        Ok(MemoryCache::get_page_by_id(self, page_id))
+    }
+
+    async fn get_page_by_url(&self, url: &str) -> Result<Option<StoredPage>> {
+        Ok(MemoryCache::get_page_by_url(self, url))
+    }
+
+    async fn cache_url_exists(&self, url: &str, exists: bool) -> Result<()> {
+        MemoryCache::cache_url_exists(self, url, exists);
+        Ok(())
+    }
+
+    async fn get_url_exists(&self, url: &str) -> Result<Option<bool>> {
+        Ok(MemoryCache::get_url_exists(self, url))
+    }
+
+    async fn cache_search_results(&self, query: &str, limit: usize, offset: usize, results: &[StoredPage]) -> Result<()> {
+        MemoryCache::cache_search_results(self, query, limit, offset, results);
+        Ok(())
+    }
+
+    async fn get_search_results(&self, query: &str, limit: usize, offset: usize) -> Result<Option<Vec<StoredPage>>> {
+        Ok(MemoryCache::get_search_results(self, query, limit, offset))
+    }
+}
+
+/// Cache shared by every crawler process pointed at the same Redis server.
+/// Values are stored as MessagePack with a TTL, keys are prefixed with `crawler:`
+#[derive(Clone)]
+pub struct RedisCache {
+    // multiplexed, clones share one connection
+    connection: MultiplexedConnection,
+    default_ttl: Duration,
+    search_ttl: Duration,
+    url_exists_ttl: Duration,
+}
+
+impl RedisCache {
+    pub async fn new(redis_url: &str, default_ttl: Duration) -> Result<Self> {
+        info!("Connecting to Redis cache at {}", redis_url);
+
+        let client = redis::Client::open(redis_url).map_err(Self::cache_error)?;
+        let connection = client.get_multiplexed_async_connection().await.map_err(Self::cache_error)?;
+
+        let settings = CacheSettings::default();
+        Ok(Self { connection, default_ttl, search_ttl: settings.search_ttl(), url_exists_ttl: settings.url_exists_ttl() })
+    }
+
+    pub fn with_search_ttl(mut self, ttl: Duration) -> Self {
+        self.search_ttl = ttl;
+        self
+    }
+
+    pub fn with_url_exists_ttl(mut self, ttl: Duration) -> Self {
+        self.url_exists_ttl = ttl;
+        self
+    }
+
+    fn cache_error(e: redis::RedisError) -> StorageError {
+        StorageError::Cache(e.to_string())
+    }
+
+    async fn put<T: Serialize + ?Sized>(&self, key: &str, value: &T, ttl: Duration) -> Result<()> {
+        // named fields, so entries written before a StoredPage field was added still decode
+        let bytes = rmp_serde::to_vec_named(value).map_err(|e| StorageError::Cache(e.to_string()))?;
+
+        let mut connection = self.connection.clone();
+        connection.set_ex::<_, _, ()>(key, bytes, ttl.as_secs().max(1)).await.map_err(Self::cache_error)?;
+        debug!("Cached value in Redis for key: {}", key);
+        Ok(())
+    }
+
+    async fn fetch<T: for<'de> Deserialize<'de>>(&self, key: &str) -> Result<Option<T>> {
+        let mut connection = self.connection.clone();
+        let bytes: Option<Vec<u8>> = connection.get(key).await.map_err(Self::cache_error)?;
+
+        bytes
+            .map(|bytes| rmp_serde::from_slice(&bytes).map_err(|e| StorageError::Cache(e.to_string())))
+            .transpose()
+    }
+}
+
+#[async_trait]
+impl CacheBackend for RedisCache {
+    async fn cache_page(&self, page: &StoredPage) -> Result<()> {
+        self.put(&format!("crawler:page:{}", page.id), page, self.default_ttl).await?;
+        self.put(&format!("crawler:url:{}", page.url), page, self.default_ttl).await
+    }
+
+    async fn get_page_by_id(&self, page_id: i64) -> Result<Option<StoredPage>> {
+        self.fetch(&format!("crawler:page:{}", page_id)).await
+    }
+
+    async fn get_page_by_url(&self, url: &str) -> Result<Option<StoredPage>> {
+        self.fetch(&format!("crawler:url:{}", url)).await
+    }
+
+    async fn cache_url_exists(&self, url: &str, exists: bool) -> Result<()> {
+        self.put(&format!("crawler:exists:{}", url), &exists, self.url_exists_ttl).await
+    }
+
+    async fn get_url_exists(&self, url: &str) -> Result<Option<bool>> {
+        self.fetch(&format!("crawler:exists:{}", url)).await
+    }
+
+    async fn cache_search_results(&self, query: &str, limit: usize, offset: usize, results: &[StoredPage]) -> Result<()> {
+        self.put(&format!("crawler:{}", search_key(query, limit, offset)), results, self.search_ttl).await
+    }
+
+    async fn get_search_results(&self, query: &str, limit: usize, offset: usize) -> Result<Option<Vec<StoredPage>>> {
+        self.fetch(&format!("crawler:{}", search_key(query, limit, offset))).await
+    }
+}
+
+/// The cache configured in `StorageSettings`
+pub enum AnyCache {
+    Memory(Box<MemoryCache>),
+    Redis(RedisCache),
+}
+
+impl AnyCache {
+    /// Redis when `redis_url` is set, otherwise an in-process cache
+    pub async fn from_settings(settings: &StorageSettings) -> Result<Self> {
+        let cache = &settings.cache;
+        match &settings.redis_url {
+            Some(redis_url) => Ok(Self::Redis(
+                RedisCache::new(redis_url, cache.default_ttl()).await?
+                    .with_search_ttl(cache.search_ttl())
+                    .with_url_exists_ttl(cache.url_exists_ttl()),
+            )),
+            None => Ok(Self::Memory(Box::new(MemoryCache::from_settings(cache)))),
+        }
+    }
+
+    fn backend(&self) -> &dyn CacheBackend {
+        match self {
+            Self::Memory(cache) => cache.as_ref(),
+            Self::Redis(cache) => cache,
+        }
+    }
+}
+
+#[async_trait]
+impl CacheBackend for AnyCache {
+    async fn cache_page(&self, page: &StoredPage) -> Result<()> {
+        self.backend().cache_page(page).await
+    }
+
+    async fn get_page_by_id(&self, page_id: i64) -> Result<Option<StoredPage>> {
+        self.backend().get_page_by_id(page_id).await
+    }
+
+    async fn get_page_by_url(&self, url: &str) -> Result<Option<StoredPage>> {
+        self.backend().get_page_by_url(url).await
+    }
+
+    async fn cache_url_exists(&self, url: &str, exists: bool) -> Result<()> {
+        self.backend().cache_url_exists(url, exists).await
+    }
+
+    async fn get_url_exists(&self, url: &str) -> Result<Option<bool>> {
+        self.backend().get_url_exists(url).await
+    }
+
+    async fn cache_search_results(&self, query: &str, limit: usize, offset: usize, results: &[StoredPage]) -> Result<()> {
+        self.backend().cache_search_results(query, limit, offset, results).await
+    }
+
+    async fn get_search_results(&self, query: &str, limit: usize, offset: usize) -> Result<Option<Vec<StoredPage>>> {
+        self.backend().get_search_results(query, limit, offset).await
+    }
+}
+
 /// Cache statistics
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CacheStats {
@@ -206,6 +550,41 @@ pub struct CacheStats {
     pub url_cache_size: u64,
     pub general_cache_size: u64,
     pub total_entries: u64,
+    /// Share of page, search and general lookups that were hits
+    pub hit_rate: f64,
+    pub miss_rate: f64,
+    pub total_requests: u64,
+    pub total_hits: u64,
+}
+
+/// Cache lookups within a recent time window
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WindowedCacheStats {
+    pub window_secs: u64,
+    pub requests: u64,
+    pub hits: u64,
+    pub misses: u64,
+    pub hit_rate: f64,
+    pub miss_rate: f64,
+}
+
+/// Hit and miss rate of `requests` lookups, both 0 without lookups
+fn hit_and_miss_rate(hits: u64, requests: u64) -> (f64, f64) {
+    if requests == 0 {
+        return (0.0, 0.0);
+    }
+    let hit_rate = hits as f64 / requests as f64;
+    (hit_rate, 1.0 - hit_rate)
+}
+
+/// Page cache key for a page ID
+fn page_id_key(page_id: i64) -> String {
+    format!("Page: {}", page_id)
+}
+
+/// Page cache key for a page URL
+fn page_url_key(url: &str) -> String {
+    format!("URL: {}", url)
 }
 
 /// Cached search query key
@@ -216,8 +595,7 @@ fn search_key(query: &str, limit: usize, offset: usize) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::storage::StoredPage;
-    use chrono::Utc;
+    use crate::storage::models::tests::stored_page;
 
     #[test]
     fn test_memory_cache_creation() {
@@ -230,27 +608,7 @@ mod tests {
     fn test_page_caching() {
         let cache = MemoryCache::default();
 
-        let page = StoredPage {
-            id: 1,
-            url: "https://example.com".to_string(),
-            url_hash: "hash123".to_string(),
-            domain: "example.com".to_string(),
-            title: Some("Test Page".to_string()),
-            description: None,
-            content: "Test content".to_string(),
-            content_hash: "content_hash".to_string(),
-            quality_score: 0.8,
-            word_count: 2,
-            language: "en".to_string(),
-            crawl_depth: 1,
-            crawled_at: Utc::now(),
-            last_modified: None,
-            status_code: 200,
-            content_type: "text/html".to_string(),
-            content_length: 12,
-            pagerank: None,
-            tfidf_score: None,
-        };
+        let page = stored_page(1, "https://example.com");
 
         // Cache the page
         cache.cache_page(&page);
@@ -342,4 +700,131 @@ mod tests {
         let stats_after = cache.get_stats();
         assert_eq!(stats_after.total_entries, 0);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_set_with_ttl_expires_before_default() {
+        let cache = MemoryCache::default();
+
+        cache.set_with_ttl("short", &"value".to_string(), Duration::from_millis(50)).unwrap();
+        cache.set("long", &"value".to_string()).unwrap();
+        assert!(cache.get::<String>("short").unwrap().is_some());
+
+        std::thread::sleep(Duration::from_millis(120));
+
+        assert!(cache.get::<String>("short").unwrap().is_none());
+        assert!(cache.get::<String>("long").unwrap().is_some());
+        assert!(cache.get_remaining_ttl("short").is_none());
+    }
+
+    #[test]
+    fn test_cache_page_with_ttl_expires() {
+        let cache = MemoryCache::default();
+        let page = stored_page(7, "https://example.com/short");
+
+        cache.cache_page_with_ttl(&page, Duration::from_millis(50));
+        assert_eq!(cache.get_page_by_url("https://example.com/short").map(|p| p.id), Some(7));
+
+        std::thread::sleep(Duration::from_millis(120));
+
+        assert!(cache.get_page_by_id(7).is_none());
+        assert!(cache.get_page_by_url("https://example.com/short").is_none());
+    }
+
+    #[test]
+    fn test_remaining_ttl_per_cache() {
+        let settings = CacheSettings { default_ttl_secs: 600, search_ttl_secs: 60, url_exists_ttl_secs: 6000, ..CacheSettings::default() };
+        let cache = MemoryCache::from_settings(&settings);
+
+        cache.set_with_ttl("key", &1, Duration::from_secs(30)).unwrap();
+        let remaining = cache.get_remaining_ttl("key").unwrap();
+        assert!(remaining <= Duration::from_secs(30) && remaining > Duration::from_secs(29));
+
+        // URL existence outlives the default TTL, search results don't reach it
+        cache.cache_url_exists("https://example.com", true);
+        assert!(cache.get_remaining_ttl("https://example.com").unwrap() > Duration::from_secs(600));
+
+        cache.cache_search_results("rust", 10, 0, &[]);
+        assert!(cache.get_remaining_ttl("Search : rust : 10 : 0").unwrap() <= Duration::from_secs(60));
+
+        assert!(cache.get_remaining_ttl("missing").is_none());
+    }
+
+    #[test]
+    fn test_hit_rate_and_windowed_stats() {
+        let cache = MemoryCache::new(1000, Duration::from_secs(60));
+        assert_eq!(cache.get_stats().hit_rate, 0.0);
+
+        cache.set("key", &1).unwrap();
+        assert_eq!(cache.get::<i32>("key").unwrap(), Some(1));
+        assert_eq!(cache.get::<i32>("missing").unwrap(), None);
+        assert!(cache.get_page_by_id(1).is_none());
+        assert!(cache.get_search_results("rust", 10, 0).is_none());
+
+        let stats = cache.get_stats();
+        assert_eq!(stats.total_requests, 4);
+        assert_eq!(stats.total_hits, 1);
+        assert_eq!(stats.hit_rate, 0.25);
+        assert_eq!(stats.miss_rate, 0.75);
+
+        let window = cache.get_windowed_stats(Duration::from_secs(60));
+        assert_eq!((window.requests, window.hits, window.misses), (4, 1, 3));
+        std::thread::sleep(Duration::from_millis(20));
+        assert_eq!(cache.get_windowed_stats(Duration::from_millis(10)).requests, 0);
+
+        cache.reset_stats();
+        assert_eq!(cache.get_stats().total_requests, 0);
+        assert_eq!(cache.get_windowed_stats(Duration::from_secs(60)).requests, 0);
+        // entries survive a reset
+        assert_eq!(cache.get::<i32>("key").unwrap(), Some(1));
+    }
+
+    #[test]
+    fn test_concurrent_lookups_are_all_counted() {
+        let cache = MemoryCache::new(1000, Duration::from_secs(60));
+        cache.set("key", &1).unwrap();
+
+        std::thread::scope(|scope| {
+            for _ in 0..8 {
+                scope.spawn(|| {
+                    for i in 0..1000 {
+                        let key = if i % 2 == 0 { "key" } else { "missing" };
+                        cache.get::<i32>(key).unwrap();
+                    }
+                });
+            }
+        });
+
+        let stats = cache.get_stats();
+        assert_eq!((stats.total_requests, stats.total_hits), (8000, 4000));
+        let window = cache.get_windowed_stats(Duration::from_secs(60));
+        assert_eq!((window.requests, window.hits, window.misses), (8000, 4000, 4000));
+    }
+
+    #[tokio::test]
+    async fn test_any_cache_without_redis_is_in_memory() {
+        let cache = AnyCache::from_settings(&StorageSettings::default()).await.unwrap();
+        assert!(matches!(cache, AnyCache::Memory(_)));
+
+        cache.cache_url_exists("https://example.com", true).await.unwrap();
+        assert_eq!(cache.get_url_exists("https://example.com").await.unwrap(), Some(true));
+        assert_eq!(cache.get_url_exists("https://unknown.com").await.unwrap(), None);
+
+        cache.cache_search_results("rust", 10, 0, &[]).await.unwrap();
+        assert_eq!(cache.get_search_results("rust", 10, 0).await.unwrap().map(|r| r.len()), Some(0));
+        assert!(cache.get_search_results("rust", 10, 10).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    #[ignore] // needs a Redis server, REDIS_URL or localhost:6379
+    async fn test_redis_cache_round_trip() {
+        let settings = StorageSettings {
+            redis_url: Some(std::env::var("REDIS_URL").unwrap_or_else(|_| "redis://localhost:6379".to_string())),
+            ..StorageSettings::default()
+        };
+        let cache = AnyCache::from_settings(&settings).await.unwrap();
+        assert!(matches!(cache, AnyCache::Redis(_)));
+
+        cache.cache_url_exists("https://example.com/redis-test", false).await.unwrap();
+        assert_eq!(cache.get_url_exists("https://example.com/redis-test").await.unwrap(), Some(false));
+    }
+}
@@ -0,0 +1,131 @@
+//! Per-host statistics and cached robots.txt, the `domains` table is keyed by host (`www.example.co.uk`)
+//! while `pages.domain` holds the registrable domain (`example.co.uk`)
+
+use crate::network::parse_crawl_delay;
+use crate::storage::models::DomainInfo;
+use crate::storage::{Result, StorageError};
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use std::time::Duration;
+use tracing::debug;
+
+// the counters are nullable in the schema, DomainInfo is not
+const DOMAIN_COLUMNS: &str = "domain, robots_txt, robots_fetched_at, COALESCE(crawl_delay, 0) AS crawl_delay, \
+     COALESCE(page_count, 0) AS page_count, avg_quality_score, last_crawled, COALESCE(crawl_allowed, TRUE) AS crawl_allowed";
+
+#[derive(Clone)]
+pub struct DomainRepository {
+    pool: PgPool,
+}
+
+impl DomainRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Insert `info` or overwrite every field of the stored domain
+    pub async fn upsert_domain(&self, info: &DomainInfo) -> Result<()> {
+        let query = r#"
+            INSERT INTO domains (
+                domain, robots_txt, robots_fetched_at, crawl_delay, page_count,
+                avg_quality_score, last_crawled, crawl_allowed
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            ON CONFLICT (domain) DO UPDATE SET
+                robots_txt = EXCLUDED.robots_txt,
+                robots_fetched_at = EXCLUDED.robots_fetched_at,
+                crawl_delay = EXCLUDED.crawl_delay,
+                page_count = EXCLUDED.page_count,
+                avg_quality_score = EXCLUDED.avg_quality_score,
+                last_crawled = EXCLUDED.last_crawled,
+                crawl_allowed = EXCLUDED.crawl_allowed
+        "#;
+
+        sqlx::query(query)
+            .bind(&info.domain)
+            .bind(&info.robots_txt)
+            .bind(info.robots_fetched_at)
+            .bind(info.crawl_delay)
+            .bind(info.page_count)
+            .bind(info.avg_quality_score)
+            .bind(info.last_crawled)
+            .bind(info.crawl_allowed)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn get_domain(&self, domain: &str) -> Result<Option<DomainInfo>> {
+        let query = format!("SELECT {} FROM domains WHERE domain = $1", DOMAIN_COLUMNS);
+
+        let info = sqlx::query_as::<_, DomainInfo>(&query)
+            .bind(domain)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(info)
+    }
+
+    /// Cache a freshly fetched robots.txt and take `crawl_delay` from its `Crawl-delay`
+    /// directive, NULL when it has none
+    pub async fn update_robots_txt(&self, domain: &str, robots_txt: &str) -> Result<()> {
+        self.update_robots_txt_at(domain, robots_txt, Utc::now()).await
+    }
+
+    /// `update_robots_txt` for a robots.txt fetched at `fetched_at`
+    pub async fn update_robots_txt_at(&self, domain: &str, robots_txt: &str, fetched_at: DateTime<Utc>) -> Result<()> {
+        let crawl_delay = parse_crawl_delay(robots_txt)
+            .map(|delay| delay.as_millis().min(i32::MAX as u128) as i32);
+
+        let query = r#"
+            INSERT INTO domains (domain, robots_txt, robots_fetched_at, crawl_delay)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (domain) DO UPDATE SET
+                robots_txt = EXCLUDED.robots_txt,
+                robots_fetched_at = EXCLUDED.robots_fetched_at,
+                crawl_delay = EXCLUDED.crawl_delay
+        "#;
+
+        sqlx::query(query)
+            .bind(domain)
+            .bind(robots_txt)
+            .bind(fetched_at)
+            .bind(crawl_delay)
+            .execute(&self.pool)
+            .await?;
+
+        debug!("Cached robots.txt of {} (crawl delay {}ms)", domain, crawl_delay.unwrap_or(0));
+        Ok(())
+    }
+
+    /// Domains whose robots.txt was never fetched or was fetched more than `older_than` ago
+    pub async fn get_domains_due_for_recheck(&self, older_than: Duration) -> Result<Vec<String>> {
+        let older_than = chrono::Duration::from_std(older_than)
+            .map_err(|e| StorageError::InvalidData(format!("recheck interval out of range: {}", e)))?;
+        let cutoff = Utc::now() - older_than;
+
+        let domains = sqlx::query_scalar::<_, String>(
+            "SELECT domain FROM domains WHERE robots_fetched_at IS NULL OR robots_fetched_at < $1 \
+             ORDER BY robots_fetched_at NULLS FIRST, domain"
+        )
+            .bind(cutoff)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(domains)
+    }
+
+    pub async fn get_top_domains_by_page_count(&self, limit: usize) -> Result<Vec<DomainInfo>> {
+        let query = format!(
+            "SELECT {} FROM domains ORDER BY page_count DESC, domain LIMIT $1",
+            DOMAIN_COLUMNS
+        );
+
+        let domains = sqlx::query_as::<_, DomainInfo>(&query)
+            .bind(limit as i64)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(domains)
+    }
+}
@@ -1,11 +1,21 @@
 // src/storage/export.rs
-use crate::storage::Result;
+use crate::core::PageProcessor;
+use crate::models::PageData;
+use crate::network::HttpResponse;
+use crate::storage::{Result, StorageError};
 use crate::storage::repository::PageRepository;
-use crate::storage::models::PageFilter;
-use csv::WriterBuilder;
-use serde::Serialize;
+use crate::storage::models::{PageFilter, StoredPage};
+use crate::utils::{canonicalize, registrable_domain};
+use chrono::{DateTime, Utc};
+use csv::{ReaderBuilder, WriterBuilder};
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use std::fs::File;
-use std::path::Path;
+use std::io::{BufRead, BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use tracing::{info, warn};
 
 #[derive(Serialize)]
 struct PageCsv {
@@ -32,7 +42,7 @@ impl<'a> DataExporter<'a> {
         filter: &PageFilter,
         path: P,
     ) -> Result<()> {
-        let pages = self.repo.get_pages(filter).await?;
+        let pages = self.repo.get_pages(filter).await?.items;
         let file = File::create(path)?;
         serde_json::to_writer_pretty(file, &pages)?;
         Ok(())
@@ -43,7 +53,7 @@ impl<'a> DataExporter<'a> {
         filter: &PageFilter,
         path: P,
     ) -> Result<()> {
-        let pages = self.repo.get_pages(filter).await?;
+        let pages = self.repo.get_pages(filter).await?.items;
         let file = File::create(path)?;
         let mut wtr = WriterBuilder::new().from_writer(file);
         for p in pages {
@@ -62,3 +72,1133 @@ impl<'a> DataExporter<'a> {
         Ok(())
     }
 }
+
+// Pages fetched per query while streaming an export
+const JSONL_BATCH_SIZE: usize = 1000;
+
+// One exported line: the stored page plus derived, export-only fields
+#[derive(Serialize, Deserialize)]
+struct JsonlPage {
+    #[serde(flatten)]
+    page: StoredPage,
+    #[serde(default, skip_deserializing)]
+    language_name: Option<String>,
+}
+
+/// Writes pages as JSON Lines, one object per page.
+/// With `pretty` every object spans several lines; JsonlImporter reads both forms.
+pub struct JsonlExporter {
+    writer: BufWriter<File>,
+    pretty: bool,
+    pages_written: u64,
+}
+
+impl JsonlExporter {
+    pub fn new(output_path: &Path, pretty: bool) -> Result<Self> {
+        let file = File::create(output_path)?;
+        info!("Writing JSONL export to {}", output_path.display());
+
+        Ok(Self {
+            writer: BufWriter::new(file),
+            pretty,
+            pages_written: 0,
+        })
+    }
+
+    pub fn write_page(&mut self, page: &StoredPage) -> Result<()> {
+        let record = JsonlPage {
+            language_name: language_name(&page.language).map(String::from),
+            page: page.clone(),
+        };
+
+        if self.pretty {
+            serde_json::to_writer_pretty(&mut self.writer, &record)?;
+        } else {
+            serde_json::to_writer(&mut self.writer, &record)?;
+        }
+        self.writer.write_all(b"\n")?;
+        self.pages_written += 1;
+        Ok(())
+    }
+
+    /// Stream every page matching `filter` in batches, `filter.limit` caps the total
+    pub async fn export_all(&mut self, repo: &PageRepository, filter: &PageFilter) -> Result<u64> {
+        let start = self.pages_written;
+        let mut cursor = 0;
+
+        loop {
+            let remaining = filter.limit.map(|limit| limit.saturating_sub((self.pages_written - start) as usize));
+            let batch_size = remaining.unwrap_or(JSONL_BATCH_SIZE).min(JSONL_BATCH_SIZE);
+            if batch_size == 0 {
+                break;
+            }
+
+            let batch_filter = filter.clone().with_limit(batch_size).with_cursor(cursor);
+            let batch = repo.get_pages(&batch_filter).await?;
+
+            for page in &batch.items {
+                self.write_page(page)?;
+            }
+
+            match batch.next_cursor {
+                Some(next) => cursor = next,
+                None => break,
+            }
+        }
+
+        self.writer.flush()?;
+        let exported = self.pages_written - start;
+        info!("Exported {} pages to JSONL", exported);
+
+        Ok(exported)
+    }
+
+    pub fn pages_written(&self) -> u64 {
+        self.pages_written
+    }
+}
+
+impl Drop for JsonlExporter {
+    fn drop(&mut self) {
+        let _ = self.writer.flush();
+    }
+}
+
+/// Loads a JsonlExporter dump back into the database
+pub struct JsonlImporter;
+
+impl JsonlImporter {
+    /// Upsert every page in the file by url, returns the number imported
+    pub async fn import(input_path: &Path, repo: &PageRepository) -> Result<u64> {
+        let file = File::open(input_path)?;
+        let mut imported = 0;
+
+        for page in Self::pages(BufReader::new(file)) {
+            repo.import_page(&page?).await?;
+            imported += 1;
+        }
+
+        info!("Imported {} pages from {}", imported, input_path.display());
+        Ok(imported)
+    }
+
+    // Whitespace separated JSON objects, so pretty-printed exports parse too
+    fn pages<R: Read>(reader: R) -> impl Iterator<Item = Result<StoredPage>> {
+        serde_json::Deserializer::from_reader(reader)
+            .into_iter::<JsonlPage>()
+            .map(|record| Ok(record?.page))
+    }
+}
+
+/// A `StoredPage` field as a CSV column, named like the field
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CsvColumn {
+    Id,
+    Url,
+    UrlHash,
+    Domain,
+    Title,
+    Description,
+    Content,
+    ContentHash,
+    QualityScore,
+    WordCount,
+    Language,
+    CrawlDepth,
+    CrawledAt,
+    LastModified,
+    StatusCode,
+    ContentType,
+    ContentLength,
+    Pagerank,
+    TfidfScore,
+    AuthorityScore,
+    HubScore,
+    Simhash,
+    PersonalizedPagerank,
+    Etag,
+    OgImage,
+    OgType,
+    ScreenshotPath,
+    ClusterId,
+}
+
+impl CsvColumn {
+    pub const ALL: [CsvColumn; 28] = [
+        CsvColumn::Id, CsvColumn::Url, CsvColumn::UrlHash, CsvColumn::Domain, CsvColumn::Title,
+        CsvColumn::Description, CsvColumn::Content, CsvColumn::ContentHash, CsvColumn::QualityScore,
+        CsvColumn::WordCount, CsvColumn::Language, CsvColumn::CrawlDepth, CsvColumn::CrawledAt,
+        CsvColumn::LastModified, CsvColumn::StatusCode, CsvColumn::ContentType, CsvColumn::ContentLength,
+        CsvColumn::Pagerank, CsvColumn::TfidfScore, CsvColumn::AuthorityScore, CsvColumn::HubScore,
+        CsvColumn::Simhash, CsvColumn::PersonalizedPagerank, CsvColumn::Etag, CsvColumn::OgImage,
+        CsvColumn::OgType, CsvColumn::ScreenshotPath, CsvColumn::ClusterId,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            CsvColumn::Id => "id",
+            CsvColumn::Url => "url",
+            CsvColumn::UrlHash => "url_hash",
+            CsvColumn::Domain => "domain",
+            CsvColumn::Title => "title",
+            CsvColumn::Description => "description",
+            CsvColumn::Content => "content",
+            CsvColumn::ContentHash => "content_hash",
+            CsvColumn::QualityScore => "quality_score",
+            CsvColumn::WordCount => "word_count",
+            CsvColumn::Language => "language",
+            CsvColumn::CrawlDepth => "crawl_depth",
+            CsvColumn::CrawledAt => "crawled_at",
+            CsvColumn::LastModified => "last_modified",
+            CsvColumn::StatusCode => "status_code",
+            CsvColumn::ContentType => "content_type",
+            CsvColumn::ContentLength => "content_length",
+            CsvColumn::Pagerank => "pagerank",
+            CsvColumn::TfidfScore => "tfidf_score",
+            CsvColumn::AuthorityScore => "authority_score",
+            CsvColumn::HubScore => "hub_score",
+            CsvColumn::Simhash => "simhash",
+            CsvColumn::PersonalizedPagerank => "personalized_pagerank",
+            CsvColumn::Etag => "etag",
+            CsvColumn::OgImage => "og_image",
+            CsvColumn::OgType => "og_type",
+            CsvColumn::ScreenshotPath => "screenshot_path",
+            CsvColumn::ClusterId => "cluster_id",
+        }
+    }
+
+    /// Comma separated column names, e.g. `url,title,pagerank`
+    pub fn parse_list(list: &str) -> Result<Vec<CsvColumn>> {
+        list.split(',')
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .map(str::parse)
+            .collect()
+    }
+
+    /// The field's value, empty for None; timestamps are RFC 3339
+    pub fn value(&self, page: &StoredPage) -> String {
+        fn opt<T: ToString>(value: &Option<T>) -> String {
+            value.as_ref().map(T::to_string).unwrap_or_default()
+        }
+
+        match self {
+            CsvColumn::Id => page.id.to_string(),
+            CsvColumn::Url => page.url.clone(),
+            CsvColumn::UrlHash => page.url_hash.clone(),
+            CsvColumn::Domain => page.domain.clone(),
+            CsvColumn::Title => opt(&page.title),
+            CsvColumn::Description => opt(&page.description),
+            CsvColumn::Content => page.content.clone(),
+            CsvColumn::ContentHash => page.content_hash.clone(),
+            CsvColumn::QualityScore => page.quality_score.to_string(),
+            CsvColumn::WordCount => page.word_count.to_string(),
+            CsvColumn::Language => page.language.clone(),
+            CsvColumn::CrawlDepth => page.crawl_depth.to_string(),
+            CsvColumn::CrawledAt => page.crawled_at.to_rfc3339(),
+            CsvColumn::LastModified => page.last_modified.map(|t| t.to_rfc3339()).unwrap_or_default(),
+            CsvColumn::StatusCode => page.status_code.to_string(),
+            CsvColumn::ContentType => page.content_type.clone(),
+            CsvColumn::ContentLength => page.content_length.to_string(),
+            CsvColumn::Pagerank => opt(&page.pagerank),
+            CsvColumn::TfidfScore => opt(&page.tfidf_score),
+            CsvColumn::AuthorityScore => opt(&page.authority_score),
+            CsvColumn::HubScore => opt(&page.hub_score),
+            CsvColumn::Simhash => opt(&page.simhash),
+            CsvColumn::PersonalizedPagerank => opt(&page.personalized_pagerank),
+            CsvColumn::Etag => opt(&page.etag),
+            CsvColumn::OgImage => opt(&page.og_image),
+            CsvColumn::OgType => opt(&page.og_type),
+            CsvColumn::ScreenshotPath => opt(&page.screenshot_path),
+            CsvColumn::ClusterId => opt(&page.cluster_id),
+        }
+    }
+
+    /// Set the field from a value written by `value`
+    pub fn apply(&self, page: &mut StoredPage, value: &str) -> Result<()> {
+        fn parse<T: FromStr>(column: &CsvColumn, value: &str) -> Result<T> {
+            value.trim().parse().map_err(|_| {
+                StorageError::InvalidData(format!("invalid {} '{}'", column.name(), value))
+            })
+        }
+        fn opt_string(value: &str) -> Option<String> {
+            Some(value.to_string()).filter(|v| !v.is_empty())
+        }
+        fn opt<T: FromStr>(column: &CsvColumn, value: &str) -> Result<Option<T>> {
+            if value.trim().is_empty() { Ok(None) } else { parse(column, value).map(Some) }
+        }
+        fn timestamp(column: &CsvColumn, value: &str) -> Result<DateTime<Utc>> {
+            DateTime::parse_from_rfc3339(value.trim())
+                .map(|t| t.with_timezone(&Utc))
+                .map_err(|_| StorageError::InvalidData(format!("invalid {} '{}'", column.name(), value)))
+        }
+
+        match self {
+            CsvColumn::Id => page.id = parse(self, value)?,
+            CsvColumn::Url => page.url = value.trim().to_string(),
+            CsvColumn::UrlHash => page.url_hash = value.to_string(),
+            CsvColumn::Domain => page.domain = value.to_string(),
+            CsvColumn::Title => page.title = opt_string(value),
+            CsvColumn::Description => page.description = opt_string(value),
+            CsvColumn::Content => page.content = value.to_string(),
+            CsvColumn::ContentHash => page.content_hash = value.to_string(),
+            CsvColumn::QualityScore => page.quality_score = parse(self, value)?,
+            CsvColumn::WordCount => page.word_count = parse(self, value)?,
+            CsvColumn::Language => page.language = value.to_string(),
+            CsvColumn::CrawlDepth => page.crawl_depth = parse(self, value)?,
+            CsvColumn::CrawledAt => page.crawled_at = timestamp(self, value)?,
+            CsvColumn::LastModified => {
+                page.last_modified = match value.trim() {
+                    "" => None,
+                    value => Some(timestamp(self, value)?),
+                }
+            }
+            CsvColumn::StatusCode => page.status_code = parse(self, value)?,
+            CsvColumn::ContentType => page.content_type = value.to_string(),
+            CsvColumn::ContentLength => page.content_length = parse(self, value)?,
+            CsvColumn::Pagerank => page.pagerank = opt(self, value)?,
+            CsvColumn::TfidfScore => page.tfidf_score = opt(self, value)?,
+            CsvColumn::AuthorityScore => page.authority_score = opt(self, value)?,
+            CsvColumn::HubScore => page.hub_score = opt(self, value)?,
+            CsvColumn::Simhash => page.simhash = opt(self, value)?,
+            CsvColumn::PersonalizedPagerank => page.personalized_pagerank = opt(self, value)?,
+            CsvColumn::Etag => page.etag = opt_string(value),
+            CsvColumn::OgImage => page.og_image = opt_string(value),
+            CsvColumn::OgType => page.og_type = opt_string(value),
+            CsvColumn::ScreenshotPath => page.screenshot_path = opt_string(value),
+            CsvColumn::ClusterId => page.cluster_id = opt(self, value)?,
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for CsvColumn {
+    type Err = StorageError;
+
+    fn from_str(name: &str) -> Result<Self> {
+        let name = name.trim().to_lowercase();
+        CsvColumn::ALL.into_iter()
+            .find(|column| column.name() == name)
+            .ok_or_else(|| StorageError::InvalidData(format!("unknown CSV column '{}'", name)))
+    }
+}
+
+fn csv_error(e: csv::Error) -> StorageError {
+    StorageError::Export(e.to_string())
+}
+
+// Pages fetched per query while streaming a CSV export
+const CSV_BATCH_SIZE: usize = 1000;
+
+/// Writes the chosen `StoredPage` fields as CSV
+#[derive(Debug, Clone)]
+pub struct CsvExporter {
+    pub columns: Vec<CsvColumn>,
+    /// Must be an ASCII character
+    pub delimiter: char,
+    pub include_header: bool,
+}
+
+impl Default for CsvExporter {
+    fn default() -> Self {
+        Self {
+            columns: CsvColumn::ALL.to_vec(),
+            delimiter: ',',
+            include_header: true,
+        }
+    }
+}
+
+impl CsvExporter {
+    /// Every column, comma separated, with a header row
+    pub fn builder() -> CsvExporterBuilder {
+        CsvExporterBuilder { exporter: Self::default() }
+    }
+
+    /// Stream every page matching `filter` to `output` in batches, `filter.limit` caps the total
+    pub async fn export_all(&self, repo: &PageRepository, filter: &PageFilter, output: &Path) -> Result<u64> {
+        let mut writer = self.writer(File::create(output)?)?;
+        let mut exported = 0u64;
+        let mut cursor = 0;
+
+        loop {
+            let remaining = filter.limit.map(|limit| limit.saturating_sub(exported as usize));
+            let batch_size = remaining.unwrap_or(CSV_BATCH_SIZE).min(CSV_BATCH_SIZE);
+            if batch_size == 0 {
+                break;
+            }
+
+            let batch_filter = filter.clone().with_limit(batch_size).with_cursor(cursor);
+            let batch = repo.get_pages(&batch_filter).await?;
+
+            for page in &batch.items {
+                self.write_page(&mut writer, page)?;
+                exported += 1;
+            }
+
+            match batch.next_cursor {
+                Some(next) => cursor = next,
+                None => break,
+            }
+        }
+
+        writer.flush()?;
+        info!("Exported {} pages to CSV {}", exported, output.display());
+
+        Ok(exported)
+    }
+
+    // CSV writer over `output`, the header row already written when enabled
+    fn writer<W: Write>(&self, output: W) -> Result<csv::Writer<W>> {
+        if !self.delimiter.is_ascii() {
+            return Err(StorageError::Export(format!("CSV delimiter '{}' is not ASCII", self.delimiter)));
+        }
+
+        let mut writer = WriterBuilder::new()
+            .delimiter(self.delimiter as u8)
+            .from_writer(output);
+        if self.include_header {
+            writer.write_record(self.columns.iter().map(CsvColumn::name)).map_err(csv_error)?;
+        }
+        Ok(writer)
+    }
+
+    fn write_page<W: Write>(&self, writer: &mut csv::Writer<W>, page: &StoredPage) -> Result<()> {
+        writer
+            .write_record(self.columns.iter().map(|column| column.value(page)))
+            .map_err(csv_error)
+    }
+}
+
+/// Fluent construction of a `CsvExporter`
+pub struct CsvExporterBuilder {
+    exporter: CsvExporter,
+}
+
+impl CsvExporterBuilder {
+    pub fn with_columns(mut self, columns: Vec<CsvColumn>) -> Self {
+        self.exporter.columns = columns;
+        self
+    }
+
+    pub fn with_delimiter(mut self, delimiter: char) -> Self {
+        self.exporter.delimiter = delimiter;
+        self
+    }
+
+    pub fn with_header(mut self, include_header: bool) -> Self {
+        self.exporter.include_header = include_header;
+        self
+    }
+
+    pub fn build(self) -> CsvExporter {
+        self.exporter
+    }
+}
+
+/// Outcome of a `CsvImporter::import` or `WarcImporter::import_all`
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ImportStats {
+    pub imported: u64,
+    /// Rows or records that could not be parsed or don't hold an importable page
+    pub skipped: u64,
+    /// Header names that don't match a `CsvColumn`, their values are ignored
+    pub unknown_columns: Vec<String>,
+}
+
+/// Seeds the database from a CSV file whose first row names the columns
+pub struct CsvImporter;
+
+impl CsvImporter {
+    /// Upsert every row by url. The delimiter (`,` `;` tab or `|`) is guessed from the header row,
+    /// a `url` column is required and missing fields get the defaults of a fresh crawl.
+    pub async fn import(path: &Path, repo: &PageRepository) -> Result<ImportStats> {
+        let (pages, mut stats) = Self::pages(BufReader::new(File::open(path)?))?;
+
+        for page in pages {
+            match page {
+                Ok(page) => {
+                    repo.import_page(&page).await?;
+                    stats.imported += 1;
+                }
+                Err(e) => {
+                    warn!("Skipping CSV row: {}", e);
+                    stats.skipped += 1;
+                }
+            }
+        }
+
+        info!("Imported {} pages from {} ({} skipped)", stats.imported, path.display(), stats.skipped);
+        Ok(stats)
+    }
+
+    // Parsed rows, lazily, plus stats with the unknown header names filled in
+    fn pages<R: BufRead>(mut reader: R) -> Result<(impl Iterator<Item = Result<StoredPage>>, ImportStats)> {
+        let delimiter = Self::sniff_delimiter(reader.fill_buf()?);
+        let mut csv_reader = ReaderBuilder::new()
+            .delimiter(delimiter)
+            .flexible(true)
+            .from_reader(reader);
+
+        let mut stats = ImportStats::default();
+        let mut columns = Vec::new();
+        for name in csv_reader.headers().map_err(csv_error)? {
+            match name.parse::<CsvColumn>() {
+                Ok(column) => columns.push(Some(column)),
+                Err(_) => {
+                    stats.unknown_columns.push(name.to_string());
+                    columns.push(None);
+                }
+            }
+        }
+        if !columns.contains(&Some(CsvColumn::Url)) {
+            return Err(StorageError::InvalidData("CSV header has no url column".to_string()));
+        }
+
+        let pages = csv_reader.into_records().map(move |record| {
+            let record = record.map_err(csv_error)?;
+            Self::page(&columns, &record)
+        });
+        Ok((pages, stats))
+    }
+
+    fn page(columns: &[Option<CsvColumn>], record: &csv::StringRecord) -> Result<StoredPage> {
+        let mut page = StoredPage {
+            id: 0,
+            url: String::new(),
+            url_hash: String::new(),
+            domain: String::new(),
+            title: None,
+            description: None,
+            content: String::new(),
+            content_hash: String::new(),
+            quality_score: 0.0,
+            word_count: 0,
+            language: "en".to_string(),
+            crawl_depth: 0,
+            crawled_at: Utc::now(),
+            last_modified: None,
+            status_code: 200,
+            content_type: "text/html".to_string(),
+            content_length: 0,
+            pagerank: None,
+            tfidf_score: None,
+            authority_score: None,
+            hub_score: None,
+            simhash: None,
+            personalized_pagerank: None,
+            etag: None,
+            og_image: None,
+            og_type: None,
+            screenshot_path: None,
+            content_compressed: false,
+            cluster_id: None,
+        };
+
+        for (column, value) in columns.iter().zip(record.iter()) {
+            if let Some(column) = column {
+                column.apply(&mut page, value)?;
+            }
+        }
+        if page.url.is_empty() {
+            return Err(StorageError::InvalidData("row has no url".to_string()));
+        }
+
+        // derive whatever the file left out from the fields it had
+        page.url = canonicalize(&page.url);
+        if page.url_hash.is_empty() {
+            page.url_hash = PageRepository::calculate_url_hash(&page.url);
+        }
+        if page.domain.is_empty() {
+            page.domain = registrable_domain(&page.url);
+        }
+        if page.content_hash.is_empty() {
+            page.content_hash = PageRepository::calculate_content_hash(&page.content);
+        }
+        if page.word_count == 0 {
+            page.word_count = page.content.split_whitespace().count() as i32;
+        }
+        if page.content_length == 0 {
+            page.content_length = page.content.len() as i32;
+        }
+        Ok(page)
+    }
+
+    // The candidate occurring most often in the header line, comma on a tie
+    fn sniff_delimiter(sample: &[u8]) -> u8 {
+        let header = sample.split(|b| *b == b'\n').next().unwrap_or_default();
+        [b',', b';', b'\t', b'|']
+            .into_iter()
+            .max_by_key(|delimiter| (header.iter().filter(|b| *b == delimiter).count(), *delimiter == b','))
+            .unwrap_or(b',')
+    }
+}
+
+// English name for an ISO 639-1 language code
+fn language_name(code: &str) -> Option<&'static str> {
+    let name = match code.to_lowercase().as_str() {
+        "ar" => "Arabic",
+        "bn" => "Bengali",
+        "cs" => "Czech",
+        "da" => "Danish",
+        "de" => "German",
+        "el" => "Greek",
+        "en" => "English",
+        "es" => "Spanish",
+        "fa" => "Persian",
+        "fi" => "Finnish",
+        "fr" => "French",
+        "he" => "Hebrew",
+        "hi" => "Hindi",
+        "hu" => "Hungarian",
+        "id" => "Indonesian",
+        "it" => "Italian",
+        "ja" => "Japanese",
+        "kn" => "Kannada",
+        "ko" => "Korean",
+        "ml" => "Malayalam",
+        "mr" => "Marathi",
+        "nl" => "Dutch",
+        "no" => "Norwegian",
+        "pl" => "Polish",
+        "pt" => "Portuguese",
+        "ro" => "Romanian",
+        "ru" => "Russian",
+        "sv" => "Swedish",
+        "ta" => "Tamil",
+        "te" => "Telugu",
+        "th" => "Thai",
+        "tr" => "Turkish",
+        "uk" => "Ukrainian",
+        "ur" => "Urdu",
+        "vi" => "Vietnamese",
+        "zh" => "Chinese",
+        _ => return None,
+    };
+    Some(name)
+}
+
+// Headers that no longer describe the stored body once reqwest has decoded it
+const SKIPPED_HEADERS: [&str; 3] = ["content-length", "content-encoding", "transfer-encoding"];
+
+/// Writes crawled pages as WARC/1.0 `response` records, readable by tools like warcio
+pub struct WarcExporter {
+    writer: BufWriter<File>,
+    records_written: u64,
+}
+
+impl WarcExporter {
+    pub fn new(output_path: &Path) -> Result<Self> {
+        let file = File::create(output_path)?;
+        let mut exporter = Self {
+            writer: BufWriter::new(file),
+            records_written: 0,
+        };
+
+        exporter.write_warcinfo(output_path)?;
+        info!("Writing WARC archive to {}", output_path.display());
+
+        Ok(exporter)
+    }
+
+    /// Export a page together with the raw HTTP response it was fetched from
+    pub fn export_page(&mut self, page: &StoredPage, response: &HttpResponse) -> Result<()> {
+        let headers: Vec<(String, String)> = response.headers.iter()
+            .filter(|(name, _)| !SKIPPED_HEADERS.contains(&name.as_str()))
+            .filter_map(|(name, value)| {
+                value.to_str().ok().map(|v| (name.to_string(), v.to_string()))
+            })
+            .collect();
+
+        let block = Self::http_block(response.status_code, &headers, &response.content);
+        self.write_response(&page.url, page.crawled_at, &block)
+    }
+
+    /// Export every stored page, rebuilding the HTTP response from the stored metadata
+    pub async fn export_all(&mut self, repo: &PageRepository) -> Result<u64> {
+        let pages = repo.get_pages(&PageFilter::new()).await?.items;
+
+        for page in &pages {
+            let headers = vec![("Content-Type".to_string(), page.content_type.clone())];
+            let block = Self::http_block(page.status_code as u16, &headers, &page.content);
+            self.write_response(&page.url, page.crawled_at, &block)?;
+        }
+
+        self.writer.flush()?;
+        info!("Exported {} pages to WARC", pages.len());
+
+        Ok(pages.len() as u64)
+    }
+
+    /// Number of response records written so far
+    pub fn records_written(&self) -> u64 {
+        self.records_written
+    }
+
+    fn write_warcinfo(&mut self, output_path: &Path) -> Result<()> {
+        let filename = output_path.file_name()
+            .map(|f| f.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let now = Utc::now();
+        let block = format!(
+            "software: {}/{}\r\nformat: WARC File Format 1.0\r\n",
+            env!("CARGO_PKG_NAME"),
+            env!("CARGO_PKG_VERSION")
+        );
+
+        let header = format!(
+            "WARC/1.0\r\n\
+             WARC-Type: warcinfo\r\n\
+             WARC-Record-ID: {}\r\n\
+             WARC-Date: {}\r\n\
+             WARC-Filename: {}\r\n\
+             Content-Type: application/warc-fields\r\n\
+             Content-Length: {}\r\n\r\n",
+            Self::record_id(&filename, now),
+            Self::warc_date(now),
+            filename,
+            block.len()
+        );
+
+        self.write_record(&header, block.as_bytes())
+    }
+
+    fn write_response(&mut self, url: &str, date: DateTime<Utc>, block: &[u8]) -> Result<()> {
+        let header = format!(
+            "WARC/1.0\r\n\
+             WARC-Type: response\r\n\
+             WARC-Record-ID: {}\r\n\
+             WARC-Date: {}\r\n\
+             WARC-Target-URI: {}\r\n\
+             Content-Type: application/http; msgtype=response\r\n\
+             Content-Length: {}\r\n\r\n",
+            Self::record_id(url, date),
+            Self::warc_date(date),
+            url,
+            block.len()
+        );
+
+        self.write_record(&header, block)?;
+        self.records_written += 1;
+        Ok(())
+    }
+
+    fn write_record(&mut self, header: &str, block: &[u8]) -> Result<()> {
+        self.writer.write_all(header.as_bytes())?;
+        self.writer.write_all(block)?;
+        self.writer.write_all(b"\r\n\r\n")?;
+        Ok(())
+    }
+
+    // Rebuild an HTTP/1.1 response message around the (decoded) body
+    fn http_block(status_code: u16, headers: &[(String, String)], body: &str) -> Vec<u8> {
+        let reason = StatusCode::from_u16(status_code)
+            .ok()
+            .and_then(|s| s.canonical_reason())
+            .unwrap_or("");
+
+        let mut block = format!("HTTP/1.1 {} {}\r\n", status_code, reason);
+        for (name, value) in headers {
+            block.push_str(&format!("{}: {}\r\n", name, value));
+        }
+        block.push_str(&format!("Content-Length: {}\r\n\r\n", body.len()));
+        block.push_str(body);
+
+        block.into_bytes()
+    }
+
+    fn warc_date(date: DateTime<Utc>) -> String {
+        date.format("%Y-%m-%dT%H:%M:%SZ").to_string()
+    }
+
+    // Deterministic urn:uuid derived from the target and capture time
+    fn record_id(target: &str, date: DateTime<Utc>) -> String {
+        let digest = Sha256::digest(format!("{}{}", target, date.to_rfc3339()).as_bytes());
+        let hex = hex::encode(&digest[..16]);
+        format!(
+            "<urn:uuid:{}-{}-{}-{}-{}>",
+            &hex[0..8], &hex[8..12], &hex[12..16], &hex[16..20], &hex[20..32]
+        )
+    }
+}
+
+impl Drop for WarcExporter {
+    fn drop(&mut self) {
+        let _ = self.writer.flush();
+    }
+}
+
+/// Seeds the database from the `response` records of a WARC/1.0 file, one record at a time
+pub struct WarcImporter {
+    reader: BufReader<File>,
+    path: PathBuf,
+}
+
+// A record's named fields and its content block
+struct WarcRecord {
+    headers: Vec<(String, String)>,
+    block: Vec<u8>,
+}
+
+// The HTTP response message in the block of a `response` record
+struct HttpMessage {
+    status_code: u16,
+    headers: Vec<(String, String)>,
+    body: Vec<u8>,
+}
+
+impl WarcImporter {
+    pub fn new(warc_path: &Path) -> Result<Self> {
+        Ok(Self {
+            reader: BufReader::new(File::open(warc_path)?),
+            path: warc_path.to_path_buf(),
+        })
+    }
+
+    /// Save the page of every successful HTML response. Other record types are passed over,
+    /// responses with an error status, another content type or a compressed body are skipped.
+    pub async fn import_all(&mut self, repo: &PageRepository) -> Result<ImportStats> {
+        let processor = PageProcessor::new();
+        let mut stats = ImportStats::default();
+
+        while let Some(record) = Self::read_record(&mut self.reader)? {
+            if !field(&record.headers, "WARC-Type").is_some_and(|t| t.eq_ignore_ascii_case("response")) {
+                continue;
+            }
+
+            match Self::page(&processor, &record).await {
+                Ok(page) => {
+                    repo.save_page(&page, 0).await?;
+                    stats.imported += 1;
+                }
+                Err(e) => {
+                    warn!("Skipping WARC record: {}", e);
+                    stats.skipped += 1;
+                }
+            }
+        }
+
+        info!("Imported {} pages from {} ({} skipped)", stats.imported, self.path.display(), stats.skipped);
+        Ok(stats)
+    }
+
+    // Next record, None at the end of the file
+    fn read_record<R: BufRead>(reader: &mut R) -> Result<Option<WarcRecord>> {
+        let mut line = String::new();
+
+        // records are separated by blank lines
+        loop {
+            line.clear();
+            if reader.read_line(&mut line)? == 0 {
+                return Ok(None);
+            }
+            if !line.trim().is_empty() {
+                break;
+            }
+        }
+        if !line.starts_with("WARC/") {
+            return Err(StorageError::InvalidData(format!("expected a WARC version line, found {:?}", line.trim())));
+        }
+
+        let mut headers = Vec::new();
+        loop {
+            line.clear();
+            if reader.read_line(&mut line)? == 0 {
+                return Err(StorageError::InvalidData("WARC record header is truncated".to_string()));
+            }
+            let line = line.trim_end();
+            if line.is_empty() {
+                break;
+            }
+            if let Some((name, value)) = line.split_once(':') {
+                headers.push((name.trim().to_string(), value.trim().to_string()));
+            }
+        }
+
+        let length: usize = field(&headers, "Content-Length")
+            .and_then(|length| length.parse().ok())
+            .ok_or_else(|| StorageError::InvalidData("WARC record has no valid Content-Length".to_string()))?;
+        let mut block = vec![0; length];
+        reader.read_exact(&mut block)?;
+
+        Ok(Some(WarcRecord { headers, block }))
+    }
+
+    async fn page(processor: &PageProcessor, record: &WarcRecord) -> Result<PageData> {
+        // some writers wrap the uri in angle brackets
+        let url = field(&record.headers, "WARC-Target-URI")
+            .map(|uri| uri.trim_start_matches('<').trim_end_matches('>'))
+            .ok_or_else(|| StorageError::InvalidData("response record has no WARC-Target-URI".to_string()))?;
+        let response = Self::http_message(&record.block)
+            .ok_or_else(|| StorageError::InvalidData(format!("{}: malformed HTTP response", url)))?;
+
+        if !(200..300).contains(&response.status_code) {
+            return Err(StorageError::InvalidData(format!("{}: HTTP {}", url, response.status_code)));
+        }
+        let content_type = field(&response.headers, "Content-Type").unwrap_or_default();
+        if !content_type.to_ascii_lowercase().contains("html") {
+            return Err(StorageError::InvalidData(format!("{}: content type {:?} is not HTML", url, content_type)));
+        }
+        if let Some(encoding) = field(&response.headers, "Content-Encoding").filter(|e| !e.eq_ignore_ascii_case("identity")) {
+            return Err(StorageError::InvalidData(format!("{}: {} encoded body", url, encoding)));
+        }
+
+        let html = String::from_utf8_lossy(&response.body);
+        let mut page = processor.process_page(url, &html, 0).await
+            .map_err(|e| StorageError::InvalidData(format!("{}: {}", url, e)))?;
+        if let Some(date) = field(&record.headers, "WARC-Date").and_then(|d| DateTime::parse_from_rfc3339(d).ok()) {
+            page.crawled_at = date.with_timezone(&Utc);
+        }
+        Ok(page)
+    }
+
+    fn http_message(block: &[u8]) -> Option<HttpMessage> {
+        let (head_len, separator_len) = block.windows(4).position(|w| w == b"\r\n\r\n").map(|i| (i, 4))
+            .or_else(|| block.windows(2).position(|w| w == b"\n\n").map(|i| (i, 2)))?;
+        let head = String::from_utf8_lossy(&block[..head_len]);
+        let mut lines = head.lines();
+
+        // HTTP/1.1 200 OK
+        let status_code = lines.next()?.split_whitespace().nth(1)?.parse().ok()?;
+        let headers: Vec<(String, String)> = lines
+            .filter_map(|line| line.split_once(':'))
+            .map(|(name, value)| (name.trim().to_string(), value.trim().to_string()))
+            .collect();
+
+        let body = &block[head_len + separator_len..];
+        let body = if field(&headers, "Transfer-Encoding").is_some_and(|t| t.eq_ignore_ascii_case("chunked")) {
+            Self::dechunk(body)?
+        } else {
+            body.to_vec()
+        };
+
+        Some(HttpMessage { status_code, headers, body })
+    }
+
+    // Join the chunks of a `Transfer-Encoding: chunked` body
+    fn dechunk(mut body: &[u8]) -> Option<Vec<u8>> {
+        let mut decoded = Vec::new();
+        loop {
+            let line_end = body.windows(2).position(|w| w == b"\r\n")?;
+            let size_line = std::str::from_utf8(&body[..line_end]).ok()?;
+            let size = usize::from_str_radix(size_line.split(';').next()?.trim(), 16).ok()?;
+            body = &body[line_end + 2..];
+            if size == 0 {
+                return Some(decoded);
+            }
+            decoded.extend_from_slice(body.get(..size)?);
+            body = body.get(size + 2..)?;
+        }
+    }
+}
+
+// Value of the first header named `name`, ignoring case
+fn field<'a>(headers: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    headers.iter()
+        .find(|(header, _)| header.eq_ignore_ascii_case(name))
+        .map(|(_, value)| value.as_str())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::models::tests::stored_page;
+    use reqwest::header::{HeaderMap, HeaderValue};
+    use tempfile::tempdir;
+
+    fn test_page() -> StoredPage {
+        StoredPage {
+            title: Some("Example".to_string()),
+            content: "<html>hello</html>".to_string(),
+            word_count: 1,
+            crawl_depth: 0,
+            content_length: 18,
+            ..stored_page(1, "https://example.com/")
+        }
+    }
+
+    #[test]
+    fn test_warc_response_record() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("crawl.warc");
+        let page = test_page();
+
+        let mut headers = HeaderMap::new();
+        headers.insert("content-type", HeaderValue::from_static("text/html"));
+        headers.insert("content-encoding", HeaderValue::from_static("gzip"));
+
+        let response = HttpResponse {
+            url: page.url.clone(),
+            final_url: page.url.clone(),
+            status_code: 200,
+            headers,
+            content: page.content.clone(),
+            content_type: "text/html".to_string(),
+            content_length: Some(page.content.len()),
+            encoding: "utf-8".to_string(),
+            fetch_time_ms: 10,
+            redirect_count: 0,
+            rendered_with_js: false,
+            raw_body: None,
+        };
+
+        let mut exporter = WarcExporter::new(&path).unwrap();
+        exporter.export_page(&page, &response).unwrap();
+        assert_eq!(exporter.records_written(), 1);
+        drop(exporter);
+
+        let warc = std::fs::read_to_string(&path).unwrap();
+        assert!(warc.starts_with("WARC/1.0\r\nWARC-Type: warcinfo\r\n"));
+        assert!(warc.contains("WARC-Type: response\r\n"));
+        assert!(warc.contains("WARC-Target-URI: https://example.com/\r\n"));
+        assert!(warc.contains("HTTP/1.1 200 OK\r\n"));
+        assert!(!warc.contains("content-encoding"));
+
+        // Content-Length must match the HTTP block exactly
+        let record = warc.split("WARC-Type: response").nth(1).unwrap();
+        let (header, rest) = record.split_once("\r\n\r\n").unwrap();
+        let length: usize = header.lines()
+            .find_map(|l| l.strip_prefix("Content-Length: "))
+            .unwrap()
+            .trim()
+            .parse()
+            .unwrap();
+        assert_eq!(&rest[length..], "\r\n\r\n");
+    }
+
+    #[test]
+    fn test_jsonl_round_trip() {
+        let dir = tempdir().unwrap();
+
+        for pretty in [false, true] {
+            let path = dir.path().join(format!("pages-{}.jsonl", pretty));
+            let mut second = test_page();
+            second.url = "https://example.com/second".to_string();
+            second.language = "xx".to_string();
+            second.pagerank = Some(0.25);
+
+            let mut exporter = JsonlExporter::new(&path, pretty).unwrap();
+            exporter.write_page(&test_page()).unwrap();
+            exporter.write_page(&second).unwrap();
+            drop(exporter);
+
+            let text = std::fs::read_to_string(&path).unwrap();
+            if !pretty {
+                assert_eq!(text.lines().count(), 2);
+                let first: serde_json::Value = serde_json::from_str(text.lines().next().unwrap()).unwrap();
+                assert_eq!(first["language_name"], "English");
+                assert_eq!(first["url"], "https://example.com/");
+            }
+
+            let pages: Vec<StoredPage> = JsonlImporter::pages(File::open(&path).unwrap())
+                .collect::<Result<_>>()
+                .unwrap();
+            assert_eq!(pages.len(), 2);
+            assert_eq!(pages[1].url, second.url);
+            assert_eq!(pages[1].pagerank, Some(0.25));
+            assert_eq!(pages[0].content, test_page().content);
+        }
+    }
+
+    #[test]
+    fn test_csv_round_trip() {
+        let first = test_page();
+        let mut second = test_page();
+        second.url = "https://example.com/second".to_string();
+        second.title = Some("Comma, \"quoted\"\nand newline".to_string());
+        second.pagerank = Some(0.25);
+
+        for delimiter in [',', ';', '\t'] {
+            let exporter = CsvExporter::builder().with_delimiter(delimiter).build();
+            let mut writer = exporter.writer(Vec::new()).unwrap();
+            exporter.write_page(&mut writer, &first).unwrap();
+            exporter.write_page(&mut writer, &second).unwrap();
+            let bytes = writer.into_inner().unwrap();
+
+            let (pages, stats) = CsvImporter::pages(bytes.as_slice()).unwrap();
+            let pages: Vec<StoredPage> = pages.collect::<Result<_>>().unwrap();
+            assert!(stats.unknown_columns.is_empty());
+            assert_eq!(pages.len(), 2);
+            assert_eq!(pages[0].crawled_at, first.crawled_at);
+            assert_eq!(pages[0].content_hash, first.content_hash);
+            assert_eq!(pages[1].title, second.title);
+            assert_eq!(pages[1].pagerank, Some(0.25));
+            assert_eq!(pages[1].description, None);
+        }
+    }
+
+    #[test]
+    fn test_csv_selected_columns_and_defaults() {
+        let exporter = CsvExporter::builder()
+            .with_columns(CsvColumn::parse_list("url, Title,pagerank").unwrap())
+            .build();
+        let mut writer = exporter.writer(Vec::new()).unwrap();
+        exporter.write_page(&mut writer, &test_page()).unwrap();
+        let text = String::from_utf8(writer.into_inner().unwrap()).unwrap();
+        assert_eq!(text, "url,title,pagerank\nhttps://example.com/,Example,\n");
+
+        let input = "URL;content;source\nhttps://example.com/a;one two three;feed\n;orphan;x\n";
+        let (pages, stats) = CsvImporter::pages(input.as_bytes()).unwrap();
+        assert_eq!(stats.unknown_columns, vec!["source".to_string()]);
+        let pages: Vec<Result<StoredPage>> = pages.collect();
+        assert_eq!(pages.len(), 2);
+        assert!(pages[1].is_err());
+
+        let page = pages[0].as_ref().unwrap();
+        assert_eq!(page.domain, "example.com");
+        assert_eq!(page.word_count, 3);
+        assert_eq!(page.url_hash, PageRepository::calculate_url_hash(&page.url));
+
+        assert!(CsvColumn::parse_list("url,bogus").is_err());
+        assert!(CsvImporter::pages("title\nx\n".as_bytes()).is_err());
+        assert!(CsvExporter::builder().with_delimiter('§').build().writer(Vec::new()).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_warc_import_records() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("crawl.warc");
+        let mut page = test_page();
+        page.content = "<html><head><title>Exported</title></head><body><p>Pages written by the exporter read back in.</p></body></html>".to_string();
+
+        let mut exporter = WarcExporter::new(&path).unwrap();
+        exporter.write_response(
+            &page.url,
+            page.crawled_at,
+            &WarcExporter::http_block(200, &[("Content-Type".to_string(), "text/html".to_string())], &page.content),
+        ).unwrap();
+        // chunked body
+        exporter.write_response(
+            "https://example.com/chunked",
+            page.crawled_at,
+            b"HTTP/1.1 200 OK\r\nContent-Type: text/html; charset=utf-8\r\nTransfer-Encoding: chunked\r\n\r\n\
+              19\r\n<html><body><p>Chunked bo\r\n10\r\ndy text here</p>\r\n0\r\n\r\n",
+        ).unwrap();
+        exporter.write_response(
+            "https://example.com/missing",
+            page.crawled_at,
+            &WarcExporter::http_block(404, &[("Content-Type".to_string(), "text/html".to_string())], "gone"),
+        ).unwrap();
+        drop(exporter);
+
+        let mut reader = BufReader::new(File::open(&path).unwrap());
+        let mut records = Vec::new();
+        while let Some(record) = WarcImporter::read_record(&mut reader).unwrap() {
+            records.push(record);
+        }
+        assert_eq!(records.len(), 4);
+        assert_eq!(field(&records[0].headers, "warc-type"), Some("warcinfo"));
+
+        let processor = PageProcessor::new();
+        let imported = WarcImporter::page(&processor, &records[1]).await.unwrap();
+        assert_eq!(imported.url, "https://example.com/");
+        assert_eq!(imported.title.as_deref(), Some("Exported"));
+        assert_eq!(imported.crawled_at.timestamp(), page.crawled_at.timestamp());
+
+        let chunked = WarcImporter::page(&processor, &records[2]).await.unwrap();
+        assert!(chunked.content.contains("Chunked body text here"));
+
+        assert!(WarcImporter::page(&processor, &records[3]).await.is_err());
+        assert!(WarcImporter::read_record(&mut "HTTP/1.1 200 OK\r\n\r\n".as_bytes()).is_err());
+    }
+}
@@ -4,13 +4,19 @@
 pub mod database;
 pub mod models;
 pub mod repository;
+pub mod domain_repository;
 pub mod search_index;
 pub mod cache;
 pub mod export;
+pub mod backend;
+pub mod sqlite;
 mod tests;
 // Re-export main types
 
-pub use models::{StoredPage, SearchResult, DatabaseStats};
+pub use models::{StoredPage, SearchResult, DatabaseStats, PageVersion, SaveResult, PagePage, StoredPageSummary};
+pub use backend::{StorageBackend, PostgresBackend};
+pub use sqlite::SqliteBackend;
+pub use database::BackendKind;
 
 
 // storage errors
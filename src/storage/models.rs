@@ -31,16 +31,57 @@ pub struct StoredPage{
 
     #[sqlx(rename = "tfidf_score")]
     pub tfidf_score: Option<f64>,
+
+    #[sqlx(default)]
+    pub authority_score: Option<f64>,
+
+    #[sqlx(default)]
+    pub hub_score: Option<f64>,
+
+    // SimHash fingerprint of the content, stored as signed for BIGINT columns
+    #[sqlx(default)]
+    pub simhash: Option<i64>,
+
+    // PageRank with teleportation restricted to topic seed pages
+    #[sqlx(default)]
+    pub personalized_pagerank: Option<f64>,
+
+    // ETag response header, sent back as If-None-Match when recrawling
+    #[sqlx(default)]
+    pub etag: Option<String>,
+
+    // Open Graph og:image and og:type of the page
+    #[sqlx(default)]
+    pub og_image: Option<String>,
+    #[sqlx(default)]
+    pub og_type: Option<String>,
+
+    // Screenshot file name, relative to the configured screenshot_dir
+    #[sqlx(default)]
+    pub screenshot_path: Option<String>,
+
+    // Content is stored zstd-compressed; reads through PageRepository decompress it
+    // (get_page_by_id, get_page_by_url, get_pages, search_pages, ...), others see an empty string
+    #[sqlx(default)]
+    #[serde(default)]
+    pub content_compressed: bool,
+
+    // Topic cluster from the last `cluster` run, see ContentCluster
+    #[sqlx(default)]
+    #[serde(default)]
+    pub cluster_id: Option<i32>,
 }
 
 impl StoredPage{
-    // Create a new Stored page from page data
+    // Create a new Stored page from page data, keyed by its canonical URL
+    // (the <link rel="canonical"> one when the page declares it)
     pub fn from_page_data(page: &crate::models::PageData, url_hash: String, content_hash: String)-> Self{
-        let domain = page.url.split('/').nth(2).unwrap_or("unknown").to_string();
+        let url = crate::utils::canonicalize(page.storage_url());
+        let domain = crate::utils::registrable_domain(&url);
 
         Self{
             id: 0, // will be set by db
-            url: page.url.clone(),
+            url,
             url_hash,
             domain,
             title: page.title.clone(),
@@ -49,15 +90,25 @@ impl StoredPage{
             content_hash,
             quality_score: page.content_quality_score,
             word_count: page.word_count as i32,
-            language: "en".to_string(),  //TODO: detect language
+            language: crate::utils::detect_language(&page.content).primary.to_string(),
             crawl_depth: page.depth as i32,
             crawled_at: page.crawled_at,
-            last_modified: None,
+            last_modified: page.last_modified,
             status_code: 200,  //TODO: get this from HTTP response
             content_type: "text/html".to_string(),
             content_length: page.content.len() as i32,
             pagerank: None,
             tfidf_score: None,
+            authority_score: None,
+            hub_score: None,
+            simhash: Some(crate::algorithms::SimHashCalculator::calculate(&page.content) as i64),
+            personalized_pagerank: None,
+            etag: page.etag.clone(),
+            og_image: page.og_image.clone(),
+            og_type: page.og_type.clone(),
+            screenshot_path: page.screenshot_path.clone(),
+            content_compressed: false,
+            cluster_id: None,
         }
     }
 
@@ -72,8 +123,16 @@ impl StoredPage{
             outgoing_links: vec![], //Would need to query liked table
             word_count: self.word_count as usize,
             content_quality_score: self.quality_score,
+            quality_factors: Vec::new(),
             crawled_at: self.crawled_at,
             depth: self.crawl_depth as u32,
+            etag: self.etag.clone(),
+            last_modified: self.last_modified,
+            feed_urls: vec![],
+            og_image: self.og_image.clone(),
+            og_type: self.og_type.clone(),
+            screenshot_path: self.screenshot_path.clone(),
+            canonical_url: None,
         }
     }
 }
@@ -136,12 +195,43 @@ impl CrawlSession{
     }
 }
 
+// Snapshot of a page's content each time it changed
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct PageVersion{
+    pub page_id: i64,
+    pub version: i32,
+    pub content_hash: String,
+    pub title: Option<String>,
+    pub crawled_at: DateTime<Utc>,
+    pub diff_size_bytes: i64,
+}
+
+// Compact view of a page, for link listings
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, FromRow)]
+pub struct StoredPageSummary{
+    pub id: i64,
+    pub url: String,
+    pub title: Option<String>,
+    pub quality_score: f64,
+    pub pagerank: Option<f64>,
+}
+
+// Outcome of saving a page
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SaveResult{
+    pub id: i64,
+    // true when the page is new or its content hash changed
+    pub changed: bool,
+    pub version: u32,
+}
+
 // Domain information
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct DomainInfo{
     pub domain: String,
     pub robots_txt: Option<String>,
     pub robots_fetched_at: Option<DateTime<Utc>>,
+    /// Milliseconds asked for by the `Crawl-delay` of robots.txt, 0 when it sets none
     pub crawl_delay: i32,
     pub page_count: i32,
     pub avg_quality_score: Option<f64>,
@@ -156,7 +246,7 @@ impl DomainInfo {
             domain,
             robots_txt: None,
             robots_fetched_at: None,
-            crawl_delay: 1000, // 1 second default
+            crawl_delay: 0,
             page_count: 0,
             avg_quality_score: None,
             last_crawled: None,
@@ -218,8 +308,12 @@ pub struct PageFilter{
     pub crawled_after: Option<DateTime<Utc>>,
     pub crawled_before: Option<DateTime<Utc>>,
     pub status_code: Option<i32>,
+    // ISO 639-1 code as stored in pages.language
+    pub language: Option<String>,
     pub limit: Option<usize>,
     pub offset: Option<usize>,
+    // keyset pagination: only pages with id greater than this, ordered by id
+    pub cursor: Option<i64>,
 }
 
 impl Default for PageFilter{
@@ -231,8 +325,10 @@ impl Default for PageFilter{
             crawled_after: None,
             crawled_before: None,
             status_code: None,
+            language: None,
             limit: None,
             offset: None,
+            cursor: None,
         }
     }
 }
@@ -242,6 +338,11 @@ impl PageFilter{
         Self::default()
     }
 
+    // Pages crawled at or after `since`
+    pub fn crawled_after(since: DateTime<Utc>) -> Self{
+        Self{ crawled_after: Some(since), ..Self::default() }
+    }
+
     pub fn with_domain(mut self, domain: String) -> Self{
         self.domain = Some(domain);
         self
@@ -252,17 +353,121 @@ impl PageFilter{
         self
     }
 
+    pub fn with_language(mut self, lang: &str) -> Self{
+        self.language = Some(lang.to_string());
+        self
+    }
+
     pub fn with_limit(mut self, limit: usize) -> Self{
         self.limit = Some(limit);
         self
     }
+
+    // Page through results by id instead of OFFSET, start with 0
+    pub fn with_cursor(mut self, last_id: i64) -> Self{
+        self.cursor = Some(last_id);
+        self
+    }
+}
+
+// One page of results from get_pages
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PagePage{
+    pub items: Vec<StoredPage>,
+    // pass to PageFilter::with_cursor to fetch the next page
+    pub next_cursor: Option<i64>,
+    pub has_more: bool,
+}
+
+impl PagePage{
+    // Build a page from rows fetched with one extra row past the limit
+    pub fn from_rows(mut items: Vec<StoredPage>, filter: &PageFilter) -> Self{
+        let has_more = match filter.limit {
+            Some(limit) if items.len() > limit => {
+                items.truncate(limit);
+                true
+            }
+            _ => false,
+        };
+
+        let next_cursor = if filter.cursor.is_some() && has_more {
+            items.last().map(|p| p.id)
+        } else {
+            None
+        };
+
+        Self{
+            items,
+            next_cursor,
+            has_more,
+        }
+    }
 }
 
 #[cfg(test)]
-mod tests{
+pub(crate) mod tests{
     use super::*;
     use crate::models::PageData;
 
+    /// Crawled page shared by tests, override fields with struct update syntax
+    pub(crate) fn page_data(url: &str, content: &str) -> PageData {
+        PageData {
+            url: url.to_string(),
+            title: Some("Test Page".to_string()),
+            description: None,
+            keywords: vec![],
+            content: content.to_string(),
+            outgoing_links: vec![],
+            word_count: content.split_whitespace().count(),
+            content_quality_score: 0.5,
+            quality_factors: Vec::new(),
+            crawled_at: Utc::now(),
+            depth: 0,
+            etag: None,
+            last_modified: None,
+            feed_urls: vec![],
+            og_image: None,
+            og_type: None,
+            screenshot_path: None,
+            canonical_url: None,
+        }
+    }
+
+    /// Stored page shared by tests, override fields with struct update syntax
+    pub(crate) fn stored_page(id: i64, url: &str) -> StoredPage {
+        StoredPage {
+            id,
+            url: url.to_string(),
+            url_hash: "hash123".to_string(),
+            domain: "example.com".to_string(),
+            title: Some("Test Page".to_string()),
+            description: None,
+            content: "Test content".to_string(),
+            content_hash: "content_hash".to_string(),
+            quality_score: 0.8,
+            word_count: 2,
+            language: "en".to_string(),
+            crawl_depth: 1,
+            crawled_at: Utc::now(),
+            last_modified: None,
+            status_code: 200,
+            content_type: "text/html".to_string(),
+            content_length: 12,
+            pagerank: None,
+            tfidf_score: None,
+            authority_score: None,
+            hub_score: None,
+            simhash: None,
+            personalized_pagerank: None,
+            etag: None,
+            og_image: None,
+            og_type: None,
+            screenshot_path: None,
+            content_compressed: false,
+            cluster_id: None,
+        }
+    }
+
     #[test]
     fn test_stored_page_from_page_data() {
         let page_data = PageData {
@@ -274,8 +479,16 @@ mod tests{
             outgoing_links: vec![],
             word_count: 2,
             content_quality_score: 0.8,
+            quality_factors: Vec::new(),
             crawled_at: Utc::now(),
             depth: 1,
+            etag: None,
+            last_modified: None,
+            feed_urls: vec![],
+            og_image: None,
+            og_type: None,
+            screenshot_path: None,
+            canonical_url: None,
         };
 
         let stored_page = StoredPage::from_page_data(&page_data, "hash123".to_string(), "content_hash".to_string());
@@ -311,5 +524,7 @@ mod tests{
         assert_eq!(filter.domain, Some("example.com".to_string()));
         assert_eq!(filter.min_quality, Some(0.5));
         assert_eq!(filter.limit, Some(100));
+        assert_eq!(filter.cursor, None);
+        assert_eq!(filter.with_cursor(42).cursor, Some(42));
     }
 }
\ No newline at end of file
@@ -1,16 +1,22 @@
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
 use tantivy::{collector::TopDocs, query::QueryParser, schema::{Field, Schema, TextOptions, TextFieldIndexing, IndexRecordOption}, Index, IndexReader, IndexWriter, ReloadPolicy, TantivyDocument, Term};
 use tantivy::schema::{NumericOptions, Value};
 use tantivy::tokenizer::{
     TextAnalyzer, SimpleTokenizer, LowerCaser, RemoveLongFilter, Stemmer, Language
 };
-use tracing::{debug, info};
+use tracing::{debug, info, warn};
 use crate::storage::{StoredPage, SearchResult, Result, StorageError};
 use crate::models::PageData;
+use crate::utils::registrable_domain;
+
+// merges started by the writer's merge policy lock their segments, retry until they finish
+const MAX_MERGE_ATTEMPTS: usize = 50;
+const MERGE_RETRY_DELAY: std::time::Duration = std::time::Duration::from_millis(100);
 
 pub struct SearchIndex {
     index: Index,
+    index_path: PathBuf,
     reader: IndexReader,
     writer: Arc<Mutex<IndexWriter>>,
     schema: Schema,
@@ -123,6 +129,7 @@ impl SearchIndex {
 
         Ok(Self {
             index,
+            index_path: index_path.to_path_buf(),
             reader,
             writer: Arc::new(Mutex::new(writer)),
             schema,
@@ -204,63 +211,9 @@ impl SearchIndex {
         info!("Registered tokenizers for: English + 6 Indian languages");
     }
 
-    // Advanced language detection using Unicode ranges [web:30][web:31]
+    // Statistical detection with a Unicode block fallback, see utils::language
     fn detect_content_language(&self, content: &str) -> String {
-        let char_counts = content.chars().fold(
-            [0u32; 7], // [english, hindi, kannada, tamil, telugu, malayalam, marathi]
-            |mut counts, c| {
-                match c as u32 {
-                    // English (Basic Latin + Latin-1)
-                    0x0000..=0x024F => counts[0] += 1,
-                    // Hindi & Marathi (Devanagari: U+0900-U+097F) [web:45]
-                    0x0900..=0x097F => {
-                        // Further distinguish Hindi vs Marathi by common patterns
-                        counts[1] += 1; // Default to Hindi
-                        counts[6] += 1; // Also count for Marathi
-                    },
-                    // Kannada (U+0C80-U+0CFF) [web:31][web:33]
-                    0x0C80..=0x0CFF => counts[2] += 1,
-                    // Tamil (U+0B80-U+0BFF)
-                    0x0B80..=0x0BFF => counts[3] += 1,
-                    // Telugu (U+0C00-U+0C7F)
-                    0x0C00..=0x0C7F => counts[4] += 1,
-                    // Malayalam (U+0D00-U+0D7F) [web:30][web:32]
-                    0x0D00..=0x0D7F => counts[5] += 1,
-                    _ => {}
-                }
-                counts
-            }
-        );
-
-        // Find the script with the highest character count - FIXED: Use owned values
-        let max_idx = char_counts.iter()
-            .enumerate()
-            .max_by_key(|(_, count)| *count) // Changed from &count to *count
-            .map(|(idx, _)| idx)
-            .unwrap_or(0);
-
-        // Return language code based on highest count
-        match max_idx {
-            1 => {
-                // Distinguish Hindi vs Marathi
-                if char_counts[6] > char_counts[1] / 2 {
-                    // If Marathi count is significant, do additional checks
-                    if content.contains("मराठी") || content.contains("महाराष्ट्र") {
-                        "mr".to_string() // Marathi
-                    } else {
-                        "hi".to_string() // Default to Hindi
-                    }
-                } else {
-                    "hi".to_string()
-                }
-            },
-            2 => "kn".to_string(), // Kannada
-            3 => "ta".to_string(), // Tamil
-            4 => "te".to_string(), // Telugu
-            5 => "ml".to_string(), // Malayalam
-            6 => "mr".to_string(), // Marathi
-            _ => "en".to_string(), // Default to English
-        }
+        crate::utils::detect_language(content).primary.to_string()
     }
 
     // Language-aware indexing with proper field mapping
@@ -276,8 +229,7 @@ impl SearchIndex {
         doc.add_i64(self.id_field, page_id);
         doc.add_text(self.url_field, &page.url);
         doc.add_text(self.language_field, detected_language);
-        let domain = page.url.split('/').nth(2).unwrap_or("unknown");
-        doc.add_text(self.domain_field, domain);
+        doc.add_text(self.domain_field, registrable_domain(&page.url));
         doc.add_f64(self.quality_field, page.content_quality_score);
 
         // Index in language-specific fields
@@ -470,6 +422,16 @@ impl SearchIndex {
                 content_length: 0,
                 pagerank: None,
                 tfidf_score: None,
+                authority_score: None,
+                hub_score: None,
+                simhash: None,
+                personalized_pagerank: None,
+                etag: None,
+                og_image: None,
+                og_type: None,
+                screenshot_path: None,
+                content_compressed: false,
+                cluster_id: None,
             };
 
             results.push(SearchResult::new(stored_page, score, snippet));
@@ -558,8 +520,7 @@ impl SearchIndex {
             doc.add_i64(self.id_field, *page_id);
             doc.add_text(self.url_field, &page.url);
             doc.add_text(self.language_field, &detected_language);
-            let domain = page.url.split('/').nth(2).unwrap_or("unknown");
-            doc.add_text(self.domain_field, domain);
+            doc.add_text(self.domain_field, registrable_domain(&page.url));
             doc.add_f64(self.quality_field, page.content_quality_score);
 
             // Index in appropriate language fields
@@ -648,21 +609,137 @@ impl SearchIndex {
         })
     }
 
+    /// Commit pending documents, merge all segments into one and delete the files
+    /// of the merged segments
     pub fn optimize(&self) -> Result<()> {
         let mut writer = self.writer.lock().unwrap();
         writer
             .commit()
             .map_err(|e| StorageError::SearchIndex(format!("Failed to commit during optimize: {}", e)))?;
+
+        merge_all_segments(&self.index, &mut writer)
+            .map_err(|e| StorageError::SearchIndex(format!("Failed to merge segments: {}", e)))?;
         Ok(())
     }
 
+    /// Merge all committed segments into one
+    pub fn merge_segments(&self) -> Result<()> {
+        let mut writer = self.writer.lock().unwrap();
+        merge_all_segments(&self.index, &mut writer)
+            .map_err(|e| StorageError::SearchIndex(format!("Failed to merge segments: {}", e)))
+    }
+
+    /// Number of committed segments, each one is searched separately
+    pub fn get_segment_count(&self) -> usize {
+        self.index
+            .searchable_segment_ids()
+            .map(|ids| ids.len())
+            .unwrap_or_else(|e| {
+                warn!("Failed to read index segments: {}", e);
+                0
+            })
+    }
+
     fn calculate_index_size_bytes(&self) -> u64 {
-        0
+        directory_size(&self.index_path)
     }
 }
 
+/// Merge every committed segment of `index` into a single one, then garbage collect
+/// the files of the merged segments
+pub(crate) fn merge_all_segments(index: &Index, writer: &mut IndexWriter) -> tantivy::Result<()> {
+    for attempt in 1..=MAX_MERGE_ATTEMPTS {
+        let segment_ids = index.searchable_segment_ids()?;
+        if segment_ids.len() <= 1 {
+            break;
+        }
+
+        match writer.merge(&segment_ids).wait() {
+            Ok(_) => info!("Merged {} index segments", segment_ids.len()),
+            Err(e) if attempt < MAX_MERGE_ATTEMPTS => {
+                debug!("Segment merge attempt {} failed, retrying: {}", attempt, e);
+                std::thread::sleep(MERGE_RETRY_DELAY);
+            }
+            Err(e) => return Err(e),
+        }
+    }
+
+    writer.garbage_collect_files().wait()?;
+    Ok(())
+}
+
+fn directory_size(path: &Path) -> u64 {
+    let Ok(entries) = std::fs::read_dir(path) else {
+        return 0;
+    };
+
+    entries
+        .flatten()
+        .map(|entry| match entry.metadata() {
+            Ok(metadata) if metadata.is_dir() => directory_size(&entry.path()),
+            Ok(metadata) => metadata.len(),
+            Err(_) => 0,
+        })
+        .sum()
+}
+
 #[derive(Debug, Clone)]
 pub struct SearchStats {
     pub total_documents: u64,
     pub index_size_bytes: u64,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use tempfile::TempDir;
+
+    fn page(id: i64) -> (i64, PageData) {
+        let page = PageData {
+            url: format!("https://example.com/page/{}", id),
+            title: Some(format!("Page {}", id)),
+            description: None,
+            keywords: vec![],
+            content: format!("rust web crawler page number {}", id),
+            outgoing_links: vec![],
+            word_count: 5,
+            content_quality_score: 0.5,
+            quality_factors: Vec::new(),
+            crawled_at: Utc::now(),
+            depth: 0,
+            etag: None,
+            last_modified: None,
+            feed_urls: vec![],
+            og_image: None,
+            og_type: None,
+            screenshot_path: None,
+            canonical_url: None,
+        };
+        (id, page)
+    }
+
+    #[test]
+    fn test_optimize_merges_segments() {
+        let dir = TempDir::new().unwrap();
+        let index = SearchIndex::new(dir.path()).unwrap();
+
+        // one segment per committed batch
+        for batch in 0..20 {
+            let pages: Vec<_> = (0..50).map(|i| page(batch * 50 + i)).collect();
+            index.batch_index_pages(&pages).unwrap();
+            index.commit().unwrap();
+        }
+        assert!(index.get_segment_count() > 1);
+        let size_before = index.get_stats().unwrap().index_size_bytes;
+        assert!(size_before > 0);
+
+        index.optimize().unwrap();
+        assert_eq!(index.get_segment_count(), 1);
+
+        index.reader.reload().unwrap();
+        let stats = index.get_stats().unwrap();
+        assert_eq!(stats.total_documents, 1000);
+        assert!(stats.index_size_bytes > 0);
+    }
+}
@@ -0,0 +1,1315 @@
+//! SQLite storage backend for local development and tests
+//!
+//! Covers the `StorageBackend` operations only, content compression included. The rest of
+//! `PageRepository` (full-text search vectors, crawl jobs, streaming, recompression) is PostgreSQL only.
+
+use crate::algorithms::SimHashCalculator;
+use crate::config::StorageSettings;
+use crate::models::{CrawlUrl, PageData};
+use crate::network::{CachedHeaders, Cookie};
+use crate::storage::Result;
+use crate::storage::backend::StorageBackend;
+use crate::storage::database::{BackendKind, DatabaseConfig, CANONICAL_URL_HASH_MIGRATION, REHASH_BATCH_SIZE};
+use crate::storage::models::{CrawlSession, DatabaseStats, PageFilter, PagePage, PageVersion, SaveResult, StoredPage, StoredPageSummary};
+use crate::storage::repository::{encode_content, pages_per_host, PageRepository};
+use crate::utils::{canonicalize, url_host};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use futures_util::StreamExt;
+use sqlx::sqlite::{SqliteConnectOptions, SqliteJournalMode, SqlitePool, SqlitePoolOptions};
+use sqlx::{QueryBuilder, Row, Sqlite};
+use std::str::FromStr;
+use tracing::{error, info, warn};
+
+const PAGE_COLUMNS: &str = "id, url, url_hash, domain, title, description, content, content_hash, \
+     quality_score, word_count, language, crawl_depth, crawled_at, last_modified, \
+     status_code, content_type, content_length, pagerank, tfidf_score, authority_score, hub_score, simhash, personalized_pagerank, etag, og_image, og_type, screenshot_path, \
+     content_zstd, content_compressed";
+
+// (column, migration adding it): SQLite has no ADD COLUMN IF NOT EXISTS, so each runs while pages lacks its column
+const COLUMN_MIGRATIONS: [(&str, &str); 2] = [
+    ("cluster_id", include_str!("../../migrations/023_sqlite_cluster_id.sql")),
+    ("content_zstd", include_str!("../../migrations/024_sqlite_compressed_content.sql")),
+];
+
+pub struct SqliteBackend {
+    pool: SqlitePool,
+    // store new content zstd-compressed, see `StorageSettings::compress_content`
+    compress_content: bool,
+}
+
+impl SqliteBackend {
+    pub async fn connect(settings: &StorageSettings) -> Result<Self> {
+        let config = DatabaseConfig::from(settings);
+        info!("Connecting to SQLite database : {}", config.database_url);
+
+        let mut options = SqliteConnectOptions::from_str(&config.database_url)?
+            .create_if_missing(true)
+            .foreign_keys(config.enable_foreign_keys);
+
+        if config.enable_wal_mode {
+            options = options.journal_mode(SqliteJournalMode::Wal);
+        }
+
+        // An in-memory database only lives as long as its connection, so keep a single one open
+        let pool_options = if config.database_url.contains(":memory:") {
+            SqlitePoolOptions::new()
+                .max_connections(1)
+                .min_connections(1)
+                .idle_timeout(None)
+                .max_lifetime(None)
+        } else {
+            SqlitePoolOptions::new()
+                .max_connections(config.max_connections)
+                .min_connections(config.min_connections)
+                .acquire_timeout(std::time::Duration::from_secs(config.connect_timeout_secs))
+                .idle_timeout(std::time::Duration::from_secs(config.idle_timeout_secs))
+        };
+
+        let pool = pool_options.connect_with(options).await?;
+
+        info!("SQLite database connected successfully");
+        Ok(Self { pool, compress_content: settings.compress_content })
+    }
+
+    pub fn pool(&self) -> &SqlitePool {
+        &self.pool
+    }
+
+    async fn upsert_page(
+        &self,
+        executor: impl sqlx::Executor<'_, Database = Sqlite>,
+        page: &PageData,
+    ) -> Result<(i64, StoredPage, Option<Vec<u8>>)> {
+        let url_hash = PageRepository::calculate_url_hash(page.storage_url());
+        let content_hash = PageRepository::calculate_content_hash(&page.content);
+        let stored_page = StoredPage::from_page_data(page, url_hash, content_hash);
+        let content_zstd = encode_content(&stored_page.content, self.compress_content)?;
+        // compressed rows keep their text column empty
+        let content = if content_zstd.is_some() { "" } else { stored_page.content.as_str() };
+
+        let query = r#"
+            INSERT INTO pages (
+                url, url_hash, domain, title, description, content, content_hash,
+                quality_score, word_count, language, crawl_depth, crawled_at,
+                status_code, content_type, content_length, simhash, last_modified, etag,
+                og_image, og_type, screenshot_path, content_zstd, content_compressed
+            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            ON CONFLICT (url_hash)
+            DO UPDATE SET
+                title = excluded.title,
+                description = excluded.description,
+                content = excluded.content,
+                content_hash = excluded.content_hash,
+                quality_score = excluded.quality_score,
+                word_count = excluded.word_count,
+                crawled_at = excluded.crawled_at,
+                status_code = excluded.status_code,
+                content_length = excluded.content_length,
+                simhash = excluded.simhash,
+                last_modified = excluded.last_modified,
+                etag = excluded.etag,
+                og_image = excluded.og_image,
+                og_type = excluded.og_type,
+                screenshot_path = COALESCE(excluded.screenshot_path, pages.screenshot_path),
+                content_zstd = excluded.content_zstd,
+                content_compressed = excluded.content_compressed
+            RETURNING id
+        "#;
+
+        let row = sqlx::query(query)
+            .bind(&stored_page.url)
+            .bind(&stored_page.url_hash)
+            .bind(&stored_page.domain)
+            .bind(&stored_page.title)
+            .bind(&stored_page.description)
+            .bind(content)
+            .bind(&stored_page.content_hash)
+            .bind(stored_page.quality_score)
+            .bind(stored_page.word_count)
+            .bind(&stored_page.language)
+            .bind(stored_page.crawl_depth)
+            .bind(stored_page.crawled_at)
+            .bind(stored_page.status_code)
+            .bind(&stored_page.content_type)
+            .bind(stored_page.content_length)
+            .bind(stored_page.simhash)
+            .bind(stored_page.last_modified)
+            .bind(&stored_page.etag)
+            .bind(&stored_page.og_image)
+            .bind(&stored_page.og_type)
+            .bind(&stored_page.screenshot_path)
+            .bind(&content_zstd)
+            .bind(content_zstd.is_some())
+            .fetch_one(executor)
+            .await?;
+
+        Ok((row.get("id"), stored_page, content_zstd))
+    }
+
+    // Same as PageRepository::delete_pages_where, versions are removed explicitly
+    // since SQLite only cascades when foreign keys are enabled
+    async fn delete_pages_where<T>(&self, condition: &str, value: T) -> Result<u64>
+    where
+        T: for<'q> sqlx::Encode<'q, Sqlite> + sqlx::Type<Sqlite> + Clone + Send + 'static,
+    {
+        let mut tx = self.pool.begin().await?;
+
+        for table in [("links", "source_page_id"), ("page_versions", "page_id")] {
+            sqlx::query(&format!("DELETE FROM {} WHERE {} IN (SELECT id FROM pages WHERE {})", table.0, table.1, condition))
+                .bind(value.clone())
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        let deleted: Vec<String> = sqlx::query_scalar(&format!("DELETE FROM pages WHERE {} RETURNING url", condition))
+            .bind(value)
+            .fetch_all(&mut *tx)
+            .await?;
+
+        for (host, removed) in pages_per_host(&deleted) {
+            sqlx::query("UPDATE domains SET page_count = MAX(page_count - ?2, 0) WHERE domain = ?1")
+                .bind(host)
+                .bind(removed)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        tx.commit().await?;
+        let deleted = deleted.len() as u64;
+
+        info!("Deleted {} pages where {}", deleted, condition);
+        Ok(deleted)
+    }
+
+    // Same as PageRepository::update_domain_stats, keyed by host
+    async fn update_domain_stats(&self, host: &str, quality_score: f64) -> Result<()> {
+        let query = r#"
+            INSERT INTO domains (domain, page_count, avg_quality_score, last_crawled)
+            VALUES (?1, 1, ?2, CURRENT_TIMESTAMP)
+            ON CONFLICT (domain) DO UPDATE SET
+                page_count = COALESCE(domains.page_count, 0) + 1,
+                avg_quality_score = (COALESCE(domains.avg_quality_score, 0) * COALESCE(domains.page_count, 0) + ?2)
+                    / (COALESCE(domains.page_count, 0) + 1),
+                last_crawled = CURRENT_TIMESTAMP
+        "#;
+
+        sqlx::query(query)
+            .bind(host)
+            .bind(quality_score)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+}
+
+impl SqliteBackend {
+    // `Database::rehash_url_hashes` for SQLite
+    async fn rehash_url_hashes(&self) -> Result<()> {
+        let applied: bool = sqlx::query_scalar("SELECT EXISTS (SELECT 1 FROM data_migrations WHERE name = ?)")
+            .bind(CANONICAL_URL_HASH_MIGRATION)
+            .fetch_one(&self.pool)
+            .await?;
+        if applied {
+            return Ok(());
+        }
+
+        let (mut last_id, mut rehashed, mut kept) = (0i64, 0u64, 0u64);
+        loop {
+            let rows: Vec<(i64, String, String)> = sqlx::query_as(
+                "SELECT id, url, url_hash FROM pages WHERE id > ? ORDER BY id LIMIT ?"
+            )
+                .bind(last_id)
+                .bind(REHASH_BATCH_SIZE)
+                .fetch_all(&self.pool)
+                .await?;
+            let Some(&(id, _, _)) = rows.last() else { break };
+            last_id = id;
+
+            let mut tx = self.pool.begin().await?;
+            for (id, url, url_hash) in rows {
+                let canonical_hash = PageRepository::calculate_url_hash(&url);
+                if canonical_hash == url_hash {
+                    continue;
+                }
+                let updated = sqlx::query(
+                    "UPDATE pages SET url_hash = ?1 WHERE id = ?2 AND NOT EXISTS (SELECT 1 FROM pages WHERE url_hash = ?1)"
+                )
+                    .bind(&canonical_hash)
+                    .bind(id)
+                    .execute(&mut *tx)
+                    .await?
+                    .rows_affected();
+                if updated > 0 {
+                    rehashed += 1;
+                } else {
+                    kept += 1;
+                    warn!("Page {} ({}) keeps its url hash, its canonical url is stored already", id, url);
+                }
+            }
+            tx.commit().await?;
+        }
+
+        sqlx::query("INSERT OR IGNORE INTO data_migrations (name) VALUES (?)")
+            .bind(CANONICAL_URL_HASH_MIGRATION)
+            .execute(&self.pool)
+            .await?;
+        info!("Re-hashed the urls of {} pages, {} kept their old hash", rehashed, kept);
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl StorageBackend for SqliteBackend {
+    fn kind(&self) -> BackendKind {
+        BackendKind::Sqlite
+    }
+
+    async fn migrate(&self) -> Result<()> {
+        info!("Running SQLite migrations ...");
+
+        let schemas = [
+            include_str!("../../migrations/007_sqlite_compat.sql"),
+            include_str!("../../migrations/022_data_migrations.sql"),
+        ];
+        let mut tx = self.pool.begin().await?;
+
+        for statement in schemas.iter().flat_map(|schema| schema.split(";")) {
+            let statement = statement.trim();
+            if !statement.is_empty() {
+                sqlx::query(statement).execute(&mut *tx).await.inspect_err(|_| {
+                    error!("Failed to run migration: {}", statement);
+                })?;
+            }
+        }
+
+        for (column, schema) in COLUMN_MIGRATIONS {
+            let exists: bool = sqlx::query_scalar("SELECT EXISTS (SELECT 1 FROM pragma_table_info('pages') WHERE name = ?)")
+                .bind(column)
+                .fetch_one(&mut *tx)
+                .await?;
+            if exists {
+                continue;
+            }
+            for statement in schema.split(";").map(str::trim).filter(|statement| !statement.is_empty()) {
+                sqlx::query(statement).execute(&mut *tx).await.inspect_err(|_| {
+                    error!("Failed to run migration: {}", statement);
+                })?;
+            }
+        }
+        tx.commit().await?;
+        self.rehash_url_hashes().await?;
+
+        info!("SQLite migration complete");
+        Ok(())
+    }
+
+    async fn health_check(&self) -> bool {
+        match sqlx::query("SELECT 1 as health_check").fetch_one(&self.pool).await {
+            Ok(row) => {
+                let result: i32 = row.get("health_check");
+                result == 1
+            }
+            Err(e) => {
+                warn!("Database health check failed, {}", e);
+                false
+            }
+        }
+    }
+
+    async fn save_page(&self, page: &PageData, _session_id: i64) -> Result<SaveResult> {
+        let url_hash = PageRepository::calculate_url_hash(page.storage_url());
+        let mut tx = self.pool.begin().await?;
+
+        let previous = sqlx::query(
+            "SELECT content_hash, CASE WHEN content_compressed THEN content_length ELSE COALESCE(LENGTH(CAST(content AS BLOB)), 0) END AS content_len \
+             FROM pages WHERE url_hash = ?"
+        )
+            .bind(&url_hash)
+            .fetch_optional(&mut *tx)
+            .await?;
+
+        let (page_id, stored_page, content_zstd) = self.upsert_page(&mut *tx, page).await?;
+
+        if let Some(alias_url) = page.alias_url() {
+            sqlx::query(
+                "INSERT INTO url_aliases (alias_url_hash, alias_url, page_id) VALUES (?, ?, ?) \
+                 ON CONFLICT (alias_url_hash) DO UPDATE SET page_id = excluded.page_id"
+            )
+                .bind(PageRepository::calculate_url_hash(alias_url))
+                .bind(canonicalize(alias_url))
+                .bind(page_id)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        let latest_version: Option<i32> = sqlx::query_scalar(
+            "SELECT MAX(version) FROM page_versions WHERE page_id = ?"
+        )
+            .bind(page_id)
+            .fetch_one(&mut *tx)
+            .await?;
+
+        let (changed, diff_size_bytes) = match &previous {
+            Some(prev) => {
+                let prev_hash: Option<String> = prev.get("content_hash");
+                let prev_len: i64 = prev.get("content_len");
+                let diff = (stored_page.content.len() as i64 - prev_len).abs();
+                (prev_hash.as_deref() != Some(stored_page.content_hash.as_str()), diff)
+            }
+            None => (true, stored_page.content.len() as i64),
+        };
+
+        let version = if changed || latest_version.is_none() {
+            let next_version = latest_version.unwrap_or(0) + 1;
+
+            sqlx::query(r#"
+                INSERT INTO page_versions (page_id, version, content_hash, title, content, crawled_at, diff_size_bytes, content_zstd)
+                VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+            "#)
+                .bind(page_id)
+                .bind(next_version)
+                .bind(&stored_page.content_hash)
+                .bind(&stored_page.title)
+                .bind(if content_zstd.is_some() { "" } else { stored_page.content.as_str() })
+                .bind(stored_page.crawled_at)
+                .bind(diff_size_bytes)
+                .bind(&content_zstd)
+                .execute(&mut *tx)
+                .await?;
+
+            next_version
+        } else {
+            latest_version.unwrap_or(1)
+        };
+
+        tx.commit().await?;
+
+        self.update_domain_stats(&url_host(&stored_page.url), stored_page.quality_score).await?;
+
+        info!("Saved page: {} (ID: {}, version {}, changed: {})", page.url, page_id, version, changed);
+        Ok(SaveResult {
+            id: page_id,
+            changed,
+            version: version as u32,
+        })
+    }
+
+    async fn get_cached_headers(&self, url: &str) -> Result<Option<CachedHeaders>> {
+        let url_hash = PageRepository::calculate_url_hash(url);
+
+        let row = sqlx::query("SELECT etag, last_modified FROM pages WHERE url_hash = ?")
+            .bind(&url_hash)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(row
+            .map(|row| CachedHeaders {
+                etag: row.get("etag"),
+                last_modified: row.get("last_modified"),
+            })
+            .filter(|headers| !headers.is_empty()))
+    }
+
+    async fn get_page_history(&self, url: &str) -> Result<Vec<PageVersion>> {
+        let url_hash = PageRepository::calculate_url_hash(url);
+
+        let query = r#"
+            SELECT v.page_id, v.version, v.content_hash, v.title, v.crawled_at, v.diff_size_bytes
+            FROM page_versions v
+            INNER JOIN pages p ON p.id = v.page_id
+            WHERE p.url_hash = ?
+            ORDER BY v.version ASC
+        "#;
+
+        let versions = sqlx::query_as::<_, PageVersion>(query)
+            .bind(&url_hash)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(versions)
+    }
+
+    async fn get_page_at_version(&self, url: &str, version: u32) -> Result<Option<StoredPage>> {
+        let url_hash = PageRepository::calculate_url_hash(url);
+
+        let query = r#"
+            SELECT p.id, p.url, p.url_hash, p.domain, v.title, p.description, v.content, v.content_hash,
+                   p.quality_score, p.word_count, p.language, p.crawl_depth, v.crawled_at, p.last_modified,
+                   p.status_code, p.content_type, LENGTH(v.content) AS content_length, p.pagerank, p.tfidf_score,
+                   v.content_zstd AS version_zstd
+            FROM page_versions v
+            INNER JOIN pages p ON p.id = v.page_id
+            WHERE p.url_hash = ? AND v.version = ?
+        "#;
+
+        let row = sqlx::query(query)
+            .bind(&url_hash)
+            .bind(version as i32)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        row.as_ref().map(PageRepository::version_page_from_row).transpose()
+    }
+
+    async fn save_links(&self, page_id: i64, links: &[CrawlUrl]) -> Result<()> {
+        if links.is_empty() {
+            return Ok(());
+        }
+
+        let query = r#"
+            INSERT INTO links (source_page_id, target_page_id, target_url, anchor_text, link_position)
+            VALUES (?1, (SELECT id FROM pages WHERE url = ?2 LIMIT 1), ?2, ?3, ?4)
+            ON CONFLICT DO NOTHING
+        "#;
+
+        for (position, link) in links.iter().enumerate() {
+            let anchor_text: Option<String> = None;
+            sqlx::query(query)
+                .bind(page_id)
+                .bind(&link.url)
+                .bind(anchor_text)
+                .bind(position as i32)
+                .execute(&self.pool)
+                .await?;
+        }
+
+        info!("Saved {} links for page ID {}", links.len(), page_id);
+        Ok(())
+    }
+
+    async fn get_page_by_id(&self, page_id: i64) -> Result<Option<StoredPage>> {
+        let query = format!("SELECT {} FROM pages WHERE id = ?", PAGE_COLUMNS);
+
+        let row = sqlx::query(&query)
+            .bind(page_id)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        row.as_ref().map(PageRepository::page_from_row).transpose()
+    }
+
+    async fn get_page_by_url(&self, url: &str) -> Result<Option<StoredPage>> {
+        let url_hash = PageRepository::calculate_url_hash(url);
+        let query = format!("SELECT {} FROM pages WHERE url_hash = ?", PAGE_COLUMNS);
+
+        let row = sqlx::query(&query)
+            .bind(&url_hash)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        row.as_ref().map(PageRepository::page_from_row).transpose()
+    }
+
+    async fn url_exists(&self, url: &str) -> Result<bool> {
+        let url_hash = PageRepository::calculate_url_hash(url);
+        let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM pages WHERE url_hash = ?")
+            .bind(&url_hash)
+            .fetch_one(&self.pool)
+            .await?;
+        Ok(count > 0)
+    }
+
+    async fn get_pages(&self, filter: &PageFilter) -> Result<PagePage> {
+        let mut qb = QueryBuilder::<Sqlite>::new(format!("SELECT {} FROM pages WHERE 1=1", PAGE_COLUMNS));
+
+        if let Some(domain) = &filter.domain {
+            qb.push(" AND domain = ").push_bind(domain);
+        }
+        if let Some(min_q) = filter.min_quality {
+            qb.push(" AND quality_score >= ").push_bind(min_q);
+        }
+        if let Some(max_q) = filter.max_quality {
+            qb.push(" AND quality_score <= ").push_bind(max_q);
+        }
+        if let Some(sc) = filter.status_code {
+            qb.push(" AND status_code = ").push_bind(sc);
+        }
+        if let Some(language) = &filter.language {
+            qb.push(" AND language = ").push_bind(language);
+        }
+        // Timestamps are stored as text, normalise both sides before comparing
+        if let Some(after) = &filter.crawled_after {
+            qb.push(" AND datetime(crawled_at) >= datetime(").push_bind(after.to_rfc3339()).push(")");
+        }
+        if let Some(before) = &filter.crawled_before {
+            qb.push(" AND datetime(crawled_at) <= datetime(").push_bind(before.to_rfc3339()).push(")");
+        }
+
+        if let Some(cursor) = filter.cursor {
+            qb.push(" AND id > ").push_bind(cursor);
+            qb.push(" ORDER BY id ASC");
+        } else {
+            qb.push(" ORDER BY quality_score DESC, crawled_at DESC");
+        }
+
+        if let Some(limit) = filter.limit {
+            qb.push(" LIMIT ").push_bind(limit as i64 + 1);
+            if let (Some(offset), None) = (filter.offset, filter.cursor) {
+                qb.push(" OFFSET ").push_bind(offset as i64);
+            }
+        }
+
+        let rows = qb.build().fetch_all(&self.pool).await?;
+        let pages = rows.iter().map(PageRepository::page_from_row).collect::<Result<Vec<_>>>()?;
+        Ok(PagePage::from_rows(pages, filter))
+    }
+
+    async fn get_language_distribution(&self) -> Result<Vec<(String, i64)>> {
+        let rows = sqlx::query_as::<_, (String, i64)>(
+            "SELECT COALESCE(language, 'en') AS lang, COUNT(*) FROM pages GROUP BY lang ORDER BY COUNT(*) DESC, lang"
+        )
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(rows)
+    }
+
+    async fn get_all_links(&self) -> Result<Vec<(String, String)>> {
+        let sql = r#"
+            SELECT DISTINCT p1.url as source_url, l.target_url as target_url
+            FROM links l
+            INNER JOIN pages p1 ON l.source_page_id = p1.id
+            INNER JOIN pages p2 ON l.target_url = p2.url
+        "#;
+
+        let rows = sqlx::query(sql)
+            .fetch_all(&self.pool)
+            .await?;
+
+        let links = rows.iter()
+            .map(|row| (row.get("source_url"), row.get("target_url")))
+            .collect();
+
+        Ok(links)
+    }
+
+    async fn get_outbound_links(&self, url: &str, limit: usize) -> Result<Vec<String>> {
+        let url_hash = PageRepository::calculate_url_hash(url);
+
+        let links = sqlx::query_scalar(r#"
+            SELECT l.target_url
+            FROM links l
+            INNER JOIN pages p ON l.source_page_id = p.id
+            WHERE p.url_hash = ?
+            GROUP BY l.target_url
+            ORDER BY MIN(l.link_position), l.target_url
+            LIMIT ?
+        "#)
+            .bind(&url_hash)
+            .bind(limit as i64)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(links)
+    }
+
+    async fn get_inbound_links(&self, url: &str, limit: usize) -> Result<Vec<StoredPageSummary>> {
+        let pages = sqlx::query_as::<_, StoredPageSummary>(r#"
+            SELECT DISTINCT p.id, p.url, p.title, p.quality_score, p.pagerank
+            FROM links l
+            INNER JOIN pages p ON l.source_page_id = p.id
+            WHERE l.target_url = ? OR l.target_url = ?
+            ORDER BY p.pagerank DESC NULLS LAST, p.id
+            LIMIT ?
+        "#)
+            .bind(url)
+            .bind(canonicalize(url))
+            .bind(limit as i64)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(pages)
+    }
+
+    async fn update_pagerank(&self, url: &str, pagerank: f64) -> Result<()> {
+        let url_hash = PageRepository::calculate_url_hash(url);
+
+        sqlx::query("UPDATE pages SET pagerank = ? WHERE url_hash = ?")
+            .bind(pagerank)
+            .bind(&url_hash)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn batch_update_pagerank(&self, ranks: &[(String, f64)]) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+
+        for (url, rank) in ranks {
+            let url_hash = PageRepository::calculate_url_hash(url);
+            sqlx::query("UPDATE pages SET pagerank = ? WHERE url_hash = ?")
+                .bind(rank)
+                .bind(url_hash)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        tx.commit().await?;
+
+        info!("Batch updated {} PageRank values", ranks.len());
+        Ok(())
+    }
+
+    async fn batch_update_personalized_pagerank(&self, ranks: &[(String, f64)]) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+
+        for (url, rank) in ranks {
+            let url_hash = PageRepository::calculate_url_hash(url);
+            sqlx::query("UPDATE pages SET personalized_pagerank = ? WHERE url_hash = ?")
+                .bind(rank)
+                .bind(url_hash)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        tx.commit().await?;
+
+        info!("Batch updated {} personalized PageRank values", ranks.len());
+        Ok(())
+    }
+
+    async fn batch_update_hits_scores(&self, scores: &[(String, f64, f64)]) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+
+        for (url, hub, authority) in scores {
+            let url_hash = PageRepository::calculate_url_hash(url);
+            sqlx::query("UPDATE pages SET hub_score = ?, authority_score = ? WHERE url_hash = ?")
+                .bind(hub)
+                .bind(authority)
+                .bind(url_hash)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        tx.commit().await?;
+
+        info!("Batch updated {} HITS scores", scores.len());
+        Ok(())
+    }
+
+    async fn get_top_pages_by_pagerank(&self, limit: usize) -> Result<Vec<StoredPage>> {
+        let query = format!(
+            "SELECT {} FROM pages WHERE pagerank IS NOT NULL ORDER BY pagerank DESC LIMIT ?",
+            PAGE_COLUMNS
+        );
+
+        let rows = sqlx::query(&query)
+            .bind(limit as i64)
+            .fetch_all(&self.pool)
+            .await?;
+
+        rows.iter().map(PageRepository::page_from_row).collect()
+    }
+
+    async fn find_near_duplicates(&self, url: &str, threshold: u32) -> Result<Vec<StoredPage>> {
+        let url_hash = PageRepository::calculate_url_hash(url);
+
+        let simhash: Option<i64> = sqlx::query_scalar("SELECT simhash FROM pages WHERE url_hash = ?")
+            .bind(&url_hash)
+            .fetch_optional(&self.pool)
+            .await?
+            .flatten();
+
+        let Some(simhash) = simhash else {
+            return Ok(Vec::new());
+        };
+
+        let bands = SimHashCalculator::bands(simhash as u64);
+        let query = format!(
+            "SELECT {} FROM pages WHERE url_hash <> ? AND simhash IS NOT NULL \
+             AND (((simhash >> 48) & 65535) = ? OR ((simhash >> 32) & 65535) = ? \
+             OR ((simhash >> 16) & 65535) = ? OR (simhash & 65535) = ?)",
+            PAGE_COLUMNS
+        );
+
+        let rows = sqlx::query(&query)
+            .bind(&url_hash)
+            .bind(bands[0] as i64)
+            .bind(bands[1] as i64)
+            .bind(bands[2] as i64)
+            .bind(bands[3] as i64)
+            .fetch_all(&self.pool)
+            .await?;
+        let candidates = rows.iter().map(PageRepository::page_from_row).collect::<Result<Vec<_>>>()?;
+
+        Ok(candidates
+            .into_iter()
+            .filter(|p| {
+                p.simhash.is_some_and(|other| {
+                    SimHashCalculator::are_near_duplicates(simhash as u64, other as u64, threshold)
+                })
+            })
+            .collect())
+    }
+
+    // Like PageRepository::search_pages, compressed pages are decompressed and checked here
+    async fn search_pages(&self, q: &str, limit: usize) -> Result<Vec<StoredPage>> {
+        let like = format!("%{}%", q);
+        let query = format!(
+            "SELECT {}, (title LIKE ?1 OR description LIKE ?1 OR content LIKE ?1) AS matched FROM pages \
+             WHERE title LIKE ?1 OR description LIKE ?1 OR content LIKE ?1 OR content_compressed \
+             ORDER BY quality_score DESC",
+            PAGE_COLUMNS
+        );
+
+        let mut pages = Vec::new();
+        let mut rows = sqlx::query(&query).bind(&like).fetch(&self.pool);
+        while pages.len() < limit
+            && let Some(row) = rows.next().await.transpose()?
+        {
+            let page = PageRepository::page_from_row(&row)?;
+            if row.get::<Option<bool>, _>("matched").unwrap_or(false) || page.content.contains(q) {
+                pages.push(page);
+            }
+        }
+
+        Ok(pages)
+    }
+
+    async fn batch_save_pages(&self, pages: &[PageData], _session_id: i64) -> Result<Vec<i64>> {
+        let mut tx = self.pool.begin().await?;
+        let mut ids = Vec::with_capacity(pages.len());
+
+        for page in pages {
+            let (id, _, _) = self.upsert_page(&mut *tx, page).await?;
+            ids.push(id);
+        }
+
+        tx.commit().await?;
+        info!("Batch saved {} pages", ids.len());
+        Ok(ids)
+    }
+
+    async fn update_tfidf_score(&self, url_hash: &str, tfidf: f64) -> Result<()> {
+        sqlx::query("UPDATE pages SET tfidf_score = ? WHERE url_hash = ?")
+            .bind(tfidf)
+            .bind(url_hash)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    async fn delete_page(&self, url: &str) -> Result<()> {
+        self.delete_pages_where("url_hash = ?1", PageRepository::calculate_url_hash(url)).await?;
+        Ok(())
+    }
+
+    async fn delete_pages_by_domain(&self, domain: &str) -> Result<u64> {
+        self.delete_pages_where("domain = ?1", domain.to_string()).await
+    }
+
+    async fn purge_stale_pages(&self, older_than: DateTime<Utc>) -> Result<u64> {
+        self.delete_pages_where("crawled_at < ?1", older_than).await
+    }
+
+    async fn create_crawl_session(
+        &self,
+        seed_urls: &[String],
+        config: &crate::config::CrawlerConfig,
+    ) -> Result<i64> {
+        let session = CrawlSession::new(seed_urls, config)?;
+
+        let row = sqlx::query(
+            "INSERT INTO crawl_sessions (started_at, seed_urls, config_snapshot, status) \
+             VALUES (?, ?, ?, ?) RETURNING id",
+        )
+            .bind(session.started_at)
+            .bind(&session.seed_urls)
+            .bind(&session.config_snapshot)
+            .bind(&session.status)
+            .fetch_one(&self.pool)
+            .await?;
+
+        Ok(row.get("id"))
+    }
+
+    async fn update_crawl_session(&self, session_id: i64, crawled: i32, failed: i32) -> Result<()> {
+        sqlx::query("UPDATE crawl_sessions SET pages_crawled = ?, pages_failed = ? WHERE id = ?")
+            .bind(crawled)
+            .bind(failed)
+            .bind(session_id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    async fn complete_crawl_session(&self, session_id: i64, status: &str) -> Result<()> {
+        sqlx::query("UPDATE crawl_sessions SET ended_at = CURRENT_TIMESTAMP, status = ? WHERE id = ?")
+            .bind(status)
+            .bind(session_id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    async fn get_stats(&self) -> Result<DatabaseStats> {
+        let row = sqlx::query(r#"
+            SELECT
+                (SELECT COUNT(*) FROM pages) as total_pages,
+                (SELECT COUNT(*) FROM links) as total_links,
+                (SELECT COUNT(*) FROM domains) as total_domains,
+                (SELECT AVG(quality_score) FROM pages WHERE quality_score > 0) as avg_quality_score,
+                (SELECT COUNT(*) FROM crawl_sessions) as crawl_sessions,
+                (SELECT page_count * page_size FROM pragma_page_count(), pragma_page_size()) as size_bytes
+        "#)
+            .fetch_one(&self.pool)
+            .await?;
+
+        let size_bytes: i64 = row.get("size_bytes");
+
+        Ok(DatabaseStats {
+            total_pages: row.get("total_pages"),
+            total_links: row.get("total_links"),
+            total_domains: row.get("total_domains"),
+            avg_quality_score: row.get("avg_quality_score"),
+            crawl_sessions: row.get("crawl_sessions"),
+            database_size_mb: size_bytes as f64 / (1024.0 * 1024.0),
+        })
+    }
+
+    async fn save_cookies(&self, cookies: &[Cookie<'static>]) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+
+        let query = r#"
+            INSERT INTO cookies (domain, path, name, cookie_json, updated_at)
+            VALUES (?, ?, ?, ?, CURRENT_TIMESTAMP)
+            ON CONFLICT (domain, path, name) DO UPDATE SET
+                cookie_json = excluded.cookie_json,
+                updated_at = CURRENT_TIMESTAMP
+        "#;
+
+        for cookie in cookies {
+            let domain = cookie.domain.as_cow().map(|d| d.into_owned()).unwrap_or_default();
+            let cookie_json = serde_json::to_string(cookie)?;
+
+            sqlx::query(query)
+                .bind(domain)
+                .bind(cookie.path.as_ref() as &str)
+                .bind(cookie.name())
+                .bind(cookie_json)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        tx.commit().await?;
+
+        info!("Saved {} cookies", cookies.len());
+        Ok(())
+    }
+
+    async fn load_cookies(&self) -> Result<Vec<Cookie<'static>>> {
+        let rows = sqlx::query("SELECT cookie_json FROM cookies")
+            .fetch_all(&self.pool)
+            .await?;
+
+        let mut cookies = Vec::with_capacity(rows.len());
+        for row in rows {
+            let cookie_json: String = row.get("cookie_json");
+            cookies.push(serde_json::from_str(&cookie_json)?);
+        }
+
+        Ok(cookies)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::models::tests::page_data;
+    use chrono::{TimeZone, Utc};
+
+    async fn memory_backend() -> SqliteBackend {
+        let config = StorageSettings {
+            database_url: "sqlite::memory:".to_string(),
+            max_connections: 5,
+            enable_wal_mode: false,
+            ..StorageSettings::default()
+        };
+
+        let backend = SqliteBackend::connect(&config).await.unwrap();
+        backend.migrate().await.unwrap();
+        backend
+    }
+
+    fn page(url: &str, quality: f64) -> PageData {
+        PageData { content_quality_score: quality, ..page_data(url, "rust web crawler content") }
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_migrate_adds_cluster_id_once() {
+        let backend = memory_backend().await;
+        backend.migrate().await.unwrap();
+
+        let columns: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM pragma_table_info('pages') WHERE name = 'cluster_id'")
+            .fetch_one(&backend.pool)
+            .await
+            .unwrap();
+        assert_eq!(columns, 1);
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_rehashes_urls_hashed_before_canonicalization() {
+        let backend = memory_backend().await;
+        backend.save_page(&page("https://example.com/guide", 0.5), 0).await.unwrap();
+        backend.save_page(&page("https://example.com/other", 0.5), 0).await.unwrap();
+
+        // hashes of the urls as given, how pages were stored before
+        let legacy = [
+            ("https://example.com/guide", "HTTP://Example.com/guide#intro"),
+            ("https://example.com/other", "https://example.com/other"),
+        ];
+        for (url, spelling) in legacy {
+            sqlx::query("UPDATE pages SET url = ?, url_hash = ? WHERE url = ?")
+                .bind(spelling)
+                .bind(PageRepository::calculate_content_hash(spelling))
+                .bind(url)
+                .execute(&backend.pool)
+                .await
+                .unwrap();
+        }
+        sqlx::query("DELETE FROM data_migrations").execute(&backend.pool).await.unwrap();
+        assert!(backend.get_page_by_url("https://example.com/guide").await.unwrap().is_none());
+
+        backend.migrate().await.unwrap();
+        let stored = backend.get_page_by_url("https://example.com/guide").await.unwrap().unwrap();
+        assert_eq!(stored.url, "HTTP://Example.com/guide#intro");
+        assert!(backend.get_page_by_url("https://example.com/other").await.unwrap().is_some());
+
+        // a page stored under a spelling of a url already stored keeps its hash
+        backend.save_page(&page("https://example.com/dup", 0.5), 0).await.unwrap();
+        let spelling = "https://example.com/dup#top";
+        let legacy_hash = PageRepository::calculate_content_hash(spelling);
+        sqlx::query("INSERT INTO pages (url, url_hash, domain, content_hash) VALUES (?, ?, 'example.com', '')")
+            .bind(spelling)
+            .bind(&legacy_hash)
+            .execute(&backend.pool)
+            .await
+            .unwrap();
+        sqlx::query("DELETE FROM data_migrations").execute(&backend.pool).await.unwrap();
+        backend.migrate().await.unwrap();
+        let kept: String = sqlx::query_scalar("SELECT url_hash FROM pages WHERE url = ?")
+            .bind(spelling)
+            .fetch_one(&backend.pool)
+            .await
+            .unwrap();
+        assert_eq!(kept, legacy_hash);
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_cached_headers() {
+        let backend = memory_backend().await;
+
+        backend.save_page(&page("https://example.com/plain", 0.5), 0).await.unwrap();
+        assert!(backend.get_cached_headers("https://example.com/plain").await.unwrap().is_none());
+        assert!(backend.get_cached_headers("https://example.com/missing").await.unwrap().is_none());
+
+        let last_modified = Utc.with_ymd_and_hms(2024, 3, 1, 12, 0, 0).unwrap();
+        let mut cached = page("https://example.com/cached", 0.5);
+        cached.etag = Some("\"v1\"".to_string());
+        cached.last_modified = Some(last_modified);
+        backend.save_page(&cached, 0).await.unwrap();
+
+        let headers = backend.get_cached_headers("http://example.com/cached#top").await.unwrap().unwrap();
+        assert_eq!(headers.etag.as_deref(), Some("\"v1\""));
+        assert_eq!(headers.last_modified, Some(last_modified));
+
+        let stored = backend.get_page_by_url("https://example.com/cached").await.unwrap().unwrap();
+        assert_eq!(stored.etag.as_deref(), Some("\"v1\""));
+        assert_eq!(stored.last_modified, Some(last_modified));
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_open_graph_round_trip() {
+        let backend = memory_backend().await;
+
+        let mut tagged = page("https://example.com/og", 0.5);
+        tagged.og_image = Some("https://example.com/og.png".to_string());
+        tagged.og_type = Some("article".to_string());
+        backend.save_page(&tagged, 0).await.unwrap();
+
+        let stored = backend.get_page_by_url("https://example.com/og").await.unwrap().unwrap();
+        assert_eq!(stored.og_image.as_deref(), Some("https://example.com/og.png"));
+        assert_eq!(stored.to_page_data().og_type.as_deref(), Some("article"));
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_canonical_url_dedup() {
+        let backend = memory_backend().await;
+
+        for crawled in ["http://www.example.com/post/", "https://example.com/post?utm_source=feed"] {
+            let mut variant = page(crawled, 0.5);
+            variant.canonical_url = Some("https://example.com/post".to_string());
+            backend.save_page(&variant, 0).await.unwrap();
+        }
+
+        let stats = backend.get_stats().await.unwrap();
+        assert_eq!(stats.total_pages, 1);
+        let stored = backend.get_page_by_url("https://example.com/post").await.unwrap().unwrap();
+        assert_eq!(stored.url, "https://example.com/post");
+
+        let aliases: Vec<String> = sqlx::query_scalar("SELECT alias_url FROM url_aliases WHERE page_id = ? ORDER BY alias_url")
+            .bind(stored.id)
+            .fetch_all(&backend.pool)
+            .await
+            .unwrap();
+        assert_eq!(aliases, vec!["https://www.example.com/post"]);
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_inbound_and_outbound_links() {
+        let backend = memory_backend().await;
+
+        let a = backend.save_page(&page("https://example.com/a", 0.9), 0).await.unwrap().id;
+        let b = backend.save_page(&page("https://example.com/b", 0.4), 0).await.unwrap().id;
+        backend.save_page(&page("https://example.com/c", 0.4), 0).await.unwrap();
+        backend.batch_update_pagerank(&[("https://example.com/b".to_string(), 0.7)]).await.unwrap();
+
+        let link = |url: &str| CrawlUrl { url: url.to_string(), priority: 1.0, depth: 1, discovered_at: 0 };
+        backend.save_links(a, &[link("https://example.com/c"), link("https://example.com/b")]).await.unwrap();
+        backend.save_links(b, &[link("https://example.com/c")]).await.unwrap();
+
+        let outbound = backend.get_outbound_links("https://example.com/a", 10).await.unwrap();
+        assert_eq!(outbound, vec!["https://example.com/c", "https://example.com/b"]);
+        assert_eq!(backend.get_outbound_links("https://example.com/a", 1).await.unwrap().len(), 1);
+        assert!(backend.get_outbound_links("https://example.com/c", 10).await.unwrap().is_empty());
+
+        let inbound = backend.get_inbound_links("http://example.com/c", 10).await.unwrap();
+        let ids: Vec<i64> = inbound.iter().map(|p| p.id).collect();
+        assert_eq!(ids, vec![b, a]);
+        assert_eq!(inbound[0].pagerank, Some(0.7));
+        assert_eq!(inbound[0].title.as_deref(), Some("Test Page"));
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_page_round_trip() {
+        let backend = memory_backend().await;
+
+        let a = backend.save_page(&page("https://example.com/a", 0.9), 0).await.unwrap().id;
+        backend.save_page(&page("https://example.com/b", 0.4), 0).await.unwrap();
+
+        let link = CrawlUrl {
+            url: "https://example.com/b".to_string(),
+            priority: 1.0,
+            depth: 1,
+            discovered_at: 0,
+        };
+        backend.save_links(a, &[link]).await.unwrap();
+
+        assert!(backend.url_exists("https://example.com/a").await.unwrap());
+        assert!(!backend.url_exists("https://example.com/c").await.unwrap());
+
+        let stored = backend.get_page_by_url("https://example.com/a").await.unwrap().unwrap();
+        assert_eq!(stored.id, a);
+        assert_eq!(stored.domain, "example.com");
+
+        let filtered = backend.get_pages(&PageFilter::new().with_min_quality(0.5)).await.unwrap();
+        assert_eq!(filtered.items.len(), 1);
+        assert!(!filtered.has_more);
+
+        // Walk both pages one at a time with the cursor
+        let first = backend.get_pages(&PageFilter::new().with_cursor(0).with_limit(1)).await.unwrap();
+        assert_eq!(first.items[0].id, a);
+        assert!(first.has_more);
+        assert_eq!(first.next_cursor, Some(a));
+
+        let second = backend.get_pages(&PageFilter::new().with_cursor(a).with_limit(1)).await.unwrap();
+        assert_eq!(second.items.len(), 1);
+        assert!(!second.has_more);
+        assert_eq!(second.next_cursor, None);
+
+        backend.update_pagerank("https://example.com/b", 0.75).await.unwrap();
+        let top = backend.get_top_pages_by_pagerank(1).await.unwrap();
+        assert_eq!(top[0].url, "https://example.com/b");
+
+        backend.batch_update_personalized_pagerank(&[("https://example.com/a".to_string(), 0.6)]).await.unwrap();
+        let stored = backend.get_page_by_url("https://example.com/a").await.unwrap().unwrap();
+        assert_eq!(stored.personalized_pagerank, Some(0.6));
+        assert_eq!(stored.pagerank, Some(0.0));
+
+        let links = backend.get_all_links().await.unwrap();
+        assert_eq!(links, vec![("https://example.com/a".to_string(), "https://example.com/b".to_string())]);
+
+        let stats = backend.get_stats().await.unwrap();
+        assert_eq!(stats.total_pages, 2);
+        assert_eq!(stats.total_links, 1);
+        assert_eq!(stats.total_domains, 1);
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_domain_stats_are_kept_per_host() {
+        let backend = memory_backend().await;
+        backend.save_page(&page("https://www.example.co.uk/a", 0.8), 0).await.unwrap();
+        backend.save_page(&page("https://www.example.co.uk/b", 0.4), 0).await.unwrap();
+
+        // pages group by registrable domain, the domains row shares the robots.txt key
+        let stored = backend.get_page_by_url("https://www.example.co.uk/a").await.unwrap().unwrap();
+        assert_eq!(stored.domain, "example.co.uk");
+        let (page_count, avg_quality): (i64, f64) =
+            sqlx::query_as("SELECT page_count, avg_quality_score FROM domains WHERE domain = 'www.example.co.uk'")
+                .fetch_one(&backend.pool)
+                .await
+                .unwrap();
+        assert_eq!(page_count, 2);
+        assert!((avg_quality - 0.6).abs() < 1e-9);
+
+        backend.delete_page("https://www.example.co.uk/a").await.unwrap();
+        let page_count: i64 = sqlx::query_scalar("SELECT page_count FROM domains WHERE domain = 'www.example.co.uk'")
+            .fetch_one(&backend.pool)
+            .await
+            .unwrap();
+        assert_eq!(page_count, 1);
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_delete_pages() {
+        let backend = memory_backend().await;
+        let page_count = |domain: &'static str| {
+            let pool = backend.pool.clone();
+            async move {
+                sqlx::query_scalar::<_, i64>("SELECT page_count FROM domains WHERE domain = ?")
+                    .bind(domain)
+                    .fetch_one(&pool)
+                    .await
+                    .unwrap()
+            }
+        };
+
+        let a = backend.save_page(&page("https://example.com/a", 0.9), 0).await.unwrap().id;
+        backend.save_page(&page("https://example.com/b", 0.4), 0).await.unwrap();
+        let mut stale = page("https://other.org/old", 0.5);
+        stale.crawled_at = Utc.with_ymd_and_hms(2020, 1, 1, 0, 0, 0).unwrap();
+        backend.save_page(&stale, 0).await.unwrap();
+        backend.save_page(&page("https://other.org/new", 0.5), 0).await.unwrap();
+
+        let link = CrawlUrl {
+            url: "https://example.com/b".to_string(),
+            priority: 1.0,
+            depth: 1,
+            discovered_at: 0,
+        };
+        backend.save_links(a, &[link]).await.unwrap();
+
+        backend.delete_page("http://example.com/a#top").await.unwrap();
+        assert!(!backend.url_exists("https://example.com/a").await.unwrap());
+        assert!(backend.get_all_links().await.unwrap().is_empty());
+        assert!(backend.get_page_history("https://example.com/a").await.unwrap().is_empty());
+        assert_eq!(page_count("example.com").await, 1);
+
+        // deleting a page that isn't stored is a no-op
+        backend.delete_page("https://example.com/missing").await.unwrap();
+
+        let cutoff = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        assert_eq!(backend.purge_stale_pages(cutoff).await.unwrap(), 1);
+        assert!(!backend.url_exists("https://other.org/old").await.unwrap());
+        assert_eq!(page_count("other.org").await, 1);
+
+        assert_eq!(backend.delete_pages_by_domain("other.org").await.unwrap(), 1);
+        assert_eq!(page_count("other.org").await, 0);
+        assert_eq!(backend.get_stats().await.unwrap().total_pages, 1);
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_page_versioning() {
+        let backend = memory_backend().await;
+        let url = "https://example.com/a";
+
+        let first = backend.save_page(&page(url, 0.5), 0).await.unwrap();
+        assert!(first.changed);
+        assert_eq!(first.version, 1);
+
+        // Same content again is not a new version
+        let same = backend.save_page(&page(url, 0.5), 0).await.unwrap();
+        assert_eq!(same.id, first.id);
+        assert!(!same.changed);
+        assert_eq!(same.version, 1);
+
+        let mut updated = page(url, 0.5);
+        updated.content = "rust web crawler content, now longer".to_string();
+        let second = backend.save_page(&updated, 0).await.unwrap();
+        assert!(second.changed);
+        assert_eq!(second.version, 2);
+
+        let history = backend.get_page_history(url).await.unwrap();
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[1].diff_size_bytes, 12);
+
+        let original = backend.get_page_at_version(url, 1).await.unwrap().unwrap();
+        assert_eq!(original.content, "rust web crawler content");
+        assert!(backend.get_page_at_version(url, 3).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_compressed_content_round_trip() {
+        let mut backend = memory_backend().await;
+        backend.compress_content = true;
+        let url = "https://example.com/compressed";
+
+        backend.save_page(&page(url, 0.5), 0).await.unwrap();
+        let mut updated = page(url, 0.5);
+        updated.content = "rust web crawler content, now longer".to_string();
+        let second = backend.save_page(&updated, 0).await.unwrap();
+        assert_eq!(second.version, 2);
+
+        let (content, compressed): (String, bool) = sqlx::query_as("SELECT content, content_compressed FROM pages WHERE url = ?")
+            .bind(url)
+            .fetch_one(&backend.pool)
+            .await
+            .unwrap();
+        assert!(content.is_empty());
+        assert!(compressed);
+
+        let stored = backend.get_page_by_url(url).await.unwrap().unwrap();
+        assert_eq!(stored.content, "rust web crawler content, now longer");
+        assert_eq!(backend.get_page_history(url).await.unwrap()[1].diff_size_bytes, 12);
+
+        let original = backend.get_page_at_version(url, 1).await.unwrap().unwrap();
+        assert_eq!(original.content, "rust web crawler content");
+
+        let found = backend.search_pages("now longer", 10).await.unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].url, url);
+        assert!(backend.search_pages("missing words", 10).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_find_near_duplicates() {
+        let backend = memory_backend().await;
+
+        let mut original = page("https://example.com/article", 0.5);
+        original.content = "Rust is a systems programming language focused on safety, speed and concurrency. \
+                            It accomplishes these goals without a garbage collector."
+            .to_string();
+        let mut mirror = original.clone();
+        mirror.url = "https://mirror.example.org/article".to_string();
+        let mut other = page("https://example.com/bread", 0.5);
+        other.content = "Sourdough bread needs flour, water, salt and a lively starter.".to_string();
+
+        for p in [&original, &mirror, &other] {
+            backend.save_page(p, 0).await.unwrap();
+        }
+
+        let duplicates = backend.find_near_duplicates(&original.url, 4).await.unwrap();
+        assert_eq!(duplicates.len(), 1);
+        assert_eq!(duplicates[0].url, mirror.url);
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_language_filter_and_distribution() {
+        let backend = memory_backend().await;
+
+        let mut tamil = page("https://example.com/ta", 0.6);
+        tamil.content = "தமிழ் ஒரு பழமையான மொழி ஆகும். இது இந்தியாவிலும் இலங்கையிலும் பேசப்படுகிறது.".to_string();
+        backend.save_page(&tamil, 0).await.unwrap();
+        backend.save_page(&page("https://example.com/en1", 0.5), 0).await.unwrap();
+        backend.save_page(&page("https://example.com/en2", 0.4), 0).await.unwrap();
+
+        let tamil_pages = backend.get_pages(&PageFilter::new().with_language("ta")).await.unwrap().items;
+        assert_eq!(tamil_pages.len(), 1);
+        assert_eq!(tamil_pages[0].url, "https://example.com/ta");
+
+        let distribution = backend.get_language_distribution().await.unwrap();
+        assert_eq!(distribution, vec![("en".to_string(), 2), ("ta".to_string(), 1)]);
+    }
+}
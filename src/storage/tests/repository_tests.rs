@@ -25,12 +25,20 @@ mod tests {
             outgoing_links: vec![],
             word_count: 5,
             content_quality_score: 0.8,
+            quality_factors: Vec::new(),
             crawled_at: Utc::now(),
             depth: 1,
+            etag: None,
+            last_modified: None,
+            feed_urls: vec![],
+            og_image: None,
+            og_type: None,
+            screenshot_path: None,
+            canonical_url: None,
         };
 
         // Save page
-        let page_id = repo.save_page(&page_data, 1).await.unwrap();
+        let page_id = repo.save_page(&page_data, 1).await.unwrap().id;
         assert!(page_id > 0);
 
         // Get page by ID
@@ -63,8 +71,16 @@ mod tests {
             outgoing_links: vec![],
             word_count: 7,
             content_quality_score: 0.9,
+            quality_factors: Vec::new(),
             crawled_at: Utc::now(),
             depth: 1,
+            etag: None,
+            last_modified: None,
+            feed_urls: vec![],
+            og_image: None,
+            og_type: None,
+            screenshot_path: None,
+            canonical_url: None,
         };
 
         let page2 = PageData {
@@ -76,8 +92,16 @@ mod tests {
             outgoing_links: vec![],
             word_count: 7,
             content_quality_score: 0.8,
+            quality_factors: Vec::new(),
             crawled_at: Utc::now(),
             depth: 1,
+            etag: None,
+            last_modified: None,
+            feed_urls: vec![],
+            og_image: None,
+            og_type: None,
+            screenshot_path: None,
+            canonical_url: None,
         };
 
         // Save pages
@@ -107,25 +131,33 @@ mod tests {
             outgoing_links: vec![],
             word_count: 3,
             content_quality_score: 0.7,
+            quality_factors: Vec::new(),
             crawled_at: Utc::now(),
             depth: 1,
+            etag: None,
+            last_modified: None,
+            feed_urls: vec![],
+            og_image: None,
+            og_type: None,
+            screenshot_path: None,
+            canonical_url: None,
         };
 
         repo.save_page(&page, 1).await.unwrap();
 
         // Filter by domain
         let filter = PageFilter::new().with_domain("github.com".to_string());
-        let results = repo.get_pages(&filter).await.unwrap();
+        let results = repo.get_pages(&filter).await.unwrap().items;
         assert_eq!(results.len(), 1);
         assert_eq!(results[0].domain, "github.com");
 
         // Filter by quality
         let filter = PageFilter::new().with_min_quality(0.8);
-        let results = repo.get_pages(&filter).await.unwrap();
+        let results = repo.get_pages(&filter).await.unwrap().items;
         assert_eq!(results.len(), 0); // Quality is 0.7, below threshold
 
         let filter = PageFilter::new().with_min_quality(0.6);
-        let results = repo.get_pages(&filter).await.unwrap();
+        let results = repo.get_pages(&filter).await.unwrap().items;
         assert_eq!(results.len(), 1);
     }
 
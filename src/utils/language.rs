@@ -0,0 +1,116 @@
+//! Content language detection for English and the Indian languages we index
+
+use lingua::{Language, LanguageDetector, LanguageDetectorBuilder};
+use std::sync::OnceLock;
+
+/// Below this the library guess is replaced by the Unicode block heuristic
+pub const MIN_LIBRARY_CONFIDENCE: f64 = 0.7;
+
+// a secondary language has to cover at least this share of the text
+const MIN_SECONDARY_SHARE: f64 = 0.2;
+
+// enough text for a stable guess without scoring whole pages
+const MAX_SAMPLE_CHARS: usize = 2000;
+
+static DETECTOR: OnceLock<LanguageDetector> = OnceLock::new();
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LanguageDetectionResult {
+    /// ISO 639-1 code
+    pub primary: &'static str,
+    pub confidence: f64,
+    /// Runner-up, either a close second guess or a notable share of a mixed-language text
+    pub secondary: Option<&'static str>,
+}
+
+/// Detect the language of `text`, falling back to script analysis when the
+/// statistical model is unsure or doesn't know the language (Kannada, Malayalam)
+pub fn detect_language(text: &str) -> LanguageDetectionResult {
+    let sample: String = text.chars().take(MAX_SAMPLE_CHARS).collect();
+    let script = detect_by_script(&sample);
+
+    let detector = DETECTOR.get_or_init(|| {
+        LanguageDetectorBuilder::from_languages(&[
+            Language::English,
+            Language::Hindi,
+            Language::Marathi,
+            Language::Tamil,
+            Language::Telugu,
+        ])
+        .build()
+    });
+
+    let ranked = detector.compute_language_confidence_values(sample.as_str());
+    let Some(&(language, confidence)) = ranked.first() else {
+        return script;
+    };
+
+    if confidence < MIN_LIBRARY_CONFIDENCE {
+        return script;
+    }
+
+    let primary = iso_code(language);
+    let secondary = ranked.get(1)
+        .filter(|(_, confidence)| *confidence >= MIN_SECONDARY_SHARE)
+        .map(|(language, _)| iso_code(*language))
+        .or(script.secondary)
+        .filter(|code| *code != primary);
+
+    LanguageDetectionResult { primary, confidence, secondary }
+}
+
+fn iso_code(language: Language) -> &'static str {
+    match language {
+        Language::Hindi => "hi",
+        Language::Marathi => "mr",
+        Language::Tamil => "ta",
+        Language::Telugu => "te",
+        _ => "en",
+    }
+}
+
+// Scripts in the order of their counts: [latin, devanagari, kannada, tamil, telugu, malayalam]
+const SCRIPT_LANGUAGES: [&str; 6] = ["en", "hi", "kn", "ta", "te", "ml"];
+
+/// Unicode block heuristic: the language whose script has the most letters.
+/// Devanagari is Hindi unless the text names Marathi or Maharashtra.
+pub fn detect_by_script(text: &str) -> LanguageDetectionResult {
+    let mut counts = [0u32; 6];
+    for c in text.chars().filter(|c| c.is_alphabetic() || ('\u{0900}'..='\u{0D7F}').contains(c)) {
+        match c as u32 {
+            0x0000..=0x024F => counts[0] += 1,
+            0x0900..=0x097F => counts[1] += 1,
+            0x0C80..=0x0CFF => counts[2] += 1,
+            0x0B80..=0x0BFF => counts[3] += 1,
+            0x0C00..=0x0C7F => counts[4] += 1,
+            0x0D00..=0x0D7F => counts[5] += 1,
+            _ => {}
+        }
+    }
+
+    let total: u32 = counts.iter().sum();
+    if total == 0 {
+        return LanguageDetectionResult { primary: "en", confidence: 0.0, secondary: None };
+    }
+
+    let mut ranked: Vec<usize> = (0..counts.len()).collect();
+    ranked.sort_by(|a, b| counts[*b].cmp(&counts[*a]).then(a.cmp(b)));
+
+    let language = |idx: usize| {
+        if idx == 1 && (text.contains("मराठी") || text.contains("महाराष्ट्र")) {
+            "mr"
+        } else {
+            SCRIPT_LANGUAGES[idx]
+        }
+    };
+
+    let secondary = Some(ranked[1])
+        .filter(|idx| counts[*idx] as f64 / total as f64 >= MIN_SECONDARY_SHARE)
+        .map(language);
+
+    LanguageDetectionResult {
+        primary: language(ranked[0]),
+        confidence: counts[ranked[0]] as f64 / total as f64,
+        secondary,
+    }
+}
@@ -1,5 +1,170 @@
-pub async fn init_metrics() -> Result<(), Box<dyn std::error::Error + Send + Sync>>{
-    // placeholder for metrics initialization
-    // add prometheus metrics here
-    Ok(())
-}
\ No newline at end of file
+//! Prometheus metrics for the crawler, search cache, indexer and database
+
+use prometheus::{Encoder, Histogram, HistogramOpts, IntCounter, IntGauge, Registry, TextEncoder};
+use serde::{Deserialize, Serialize};
+use std::sync::OnceLock;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+static GLOBAL: OnceLock<MetricsHandle> = OnceLock::new();
+
+/// Cheap to clone handle to a set of registered metrics, clones share the same values
+#[derive(Clone)]
+pub struct MetricsHandle {
+    registry: Registry,
+    pub pages_crawled_total: IntCounter,
+    pub pages_failed_total: IntCounter,
+    pub urls_discovered_total: IntCounter,
+    pub active_workers: IntGauge,
+    pub queue_depth: IntGauge,
+    pub cache_hits_total: IntCounter,
+    pub cache_misses_total: IntCounter,
+    pub index_documents_total: IntCounter,
+    pub db_query_duration_seconds: Histogram,
+}
+
+impl MetricsHandle {
+    /// Metrics in a fresh registry, independent of the process-wide one
+    pub fn new() -> prometheus::Result<Self> {
+        let registry = Registry::new();
+
+        let counter = |name: &str, help: &str| -> prometheus::Result<IntCounter> {
+            let counter = IntCounter::new(name, help)?;
+            registry.register(Box::new(counter.clone()))?;
+            Ok(counter)
+        };
+        let gauge = |name: &str, help: &str| -> prometheus::Result<IntGauge> {
+            let gauge = IntGauge::new(name, help)?;
+            registry.register(Box::new(gauge.clone()))?;
+            Ok(gauge)
+        };
+
+        let db_query_duration_seconds = Histogram::with_opts(
+            HistogramOpts::new("db_query_duration_seconds", "Database query latency in seconds")
+                .buckets(vec![0.001, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5]),
+        )?;
+        registry.register(Box::new(db_query_duration_seconds.clone()))?;
+
+        Ok(Self {
+            pages_crawled_total: counter("pages_crawled_total", "Pages fetched and processed successfully")?,
+            pages_failed_total: counter("pages_failed_total", "Pages that failed to crawl")?,
+            urls_discovered_total: counter("urls_discovered_total", "New URLs added to the frontier")?,
+            active_workers: gauge("active_workers", "Workers currently crawling a page")?,
+            queue_depth: gauge("queue_depth", "URLs waiting in the frontier")?,
+            cache_hits_total: counter("cache_hits_total", "Search result cache hits")?,
+            cache_misses_total: counter("cache_misses_total", "Search result cache misses")?,
+            index_documents_total: counter("index_documents_total", "Documents added to the search index")?,
+            db_query_duration_seconds,
+            registry,
+        })
+    }
+
+    /// The process-wide metrics, created on first use
+    pub fn global() -> Self {
+        GLOBAL
+            .get_or_init(|| Self::new().expect("metric definitions are valid"))
+            .clone()
+    }
+
+    pub fn observe_db_query(&self, elapsed: Duration) {
+        self.db_query_duration_seconds.observe(elapsed.as_secs_f64());
+    }
+
+    /// All metrics in the Prometheus text exposition format
+    pub fn render(&self) -> String {
+        let mut buffer = Vec::new();
+        if let Err(e) = TextEncoder::new().encode(&self.registry.gather(), &mut buffer) {
+            tracing::warn!("Failed to encode metrics: {}", e);
+        }
+        String::from_utf8(buffer).unwrap_or_default()
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        prometheus::TEXT_FORMAT
+    }
+}
+
+/// In-process counters of a single crawl, read back into its `CrawlStatistics`
+#[derive(Debug, Default)]
+pub struct CrawlMetrics {
+    pages_crawled: AtomicU64,
+    pages_failed: AtomicU64,
+    bytes_downloaded: AtomicU64,
+    total_fetch_time_ms: AtomicU64,
+    cache_hits: AtomicU64,
+    cache_misses: AtomicU64,
+}
+
+/// Point-in-time copy of `CrawlMetrics`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct MetricsSnapshot {
+    pub pages_crawled: u64,
+    pub pages_failed: u64,
+    pub bytes_downloaded: u64,
+    pub total_fetch_time_ms: u64,
+    pub cache_hits: u64,
+    pub cache_misses: u64,
+}
+
+impl CrawlMetrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record_page_crawled(&self, bytes: u64, fetch_time_ms: u64) {
+        self.pages_crawled.fetch_add(1, Ordering::Relaxed);
+        self.bytes_downloaded.fetch_add(bytes, Ordering::Relaxed);
+        self.total_fetch_time_ms.fetch_add(fetch_time_ms, Ordering::Relaxed);
+    }
+
+    pub fn record_page_failed(&self) {
+        self.pages_failed.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn record_cache_hit(&self) {
+        self.cache_hits.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn record_cache_miss(&self) {
+        self.cache_misses.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn pages_crawled(&self) -> u64 {
+        self.pages_crawled.load(Ordering::Relaxed)
+    }
+
+    pub fn snapshot(&self) -> MetricsSnapshot {
+        MetricsSnapshot {
+            pages_crawled: self.pages_crawled.load(Ordering::Relaxed),
+            pages_failed: self.pages_failed.load(Ordering::Relaxed),
+            bytes_downloaded: self.bytes_downloaded.load(Ordering::Relaxed),
+            total_fetch_time_ms: self.total_fetch_time_ms.load(Ordering::Relaxed),
+            cache_hits: self.cache_hits.load(Ordering::Relaxed),
+            cache_misses: self.cache_misses.load(Ordering::Relaxed),
+        }
+    }
+
+    pub fn reset(&self) {
+        for counter in [
+            &self.pages_crawled,
+            &self.pages_failed,
+            &self.bytes_downloaded,
+            &self.total_fetch_time_ms,
+            &self.cache_hits,
+            &self.cache_misses,
+        ] {
+            counter.store(0, Ordering::Relaxed);
+        }
+    }
+}
+
+impl MetricsSnapshot {
+    /// Mean fetch time of the crawled pages, 0 before the first one
+    pub fn avg_fetch_time_ms(&self) -> f64 {
+        if self.pages_crawled == 0 {
+            0.0
+        } else {
+            self.total_fetch_time_ms as f64 / self.pages_crawled as f64
+        }
+    }
+}
@@ -1,10 +1,13 @@
 mod logging;
-mod metrics;
+pub mod language;
+pub mod metrics;
 mod url_utils;
 mod bloom_filter;
 pub use url_utils::*;
 pub use bloom_filter::*;
 pub use logging::*;
+pub use metrics::{CrawlMetrics, MetricsHandle, MetricsSnapshot};
+pub use language::{detect_language, LanguageDetectionResult};
 
 // Utility functions for the crawler
 
@@ -31,11 +34,10 @@ pub fn init_logging() -> crate::Result<()> {
     Ok(())
 }
 
-/// Initialize metrics collection (placeholder for future implementation)
+/// Initialize metrics collection, registering the process-wide Prometheus metrics
 pub async fn init_metrics() -> crate::Result<()> {
-    // Placeholder for future metrics implementation (Prometheus, etc.)
-    // For now, just return Ok
-    tracing::info!("Metrics system initialized (placeholder)");
+    MetricsHandle::global();
+    tracing::info!("Metrics system initialized");
     Ok(())
 }
 
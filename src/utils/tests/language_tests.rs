@@ -0,0 +1,54 @@
+use crate::utils::language::{detect_by_script, MIN_LIBRARY_CONFIDENCE};
+use crate::utils::detect_language;
+
+const HINDI: &str = "भारत एक विशाल देश है जिसकी संस्कृति बहुत पुरानी है। यहाँ अनेक भाषाएँ बोली जाती हैं और लोग मिलजुल कर रहते हैं। हर साल लाखों लोग इसकी सुंदरता देखने आते हैं।";
+const MARATHI: &str = "माझे नाव राहुल आहे. मी पुण्यात राहतो आणि मला पुस्तके वाचायला खूप आवडतात. आज आमच्या शाळेत मोठा कार्यक्रम होता आणि सगळे विद्यार्थी आनंदात होते.";
+const KANNADA: &str = "ಕನ್ನಡ ಕರ್ನಾಟಕ ರಾಜ್ಯದ ಅಧಿಕೃತ ಭಾಷೆಯಾಗಿದೆ. ಬೆಂಗಳೂರು ಕರ್ನಾಟಕದ ರಾಜಧಾನಿ ಮತ್ತು ದೊಡ್ಡ ನಗರ.";
+const TAMIL: &str = "தமிழ் உலகின் மிகப் பழமையான மொழிகளில் ஒன்றாகும். சென்னை தமிழ்நாட்டின் தலைநகரம் ஆகும்.";
+const TELUGU: &str = "తెలుగు భారతదేశంలో ఎక్కువగా మాట్లాడే భాషలలో ఒకటి. హైదరాబాద్ తెలంగాణ రాష్ట్ర రాజధాని.";
+const MALAYALAM: &str = "മലയാളം കേരളത്തിലെ ഔദ്യോഗിക ഭാഷയാണ്. തിരുവനന്തപുരം കേരളത്തിന്റെ തലസ്ഥാനമാണ്.";
+
+#[test]
+fn test_detects_indian_languages() {
+    for (text, expected) in [
+        (HINDI, "hi"),
+        (MARATHI, "mr"),
+        (KANNADA, "kn"),
+        (TAMIL, "ta"),
+        (TELUGU, "te"),
+        (MALAYALAM, "ml"),
+    ] {
+        let result = detect_language(text);
+        assert_eq!(result.primary, expected, "wrong language for {}", text);
+        assert!(result.confidence > 0.5);
+    }
+}
+
+#[test]
+fn test_detects_english() {
+    let result = detect_language("The crawler fetches pages, extracts their links and stores everything for search.");
+    assert_eq!(result.primary, "en");
+    assert!(result.confidence >= MIN_LIBRARY_CONFIDENCE);
+}
+
+#[test]
+fn test_mixed_page_reports_secondary_language() {
+    let text = format!("{} The festival is celebrated across the country with music and food.", HINDI);
+    let result = detect_language(&text);
+
+    assert_eq!(result.primary, "hi");
+    assert_eq!(result.secondary, Some("en"));
+}
+
+#[test]
+fn test_script_fallback() {
+    // Devanagari alone can't separate Hindi from Marathi without a hint
+    assert_eq!(detect_by_script(MARATHI).primary, "hi");
+    assert_eq!(detect_by_script("महाराष्ट्र राज्य").primary, "mr");
+    assert_eq!(detect_by_script(KANNADA).primary, "kn");
+    assert_eq!(detect_by_script(MALAYALAM).confidence, 1.0);
+
+    let empty = detect_language("1234 !!");
+    assert_eq!(empty.primary, "en");
+    assert_eq!(empty.confidence, 0.0);
+}
@@ -0,0 +1,66 @@
+use crate::utils::MetricsHandle;
+use std::time::Duration;
+
+#[test]
+fn test_metrics_render_all_series() {
+    let metrics = MetricsHandle::new().unwrap();
+    let output = metrics.render();
+
+    for name in [
+        "pages_crawled_total",
+        "pages_failed_total",
+        "urls_discovered_total",
+        "active_workers",
+        "queue_depth",
+        "cache_hits_total",
+        "cache_misses_total",
+        "index_documents_total",
+        "db_query_duration_seconds",
+    ] {
+        assert!(output.contains(&format!("# TYPE {} ", name)), "{} missing from output", name);
+    }
+}
+
+#[test]
+fn test_metrics_handles_are_independent() {
+    let metrics = MetricsHandle::new().unwrap();
+    let other = MetricsHandle::new().unwrap();
+
+    metrics.pages_crawled_total.inc_by(3);
+    metrics.queue_depth.set(42);
+    metrics.observe_db_query(Duration::from_millis(20));
+
+    // clones share values, separate handles don't
+    let output = metrics.clone().render();
+    assert!(output.contains("pages_crawled_total 3"));
+    assert!(output.contains("queue_depth 42"));
+    assert!(output.contains("db_query_duration_seconds_count 1"));
+    assert!(other.render().contains("pages_crawled_total 0"));
+}
+
+#[test]
+fn test_crawl_metrics_snapshot_and_reset() {
+    use crate::utils::CrawlMetrics;
+
+    let metrics = CrawlMetrics::new();
+    assert_eq!(metrics.snapshot().avg_fetch_time_ms(), 0.0);
+
+    metrics.record_page_crawled(1000, 100);
+    metrics.record_page_crawled(3000, 300);
+    metrics.record_page_failed();
+    metrics.record_cache_hit();
+    metrics.record_cache_miss();
+    metrics.record_cache_miss();
+
+    let snapshot = metrics.snapshot();
+    assert_eq!(snapshot.pages_crawled, 2);
+    assert_eq!(snapshot.pages_failed, 1);
+    assert_eq!(snapshot.bytes_downloaded, 4000);
+    assert_eq!(snapshot.total_fetch_time_ms, 400);
+    assert_eq!(snapshot.avg_fetch_time_ms(), 200.0);
+    assert_eq!(snapshot.cache_hits, 1);
+    assert_eq!(snapshot.cache_misses, 2);
+
+    metrics.reset();
+    assert_eq!(metrics.snapshot(), Default::default());
+}
@@ -4,3 +4,7 @@
 mod logging_tests;
 #[cfg(test)]
 mod url_utils_tests;
+#[cfg(test)]
+mod metrics_tests;
+#[cfg(test)]
+mod language_tests;
@@ -1,10 +1,102 @@
 #[cfg(test)]
 mod tests {
-    // If you have url_utils functions, test them here
+    use crate::utils::{canonicalize, canonicalize_with, extract_domain_info, registrable_domain, url_host};
 
     #[test]
-    fn test_placeholder() {
-        // Add actual URL utility tests when they exist
-        assert!(true);
+    fn test_canonicalize_collapses_variants() {
+        let variants = [
+            "http://example.com/page",
+            "https://example.com/page",
+            "https://example.com/page?utm_source=twitter",
+            "https://example.com/page#section",
+            "https://example.com/page/",
+            "https://EXAMPLE.com:443/page",
+            "http://example.com:80/page?fbclid=abc&utm_medium=social#top",
+        ];
+
+        for variant in variants {
+            assert_eq!(canonicalize(variant), "https://example.com/page", "{}", variant);
+        }
+    }
+
+    #[test]
+    fn test_canonicalize_sorts_and_keeps_real_params() {
+        assert_eq!(
+            canonicalize("https://example.com/search?q=rust&page=2&gclid=x"),
+            "https://example.com/search?page=2&q=rust"
+        );
+        assert_eq!(
+            canonicalize("https://example.com/search?page=2&q=rust"),
+            canonicalize("https://example.com/search?q=rust&page=2")
+        );
+    }
+
+    #[test]
+    fn test_canonicalize_keeps_root_and_non_default_ports() {
+        assert_eq!(canonicalize("http://example.com"), "https://example.com");
+        assert_eq!(canonicalize("https://example.com/"), "https://example.com");
+        assert_eq!(canonicalize("https://example.com/?b=2&a=1"), "https://example.com/?a=1&b=2");
+        assert_eq!(canonicalize("https://example.com:8443/a/"), "https://example.com:8443/a");
+    }
+
+    #[test]
+    fn test_canonicalize_custom_strip_params() {
+        let params = ["session*", "ref"];
+        assert_eq!(
+            canonicalize_with("https://example.com/?sessionid=1&ref=home&id=7&utm_source=x", &params),
+            "https://example.com/?id=7&utm_source=x"
+        );
+    }
+
+    #[test]
+    fn test_canonicalize_is_idempotent_and_tolerates_garbage() {
+        let once = canonicalize("http://example.com/a/b/?z=1&a=2#frag");
+        assert_eq!(canonicalize(&once), once);
+        assert_eq!(canonicalize("not a url"), "not a url");
+    }
+
+    #[test]
+    fn test_extract_domain_info_groups_by_registrable_domain() {
+        let info = extract_domain_info("https://blog.example.co.uk:8443/post").unwrap();
+        assert_eq!(info.host, "blog.example.co.uk");
+        assert_eq!(info.registrable_domain, "example.co.uk");
+        assert_eq!(info.public_suffix, "co.uk");
+        assert_eq!(info.port, Some(8443));
+
+        // default ports, missing schemes and trailing dots don't change the grouping
+        assert_eq!(extract_domain_info("https://WWW.Example.com:443/").unwrap().port, None);
+        for url in ["example.com/page", "example.com:8080", "http://www.example.com./a", "https://user:pw@docs.example.com"] {
+            assert_eq!(registrable_domain(url), "example.com", "{}", url);
+        }
+        assert_eq!(registrable_domain("user.github.io"), "user.github.io");
+        assert_eq!(registrable_domain("not a url"), "unknown");
+
+        assert_eq!(url_host("https://WWW.Example.co.uk:8443/a"), "www.example.co.uk");
+        assert_eq!(url_host("not a url"), "unknown");
+    }
+
+    #[test]
+    fn test_extract_domain_info_ip_localhost_and_idn() {
+        let ipv4 = extract_domain_info("http://192.168.1.10:8080/admin").unwrap();
+        assert_eq!((ipv4.host.as_str(), ipv4.registrable_domain.as_str()), ("192.168.1.10", "192.168.1.10"));
+        assert_eq!((ipv4.public_suffix.as_str(), ipv4.port), ("", Some(8080)));
+
+        let ipv6 = extract_domain_info("http://[::1]:3000/").unwrap();
+        assert_eq!(ipv6.registrable_domain, "::1");
+        assert_eq!(ipv6.public_suffix, "");
+
+        let localhost = extract_domain_info("http://localhost:3000/api").unwrap();
+        assert_eq!(localhost.registrable_domain, "localhost");
+        assert_eq!(localhost.public_suffix, "");
+
+        // IDNs are compared in punycode, whichever form the URL used
+        let idn = extract_domain_info("https://shop.münchen.de/").unwrap();
+        assert_eq!(idn.host, "shop.xn--mnchen-3ya.de");
+        assert_eq!(idn.registrable_domain, "xn--mnchen-3ya.de");
+        assert_eq!(registrable_domain("https://xn--mnchen-3ya.de/"), idn.registrable_domain);
+
+        let idn_suffix = extract_domain_info("http://www.例子.香港/").unwrap();
+        assert_eq!(idn_suffix.public_suffix, "xn--j6w193g");
+        assert_eq!(idn_suffix.registrable_domain, "xn--fsqu00a.xn--j6w193g");
     }
 }
@@ -1,4 +1,26 @@
-use url::Url;
+use publicsuffix::{List, Psl};
+use std::sync::LazyLock;
+use url::{Host, Url};
+
+// Snapshot of https://publicsuffix.org/list/public_suffix_list.dat, ICANN and private sections
+static PUBLIC_SUFFIXES: LazyLock<List> = LazyLock::new(|| {
+    include_str!("public_suffix_list.dat").parse().expect("bundled public suffix list is valid")
+});
+
+/// Where a URL points, split by the public suffix list
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DomainInfo {
+    /// Lowercased host, IDNs in punycode
+    pub host: String,
+    /// Public suffix plus one label (`example.co.uk` for `blog.example.co.uk`). The host
+    /// itself for IP addresses, single-label hosts like `localhost` and bare suffixes
+    pub registrable_domain: String,
+    /// `co.uk` for `blog.example.co.uk`, empty for IP addresses and hosts like `localhost`
+    /// that match no rule of the list
+    pub public_suffix: String,
+    /// Explicit port, None for the scheme's default port
+    pub port: Option<u16>,
+}
 
 pub fn normalize_url(url: &str) -> Result<String, url::ParseError> {
     let parsed = Url::parse(url)?;
@@ -12,9 +34,130 @@ pub fn extract_domain(url: &str) -> Option<String> {
         .map(|h| h.to_string())
 }
 
+/// Host, registrable domain and port of `url`; a URL without scheme is read as http.
+/// None if it has no host
+pub fn extract_domain_info(url: &str) -> Option<DomainInfo> {
+    let url = url.trim();
+    let parsed = match Url::parse(url) {
+        Ok(parsed) if parsed.has_host() => parsed,
+        // "example.com/page" is relative, "example.com:8080" parses with scheme "example.com"
+        _ => Url::parse(&format!("http://{}", url)).ok()?,
+    };
+
+    let port = parsed.port();
+    let (host, registrable_domain, public_suffix) = match parsed.host()? {
+        Host::Ipv4(ip) => (ip.to_string(), ip.to_string(), String::new()),
+        Host::Ipv6(ip) => (ip.to_string(), ip.to_string(), String::new()),
+        Host::Domain(domain) => {
+            let host = domain.trim_end_matches('.').to_string();
+            if host.is_empty() {
+                return None;
+            }
+
+            match PUBLIC_SUFFIXES.domain(host.as_bytes()) {
+                Some(domain) => (
+                    host.clone(),
+                    String::from_utf8_lossy(domain.as_bytes()).into_owned(),
+                    String::from_utf8_lossy(domain.suffix().as_bytes()).into_owned(),
+                ),
+                None => {
+                    let suffix = PUBLIC_SUFFIXES.suffix(host.as_bytes())
+                        .filter(|suffix| suffix.is_known())
+                        .map(|suffix| String::from_utf8_lossy(suffix.as_bytes()).into_owned())
+                        .unwrap_or_default();
+                    (host.clone(), host, suffix)
+                }
+            }
+        }
+    };
+
+    Some(DomainInfo { host, registrable_domain, public_suffix, port })
+}
+
+/// Registrable domain of `url` pages are grouped by, "unknown" if it has no host
+pub fn registrable_domain(url: &str) -> String {
+    extract_domain_info(url)
+        .map(|info| info.registrable_domain)
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+/// Lowercased host of `url`, the key of rate limits and the `domains` table. "unknown" without one
+pub fn url_host(url: &str) -> String {
+    extract_domain_info(url)
+        .map(|info| info.host)
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
 pub fn is_same_domain(url1: &str, url2: &str) -> bool {
     match (extract_domain(url1), extract_domain(url2)) {
         (Some(d1), Some(d2)) => d1 == d2,
         _ => false,
     }
-}
\ No newline at end of file
+}
+/// Query parameters dropped by `canonicalize`, a trailing `*` matches any suffix
+pub const DEFAULT_STRIP_PARAMS: &[&str] = &[
+    "utm_*", "fbclid", "gclid", "dclid", "msclkid", "yclid", "mc_cid", "mc_eid", "igshid", "_ga", "ref_src",
+];
+
+pub fn default_strip_params() -> Vec<String> {
+    DEFAULT_STRIP_PARAMS.iter().map(|p| p.to_string()).collect()
+}
+
+/// Canonical form of a URL using the default tracking parameter list
+pub fn canonicalize(url: &str) -> String {
+    canonicalize_with(url, DEFAULT_STRIP_PARAMS)
+}
+
+/// Canonical form of a URL so trivially different spellings dedupe to one entry:
+/// https scheme, no default port, no fragment, no tracking parameters, sorted query
+/// and no trailing slash. Unparseable input is returned unchanged.
+pub fn canonicalize_with<S: AsRef<str>>(url: &str, strip_params: &[S]) -> String {
+    let Ok(mut parsed) = Url::parse(url.trim()) else {
+        return url.to_string();
+    };
+
+    if !matches!(parsed.scheme(), "http" | "https") {
+        return parsed.to_string();
+    }
+
+    if parsed.scheme() == "http" {
+        let _ = parsed.set_scheme("https");
+    }
+    if matches!(parsed.port(), Some(80) | Some(443)) {
+        let _ = parsed.set_port(None);
+    }
+    parsed.set_fragment(None);
+
+    let mut params: Vec<(String, String)> = parsed
+        .query_pairs()
+        .filter(|(key, _)| !is_stripped_param(key, strip_params))
+        .map(|(key, value)| (key.into_owned(), value.into_owned()))
+        .collect();
+    params.sort();
+
+    if params.is_empty() {
+        parsed.set_query(None);
+    } else {
+        parsed.query_pairs_mut().clear().extend_pairs(&params);
+    }
+
+    let trimmed = parsed.path().trim_end_matches('/').to_string();
+    if trimmed.is_empty() && parsed.query().is_none() {
+        // the url crate always serializes a root path as "/"
+        return parsed.to_string().trim_end_matches('/').to_string();
+    }
+    parsed.set_path(if trimmed.is_empty() { "/" } else { &trimmed });
+
+    parsed.to_string()
+}
+
+fn is_stripped_param<S: AsRef<str>>(key: &str, strip_params: &[S]) -> bool {
+    let key = key.to_lowercase();
+    strip_params.iter().any(|param| {
+        let param = param.as_ref();
+        match param.strip_suffix('*') {
+            Some(prefix) => key.starts_with(prefix),
+            None => key == param,
+        }
+    })
+}